@@ -1,4 +1,13 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use time::format_description::well_known::Rfc3339;
+use time::{format_description, Date, OffsetDateTime};
+
+use crate::infra::git::DEFAULT_CLONE_DEPTH;
+use crate::infra::http_retry::DEFAULT_MAX_RETRIES;
+use crate::pkg::policy::Severity;
+use crate::scanner::DEFAULT_DEPENDENCY_TIMEOUT;
+
+pub const STDOUT_OUTPUT_FILE: &str = "-";
 
 #[derive(Parser, Debug)]
 #[clap(version, author, about, long_about = None)]
@@ -14,33 +23,299 @@ pub struct Args {
         help = "Valid values, from more verbose to less are: trace, debug, info, warn, error, off"
     )]
     pub log_level: String,
+
+    #[clap(
+        global = true,
+        long,
+        help = "Directory of the database of the caches and the scan history. Defaults to DEAN_CACHE_DIR, or to the dean directory in the cache directory of the user, e.g. ~/.cache/dean"
+    )]
+    pub cache_dir: Option<String>,
+
+    #[clap(
+        global = true,
+        long,
+        help = "Keeps the caches and the scan history in memory instead of the database of the cache directory, for one-off scans or read-only filesystems"
+    )]
+    pub no_cache: bool,
+
+    #[clap(
+        global = true,
+        long,
+        help = "Config file to use instead of ~/.config/dean.yaml, e.g. one checked into the project. Fails if it can't be loaded instead of using the default config"
+    )]
+    pub config: Option<String>,
+
+    #[clap(
+        global = true,
+        long,
+        short = 'q',
+        help = "Doesn't log the dependencies that pass or are skipped by a policy, keeping the failures"
+    )]
+    pub quiet: bool,
+
+    #[clap(
+        global = true,
+        long,
+        value_enum,
+        default_value = "text",
+        help = "Format of the logs, with json writing a JSON object per line for log aggregation tools"
+    )]
+    pub log_format: LogFormat,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
-    #[clap(about = "Scans the dependencies of a given lock file.")]
+    #[clap(about = "Scans the dependencies of the given lock files.")]
     Scan {
         #[clap(
             long,
             short,
             default_value = "Cargo.lock",
-            help = "Lock file where the dependencies are defined. Supported locks are: Cargo.lock, package-lock.json and yarn.lock"
+            help = "Lock file where the dependencies are defined, or a directory to look for lock files in. Can be repeated. Supported locks are: Cargo.lock, package-lock.json, yarn.lock, pnpm-lock.yaml, requirements.txt, poetry.lock, go.mod, Gemfile.lock and composer.lock, as well as Cargo.toml for the declared dependencies only"
+        )]
+        lock_file: Vec<String>,
+
+        #[clap(
+            long,
+            short,
+            value_enum,
+            default_value = "csv",
+            help = "Format of the report with the results of the scan"
+        )]
+        format: OutputFormat,
+
+        #[clap(
+            long,
+            short,
+            help = "File where the report is written, or - to write it to stdout. Defaults to result.csv, result.json, result.sarif, result.toml, result.html or result.md depending on the format"
+        )]
+        output: Option<String>,
+
+        #[clap(
+            long,
+            help = "Only scans the dependencies whose name matches this regex. Can be repeated"
+        )]
+        only: Vec<String>,
+
+        #[clap(
+            long,
+            help = "Skips the dependencies whose name matches this regex, even if matched by --only, e.g. the internal packages. Can be repeated"
+        )]
+        exclude: Vec<String>,
+
+        #[clap(
+            long,
+            conflicts_with = "include-dev",
+            help = "Skips the dependencies that the lock file flags as dev ones, which only the npm and pnpm lock files record"
+        )]
+        prod_only: bool,
+
+        #[clap(long, help = "Scans the dev dependencies too, as it's done by default")]
+        include_dev: bool,
+
+        #[clap(
+            long,
+            help = "Only writes to the report the dependencies that failed any policy, with all their evaluations"
+        )]
+        failures_only: bool,
+
+        #[clap(
+            long,
+            help = "Reports the number of evaluated dependencies to stderr. Enabled by default when stderr is a terminal"
+        )]
+        progress: bool,
+
+        #[clap(
+            long,
+            default_value = "0",
+            help = "Exits with code 2 if the summed fail score of any dependency is greater than this threshold. With 0, any failure exits with code 2"
+        )]
+        fail_threshold: f64,
+
+        #[clap(
+            long,
+            value_enum,
+            default_value = "info",
+            help = "Only considers the failures with at least this severity for the fail threshold, e.g. error to ignore the failures of the policies configured as info or warn"
+        )]
+        fail_severity: Severity,
+
+        #[clap(
+            long,
+            help = "Compares the risk score of each dependency, from 0 to 100, with the fail threshold instead of its summed fail score"
+        )]
+        risk_score: bool,
+
+        #[clap(
+            long,
+            help = "Exits with code 2 if more than this number of dependencies fail any policy"
+        )]
+        max_failures: Option<usize>,
+
+        #[clap(
+            long,
+            default_value_t = DEFAULT_CLONE_DEPTH,
+            help = "Number of commits fetched when cloning the repository of a dependency, deepened as the policies need older commits. With 0, the whole history is cloned"
+        )]
+        clone_depth: u32,
+
+        #[clap(
+            long,
+            default_value_t = DEFAULT_MAX_RETRIES,
+            help = "Number of times a request to the npm or crates.io registries is retried when it fails with a connection error, a timeout, a 5xx or a 429"
+        )]
+        max_retries: u32,
+
+        #[clap(
+            long,
+            default_value_t = DEFAULT_DEPENDENCY_TIMEOUT.as_secs(),
+            help = "Seconds after which the evaluation of a dependency is stopped and its policies reported as skipped"
+        )]
+        dependency_timeout: u64,
+
+        #[clap(
+            long,
+            help = "Lists the policies each dependency would be evaluated with, without requesting the registries or cloning the repositories"
+        )]
+        offline: bool,
+
+        #[clap(
+            long,
+            help = "Resumes the interrupted scan of the same lock files, only evaluating the dependencies it didn't evaluate and reporting them together with the ones it did"
+        )]
+        resume: bool,
+
+        #[clap(
+            long,
+            help = "Prints to stderr the time spent cloning the repositories, retrieving their tags and issues and evaluating the policies, with the slowest dependencies"
+        )]
+        timings: bool,
+
+        #[clap(
+            long,
+            value_parser = parse_as_of,
+            help = "Evaluates the repositories as they were at this date, e.g. 2024-06-30 or 2024-06-30T12:00:00Z, leaving out the tags and commits created after it, to reproduce a past audit"
+        )]
+        as_of: Option<u64>,
+    },
+
+    #[clap(about = "Explains in detail the evaluation of a dependency of the given lock files.")]
+    Explain {
+        #[clap(help = "Name of the dependency to explain")]
+        dependency: String,
+
+        #[clap(
+            long,
+            short,
+            default_value = "Cargo.lock",
+            help = "Lock file where the dependency is defined, or a directory to look for lock files in. Can be repeated"
         )]
-        lock_file: String,
+        lock_file: Vec<String>,
     },
 
+    #[clap(
+        about = "Compares the latest two scans, listing the dependencies that started failing or passing, and the added and removed ones."
+    )]
+    Diff,
+
     #[clap(about = "Manages the configuration of the tool.")]
     #[clap(arg_required_else_help(true))]
     Config {
         #[clap(subcommand)]
         command: ConfigCommands,
     },
+
+    #[clap(about = "Manages the local cache of repository data.")]
+    #[clap(arg_required_else_help(true))]
+    Cache {
+        #[clap(subcommand)]
+        command: CacheCommands,
+    },
 }
 
 #[derive(Subcommand, Debug)]
 pub enum ConfigCommands {
     #[clap(about = "Displays the current configuration")]
     Show,
+    #[clap(about = "Writes the default configuration to ~/.config/dean.yaml")]
+    Init {
+        #[clap(long, help = "Overwrites the config file if it already exists")]
+        force: bool,
+    },
+    #[clap(about = "Checks that a config file is valid")]
+    Validate {
+        #[clap(
+            help = "Config file to check. Defaults to the one of --config, or to ~/.config/dean.yaml"
+        )]
+        path: Option<String>,
+    },
+    #[clap(about = "Prints the JSON Schema of the config file")]
+    Schema,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommands {
+    #[clap(about = "Deletes all the cached tags, commits, issues and pull requests")]
+    Clear,
+    #[clap(about = "Displays the number of cached and expired entries")]
+    Stats,
+    #[clap(
+        about = "Prints every cached tag, commit, issue, pull request and vulnerability to stdout as a JSON object per line, with its table and repository"
+    )]
+    Export,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+    Sarif,
+    Toml,
+    Html,
+    Markdown,
+}
+
+impl OutputFormat {
+    pub fn default_output_file(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "result.csv",
+            OutputFormat::Json => "result.json",
+            OutputFormat::Sarif => "result.sarif",
+            OutputFormat::Toml => "result.toml",
+            OutputFormat::Html => "result.html",
+            OutputFormat::Markdown => "result.md",
+        }
+    }
+}
+
+/// Parses an RFC 3339 date-time, or a date, which includes the whole day in UTC, into seconds since
+/// the epoch. The ones before the epoch are rejected, as the clocks of the policies can't go back
+/// that far.
+fn parse_as_of(value: &str) -> Result<u64, String> {
+    let timestamp = parse_timestamp(value)?;
+    u64::try_from(timestamp).map_err(|_| format!("{value} is before 1970-01-01"))
+}
+
+fn parse_timestamp(value: &str) -> Result<i64, String> {
+    if let Ok(date_time) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Ok(date_time.unix_timestamp());
+    }
+
+    let date_format = format_description::parse("[year]-[month]-[day]")
+        .map_err(|e| format!("invalid date format: {e}"))?;
+    let date = Date::parse(value, &date_format)
+        .map_err(|_| format!("{value} is not a date like 2024-06-30 or an RFC 3339 date-time"))?;
+    let end_of_day = date
+        .with_hms(23, 59, 59)
+        .map_err(|e| format!("invalid time: {e}"))?;
+    Ok(end_of_day.assume_utc().unix_timestamp())
 }
 
 pub fn parse_args() -> Args {
@@ -1,150 +1,558 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::{anyhow, Context};
 use log::info;
+use semver::VersionReq;
 use tokio::fs::File;
+use tokio::io::AsyncWrite;
 use tokio::sync::Mutex;
-use tokio_stream::Stream;
+use tokio_stream::{Stream, StreamExt};
 
-use crate::infra::cached_issue_client::IssueStore;
-use crate::infra::clock::Clock;
-use crate::infra::git::{CommitStore, RepositoryRetriever};
-use crate::infra::github;
+use crate::cmd::{OutputFormat, STDOUT_OUTPUT_FILE};
+use crate::infra::cache_store::{self, CacheStore};
+use crate::infra::cached_info_retriever::CachedInfoRetriever;
+use crate::infra::cached_issue_client::{IssueClient, IssueStore};
+use crate::infra::clock::{Clock, FixedClock};
+use crate::infra::git::{CommitStore, RepositoryRetriever, DEFAULT_CLONE_DEPTH};
+use crate::infra::http_retry::DEFAULT_MAX_RETRIES;
+use crate::infra::offline_info_retriever::OfflineInfoRetriever;
+use crate::infra::osv::VulnerabilityStore;
 use crate::infra::package_manager::cargo::InfoRetriever as CargoInfoRetriever;
+use crate::infra::package_manager::go::InfoRetriever as GoInfoRetriever;
 use crate::infra::package_manager::npm::InfoRetriever as NpmInfoRetriever;
-use crate::infra::repo_contribution;
-use crate::infra::{commit_store, issue_store};
+use crate::infra::package_manager::npmrc;
+use crate::infra::package_manager::packagist::InfoRetriever as PackagistInfoRetriever;
+use crate::infra::package_manager::pypi::InfoRetriever as PypiInfoRetriever;
+use crate::infra::package_manager::rubygems::InfoRetriever as RubygemsInfoRetriever;
+use crate::infra::{commit_store, issue_store, osv, scan_state, scan_store, vulnerability_store};
+use crate::infra::{github, github_graphql, gitlab, http};
+use crate::infra::{repo_contribution, repository_status, rustsec};
 use crate::lazy::Lazy;
-use crate::pkg::config::{Config, Policies};
+use crate::pkg::config::{risk_score, Config, Policies, PolicyOptions};
 use crate::pkg::engine::{ExecutionConfig, PolicyExecutor};
-use crate::pkg::format::csv::Reporter;
-use crate::pkg::package_manager::{cargo, npm, yarn};
+use crate::pkg::format::{csv, html, json, markdown, sarif, toml, Reporter};
+use crate::pkg::package_manager::{
+    bundler, cargo, cargo_manifest, composer, go, npm, pip, pnpm, poetry, yarn,
+};
 use crate::pkg::policy::{
-    CommitRetriever, ContributionDataRetriever, ContributorsRatio, MaxIssueLifespan,
-    MaxPullRequestLifespan, MinNumberOfReleasesRequired, Policy,
+    AdvisoryRetriever, BlockedDependency, Blocklist, CommitRetriever, ContributionDataRetriever,
+    ContributorsRatio, IssueCloseRate, IssueWindow, MaxIssueLifespan, MaxOpenIssueRatio,
+    MaxPullRequestLifespan, MaxStaleOpenPullRequests, MaxTimeSinceLastCommit,
+    MaxTransitiveDependencies, MaxVersionsBehind, MinContributors, MinDownloads,
+    MinNumberOfReleasesRequired, MinRepositoryAge, MinWeeklyDownloads, NoKnownVulnerabilities,
+    NoPrereleaseVersion, NoRustsecAdvisories, NotDeprecated, Policy, RecentContributorDiversity,
+    RepositoryArchived, RepositoryStatusRetriever, RequireRecentPatchRelease,
+    RequireSecurityPolicy, SignedReleases, VulnerabilityRetriever, WithOptions,
 };
 use crate::pkg::recognizer::PackageManager;
-use crate::pkg::{DependencyRetriever, InfoRetriever};
+use crate::pkg::timings::Timings;
+use crate::pkg::{
+    DependencyFilter, DependencyRetriever, InfoRetriever, DEFAULT_GITHUB_HOST, HOSTS,
+};
 use crate::{Dependency, Result};
 
 pub struct Factory {
     config: Rc<Config>,
+    clone_depth: u32,
+    offline: bool,
+    max_retries: u32,
+    cache_dir: PathBuf,
+    no_cache: bool,
+    timings: Option<Arc<Timings>>,
+    as_of: Option<u64>,
 
-    info_retriever: Lazy<Arc<dyn InfoRetriever>>,
+    info_retrievers: RefCell<HashMap<&'static str, Arc<dyn InfoRetriever>>>,
     http_client: Lazy<Arc<reqwest::Client>>,
     repository_retriever: Lazy<Arc<dyn CommitRetriever>>,
     contribution_retriever: Lazy<Arc<dyn ContributionDataRetriever>>,
+    repository_status_retriever: Lazy<Arc<dyn RepositoryStatusRetriever>>,
+    vulnerability_retriever: Lazy<Arc<dyn VulnerabilityRetriever>>,
+    advisory_retriever: Lazy<Arc<dyn AdvisoryRetriever>>,
     github_client: Lazy<Arc<github::Client>>,
-    commit_store: Lazy<Arc<dyn CommitStore>>,
-    issue_store: Lazy<Arc<dyn IssueStore>>,
+    gitlab_client: Lazy<Arc<gitlab::Client>>,
+    database_pool: Lazy<cache_store::Pool>,
+    commit_store: Lazy<Arc<commit_store::Sqlite>>,
+    issue_store: Lazy<Arc<issue_store::Sqlite>>,
+    vulnerability_store: Lazy<Arc<vulnerability_store::Sqlite>>,
+    scan_store: Lazy<Arc<scan_store::Sqlite>>,
+    scan_state: Lazy<Arc<scan_state::Sqlite>>,
 }
 
 const DAYS_TO_SECONDS: u64 = 86400;
+const IGNORED_DIRECTORIES: [&str; 3] = ["node_modules", "target", ".git"];
 
 impl Factory {
     pub async fn dependency_reader<'a, T: tokio::io::AsyncRead + Unpin + Send + 'a>(
         &self,
         reader: T,
         lock_file: &str,
+        filter: &DependencyFilter,
     ) -> Box<dyn Stream<Item = Dependency> + Unpin + Send + 'a> {
-        let retriever = self.info_retriever(lock_file);
-
-        match Self::package_manager(lock_file) {
-            PackageManager::Npm => Box::new(
-                npm::DependencyReader::new(reader, retriever)
-                    .dependencies()
-                    .await
-                    .expect("failed to retrieve npm dependencies from reader"),
-            ),
-            PackageManager::Cargo => Box::new(
-                cargo::DependencyReader::new(reader, retriever)
-                    .dependencies()
-                    .await
-                    .expect("failed to retrieve cargo dependencies from reader"),
-            ),
-            PackageManager::Yarn => Box::new(
-                yarn::DependencyReader::new(reader, retriever)
-                    .dependencies()
-                    .await
-                    .expect("failed to retrieve yarn dependencies from reader"),
-            ),
+        let package_manager = Self::package_manager(lock_file);
+        let retriever = self.info_retriever(package_manager);
+
+        let dependencies: Box<dyn Stream<Item = Dependency> + Unpin + Send + 'a> =
+            match package_manager {
+                PackageManager::Npm => Box::new(
+                    npm::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve npm dependencies from reader"),
+                ),
+                PackageManager::Cargo => Box::new(
+                    cargo::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve cargo dependencies from reader"),
+                ),
+                PackageManager::CargoManifest => Box::new(
+                    cargo_manifest::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve cargo manifest dependencies from reader"),
+                ),
+                PackageManager::Yarn => Box::new(
+                    yarn::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve yarn dependencies from reader"),
+                ),
+                PackageManager::Pnpm => Box::new(
+                    pnpm::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve pnpm dependencies from reader"),
+                ),
+                PackageManager::Pip => Box::new(
+                    pip::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve pip dependencies from reader"),
+                ),
+                PackageManager::Poetry => Box::new(
+                    poetry::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve poetry dependencies from reader"),
+                ),
+                PackageManager::Go => Box::new(
+                    go::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve go dependencies from reader"),
+                ),
+                PackageManager::Bundler => Box::new(
+                    bundler::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve bundler dependencies from reader"),
+                ),
+                PackageManager::Composer => Box::new(
+                    composer::DependencyReader::new(reader, retriever)
+                        .dependencies(filter)
+                        .await
+                        .expect("failed to retrieve composer dependencies from reader"),
+                ),
+            };
+
+        let ecosystem = package_manager.ecosystem();
+        let repository_overrides = self.config.repository_overrides();
+        let lock_file = lock_file.to_string();
+        Box::new(dependencies.map(move |mut dependency| {
+            dependency.ecosystem = Some(ecosystem);
+            dependency.lock_file = Some(lock_file.clone());
+            if let Some((_, repository)) = repository_overrides
+                .iter()
+                .find(|(regex, _)| regex.is_match(&dependency.name))
+            {
+                dependency.repository = repository.clone();
+            }
+            dependency
+        }))
+    }
+
+    /// Reads the dependencies of all the lock files, walking the directories to find the lock
+    /// files inside them. A dependency found in several lock files of the same ecosystem is only
+    /// returned once.
+    pub async fn lock_files_dependency_reader(
+        &self,
+        paths: &[String],
+        filter: &DependencyFilter,
+    ) -> Result<Box<dyn Stream<Item = Dependency> + Unpin + Send>> {
+        let mut readers = Vec::new();
+        for lock_file_name in find_lock_files(paths)? {
+            let lock_file = File::open(&lock_file_name)
+                .await
+                .with_context(|| format!("failed to open lock file: {lock_file_name}"))?;
+            readers.push(
+                self.dependency_reader(lock_file, &lock_file_name, filter)
+                    .await,
+            );
         }
+
+        let mut seen = HashSet::new();
+        Ok(Box::new(futures::stream::select_all(readers).filter(
+            move |dependency| {
+                seen.insert((
+                    dependency.name.clone(),
+                    dependency.version.clone(),
+                    dependency.ecosystem,
+                ))
+            },
+        )))
     }
 
-    fn config_policies_to_vector(&self, config_policies: &Policies) -> Vec<Box<dyn Policy>> {
-        let repository_retriever = self.repository_retriever();
+    fn config_policies_to_vector(
+        &self,
+        config_policies: &Policies,
+    ) -> Result<Vec<Box<dyn Policy>>> {
+        let mut policies = self.commit_policies(config_policies)?;
+        policies.extend(self.contribution_policies(config_policies));
+        policies.extend(self.repository_status_policies(config_policies));
+        policies.extend(self.version_policies(config_policies)?);
+        policies.extend(self.registry_policies(config_policies));
+
+        Ok(policies)
+    }
+
+    /// Returns the configured policies evaluated with the tags and commits of the clones of the
+    /// repositories of the dependencies.
+    fn commit_policies(&self, config_policies: &Policies) -> Result<Vec<Box<dyn Policy>>> {
         let mut policies: Vec<Box<dyn Policy>> = Vec::new();
 
         if let Some(policy) = &config_policies.min_number_of_releases_required {
             policies.push(Box::new(MinNumberOfReleasesRequired::new(
-                repository_retriever.clone(),
+                self.repository_retriever(),
                 policy.min_number_of_releases,
                 Duration::from_secs(policy.days * DAYS_TO_SECONDS),
-                Box::new(Clock {}),
+                regex::Regex::new(&policy.tag_pattern)
+                    .context("invalid tag_pattern of min_number_of_releases_required")?,
+                self.clock(),
+            )));
+        }
+        if let Some(policy) = &config_policies.max_days_since_last_commit {
+            policies.push(Box::new(MaxTimeSinceLastCommit::new(
+                self.repository_retriever(),
+                policy.max_days,
+                self.clock(),
             )));
         }
         if let Some(policy) = &config_policies.contributors_ratio {
             policies.push(Box::new(ContributorsRatio::new(
-                repository_retriever.clone(),
+                self.repository_retriever(),
                 policy.max_number_of_releases_to_check,
                 policy.max_contributor_ratio,
+                policy.normalize_emails,
+                &policy.author_aliases,
+            )));
+        }
+        if let Some(policy) = &config_policies.min_contributors {
+            policies.push(Box::new(MinContributors::new(
+                self.repository_retriever(),
+                policy.max_number_of_releases_to_check,
+                policy.min_contributors,
+            )));
+        }
+        if let Some(policy) = &config_policies.signed_releases {
+            policies.push(Box::new(SignedReleases::new(
+                self.repository_retriever(),
+                policy.last_tags,
+                policy.min_signed_ratio,
+            )));
+        }
+        if let Some(policy) = &config_policies.min_repository_age {
+            policies.push(Box::new(MinRepositoryAge::new(
+                self.repository_retriever(),
+                policy.min_age_days,
+                self.clock(),
+            )));
+        }
+        if let Some(policy) = &config_policies.require_recent_patch_release {
+            policies.push(Box::new(RequireRecentPatchRelease::new(
+                self.repository_retriever(),
+                policy.max_days_since_patch,
+                self.clock(),
+            )));
+        }
+        if let Some(policy) = &config_policies.recent_contributor_diversity {
+            policies.push(Box::new(RecentContributorDiversity::new(
+                self.repository_retriever(),
+                policy.window_releases,
+                policy.window_days,
+                policy.max_recent_contributor_ratio,
+                self.clock(),
+            )));
+        }
+
+        Ok(policies)
+    }
+
+    /// Returns the configured policies evaluated with the status of the repositories of the
+    /// dependencies in their forge.
+    fn repository_status_policies(&self, config_policies: &Policies) -> Vec<Box<dyn Policy>> {
+        let mut policies: Vec<Box<dyn Policy>> = Vec::new();
+
+        if let Some(policy) = &config_policies.repository_archived {
+            if policy.enabled {
+                policies.push(Box::new(RepositoryArchived::new(
+                    self.repository_status_retriever(),
+                )));
+            }
+        }
+        if let Some(policy) = &config_policies.require_security_policy {
+            if policy.enabled {
+                policies.push(Box::new(RequireSecurityPolicy::new(
+                    self.repository_status_retriever(),
+                )));
+            }
+        }
+
+        policies
+    }
+
+    /// Returns the configured policies evaluated with the version of the dependencies, against
+    /// the advisory databases or the lock file itself.
+    fn version_policies(&self, config_policies: &Policies) -> Result<Vec<Box<dyn Policy>>> {
+        let mut policies: Vec<Box<dyn Policy>> = Vec::new();
+
+        if let Some(policy) = &config_policies.blocklist {
+            policies.push(Box::new(Blocklist::new(
+                policy
+                    .blocked
+                    .iter()
+                    .map(|blocked| {
+                        Ok(BlockedDependency {
+                            name: blocked.name.clone(),
+                            version_range: VersionReq::parse(&blocked.version_range).with_context(
+                                || {
+                                    format!(
+                                        "invalid version_range of the blocked dependency {}",
+                                        blocked.name
+                                    )
+                                },
+                            )?,
+                            reason: blocked.reason.clone(),
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            )));
+        }
+        if let Some(policy) = &config_policies.no_known_vulnerabilities {
+            if policy.enabled {
+                policies.push(Box::new(NoKnownVulnerabilities::new(
+                    self.vulnerability_retriever(),
+                )));
+            }
+        }
+        if let Some(policy) = &config_policies.no_rustsec_advisories {
+            if policy.enabled {
+                policies.push(Box::new(NoRustsecAdvisories::new(
+                    self.advisory_retriever(),
+                )));
+            }
+        }
+        if let Some(policy) = &config_policies.no_prerelease_version {
+            if policy.fail_on_prerelease {
+                policies.push(Box::new(NoPrereleaseVersion));
+            }
+        }
+        if let Some(policy) = &config_policies.max_transitive_dependencies {
+            policies.push(Box::new(MaxTransitiveDependencies::new(
+                policy.max_transitive,
             )));
         }
+
+        Ok(policies)
+    }
+
+    /// Returns the configured policies evaluated with the issues and pull requests of the
+    /// repositories of the dependencies.
+    fn contribution_policies(&self, config_policies: &Policies) -> Vec<Box<dyn Policy>> {
+        let mut policies: Vec<Box<dyn Policy>> = Vec::new();
+
         if let Some(policy) = &config_policies.max_issue_lifespan {
-            policies.push(Box::new(
-                #[allow(clippy::cast_precision_loss)]
-                MaxIssueLifespan::new(
-                    self.contribution_retriever(),
-                    policy.max_lifespan_in_seconds as f64,
-                    policy.last_issues,
+            policies.push(Box::new(MaxIssueLifespan::new(
+                self.contribution_retriever(),
+                Self::max_lifespan_in_seconds(
+                    policy.max_lifespan_in_seconds,
+                    policy.max_lifespan_days,
                 ),
-            ));
+                IssueWindow::new(policy.last_issues, policy.since_days),
+            )));
         }
         if let Some(policy) = &config_policies.max_pull_request_lifespan {
-            policies.push(Box::new(
-                #[allow(clippy::cast_precision_loss)]
-                MaxPullRequestLifespan::new(
-                    self.contribution_retriever(),
-                    policy.max_lifespan_in_seconds as f64,
-                    policy.last_pull_requests,
+            policies.push(Box::new(MaxPullRequestLifespan::new(
+                self.contribution_retriever(),
+                Self::max_lifespan_in_seconds(
+                    policy.max_lifespan_in_seconds,
+                    policy.max_lifespan_days,
                 ),
-            ));
+                IssueWindow::new(policy.last_pull_requests, policy.since_days),
+            )));
+        }
+        if let Some(policy) = &config_policies.max_stale_open_pull_requests {
+            policies.push(Box::new(MaxStaleOpenPullRequests::new(
+                self.contribution_retriever(),
+                policy.max_stale_open_pull_requests,
+                policy.stale_after_days,
+                IssueWindow::new(policy.last_pull_requests, policy.since_days),
+            )));
+        }
+        if let Some(policy) = &config_policies.max_open_issue_ratio {
+            policies.push(Box::new(MaxOpenIssueRatio::new(
+                self.contribution_retriever(),
+                policy.max_open_issue_ratio,
+                IssueWindow::new(policy.last_issues, policy.since_days),
+            )));
+        }
+        if let Some(policy) = &config_policies.issue_close_rate {
+            policies.push(Box::new(IssueCloseRate::new(
+                self.contribution_retriever(),
+                policy.min_close_rate,
+                IssueWindow::new(policy.last_issues, policy.since_days),
+            )));
         }
 
         policies
     }
 
+    /// Returns the configured policies evaluated with the data of the dependencies in their
+    /// registries instead of their repositories.
+    fn registry_policies(&self, config_policies: &Policies) -> Vec<Box<dyn Policy>> {
+        let mut policies: Vec<Box<dyn Policy>> = Vec::new();
+
+        if let Some(policy) = &config_policies.max_versions_behind {
+            policies.push(Box::new(MaxVersionsBehind::new(
+                policy.max_major_behind,
+                policy.max_minor_behind,
+            )));
+        }
+        if let Some(policy) = &config_policies.not_deprecated {
+            if policy.enabled {
+                policies.push(Box::new(NotDeprecated::new(
+                    [PackageManager::Npm, PackageManager::Cargo]
+                        .into_iter()
+                        .map(|pm| (pm.ecosystem(), self.info_retriever(pm)))
+                        .collect(),
+                )));
+            }
+        }
+        if let Some(policy) = &config_policies.min_downloads {
+            policies.push(Box::new(MinDownloads::new(
+                HashMap::from([(
+                    PackageManager::Cargo.ecosystem(),
+                    self.info_retriever(PackageManager::Cargo),
+                )]),
+                policy.min_downloads,
+            )));
+        }
+        if let Some(policy) = &config_policies.min_weekly_downloads {
+            policies.push(Box::new(MinWeeklyDownloads::new(
+                HashMap::from([(
+                    PackageManager::Npm.ecosystem(),
+                    self.info_retriever(PackageManager::Npm),
+                )]),
+                policy.min_weekly_downloads,
+            )));
+        }
+
+        policies
+    }
+
+    /// Returns the configured policies, wrapping the ones with options so that they are skipped
+    /// for the dependencies in their skip list and their failures are weighted.
+    fn config_policies(&self, config_policies: &Policies) -> Result<Vec<Box<dyn Policy>>> {
+        let options = config_policies.options();
+
+        self.config_policies_to_vector(config_policies)?
+            .into_iter()
+            .map(|policy| {
+                let policy_name = policy.name();
+                match options.iter().find(|(name, _)| *name == policy_name) {
+                    Some((_, options)) if **options != PolicyOptions::default() => {
+                        let policy = WithOptions::new(
+                            policy,
+                            &options.skip,
+                            options.weight.unwrap_or(1.0),
+                            options.severity.unwrap_or_default(),
+                        )
+                        .with_context(|| format!("invalid skip list of {policy_name}"))?;
+                        Ok(Box::new(policy) as Box<dyn Policy>)
+                    }
+                    _ => Ok(policy),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the maximum lifespan in seconds, using the days instead when they are set.
+    #[allow(clippy::cast_precision_loss)]
+    fn max_lifespan_in_seconds(
+        max_lifespan_in_seconds: usize,
+        max_lifespan_days: Option<u64>,
+    ) -> f64 {
+        max_lifespan_days.map_or(max_lifespan_in_seconds as f64, |days| {
+            (days * DAYS_TO_SECONDS) as f64
+        })
+    }
+
     fn execution_configs(&self) -> Result<Vec<ExecutionConfig>> {
         let mut execution_configs = vec![];
 
         for dependency_config in &self.config.dependency_config {
             execution_configs.push(ExecutionConfig::new(
-                self.config_policies_to_vector(&dependency_config.policies),
+                self.config_policies(&dependency_config.policies)?,
                 Some(&dependency_config.name),
+                dependency_config.merge_with_defaults,
             )?);
         }
 
-        let policies = self.config_policies_to_vector(&self.config.default_policies);
+        let policies = self.config_policies(&self.config.default_policies)?;
         if !policies.is_empty() {
-            execution_configs.push(ExecutionConfig::new(policies, None)?);
+            execution_configs.push(ExecutionConfig::new(policies, None, false)?);
         }
 
         Ok(execution_configs)
     }
 
-    fn info_retriever(&self, lock_file: &str) -> Arc<dyn InfoRetriever> {
-        let info_retriever = &self.info_retriever;
-        info_retriever
-            .get(|| {
+    /// Returns the info retriever of the registry of the package manager, sharing the same one
+    /// among the lock files of the same ecosystem so their cache is shared too.
+    fn info_retriever(&self, package_manager: PackageManager) -> Arc<dyn InfoRetriever> {
+        self.info_retrievers
+            .borrow_mut()
+            .entry(package_manager.ecosystem())
+            .or_insert_with(|| {
+                if self.offline {
+                    return Arc::new(OfflineInfoRetriever);
+                }
+
                 let http_client = self.http_client();
 
-                match Self::package_manager(lock_file) {
-                    PackageManager::Npm | PackageManager::Yarn => {
-                        Arc::new(NpmInfoRetriever::new(http_client))
+                let retriever: Arc<dyn InfoRetriever> = match package_manager {
+                    PackageManager::Npm | PackageManager::Yarn | PackageManager::Pnpm => Arc::new(
+                        NpmInfoRetriever::new(http_client)
+                            .with_max_retries(self.max_retries)
+                            .with_registries(npmrc::Registries::from_environment()),
+                    ),
+                    PackageManager::Cargo | PackageManager::CargoManifest => Arc::new(
+                        CargoInfoRetriever::new(http_client).with_max_retries(self.max_retries),
+                    ),
+                    PackageManager::Pip | PackageManager::Poetry => {
+                        Arc::new(PypiInfoRetriever::new(http_client))
                     }
-                    PackageManager::Cargo => Arc::new(CargoInfoRetriever::new(http_client)),
-                }
+                    PackageManager::Go => Arc::new(GoInfoRetriever::new(http_client)),
+                    PackageManager::Bundler => Arc::new(RubygemsInfoRetriever::new(http_client)),
+                    PackageManager::Composer => Arc::new(
+                        PackagistInfoRetriever::new(http_client).with_max_retries(self.max_retries),
+                    ),
+                };
+
+                Arc::new(CachedInfoRetriever::new(retriever))
             })
             .clone()
     }
@@ -152,7 +560,14 @@ impl Factory {
     fn http_client(&self) -> Arc<reqwest::Client> {
         self.http_client
             .get(|| {
+                let user_agent = self
+                    .config
+                    .user_agent
+                    .as_deref()
+                    .unwrap_or(http::DEFAULT_USER_AGENT);
                 let reqwest_client = reqwest::Client::builder()
+                    .user_agent(user_agent)
+                    .proxy(reqwest::Proxy::custom(http::proxy_for))
                     .timeout(Duration::from_secs(600))
                     .build()
                     .expect("unable to create the reqwest client");
@@ -170,25 +585,80 @@ impl Factory {
     fn repository_retriever(&self) -> Arc<dyn CommitRetriever> {
         self.repository_retriever
             .get(|| {
-                let git_repository_retriever = RepositoryRetriever::new(self.commit_store());
+                let mut git_repository_retriever =
+                    RepositoryRetriever::new(self.commit_store(), self.clone_depth);
+                if let Some(timings) = &self.timings {
+                    git_repository_retriever =
+                        git_repository_retriever.with_timings(timings.clone());
+                }
+                if let Some(as_of) = self.as_of {
+                    git_repository_retriever = git_repository_retriever.with_as_of(as_of);
+                }
 
                 Arc::new(git_repository_retriever)
             })
             .clone()
     }
 
+    /// Returns the clock of the policies measuring ages, stopped at the as-of timestamp if any.
+    fn clock(&self) -> Box<dyn crate::pkg::policy::Clock> {
+        match self.as_of {
+            Some(as_of) => Box::new(FixedClock::new(as_of)),
+            None => Box::new(Clock {}),
+        }
+    }
+
     fn contribution_retriever(&self) -> Arc<dyn ContributionDataRetriever> {
         self.contribution_retriever
             .get(|| {
-                let git_contributor_retriever =
-                    repo_contribution::Retriever::new(self.github_client(), self.issue_store());
+                let mut git_contributor_retriever = repo_contribution::Retriever::new(
+                    self.github_issue_client(),
+                    self.gitlab_client(),
+                    self.issue_store(),
+                    self.clock(),
+                );
+                if let Some(timings) = &self.timings {
+                    git_contributor_retriever = git_contributor_retriever.with_timings(timings);
+                }
 
                 Arc::new(git_contributor_retriever)
             })
             .clone()
     }
 
+    fn repository_status_retriever(&self) -> Arc<dyn RepositoryStatusRetriever> {
+        self.repository_status_retriever
+            .get(|| Arc::new(repository_status::Retriever::new(self.github_client())))
+            .clone()
+    }
+
+    fn vulnerability_retriever(&self) -> Arc<dyn VulnerabilityRetriever> {
+        self.vulnerability_retriever
+            .get(|| {
+                Arc::new(
+                    osv::Client::new(self.http_client(), self.vulnerability_store())
+                        .with_max_retries(self.max_retries),
+                )
+            })
+            .clone()
+    }
+
+    fn advisory_retriever(&self) -> Arc<dyn AdvisoryRetriever> {
+        self.advisory_retriever
+            .get(|| {
+                Arc::new(rustsec::AdvisoryDatabase::new(
+                    rustsec::DEFAULT_ADVISORY_DB_URL,
+                ))
+            })
+            .clone()
+    }
+
     fn github_authentication() -> github::Authentication {
+        if let Ok(github_token) = std::env::var("GITHUB_TOKEN") {
+            info!(target: "dean::github_authentication", "using token authentication");
+            return github::Authentication::Token(github_token);
+        }
+
         let github_username = std::env::var("GITHUB_USERNAME").ok();
         let github_password = std::env::var("GITHUB_PASSWORD").ok();
 
@@ -210,40 +680,226 @@ impl Factory {
         }
     }
 
+    /// The GitHub API URL is taken from `GITHUB_API_URL`, falling back to the GitHub Enterprise
+    /// API of `GITHUB_HOST` when only the host is set.
+    fn github_api_url() -> String {
+        if let Ok(api_url) = std::env::var("GITHUB_API_URL") {
+            return api_url;
+        }
+
+        if HOSTS.github() == DEFAULT_GITHUB_HOST {
+            github::DEFAULT_API_URL.to_string()
+        } else {
+            format!("https://{}/api/v3", HOSTS.github())
+        }
+    }
+
+    /// The GitLab API URL is taken from `GITLAB_API_URL`, falling back to the API of
+    /// `GITLAB_HOST`.
+    fn gitlab_api_url() -> String {
+        std::env::var("GITLAB_API_URL")
+            .unwrap_or_else(|_| format!("https://{}/api/v4", HOSTS.gitlab()))
+    }
+
     fn github_client(&self) -> Arc<github::Client> {
         self.github_client
             .get(|| {
                 let github_client =
-                    github::Client::new(reqwest::Client::new(), Self::github_authentication());
+                    github::Client::new(self.http_client(), Self::github_authentication())
+                        .with_api_url(&Self::github_api_url());
 
                 Arc::new(github_client)
             })
             .clone()
     }
 
-    pub fn result_reporter() -> Reporter<File> {
-        let reader = std::fs::File::options()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open("result.csv")
-            .expect("unable to open result.csv");
+    /// The issues and pull requests are retrieved through the GraphQL API when a token is set, as
+    /// it takes fewer requests, falling back to the REST API otherwise.
+    fn github_issue_client(&self) -> Arc<dyn IssueClient> {
+        match Self::github_authentication() {
+            github::Authentication::Token(token) => Arc::new(
+                github_graphql::Client::new(self.http_client(), &token)
+                    .with_api_url(&Self::github_api_url()),
+            ),
+            _ => self.github_client(),
+        }
+    }
 
-        let reader = File::from_std(reader);
+    fn gitlab_client(&self) -> Arc<gitlab::Client> {
+        self.gitlab_client
+            .get(|| {
+                let mut gitlab_client =
+                    gitlab::Client::new(self.http_client()).with_api_url(&Self::gitlab_api_url());
+                if let Ok(gitlab_token) = std::env::var("GITLAB_TOKEN") {
+                    info!(target: "dean::gitlab_authentication", "using token authentication");
+                    gitlab_client = gitlab_client.with_token(&gitlab_token);
+                }
 
-        Reporter::new(Arc::new(Mutex::new(reader)))
+                Arc::new(gitlab_client)
+            })
+            .clone()
+    }
+
+    /// Builds the reporter for the given format, writing to stdout when the output file is `-`.
+    pub fn result_reporter(
+        format: OutputFormat,
+        output_file: &str,
+        lock_file: &str,
+        risk_score: risk_score::Config,
+    ) -> Result<Reporter<Box<dyn AsyncWrite + Unpin + Send>>> {
+        let writer: Box<dyn AsyncWrite + Unpin + Send> = if output_file == STDOUT_OUTPUT_FILE {
+            Box::new(tokio::io::stdout())
+        } else {
+            let file = std::fs::File::options()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(output_file)
+                .with_context(|| format!("unable to open {output_file}"))?;
+            Box::new(File::from_std(file))
+        };
+
+        let reader = Arc::new(Mutex::new(writer));
+
+        Ok(match format {
+            OutputFormat::Csv => {
+                Reporter::Csv(csv::Reporter::new(reader).with_risk_score(risk_score))
+            }
+            OutputFormat::Json => {
+                Reporter::Json(json::Reporter::new(reader).with_risk_score(risk_score))
+            }
+            OutputFormat::Sarif => Reporter::Sarif(sarif::Reporter::new(reader, lock_file)),
+            OutputFormat::Toml => {
+                Reporter::Toml(toml::Reporter::new(reader).with_risk_score(risk_score))
+            }
+            OutputFormat::Html => {
+                Reporter::Html(html::Reporter::new(reader).with_risk_score(risk_score))
+            }
+            OutputFormat::Markdown => {
+                Reporter::Markdown(markdown::Reporter::new(reader).with_risk_score(risk_score))
+            }
+        })
     }
 
     pub fn engine(&mut self) -> Result<PolicyExecutor> {
-        Ok(PolicyExecutor::new(self.execution_configs()?))
+        let engine = PolicyExecutor::new(self.execution_configs()?);
+        Ok(match &self.timings {
+            Some(timings) => engine.with_timings(timings.clone()),
+            None => engine,
+        })
+    }
+
+    /// The cache directory is taken from `DEAN_CACHE_DIR`, falling back to the `dean` directory in
+    /// the cache directory of the user, e.g. `~/.cache/dean` in Linux.
+    fn default_cache_dir() -> PathBuf {
+        if let Ok(cache_dir) = std::env::var("DEAN_CACHE_DIR") {
+            return PathBuf::from(cache_dir);
+        }
+
+        dirs_next::cache_dir().map_or_else(|| PathBuf::from("."), |dir| dir.join("dean"))
+    }
+
+    /// Returns the pool of connections to the database shared by the stores, which are accessed
+    /// concurrently during the scan.
+    fn database_pool(&self) -> cache_store::Pool {
+        self.database_pool
+            .get(|| {
+                if self.no_cache {
+                    return cache_store::in_memory_pool()
+                        .expect("unable to open an in-memory database");
+                }
+
+                let path = self.database_path();
+                cache_store::file_pool(&path, self.busy_timeout())
+                    .unwrap_or_else(|e| panic!("unable to open {}: {e}", path.display()))
+            })
+            .clone()
+    }
+
+    fn busy_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.cache.busy_timeout_in_milliseconds)
+    }
+
+    /// Returns the path of the database, creating the cache directory if needed.
+    fn database_path(&self) -> PathBuf {
+        std::fs::create_dir_all(&self.cache_dir).unwrap_or_else(|e| {
+            panic!(
+                "unable to create the cache directory {}: {e}",
+                self.cache_dir.display()
+            )
+        });
+        self.cache_dir.join("dean.db3")
+    }
+
+    pub fn cache_stores(&self) -> Vec<Arc<dyn CacheStore>> {
+        vec![
+            self.sqlite_commit_store(),
+            self.sqlite_issue_store(),
+            self.sqlite_vulnerability_store(),
+        ]
+    }
+
+    /// Returns the store of the evaluations of the previous scans.
+    pub fn scan_store(&self) -> Arc<scan_store::Sqlite> {
+        self.scan_store
+            .get(|| {
+                let scan_store = scan_store::Sqlite::new(self.database_pool());
+                scan_store.init().expect("unable to init scan store");
+
+                Arc::new(scan_store)
+            })
+            .clone()
+    }
+
+    /// Returns the store of the evaluations of the scan in progress, to resume it if interrupted.
+    pub fn scan_state(&self) -> Arc<scan_state::Sqlite> {
+        self.scan_state
+            .get(|| {
+                let scan_state = scan_state::Sqlite::new(self.database_pool());
+                scan_state.init().expect("unable to init scan state");
+
+                Arc::new(scan_state)
+            })
+            .clone()
+    }
+
+    /// Hashes the paths and the contents of the lock files to scan, so that a scan is only
+    /// resumed when the lock files didn't change since it was interrupted.
+    pub fn lock_files_hash(paths: &[String]) -> Result<String> {
+        // FNV-1a, which unlike the hasher of the standard library is stable across Rust versions.
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for lock_file_name in find_lock_files(paths)? {
+            let contents = std::fs::read(&lock_file_name)
+                .with_context(|| format!("failed to read lock file: {lock_file_name}"))?;
+            for byte in lock_file_name.bytes().chain([0]).chain(contents) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+
+        Ok(format!("{hash:016x}"))
     }
 
     fn commit_store(&self) -> Arc<dyn CommitStore> {
+        self.sqlite_commit_store()
+    }
+
+    fn issue_store(&self) -> Arc<dyn IssueStore> {
+        self.sqlite_issue_store()
+    }
+
+    fn vulnerability_store(&self) -> Arc<dyn VulnerabilityStore> {
+        self.sqlite_vulnerability_store()
+    }
+
+    fn sqlite_commit_store(&self) -> Arc<commit_store::Sqlite> {
         self.commit_store
             .get(|| {
-                let connection =
-                    rusqlite::Connection::open("dean.db3").expect("unable to open dean.db3");
-                let commit_store = commit_store::Sqlite::new(std::sync::Mutex::new(connection));
+                let commit_store = commit_store::Sqlite::new(
+                    self.database_pool(),
+                    self.config.cache.ttl_in_seconds,
+                    Box::new(Clock {}),
+                );
                 commit_store.init().expect("unable to init commit store");
 
                 Arc::new(commit_store)
@@ -251,32 +907,149 @@ impl Factory {
             .clone()
     }
 
-    fn issue_store(&self) -> Arc<dyn IssueStore> {
+    fn sqlite_issue_store(&self) -> Arc<issue_store::Sqlite> {
         self.issue_store
             .get(|| {
-                let connection =
-                    rusqlite::Connection::open("dean.db3").expect("unable to open dean.db3");
-                let issue_store = issue_store::Sqlite::new(std::sync::Mutex::new(connection));
+                let issue_store = issue_store::Sqlite::new(
+                    self.database_pool(),
+                    self.config.cache.ttl_in_seconds,
+                    Box::new(Clock {}),
+                );
                 issue_store.init().expect("unable to init issue store");
 
                 Arc::new(issue_store)
             })
             .clone()
     }
+
+    fn sqlite_vulnerability_store(&self) -> Arc<vulnerability_store::Sqlite> {
+        self.vulnerability_store
+            .get(|| {
+                let vulnerability_store = vulnerability_store::Sqlite::new(
+                    self.database_pool(),
+                    self.config.cache.ttl_in_seconds,
+                    Box::new(Clock {}),
+                );
+                vulnerability_store
+                    .init()
+                    .expect("unable to init vulnerability store");
+
+                Arc::new(vulnerability_store)
+            })
+            .clone()
+    }
 }
 
 impl Factory {
     pub fn new(config: Rc<Config>) -> Self {
         Self {
             config,
+            clone_depth: DEFAULT_CLONE_DEPTH,
+            offline: false,
+            max_retries: DEFAULT_MAX_RETRIES,
+            cache_dir: Self::default_cache_dir(),
+            no_cache: false,
+            timings: None,
+            as_of: None,
 
-            info_retriever: Lazy::new(),
+            info_retrievers: RefCell::new(HashMap::new()),
             http_client: Lazy::new(),
             repository_retriever: Lazy::new(),
             contribution_retriever: Lazy::new(),
+            repository_status_retriever: Lazy::new(),
+            vulnerability_retriever: Lazy::new(),
+            advisory_retriever: Lazy::new(),
             github_client: Lazy::new(),
+            gitlab_client: Lazy::new(),
+            database_pool: Lazy::new(),
             commit_store: Lazy::new(),
             issue_store: Lazy::new(),
+            vulnerability_store: Lazy::new(),
+            scan_store: Lazy::new(),
+            scan_state: Lazy::new(),
+        }
+    }
+
+    /// Sets the depth of the repository clones. It must be called before the repository retriever
+    /// is first built.
+    pub fn set_clone_depth(&mut self, clone_depth: u32) {
+        self.clone_depth = clone_depth;
+    }
+
+    /// Avoids requesting the package registries. It must be called before the info retriever is
+    /// first built.
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Sets the directory of the database of the caches and the scan history. It must be called
+    /// before any of the stores is first built.
+    pub fn set_cache_dir(&mut self, cache_dir: PathBuf) {
+        self.cache_dir = cache_dir;
+    }
+
+    /// Keeps the database of the caches and the scan history in memory, so nothing is read from
+    /// or written to the cache directory. It must be called before any of the stores is first
+    /// built.
+    pub fn set_no_cache(&mut self, no_cache: bool) {
+        self.no_cache = no_cache;
+    }
+
+    /// Records the durations of the clones, the issue retrievals and the evaluations in the
+    /// timings. It must be called before the retrievers and the engine are first built.
+    pub fn set_timings(&mut self, timings: Arc<Timings>) {
+        self.timings = Some(timings);
+    }
+
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.max_retries = max_retries;
+    }
+
+    /// Evaluates the repositories as they were at the timestamp, in seconds since the epoch. It
+    /// must be called before the repository retriever and the policies are first built.
+    pub fn set_as_of(&mut self, as_of: u64) {
+        self.as_of = Some(as_of);
+    }
+}
+
+/// Expands the paths into the lock files to scan: files are kept as they are and directories
+/// are walked looking for the lock files of the supported package managers.
+fn find_lock_files(paths: &[String]) -> Result<Vec<String>> {
+    let mut lock_files = Vec::new();
+    for path in paths {
+        if Path::new(path).is_dir() {
+            walk_lock_files(Path::new(path), &mut lock_files)?;
+        } else {
+            lock_files.push(path.clone());
+        }
+    }
+
+    if lock_files.is_empty() {
+        return Err(anyhow!("no lock files found in {}", paths.join(", ")));
+    }
+    Ok(lock_files)
+}
+
+fn walk_lock_files(directory: &Path, lock_files: &mut Vec<String>) -> Result<()> {
+    let mut entries = std::fs::read_dir(directory)
+        .with_context(|| format!("failed to read directory: {}", directory.display()))?
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("failed to read directory: {}", directory.display()))?;
+    entries.sort_by_key(std::fs::DirEntry::file_name);
+
+    for entry in entries {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !IGNORED_DIRECTORIES.contains(&file_name.as_str()) {
+                walk_lock_files(&path, lock_files)?;
+            }
+        } else if PackageManager::from_filename(&file_name)
+            .is_some_and(|package_manager| !package_manager.is_manifest())
+        {
+            lock_files.push(path.to_string_lossy().to_string());
         }
     }
+
+    Ok(())
 }
@@ -0,0 +1,163 @@
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::ValueRef;
+use serde_json::{Map, Value};
+
+/// Pool of connections to the database of the caches, so that the stores read it in parallel.
+pub type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// Number of connections of the pools of the database files.
+const MAX_CONNECTIONS: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableStats {
+    pub table: String,
+    pub rows: usize,
+    pub expired_rows: usize,
+}
+
+/// A persistent store that caches the data retrieved from remote sources and can be inspected
+/// and emptied from the command line.
+pub trait CacheStore: Send + Sync {
+    fn clear(&self) -> Result<(), Box<dyn Error>>;
+    fn stats(&self) -> Result<Vec<TableStats>, Box<dyn Error>>;
+    /// Returns every cached row, including the expired ones, as a JSON object with the name of
+    /// its table.
+    fn export(&self) -> Result<Vec<Value>, Box<dyn Error>>;
+}
+
+/// Opens a pool of connections to the database file, each of them set up for concurrent access.
+pub fn file_pool(path: &Path, busy_timeout: Duration) -> Result<Pool, r2d2::Error> {
+    let manager = SqliteConnectionManager::file(path)
+        .with_init(move |connection| enable_concurrent_access(connection, busy_timeout));
+    r2d2::Pool::builder()
+        .max_size(MAX_CONNECTIONS)
+        .build(manager)
+}
+
+/// Opens a pool of a single in-memory connection, as every in-memory connection is a database of
+/// its own. The connection is never recycled, as its data would go with it.
+pub fn in_memory_pool() -> Result<Pool, r2d2::Error> {
+    r2d2::Pool::builder()
+        .max_size(1)
+        .max_lifetime(None)
+        .idle_timeout(None)
+        .build(SqliteConnectionManager::memory())
+}
+
+/// Switches the database to WAL mode, so that the readers don't block each other nor wait for the
+/// writer. The mode is kept in the database file, and the in-memory databases ignore it.
+pub fn enable_wal(connection: &rusqlite::Connection) -> rusqlite::Result<()> {
+    connection.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))
+}
+
+/// Lets the connection share the database file with the other connections of this and other dean
+/// processes: on top of the WAL mode, it waits up to the busy timeout for the writer holding the
+/// lock instead of failing with `database is locked`. The timeout is per connection, so it must be
+/// set on each of them.
+pub fn enable_concurrent_access(
+    connection: &rusqlite::Connection,
+    busy_timeout: Duration,
+) -> rusqlite::Result<()> {
+    enable_wal(connection)?;
+    connection.busy_timeout(busy_timeout)
+}
+
+/// Adds the `fetched_at` column to tables created before entries had an expiration. Existing
+/// rows get a timestamp of 0, so they are considered expired and fetched again.
+pub fn add_fetched_at_column_if_missing(
+    connection: &rusqlite::Connection,
+    table: &str,
+) -> rusqlite::Result<()> {
+    if connection
+        .prepare(&format!("SELECT fetched_at FROM {table} LIMIT 0"))
+        .is_ok()
+    {
+        return Ok(());
+    }
+
+    connection.execute_batch(&format!(
+        "ALTER TABLE {table} ADD COLUMN fetched_at INTEGER NOT NULL DEFAULT 0"
+    ))
+}
+
+pub fn clear_tables(connection: &rusqlite::Connection, tables: &[&str]) -> rusqlite::Result<()> {
+    for table in tables {
+        connection.execute(&format!("DELETE FROM {table}"), [])?;
+    }
+    Ok(())
+}
+
+pub fn table_stats(
+    connection: &rusqlite::Connection,
+    tables: &[&str],
+    oldest_valid_timestamp: u64,
+) -> rusqlite::Result<Vec<TableStats>> {
+    tables
+        .iter()
+        .map(|table| {
+            let (rows, expired_rows) = connection.query_row(
+                &format!(
+                    "SELECT COUNT(*), COUNT(CASE WHEN fetched_at < ?1 THEN 1 END) FROM {table}"
+                ),
+                [oldest_valid_timestamp],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+
+            Ok(TableStats {
+                table: (*table).to_string(),
+                rows,
+                expired_rows,
+            })
+        })
+        .collect()
+}
+
+/// Reads every row of the tables as a JSON object of its columns and its table, ordered by the
+/// first columns, which identify the repository or the package it belongs to. The `json_columns`
+/// hold serialized JSON, which is embedded instead of exported as a string.
+pub fn export_tables(
+    connection: &rusqlite::Connection,
+    tables: &[&str],
+    json_columns: &[&str],
+) -> rusqlite::Result<Vec<Value>> {
+    let mut rows = Vec::new();
+    for table in tables {
+        let mut statement = connection.prepare(&format!("SELECT * FROM {table} ORDER BY 1, 2"))?;
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+        let table_rows = statement.query_map([], |row| {
+            let mut object = Map::new();
+            object.insert("table".to_string(), Value::String((*table).to_string()));
+            for (index, column) in columns.iter().enumerate() {
+                let value = match row.get_ref(index)? {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Integer(integer) => Value::from(integer),
+                    ValueRef::Real(real) => Value::from(real),
+                    ValueRef::Text(text) => {
+                        let text = String::from_utf8_lossy(text);
+                        if json_columns.contains(&column.as_str()) {
+                            serde_json::from_str(&text)
+                                .unwrap_or_else(|_| Value::String(text.into_owned()))
+                        } else {
+                            Value::String(text.into_owned())
+                        }
+                    }
+                    ValueRef::Blob(blob) => Value::String(String::from_utf8_lossy(blob).into()),
+                };
+                object.insert(column.clone(), value);
+            }
+            Ok(Value::Object(object))
+        })?;
+        for row in table_rows {
+            rows.push(row?);
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,327 @@
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use moka::future::{Cache, CacheBuilder};
+
+use crate::pkg::{InfoRetriever, PackageInfo, PackageNotFound, Repository};
+use crate::Result;
+
+pub struct CachedInfoRetriever {
+    inner: Arc<dyn InfoRetriever>,
+    latest_version_cache: Cache<String, String>,
+    repository_cache: Cache<String, Repository>,
+    info_cache: Cache<String, PackageInfo>,
+    deprecation_cache: Cache<(String, String), Option<String>>,
+    downloads_cache: Cache<String, Option<u64>>,
+    weekly_downloads_cache: Cache<String, Option<u64>>,
+    /// Packages the registry answered it doesn't have, which are not requested again.
+    not_found_cache: Cache<String, ()>,
+}
+
+impl CachedInfoRetriever {
+    pub fn new<R>(inner: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            inner: inner.into(),
+            latest_version_cache: CacheBuilder::default().build(),
+            repository_cache: CacheBuilder::default().build(),
+            info_cache: CacheBuilder::default().build(),
+            deprecation_cache: CacheBuilder::default().build(),
+            downloads_cache: CacheBuilder::default().build(),
+            weekly_downloads_cache: CacheBuilder::default().build(),
+            not_found_cache: CacheBuilder::default().build(),
+        }
+    }
+
+    /// Retrieves the value from the cache, or initializes it unless the registry already answered
+    /// that it doesn't have the dependency. Only the `PackageNotFound` errors are remembered, so
+    /// that the transient failures are retried on the next call.
+    async fn try_get_with<K, V, F>(
+        &self,
+        cache: &Cache<K, V>,
+        dependency: &str,
+        key: K,
+        init: F,
+    ) -> Result<V>
+    where
+        K: Hash + Eq + Send + Sync + 'static,
+        V: Clone + Send + Sync + 'static,
+        F: Future<Output = Result<V>>,
+    {
+        if self.not_found_cache.contains_key(dependency) {
+            return Err(anyhow::Error::new(PackageNotFound {
+                package_name: dependency.to_string(),
+            }));
+        }
+
+        match cache.try_get_with(key, init).await {
+            Ok(value) => Ok(value),
+            Err(e) => match e.downcast_ref::<PackageNotFound>() {
+                Some(not_found) => {
+                    self.not_found_cache
+                        .insert(dependency.to_string(), ())
+                        .await;
+                    Err(anyhow::Error::new(not_found.clone()))
+                }
+                None => Err(anyhow!(e)),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl InfoRetriever for CachedInfoRetriever {
+    async fn latest_version(&self, dependency: &str) -> Result<String> {
+        self.try_get_with(
+            &self.latest_version_cache,
+            dependency,
+            dependency.to_string(),
+            self.inner.latest_version(dependency),
+        )
+        .await
+    }
+
+    async fn repository(&self, dependency: &str) -> Result<Repository> {
+        self.try_get_with(
+            &self.repository_cache,
+            dependency,
+            dependency.to_string(),
+            self.inner.repository(dependency),
+        )
+        .await
+    }
+
+    async fn info(&self, dependency: &str) -> Result<PackageInfo> {
+        self.try_get_with(
+            &self.info_cache,
+            dependency,
+            dependency.to_string(),
+            self.inner.info(dependency),
+        )
+        .await
+    }
+
+    async fn deprecation(&self, dependency: &str, version: &str) -> Result<Option<String>> {
+        self.try_get_with(
+            &self.deprecation_cache,
+            dependency,
+            (dependency.to_string(), version.to_string()),
+            self.inner.deprecation(dependency, version),
+        )
+        .await
+    }
+
+    async fn downloads(&self, dependency: &str) -> Result<Option<u64>> {
+        self.try_get_with(
+            &self.downloads_cache,
+            dependency,
+            dependency.to_string(),
+            self.inner.downloads(dependency),
+        )
+        .await
+    }
+
+    async fn weekly_downloads(&self, dependency: &str) -> Result<Option<u64>> {
+        self.try_get_with(
+            &self.weekly_downloads_cache,
+            dependency,
+            dependency.to_string(),
+            self.inner.weekly_downloads(dependency),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::pkg::MockInfoRetriever;
+
+    #[tokio::test]
+    async fn it_retrieves_the_latest_version_exactly_once_per_dependency() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_latest_version()
+                .with(eq("serde"))
+                .once()
+                .return_once(|_| Ok("1.0.138".into()));
+            retriever
+        };
+        let cached_retriever = CachedInfoRetriever::new(retriever);
+
+        let first_call = cached_retriever.latest_version("serde").await.unwrap();
+        let second_call = cached_retriever.latest_version("serde").await.unwrap();
+
+        assert_eq!(first_call, "1.0.138");
+        assert_eq!(second_call, "1.0.138");
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_repository_exactly_once_per_dependency() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_repository()
+                .with(eq("serde"))
+                .once()
+                .return_once(|_| {
+                    Ok(Repository::GitHub {
+                        organization: "serde-rs".into(),
+                        name: "serde".into(),
+                    })
+                });
+            retriever
+        };
+        let cached_retriever = CachedInfoRetriever::new(retriever);
+
+        let first_call = cached_retriever.repository("serde").await.unwrap();
+        let second_call = cached_retriever.repository("serde").await.unwrap();
+
+        assert_eq!(first_call, second_call);
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_info_exactly_once_per_dependency() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .with(eq("serde"))
+                .once()
+                .return_once(|_| {
+                    Ok(PackageInfo {
+                        latest_version: Some("1.0.138".into()),
+                        repository: Repository::GitHub {
+                            organization: "serde-rs".into(),
+                            name: "serde".into(),
+                        },
+                        repository_directory: None,
+                    })
+                });
+            retriever
+        };
+        let cached_retriever = CachedInfoRetriever::new(retriever);
+
+        let first_call = cached_retriever.info("serde").await.unwrap();
+        let second_call = cached_retriever.info("serde").await.unwrap();
+
+        assert_eq!(first_call, second_call);
+    }
+
+    #[tokio::test]
+    async fn it_does_not_cache_the_errors() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_latest_version()
+                .times(2)
+                .returning(|_| Err(anyhow!("registry unavailable")));
+            retriever
+        };
+        let cached_retriever = CachedInfoRetriever::new(retriever);
+
+        assert!(cached_retriever.latest_version("serde").await.is_err());
+        assert!(cached_retriever.latest_version("serde").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_does_not_request_again_the_dependencies_missing_from_the_registry() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_latest_version()
+                .with(eq("missing"))
+                .once()
+                .returning(|dependency| {
+                    Err(anyhow::Error::new(PackageNotFound {
+                        package_name: dependency.to_string(),
+                    }))
+                });
+            retriever
+        };
+        let cached_retriever = CachedInfoRetriever::new(retriever);
+
+        let first_call = cached_retriever.latest_version("missing").await;
+        let second_call = cached_retriever.latest_version("missing").await;
+        let repository = cached_retriever.repository("missing").await;
+
+        for result in [
+            first_call.map(|_| ()),
+            second_call.map(|_| ()),
+            repository.map(|_| ()),
+        ] {
+            assert_eq!(
+                result.unwrap_err().downcast_ref::<PackageNotFound>(),
+                Some(&PackageNotFound {
+                    package_name: "missing".to_string()
+                })
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn it_retrieves_each_dependency_once_under_concurrent_calls() {
+        let retriever = Arc::new(CountingRetriever::default());
+        let cached_retriever = Arc::new(CachedInfoRetriever::new(
+            retriever.clone() as Arc<dyn InfoRetriever>
+        ));
+
+        let calls = (0..500).map(|i| {
+            let cached_retriever = cached_retriever.clone();
+            // The first 400 calls share 10 dependencies, and the last 100 ones are distinct.
+            let dependency = if i < 400 {
+                format!("dep{}", i % 10)
+            } else {
+                format!("dep{i}")
+            };
+            tokio::spawn(async move {
+                let latest_version = cached_retriever.latest_version(&dependency).await;
+                (dependency, latest_version)
+            })
+        });
+
+        for result in futures::future::join_all(calls).await {
+            let (dependency, latest_version) = result.unwrap();
+            assert_eq!(latest_version.unwrap(), format!("{dependency}-1.0.0"));
+        }
+        let calls_per_dependency = retriever.calls.lock().unwrap();
+        assert_eq!(calls_per_dependency.len(), 110);
+        assert!(calls_per_dependency.values().all(|calls| *calls == 1));
+    }
+
+    /// Counts the calls per dependency, taking some time to answer so that the calls overlap.
+    #[derive(Default)]
+    struct CountingRetriever {
+        calls: std::sync::Mutex<std::collections::HashMap<String, usize>>,
+    }
+
+    #[async_trait]
+    impl InfoRetriever for CountingRetriever {
+        async fn latest_version(&self, dependency: &str) -> Result<String> {
+            *self
+                .calls
+                .lock()
+                .unwrap()
+                .entry(dependency.to_string())
+                .or_default() += 1;
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            Ok(format!("{dependency}-1.0.0"))
+        }
+
+        async fn repository(&self, _dependency: &str) -> Result<Repository> {
+            Ok(Repository::Unknown)
+        }
+
+        async fn deprecation(&self, _dependency: &str, _version: &str) -> Result<Option<String>> {
+            Ok(None)
+        }
+    }
+}
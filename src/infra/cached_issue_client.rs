@@ -5,24 +5,78 @@ use async_trait::async_trait;
 use log::error;
 use moka::future::{Cache, CacheBuilder};
 use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 use tokio::pin;
 use tokio_stream::{Stream, StreamExt};
 
+use crate::infra::clock::Clock;
+use crate::pkg::policy::{self, IssueWindow};
+use crate::pkg::timings::{Phase, Timings};
+
+const DAYS_TO_SECONDS: u64 = 24 * 60 * 60;
+
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait IssueClient: Send + Sync {
+    /// Retrieves the issues in the window, the most recently created first.
     async fn get_last_issues(
         &self,
         organization: &str,
         repo: &str,
+        window: IssueWindow,
     ) -> Box<dyn Stream<Item = Value> + Unpin + Send>;
+    /// Retrieves the pull requests in the window, the most recently created first.
     async fn get_last_pull_requests(
         &self,
         organization: &str,
         repo: &str,
+        window: IssueWindow,
     ) -> Box<dyn Stream<Item = Value> + Unpin + Send>;
 }
 
+/// Stops the stream of issues or pull requests, the most recently created first, at the first one
+/// out of the window, so that the following pages are not requested.
+pub fn take_window<S>(
+    stream: S,
+    window: IssueWindow,
+) -> Box<dyn Stream<Item = Value> + Unpin + Send>
+where
+    S: Stream<Item = Value> + Send + 'static,
+{
+    take_window_at(
+        stream,
+        window,
+        policy::Clock::now_timestamp(&Clock::default()),
+    )
+}
+
+fn take_window_at<S>(
+    stream: S,
+    window: IssueWindow,
+    now_timestamp: u64,
+) -> Box<dyn Stream<Item = Value> + Unpin + Send>
+where
+    S: Stream<Item = Value> + Send + 'static,
+{
+    match window {
+        IssueWindow::Last(count) => Box::new(Box::pin(stream.take(count))),
+        IssueWindow::Days(days) => {
+            let oldest_timestamp = now_timestamp.saturating_sub(days * DAYS_TO_SECONDS);
+            Box::new(Box::pin(stream.take_while(move |issue_or_pr| {
+                created_at_timestamp(issue_or_pr)
+                    .is_some_and(|created_at| created_at >= oldest_timestamp)
+            })))
+        }
+    }
+}
+
+fn created_at_timestamp(issue_or_pr: &Value) -> Option<u64> {
+    let created_at = issue_or_pr.get("created_at")?.as_str()?;
+    let created_at = OffsetDateTime::parse(created_at, &Rfc3339).ok()?;
+    u64::try_from(created_at.unix_timestamp()).ok()
+}
+
 #[cfg_attr(test, mockall::automock)]
 pub trait IssueStore: Send + Sync {
     fn get_issues(&self, provider: &str, organization: &str, repo: &str) -> Option<Vec<Value>>;
@@ -60,6 +114,7 @@ pub struct CachedClient {
     store: Arc<dyn IssueStore>,
     issue_cache: Cache<CacheKey, Vec<Value>>,
     pull_request_cache: Cache<CacheKey, Vec<Value>>,
+    timings: Option<Arc<Timings>>,
 }
 
 impl CachedClient {
@@ -74,14 +129,22 @@ impl CachedClient {
             store: store.into(),
             issue_cache: CacheBuilder::default().build(),
             pull_request_cache: CacheBuilder::default().build(),
+            timings: None,
         }
     }
 
+    /// Records the durations of the retrievals of the issues and pull requests that are not in
+    /// the store.
+    pub fn with_timings(mut self, timings: Arc<Timings>) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
     pub async fn get_last_issues(
         &self,
         organization: &str,
         repo: &str,
-        last_issues: usize,
+        window: IssueWindow,
     ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
         let key = CacheKey {
             organization: organization.to_string(),
@@ -93,16 +156,21 @@ impl CachedClient {
                 return Ok(issues);
             }
 
-            let issues = self
-                .inner
-                .get_last_issues(organization, repo)
-                .await
-                .take(last_issues);
-            pin!(issues);
-            let mut issue_vec: Vec<_> = Vec::new();
-            while let Some(issue) = issues.next().await {
-                issue_vec.push(issue);
-            }
+            let issue_vec = Timings::measure(
+                self.timings.as_deref(),
+                Phase::IssueFetch,
+                &format!("{organization}/{repo}"),
+                async {
+                    let issues = self.inner.get_last_issues(organization, repo, window).await;
+                    pin!(issues);
+                    let mut issue_vec: Vec<_> = Vec::new();
+                    while let Some(issue) = issues.next().await {
+                        issue_vec.push(issue);
+                    }
+                    issue_vec
+                },
+            )
+            .await;
 
             match self
                 .store
@@ -129,7 +197,7 @@ impl CachedClient {
         &self,
         organization: &str,
         repo: &str,
-        last_pull_requests: usize,
+        window: IssueWindow,
     ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
         let key = CacheKey {
             organization: organization.to_string(),
@@ -144,15 +212,23 @@ impl CachedClient {
                 return Ok(pull_requests);
             }
 
-            let mut pull_requests = self
-                .inner
-                .get_last_pull_requests(organization, repo)
-                .await
-                .take(last_pull_requests);
-            let mut pull_request_vec = Vec::new();
-            while let Some(pull_request) = pull_requests.next().await {
-                pull_request_vec.push(pull_request);
-            }
+            let pull_request_vec = Timings::measure(
+                self.timings.as_deref(),
+                Phase::IssueFetch,
+                &format!("{organization}/{repo}"),
+                async {
+                    let mut pull_requests = self
+                        .inner
+                        .get_last_pull_requests(organization, repo, window)
+                        .await;
+                    let mut pull_request_vec = Vec::new();
+                    while let Some(pull_request) = pull_requests.next().await {
+                        pull_request_vec.push(pull_request);
+                    }
+                    pull_request_vec
+                },
+            )
+            .await;
 
             match self.store.save_pull_requests(
                 &self.provider,
@@ -181,6 +257,7 @@ impl CachedClient {
 #[cfg(test)]
 mod tests {
     use futures::StreamExt;
+    use mockall::predicate::eq;
 
     use super::*;
 
@@ -199,7 +276,8 @@ mod tests {
             let mut issue_client = Box::new(MockIssueClient::new());
             issue_client
                 .expect_get_last_issues()
-                .return_once(|_, _| Box::new(tokio_stream::iter(issues_in_repo())))
+                .with(eq("some_org"), eq("some_repo"), eq(IssueWindow::Last(10)))
+                .return_once(|_, _, _| Box::new(tokio_stream::iter(issues_in_repo())))
                 .once();
             issue_client
         };
@@ -207,12 +285,12 @@ mod tests {
         let cached_client = CachedClient::new("github", issue_client, issue_store);
 
         let first_call_issues = cached_client
-            .get_last_issues("some_org", "some_repo", 10)
+            .get_last_issues("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
         let second_call_issues = cached_client
-            .get_last_issues("some_org", "some_repo", 10)
+            .get_last_issues("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
@@ -236,12 +314,12 @@ mod tests {
         let cached_client = CachedClient::new("github", issue_client, issue_store);
 
         let first_call_issues = cached_client
-            .get_last_issues("some_org", "some_repo", 10)
+            .get_last_issues("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
         let second_call_issues = cached_client
-            .get_last_issues("some_org", "some_repo", 10)
+            .get_last_issues("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
@@ -268,7 +346,8 @@ mod tests {
             let mut issue_client = Box::new(MockIssueClient::new());
             issue_client
                 .expect_get_last_pull_requests()
-                .return_once(|_, _| Box::new(tokio_stream::iter(pull_requests_in_repo())))
+                .with(eq("some_org"), eq("some_repo"), eq(IssueWindow::Last(10)))
+                .return_once(|_, _, _| Box::new(tokio_stream::iter(pull_requests_in_repo())))
                 .once();
             issue_client
         };
@@ -276,12 +355,12 @@ mod tests {
         let cached_client = CachedClient::new("github", issue_client, issue_store);
 
         let first_call_pull_requests = cached_client
-            .get_pull_requests("some_org", "some_repo", 10)
+            .get_pull_requests("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
         let second_call_pull_requests = cached_client
-            .get_pull_requests("some_org", "some_repo", 10)
+            .get_pull_requests("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
@@ -306,12 +385,12 @@ mod tests {
         let cached_client = CachedClient::new("github", issue_client, issue_store);
 
         let first_call_pull_requests = cached_client
-            .get_pull_requests("some_org", "some_repo", 10)
+            .get_pull_requests("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
         let second_call_pull_requests = cached_client
-            .get_pull_requests("some_org", "some_repo", 10)
+            .get_pull_requests("some_org", "some_repo", IssueWindow::Last(10))
             .await
             .collect::<Vec<_>>()
             .await;
@@ -320,6 +399,30 @@ mod tests {
         assert!(second_call_pull_requests.eq(&pull_requests_in_repo()));
     }
 
+    #[tokio::test]
+    async fn it_takes_the_issues_created_in_the_days_of_the_window() {
+        let issues = vec![
+            serde_json::json!({"created_at": "2022-03-29T10:00:00Z"}),
+            serde_json::json!({"created_at": "2022-03-20T10:00:00Z"}),
+            serde_json::json!({"created_at": "2022-02-01T10:00:00Z"}),
+            serde_json::json!({"created_at": "2022-03-25T10:00:00Z"}),
+        ];
+        let now = OffsetDateTime::parse("2022-03-30T00:00:00Z", &Rfc3339)
+            .unwrap()
+            .unix_timestamp()
+            .unsigned_abs();
+
+        let issues_in_window = take_window_at(
+            tokio_stream::iter(issues.clone()),
+            IssueWindow::Days(30),
+            now,
+        )
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(issues_in_window, issues[..2]);
+    }
+
     fn pull_requests_in_repo() -> Vec<Value> {
         vec![
             Value::String("pull_request_1".to_string()),
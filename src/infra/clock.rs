@@ -13,3 +13,22 @@ impl policy::Clock for Clock {
             .as_secs()
     }
 }
+
+/// Clock stopped at a timestamp, so that the ages the policies measure are the ones they measured
+/// at that time.
+#[derive(Copy, Clone)]
+pub struct FixedClock {
+    timestamp: u64,
+}
+
+impl FixedClock {
+    pub fn new(timestamp: u64) -> Self {
+        Self { timestamp }
+    }
+}
+
+impl policy::Clock for FixedClock {
+    fn now_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
@@ -1,16 +1,25 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::sync::Arc;
-use std::sync::Mutex;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use serde_json::Value;
 
+use crate::infra::cache_store::{self, CacheStore, Pool, TableStats};
 use crate::infra::git::CommitStore;
-use crate::pkg::policy::{Commit, Tag};
+use crate::pkg::policy::{self, Commit, Tag};
+
+const TABLES: [&str; 4] = [
+    "commitstore_tags",
+    "commitstore_commits_for_each_tag",
+    "commitstore_latest_commit",
+    "commitstore_earliest_commit",
+];
 
 pub struct Sqlite {
-    db: Arc<Mutex<rusqlite::Connection>>,
+    pool: Pool,
+    ttl_in_seconds: u64,
+    clock: Box<dyn policy::Clock>,
 }
 
 #[async_trait]
@@ -19,21 +28,22 @@ impl CommitStore for Sqlite {
         &self,
         repository_url: &str,
     ) -> Option<HashMap<String, Vec<Commit>>> {
-        let connection = self.db.clone();
+        let pool = self.pool.clone();
         let repository_url = repository_url.to_string();
+        let oldest_valid_timestamp = self.oldest_valid_timestamp();
 
         tokio::task::spawn_blocking(move || {
-            let lock = connection.lock().ok()?;
+            let lock = pool.get().ok()?;
 
             let mut select_tag_stmt = lock.prepare(
-                "SELECT tag FROM commitstore_commits_for_each_tag WHERE repository = ? GROUP BY tag",
+                "SELECT tag FROM commitstore_commits_for_each_tag WHERE repository = ? AND fetched_at >= ? GROUP BY tag",
             ).ok()?;
             let mut select_commit_info_stmt = lock.prepare(
                 "SELECT commit_id, commit_author_email, commit_author_name, commit_creation_timestamp FROM commitstore_commits_for_each_tag WHERE repository = ? AND tag = ?",
             ).ok()?;
 
             let tags = select_tag_stmt
-                .query_map([&repository_url], |row| {
+                .query_map(rusqlite::params![&repository_url, oldest_valid_timestamp], |row| {
                     let tag: String = row.get(0)?;
                     Ok(tag)
                 })
@@ -77,18 +87,24 @@ impl CommitStore for Sqlite {
         repository_url: &str,
         commits_for_each_tag: &HashMap<String, Vec<Commit>>,
     ) -> Result<(), Box<dyn Error>> {
-        let connection = self.db.clone();
+        let pool = self.pool.clone();
         let repository_url = repository_url.to_string();
         let commits_for_each_tag = commits_for_each_tag.clone();
+        let fetched_at = self.now_timestamp();
 
         let result: Result<(), anyhow::Error> = tokio::task::spawn_blocking(move || {
-            let mut lock = connection.lock().map_err(|e| anyhow!("unable to lock the database: {}", e))?;
+            let mut lock = pool.get().map_err(|e| anyhow!("unable to get a connection to the database: {}", e))?;
 
             let tx = lock.transaction()?;
 
             {
+                tx.execute(
+                    "DELETE FROM commitstore_commits_for_each_tag WHERE repository = ?",
+                    [&repository_url],
+                )?;
+
                 let mut stmt = tx.prepare(
-                    "INSERT OR IGNORE INTO commitstore_commits_for_each_tag (repository, tag, commit_id, commit_author_email, commit_author_name, commit_creation_timestamp) VALUES (?, ?, ?, ?, ?, ?)",
+                    "INSERT OR IGNORE INTO commitstore_commits_for_each_tag (repository, tag, commit_id, commit_author_email, commit_author_name, commit_creation_timestamp, fetched_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
                 )?;
 
                 for (tag_name, commits) in commits_for_each_tag {
@@ -100,6 +116,7 @@ impl CommitStore for Sqlite {
                             &commit.author_email,
                             &commit.author_name,
                             &commit.creation_timestamp.to_string(),
+                            &fetched_at.to_string(),
                         ])?;
                     }
                 }
@@ -114,26 +131,29 @@ impl CommitStore for Sqlite {
     }
 
     async fn get_all_tags(&self, repository_url: &str) -> Option<Vec<Tag>> {
-        let connection = self.db.clone();
+        let pool = self.pool.clone();
         let repository_url = repository_url.to_string();
+        let oldest_valid_timestamp = self.oldest_valid_timestamp();
 
         let result = tokio::task::spawn_blocking(move || {
-            let lock = connection.lock().ok()?;
+            let lock = pool.get().ok()?;
 
             let mut stmt = lock
-                .prepare("SELECT name, commit_id, commit_timestamp FROM commitstore_tags WHERE repository = ?")
+                .prepare("SELECT name, commit_id, commit_timestamp, is_signed FROM commitstore_tags WHERE repository = ? AND fetched_at >= ?")
                 .ok()?;
 
             let iter = stmt
-                .query_map([&repository_url], |row| {
+                .query_map(rusqlite::params![&repository_url, oldest_valid_timestamp], |row| {
                     let name: String = row.get(0)?;
                     let commit_id: String = row.get(1)?;
                     let commit_timestamp: u64 = row.get(2)?;
+                    let is_signed: bool = row.get(3)?;
 
                     Ok(Tag {
                         name,
                         commit_id,
                         commit_timestamp,
+                        is_signed,
                     })
                 })
                 .ok()?;
@@ -154,24 +174,29 @@ impl CommitStore for Sqlite {
         repository_url: &str,
         all_tags: &[Tag],
     ) -> Result<(), Box<dyn Error>> {
-        let connection = self.db.clone();
+        let pool = self.pool.clone();
         let repository_url = repository_url.to_string();
         let all_tags = all_tags.to_vec();
+        let fetched_at = self.now_timestamp();
 
         let result: Result<(), anyhow::Error> = tokio::task::spawn_blocking(move || {
-            let mut lock = connection.lock().map_err(|e| anyhow!("unable to lock the database: {}", e))?;
+            let mut lock = pool.get().map_err(|e| anyhow!("unable to get a connection to the database: {}", e))?;
             let tx = lock.transaction()?;
 
             {
+                tx.execute("DELETE FROM commitstore_tags WHERE repository = ?", [&repository_url])?;
+
                 let mut stmt = tx
-                    .prepare("INSERT OR IGNORE INTO commitstore_tags (repository, name, commit_id, commit_timestamp) VALUES (?, ?, ?, ?)")?;
+                    .prepare("INSERT OR IGNORE INTO commitstore_tags (repository, name, commit_id, commit_timestamp, is_signed, fetched_at) VALUES (?, ?, ?, ?, ?, ?)")?;
 
                 for tag in all_tags {
-                    stmt.execute([
+                    stmt.execute(rusqlite::params![
                         repository_url.as_str(),
                         &tag.name,
                         &tag.commit_id,
-                        tag.commit_timestamp.to_string().as_str(),
+                        tag.commit_timestamp,
+                        tag.is_signed,
+                        fetched_at,
                     ])?;
                 }
             }
@@ -183,24 +208,157 @@ impl CommitStore for Sqlite {
 
         result.map_err(std::convert::Into::into)
     }
+
+    async fn get_latest_commit_timestamp(&self, repository_url: &str) -> Option<u64> {
+        let pool = self.pool.clone();
+        let repository_url = repository_url.to_string();
+        let oldest_valid_timestamp = self.oldest_valid_timestamp();
+
+        tokio::task::spawn_blocking(move || {
+            let lock = pool.get().ok()?;
+
+            lock.query_row(
+                "SELECT commit_timestamp FROM commitstore_latest_commit WHERE repository = ? AND fetched_at >= ?",
+                rusqlite::params![&repository_url, oldest_valid_timestamp],
+                |row| row.get(0),
+            )
+            .ok()
+        })
+        .await
+        .ok()?
+    }
+
+    async fn save_latest_commit_timestamp(
+        &self,
+        repository_url: &str,
+        latest_commit_timestamp: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let pool = self.pool.clone();
+        let repository_url = repository_url.to_string();
+        let fetched_at = self.now_timestamp();
+
+        let result: Result<(), anyhow::Error> = tokio::task::spawn_blocking(move || {
+            let lock = pool.get().map_err(|e| anyhow!("unable to get a connection to the database: {}", e))?;
+
+            lock.execute(
+                "INSERT OR REPLACE INTO commitstore_latest_commit (repository, commit_timestamp, fetched_at) VALUES (?, ?, ?)",
+                rusqlite::params![&repository_url, latest_commit_timestamp, fetched_at],
+            )?;
+
+            Ok(())
+        }).await?;
+
+        result.map_err(std::convert::Into::into)
+    }
+
+    async fn get_earliest_commit_timestamp(&self, repository_url: &str) -> Option<u64> {
+        let pool = self.pool.clone();
+        let repository_url = repository_url.to_string();
+        let oldest_valid_timestamp = self.oldest_valid_timestamp();
+
+        tokio::task::spawn_blocking(move || {
+            let lock = pool.get().ok()?;
+
+            lock.query_row(
+                "SELECT commit_timestamp FROM commitstore_earliest_commit WHERE repository = ? AND fetched_at >= ?",
+                rusqlite::params![&repository_url, oldest_valid_timestamp],
+                |row| row.get(0),
+            )
+            .ok()
+        })
+        .await
+        .ok()?
+    }
+
+    async fn save_earliest_commit_timestamp(
+        &self,
+        repository_url: &str,
+        earliest_commit_timestamp: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let pool = self.pool.clone();
+        let repository_url = repository_url.to_string();
+        let fetched_at = self.now_timestamp();
+
+        let result: Result<(), anyhow::Error> = tokio::task::spawn_blocking(move || {
+            let lock = pool.get().map_err(|e| anyhow!("unable to get a connection to the database: {}", e))?;
+
+            lock.execute(
+                "INSERT OR REPLACE INTO commitstore_earliest_commit (repository, commit_timestamp, fetched_at) VALUES (?, ?, ?)",
+                rusqlite::params![&repository_url, earliest_commit_timestamp, fetched_at],
+            )?;
+
+            Ok(())
+        }).await?;
+
+        result.map_err(std::convert::Into::into)
+    }
+}
+
+impl CacheStore for Sqlite {
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        let lock = self.pool.get()?;
+        cache_store::clear_tables(&lock, &TABLES)?;
+        Ok(())
+    }
+
+    fn stats(&self) -> Result<Vec<TableStats>, Box<dyn Error>> {
+        let lock = self.pool.get()?;
+        let stats = cache_store::table_stats(&lock, &TABLES, self.oldest_valid_timestamp())?;
+        Ok(stats)
+    }
+
+    fn export(&self) -> Result<Vec<Value>, Box<dyn Error>> {
+        let lock = self.pool.get()?;
+        let rows = cache_store::export_tables(&lock, &TABLES, &[])?;
+        Ok(rows)
+    }
 }
 
 impl Sqlite {
-    pub fn new<T: Into<Arc<Mutex<rusqlite::Connection>>>>(db: T) -> Self {
-        Self { db: db.into() }
+    pub fn new(pool: Pool, ttl_in_seconds: u64, clock: Box<dyn policy::Clock>) -> Self {
+        Self {
+            pool,
+            ttl_in_seconds,
+            clock,
+        }
+    }
+
+    fn now_timestamp(&self) -> u64 {
+        self.clock.now_timestamp()
+    }
+
+    fn oldest_valid_timestamp(&self) -> u64 {
+        self.now_timestamp().saturating_sub(self.ttl_in_seconds)
+    }
+
+    /// Adds the `is_signed` column to the tags cached before the signatures were stored, expiring
+    /// them so that they are fetched again instead of being considered unsigned.
+    fn add_is_signed_column_if_missing(connection: &rusqlite::Connection) -> rusqlite::Result<()> {
+        if connection
+            .prepare("SELECT is_signed FROM commitstore_tags LIMIT 0")
+            .is_ok()
+        {
+            return Ok(());
+        }
+
+        connection.execute_batch(
+            "ALTER TABLE commitstore_tags ADD COLUMN is_signed INTEGER NOT NULL DEFAULT 0; UPDATE commitstore_tags SET fetched_at = 0;",
+        )
     }
 
     pub fn init(&self) -> Result<(), Box<dyn Error>> {
-        self.db
-            .lock()
-            .map_err(|e| format!("unable to lock the database: {e}"))?
-            .execute_batch(
-                r#"
+        let lock = self.pool.get()?;
+        cache_store::enable_wal(&lock)?;
+
+        lock.execute_batch(
+            r#"
 CREATE TABLE IF NOT EXISTS commitstore_tags (
     repository TEXT NOT NULL,
     name TEXT NOT NULL,
     commit_id TEXT NOT NULL,
     commit_timestamp INTEGER NOT NULL,
+    is_signed INTEGER NOT NULL DEFAULT 0,
+    fetched_at INTEGER NOT NULL DEFAULT 0,
     PRIMARY KEY (repository, name)
 );
 
@@ -211,10 +369,30 @@ CREATE TABLE IF NOT EXISTS commitstore_commits_for_each_tag (
     commit_author_name TEXT NOT NULL,
     commit_author_email TEXT NOT NULL,
     commit_creation_timestamp INTEGER NOT NULL,
+    fetched_at INTEGER NOT NULL DEFAULT 0,
     PRIMARY KEY (repository, tag, commit_id)
 );
+
+CREATE TABLE IF NOT EXISTS commitstore_latest_commit (
+    repository TEXT NOT NULL,
+    commit_timestamp INTEGER NOT NULL,
+    fetched_at INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (repository)
+);
+
+CREATE TABLE IF NOT EXISTS commitstore_earliest_commit (
+    repository TEXT NOT NULL,
+    commit_timestamp INTEGER NOT NULL,
+    fetched_at INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (repository)
+);
                     "#,
-            )?;
+        )?;
+
+        for table in TABLES {
+            cache_store::add_fetched_at_column_if_missing(&lock, table)?;
+        }
+        Self::add_is_signed_column_if_missing(&lock)?;
 
         Ok(())
     }
@@ -222,7 +400,12 @@ CREATE TABLE IF NOT EXISTS commitstore_commits_for_each_tag (
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use super::*;
+    use crate::infra::clock::FixedClock;
+
+    const NOW: u64 = 1_700_000_000;
 
     #[tokio::test]
     async fn it_saves_and_retrieves_all_the_tags() {
@@ -273,9 +456,222 @@ mod tests {
         assert_eq!(commits_for_each_tag, None);
     }
 
+    #[tokio::test]
+    async fn it_saves_and_retrieves_the_latest_commit_timestamp() {
+        let commit_store = commit_store();
+
+        commit_store
+            .save_latest_commit_timestamp("repository", 1_645_905_004)
+            .await
+            .unwrap();
+        let latest_commit_timestamp = commit_store.get_latest_commit_timestamp("repository").await;
+
+        assert_eq!(latest_commit_timestamp, Some(1_645_905_004));
+    }
+
+    #[tokio::test]
+    async fn it_saves_and_retrieves_the_earliest_commit_timestamp() {
+        let commit_store = commit_store();
+
+        commit_store
+            .save_earliest_commit_timestamp("repository", 1_294_920_811)
+            .await
+            .unwrap();
+        let earliest_commit_timestamp = commit_store
+            .get_earliest_commit_timestamp("repository")
+            .await;
+
+        assert_eq!(earliest_commit_timestamp, Some(1_294_920_811));
+    }
+
+    #[tokio::test]
+    async fn if_the_tags_are_expired_it_returns_none() {
+        let commit_store = commit_store();
+
+        commit_store
+            .save_all_tags("repository", &tags_in_repo())
+            .await
+            .unwrap();
+        expire_all_entries(&commit_store);
+        let tags = commit_store.get_all_tags("repository").await;
+
+        assert_eq!(tags, None);
+    }
+
+    #[tokio::test]
+    async fn if_the_commits_are_expired_it_returns_none() {
+        let commit_store = commit_store();
+
+        commit_store
+            .save_commits_for_each_tag("repository", &commits_for_each_tag_in_repo())
+            .await
+            .unwrap();
+        expire_all_entries(&commit_store);
+        let commits_for_each_tag = commit_store.get_commits_for_each_tag("repository").await;
+
+        assert_eq!(commits_for_each_tag, None);
+    }
+
+    #[tokio::test]
+    async fn it_clears_the_stored_tags_and_commits() {
+        let commit_store = commit_store();
+        commit_store
+            .save_all_tags("repository", &tags_in_repo())
+            .await
+            .unwrap();
+        commit_store
+            .save_commits_for_each_tag("repository", &commits_for_each_tag_in_repo())
+            .await
+            .unwrap();
+
+        commit_store.clear().unwrap();
+
+        assert_eq!(
+            commit_store.stats().unwrap(),
+            vec![
+                TableStats {
+                    table: "commitstore_tags".to_string(),
+                    rows: 0,
+                    expired_rows: 0,
+                },
+                TableStats {
+                    table: "commitstore_commits_for_each_tag".to_string(),
+                    rows: 0,
+                    expired_rows: 0,
+                },
+                TableStats {
+                    table: "commitstore_latest_commit".to_string(),
+                    rows: 0,
+                    expired_rows: 0,
+                },
+                TableStats {
+                    table: "commitstore_earliest_commit".to_string(),
+                    rows: 0,
+                    expired_rows: 0,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_exports_the_stored_tags_with_their_repository() {
+        let commit_store = commit_store();
+        commit_store
+            .save_all_tags("repository", &tags_in_repo())
+            .await
+            .unwrap();
+
+        let rows = commit_store.export().unwrap();
+
+        assert_eq!(rows.len(), tags_in_repo().len());
+        assert!(rows
+            .iter()
+            .all(|row| row["table"] == "commitstore_tags" && row["repository"] == "repository"));
+        assert!(rows
+            .iter()
+            .any(|row| row["name"] == tags_in_repo()[0].name.as_str()));
+    }
+
+    #[tokio::test]
+    async fn it_saves_from_two_connections_to_the_same_database_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("dean.db3");
+        let open_store = || {
+            let pool = cache_store::file_pool(&path, Duration::from_secs(5)).unwrap();
+            let commit_store = Sqlite::new(pool, 3600, Box::new(FixedClock::new(NOW)));
+            commit_store.init().unwrap();
+            commit_store
+        };
+        let (first_store, second_store) = (open_store(), open_store());
+
+        futures::join!(
+            save_repositories(&first_store, "first"),
+            save_repositories(&second_store, "second")
+        );
+
+        assert_eq!(
+            first_store.get_commits_for_each_tag("second9").await,
+            Some(commits_for_each_tag_in_repo())
+        );
+        assert_eq!(
+            second_store.get_commits_for_each_tag("first9").await,
+            Some(commits_for_each_tag_in_repo())
+        );
+    }
+
+    async fn save_repositories(commit_store: &Sqlite, prefix: &str) {
+        for repository in 0..10 {
+            commit_store
+                .save_commits_for_each_tag(
+                    &format!("{prefix}{repository}"),
+                    &commits_for_each_tag_in_repo(),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    fn expire_all_entries(commit_store: &Sqlite) {
+        let lock = commit_store.pool.get().unwrap();
+        for table in TABLES {
+            lock.execute(&format!("UPDATE {table} SET fetched_at = 0"), [])
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_expires_the_tags_cached_before_their_signature_was_stored() {
+        let pool = cache_store::in_memory_pool().unwrap();
+        let connection = pool.get().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE commitstore_tags (repository TEXT NOT NULL, name TEXT NOT NULL, commit_id TEXT NOT NULL, commit_timestamp INTEGER NOT NULL, fetched_at INTEGER NOT NULL DEFAULT 0, PRIMARY KEY (repository, name));",
+            )
+            .unwrap();
+        connection
+            .execute(
+                "INSERT INTO commitstore_tags VALUES ('repository', 'v1.0.0', 'commit_id', 1, ?)",
+                [NOW],
+            )
+            .unwrap();
+        drop(connection);
+        let commit_store = Sqlite::new(pool, 3600, Box::new(FixedClock::new(NOW)));
+
+        commit_store.init().unwrap();
+
+        assert_eq!(commit_store.get_all_tags("repository").await, None);
+    }
+
+    #[tokio::test]
+    async fn it_expires_the_tags_once_the_ttl_has_elapsed() {
+        let pool = cache_store::in_memory_pool().unwrap();
+        let commit_store = Sqlite::new(pool.clone(), 3600, Box::new(FixedClock::new(NOW)));
+        commit_store.init().unwrap();
+        commit_store
+            .save_all_tags("repository", &tags_in_repo())
+            .await
+            .unwrap();
+
+        let commit_store_within_ttl =
+            Sqlite::new(pool.clone(), 3600, Box::new(FixedClock::new(NOW + 3600)));
+        let commit_store_after_ttl = Sqlite::new(pool, 3600, Box::new(FixedClock::new(NOW + 3601)));
+
+        assert_eq!(
+            commit_store_within_ttl.get_all_tags("repository").await,
+            Some(tags_in_repo())
+        );
+        assert_eq!(
+            commit_store_after_ttl.get_all_tags("repository").await,
+            None
+        );
+    }
+
     fn commit_store() -> Sqlite {
-        let in_memory_connection = Mutex::new(rusqlite::Connection::open_in_memory().unwrap());
-        let commit_store = Sqlite::new(in_memory_connection);
+        let commit_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
         commit_store.init().unwrap();
         commit_store
     }
@@ -308,11 +704,13 @@ mod tests {
                 name: "v1.0.0".to_string(),
                 commit_id: "commit_id".to_string(),
                 commit_timestamp: 1,
+                is_signed: false,
             },
             Tag {
                 name: "v1.0.1".to_string(),
                 commit_id: "commit_id".to_string(),
                 commit_timestamp: 2,
+                is_signed: true,
             },
         ]
     }
@@ -1,22 +1,84 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::error::Error;
+use std::num::NonZeroUsize;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use futures::future::join_all;
 use futures::stream::FuturesOrdered;
 use futures::StreamExt;
 use git2::Oid;
+use log::{debug, warn};
 use moka::future::{Cache, CacheBuilder};
 use tokio::sync::Mutex;
 
-use crate::pkg::policy::{Commit, CommitRetriever, Tag};
+use crate::infra::http;
+use crate::pkg::policy::{Commit, CommitRetriever, RepositoryUnavailable, Tag};
+use crate::pkg::timings::{Phase, Timings};
 
 #[derive(Clone)]
 struct RepositoryResult {
     commits_for_each_tag: HashMap<String, Vec<Commit>>,
     all_tags: Vec<Tag>,
+    latest_commit_timestamp: u64,
+}
+
+impl RepositoryResult {
+    /// Leaves out the tags and the commits created after the timestamp, as if the repository was
+    /// cloned at that time. The latest commit is approximated from the remaining commits and tags,
+    /// as the untagged commits of the default branch are not retrieved, and kept when none remain.
+    fn at(self, as_of: u64) -> Self {
+        let all_tags = tags_as_of(self.all_tags, as_of);
+        let commits_for_each_tag = commits_as_of(self.commits_for_each_tag, &all_tags, as_of);
+        let latest_commit_timestamp = if self.latest_commit_timestamp <= as_of {
+            self.latest_commit_timestamp
+        } else {
+            commits_for_each_tag
+                .values()
+                .flatten()
+                .filter_map(|commit| u64::try_from(commit.creation_timestamp).ok())
+                .chain(all_tags.iter().map(|tag| tag.commit_timestamp))
+                .max()
+                .unwrap_or(self.latest_commit_timestamp)
+        };
+
+        Self {
+            commits_for_each_tag,
+            all_tags,
+            latest_commit_timestamp,
+        }
+    }
+}
+
+fn tags_as_of(tags: Vec<Tag>, as_of: u64) -> Vec<Tag> {
+    tags.into_iter()
+        .filter(|tag| tag.commit_timestamp <= as_of)
+        .collect()
+}
+
+/// Keeps the commits created on or before the timestamp of the tags that already existed.
+fn commits_as_of(
+    commits_for_each_tag: CommitsForEachTag,
+    tags: &[Tag],
+    as_of: u64,
+) -> CommitsForEachTag {
+    commits_for_each_tag
+        .into_iter()
+        .filter(|(tag, _)| tags.iter().any(|t| &t.name == tag))
+        .map(|(tag, commits)| {
+            let commits = commits
+                .into_iter()
+                .filter(|commit| {
+                    u64::try_from(commit.creation_timestamp)
+                        .map_or(true, |timestamp| timestamp <= as_of)
+                })
+                .collect();
+            (tag, commits)
+        })
+        .collect()
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -38,11 +100,46 @@ pub trait CommitStore: Send + Sync {
         repository_url: &str,
         all_tags: &[Tag],
     ) -> Result<(), Box<dyn Error>>;
+
+    async fn get_latest_commit_timestamp(&self, repository_url: &str) -> Option<u64>;
+    async fn save_latest_commit_timestamp(
+        &self,
+        repository_url: &str,
+        latest_commit_timestamp: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    async fn get_earliest_commit_timestamp(&self, repository_url: &str) -> Option<u64>;
+    async fn save_earliest_commit_timestamp(
+        &self,
+        repository_url: &str,
+        earliest_commit_timestamp: u64,
+    ) -> Result<(), Box<dyn Error>>;
 }
 
+pub const DEFAULT_CLONE_DEPTH: u32 = 100;
+
+/// Program run for the shallow clones, which libgit2 doesn't support.
+const GIT_PROGRAM: &str = "git";
+
+/// Whether the git command line is installed, which is required to clone the repositories with a
+/// depth. Without it, they are fully cloned with libgit2 instead.
+pub fn is_git_installed() -> bool {
+    std::process::Command::new(GIT_PROGRAM)
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+type CommitsForEachTag = HashMap<String, Vec<Commit>>;
+
 pub struct RepositoryRetriever {
-    cache: Cache<String, RepositoryResult>,
+    cache: Cache<String, Result<RepositoryResult, RepositoryUnavailable>>,
+    directory_cache: Cache<(String, String), Result<CommitsForEachTag, RepositoryUnavailable>>,
+    earliest_commit_cache: Cache<String, Result<u64, RepositoryUnavailable>>,
     commit_store: Arc<dyn CommitStore>,
+    clone_depth: u32,
+    timings: Option<Arc<Timings>>,
+    as_of: Option<u64>,
 }
 
 #[async_trait]
@@ -51,117 +148,438 @@ impl CommitRetriever for RepositoryRetriever {
         &self,
         repository_url: &str,
     ) -> Result<HashMap<String, Vec<Commit>>, Box<dyn Error>> {
-        self.cache
+        self.repository_result(repository_url)
+            .await
+            .map(|handle| handle.commits_for_each_tag)
+    }
+
+    async fn commits_for_each_tag_in_directory(
+        &self,
+        repository_url: &str,
+        directory: &str,
+    ) -> Result<HashMap<String, Vec<Commit>>, Box<dyn Error>> {
+        let result = self
+            .directory_cache
             .try_get_with(
-                repository_url.to_string(),
-                self.repository_result_from_url(repository_url),
+                (repository_url.to_string(), directory.to_string()),
+                self.commits_for_each_tag_in_directory_from_url(repository_url, directory),
             )
             .await
-            .map(|handle| handle.commits_for_each_tag)
-            .map_err(|e| anyhow!(e).into())
+            .map_err(|e| anyhow!(e))?;
+        let commits_for_each_tag = result?;
+
+        match self.as_of {
+            Some(as_of) => {
+                let all_tags = self.all_tags(repository_url).await?;
+                Ok(commits_as_of(commits_for_each_tag, &all_tags, as_of))
+            }
+            None => Ok(commits_for_each_tag),
+        }
     }
 
     async fn all_tags(&self, repository_url: &str) -> Result<Vec<Tag>, Box<dyn Error>> {
-        self.cache
+        self.repository_result(repository_url)
+            .await
+            .map(|handle| handle.all_tags)
+    }
+
+    async fn latest_commit_timestamp(&self, repository_url: &str) -> Result<u64, Box<dyn Error>> {
+        self.repository_result(repository_url)
+            .await
+            .map(|handle| handle.latest_commit_timestamp)
+    }
+
+    async fn earliest_commit_timestamp(&self, repository_url: &str) -> Result<u64, Box<dyn Error>> {
+        let result = self
+            .earliest_commit_cache
             .try_get_with(
                 repository_url.to_string(),
-                self.repository_result_from_url(repository_url),
+                self.earliest_commit_timestamp_from_url(repository_url),
             )
             .await
-            .map(|handle| handle.all_tags)
-            .map_err(|e| anyhow!(e).into())
+            .map_err(|e| anyhow!(e))?;
+
+        result.map_err(Into::into)
     }
 }
 
 impl RepositoryRetriever {
-    pub fn new<T: Into<Arc<dyn CommitStore>>>(commit_store: T) -> Self {
+    pub fn new<T: Into<Arc<dyn CommitStore>>>(commit_store: T, clone_depth: u32) -> Self {
         let cache = CacheBuilder::default().build();
         Self {
             cache,
+            directory_cache: CacheBuilder::default().build(),
+            earliest_commit_cache: CacheBuilder::default().build(),
             commit_store: commit_store.into(),
+            clone_depth,
+            timings: None,
+            as_of: None,
+        }
+    }
+
+    /// Retrieves the repositories as they were at the timestamp, so that the audits of a past date
+    /// can be reproduced: the tags and the commits created after it are left out. The cache still
+    /// holds the current state of the repositories.
+    pub fn with_as_of(mut self, as_of: u64) -> Self {
+        self.as_of = Some(as_of);
+        self
+    }
+
+    /// Records the durations of the clones and the tag retrievals of the repositories.
+    pub fn with_timings(mut self, timings: Arc<Timings>) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
+    /// Retrieves the result of the repository, caching the failure to clone it so that it is not
+    /// retried for every dependency sharing the same repository.
+    async fn repository_result(
+        &self,
+        repository_url: &str,
+    ) -> Result<RepositoryResult, Box<dyn Error>> {
+        let result = self
+            .cache
+            .try_get_with(
+                repository_url.to_string(),
+                self.repository_result_from_url(repository_url),
+            )
+            .await
+            .map_err(|e| anyhow!(e))?;
+
+        match self.as_of {
+            Some(as_of) => Ok(result?.at(as_of)),
+            None => result.map_err(Into::into),
         }
     }
 
     async fn repository_result_from_url(
         &self,
         repository_url: &str,
-    ) -> Result<RepositoryResult, anyhow::Error> {
+    ) -> Result<Result<RepositoryResult, RepositoryUnavailable>, anyhow::Error> {
         let commits_for_each_tag = self
             .commit_store
             .get_commits_for_each_tag(repository_url)
             .await;
         let all_tags = self.commit_store.get_all_tags(repository_url).await;
+        let latest_commit_timestamp = self
+            .commit_store
+            .get_latest_commit_timestamp(repository_url)
+            .await;
 
-        if let Some(commits) = &commits_for_each_tag {
-            if let Some(tags) = &all_tags {
-                return Ok(RepositoryResult {
-                    commits_for_each_tag: commits.clone(),
-                    all_tags: tags.clone(),
-                });
-            }
+        if let (Some(commits), Some(tags), Some(latest)) =
+            (&commits_for_each_tag, &all_tags, latest_commit_timestamp)
+        {
+            return Ok(Ok(RepositoryResult {
+                commits_for_each_tag: commits.clone(),
+                all_tags: tags.clone(),
+                latest_commit_timestamp: latest,
+            }));
         }
 
-        let repository = Repository::new(repository_url)
-            .await
-            .map_err(|e| anyhow!("unable to create repository: {}", e))?;
-
-        let (commits_for_each_tag_future, all_tags_future) =
-            futures::join!(repository.commits_for_each_tag(), repository.all_tags());
-
-        let commits_for_each_tag_in_repository = commits_for_each_tag_future
-            .map_err(|e| anyhow!("error retrieving commits for each tag: {}", e))?;
-        let all_tags_in_repository =
-            all_tags_future.map_err(|e| anyhow!("error retrieving tags: {}", e))?;
+        let result = match self.clone_repository(repository_url).await? {
+            Ok(result) => result,
+            Err(unavailable) => return Ok(Err(unavailable)),
+        };
 
         if commits_for_each_tag.is_none() {
-            let commits_for_each_tag = commits_for_each_tag_in_repository.clone();
             self.commit_store
-                .save_commits_for_each_tag(repository_url, &commits_for_each_tag)
+                .save_commits_for_each_tag(repository_url, &result.commits_for_each_tag)
                 .await
                 .map_err(|e| anyhow!("unable to save commits for each tag: {}", e))?;
         }
 
         if all_tags.is_none() {
-            let all_tags = all_tags_in_repository.clone();
             self.commit_store
-                .save_all_tags(repository_url, &all_tags)
+                .save_all_tags(repository_url, &result.all_tags)
                 .await
                 .map_err(|e| anyhow!("unable to save all tags: {}", e))?;
         }
 
-        Ok(RepositoryResult {
-            commits_for_each_tag: commits_for_each_tag_in_repository,
-            all_tags: all_tags_in_repository,
+        if latest_commit_timestamp.is_none() {
+            self.commit_store
+                .save_latest_commit_timestamp(repository_url, result.latest_commit_timestamp)
+                .await
+                .map_err(|e| anyhow!("unable to save the latest commit timestamp: {}", e))?;
+        }
+
+        Ok(Ok(result))
+    }
+
+    /// Retrieves the earliest commit apart from the rest of the repository, as only the
+    /// repositories without tags need it. They are fully cloned for it, as the first commit is
+    /// out of a shallow clone, instead of unshallowing the clone of the other retrievals.
+    async fn earliest_commit_timestamp_from_url(
+        &self,
+        repository_url: &str,
+    ) -> Result<Result<u64, RepositoryUnavailable>, anyhow::Error> {
+        if let Some(earliest_commit_timestamp) = self
+            .commit_store
+            .get_earliest_commit_timestamp(repository_url)
+            .await
+        {
+            return Ok(Ok(earliest_commit_timestamp));
+        }
+
+        let repository = match self.open_repository_with_depth(repository_url, 0).await {
+            Ok(repository) => repository,
+            Err(unavailable) => return Ok(Err(unavailable)),
+        };
+        let earliest_commit_timestamp = repository
+            .earliest_commit_timestamp()
+            .await
+            .map_err(|e| anyhow!("error retrieving the earliest commit: {}", e))?;
+
+        self.commit_store
+            .save_earliest_commit_timestamp(repository_url, earliest_commit_timestamp)
+            .await
+            .map_err(|e| anyhow!("unable to save the earliest commit timestamp: {}", e))?;
+
+        Ok(Ok(earliest_commit_timestamp))
+    }
+
+    /// Clones the repository to retrieve the commits touching the directory, which are not
+    /// stored, as only the packages of monorepos need them.
+    async fn commits_for_each_tag_in_directory_from_url(
+        &self,
+        repository_url: &str,
+        directory: &str,
+    ) -> Result<Result<CommitsForEachTag, RepositoryUnavailable>, anyhow::Error> {
+        let repository = match self.open_repository(repository_url).await {
+            Ok(repository) => repository,
+            Err(unavailable) => return Ok(Err(unavailable)),
+        };
+
+        let commits_for_each_tag = Timings::measure(
+            self.timings.as_deref(),
+            Phase::TagRetrieval,
+            repository_url,
+            repository.commits_for_each_tag_in(directory),
+        )
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "error retrieving commits for each tag in {}: {}",
+                directory,
+                e
+            )
+        })?;
+        Ok(Ok(commits_for_each_tag))
+    }
+
+    /// Clones the repository, recording the duration of the clone.
+    async fn open_repository(
+        &self,
+        repository_url: &str,
+    ) -> Result<Repository, RepositoryUnavailable> {
+        self.open_repository_with_depth(repository_url, self.clone_depth)
+            .await
+    }
+
+    async fn open_repository_with_depth(
+        &self,
+        repository_url: &str,
+        clone_depth: u32,
+    ) -> Result<Repository, RepositoryUnavailable> {
+        Timings::measure(
+            self.timings.as_deref(),
+            Phase::RepositoryClone,
+            repository_url,
+            Repository::new(repository_url, clone_depth),
+        )
+        .await
+        .map_err(|e| {
+            warn!("unable to clone repository {}: {}", repository_url, e);
+            RepositoryUnavailable {
+                repository_url: repository_url.to_string(),
+                reason: e.to_string(),
+            }
         })
     }
+
+    /// Clones the repository and retrieves its tags and commits, recording the durations of both
+    /// phases.
+    async fn clone_repository(
+        &self,
+        repository_url: &str,
+    ) -> Result<Result<RepositoryResult, RepositoryUnavailable>, anyhow::Error> {
+        let repository = match self.open_repository(repository_url).await {
+            Ok(repository) => repository,
+            Err(unavailable) => return Ok(Err(unavailable)),
+        };
+
+        let (commits_for_each_tag_future, all_tags_future, latest_commit_timestamp_future) =
+            Timings::measure(
+                self.timings.as_deref(),
+                Phase::TagRetrieval,
+                repository_url,
+                async {
+                    futures::join!(
+                        repository.commits_for_each_tag(),
+                        repository.all_tags(),
+                        repository.latest_commit_timestamp()
+                    )
+                },
+            )
+            .await;
+
+        Ok(Ok(RepositoryResult {
+            commits_for_each_tag: commits_for_each_tag_future
+                .map_err(|e| anyhow!("error retrieving commits for each tag: {}", e))?,
+            all_tags: all_tags_future.map_err(|e| anyhow!("error retrieving tags: {}", e))?,
+            latest_commit_timestamp: latest_commit_timestamp_future
+                .map_err(|e| anyhow!("error retrieving the latest commit: {}", e))?,
+        }))
+    }
 }
 
 pub struct Repository {
-    repository: Arc<Mutex<git2::Repository>>,
-    #[allow(unused)]
+    repo: Arc<Mutex<git2::Repository>>,
+    clone_depth: u32,
     temp_dir: tempfile::TempDir,
 }
 
 impl Repository {
-    pub async fn new(url: &str) -> Result<Self, Box<dyn Error>> {
+    pub async fn new(url: &str, clone_depth: u32) -> Result<Self, Box<dyn Error>> {
         let url = url.to_string();
-        let result: Result<Repository, anyhow::Error> = tokio::task::spawn_blocking(move || {
-            let temp_dir = tempfile::tempdir().context("unable to create temp dir")?;
-            let repository = git2::build::RepoBuilder::new()
-                .bare(true)
-                .clone(&url, temp_dir.path())
-                .context("unable to clone repository")?;
-
-            Ok(Repository {
-                repository: Arc::new(Mutex::new(repository)),
-                temp_dir,
-            })
-        })
-        .await?;
+        let result: Result<Repository, anyhow::Error> =
+            tokio::task::spawn_blocking(move || Self::clone_with(&url, clone_depth, GIT_PROGRAM))
+                .await?;
 
         result.map_err(std::convert::Into::into)
     }
 
+    /// Clones the repository into a temporary directory, with the given git program for the
+    /// shallow clones. When it fails, e.g. because git is not installed, the whole history is
+    /// cloned with libgit2 instead.
+    fn clone_with(url: &str, clone_depth: u32, git: &str) -> Result<Self, anyhow::Error> {
+        let temp_dir = tempfile::tempdir().context("unable to create temp dir")?;
+        let repository = if clone_depth == 0 {
+            Self::full_clone(url, temp_dir.path())?
+        } else {
+            Self::shallow_clone(url, temp_dir.path(), clone_depth, git).or_else(|e| {
+                warn!(
+                    "unable to shallow clone {}, falling back to a full clone: {}",
+                    url, e
+                );
+                Self::full_clone(url, temp_dir.path())
+            })?
+        };
+
+        Ok(Repository {
+            repo: Arc::new(Mutex::new(repository)),
+            clone_depth,
+            temp_dir,
+        })
+    }
+
+    fn full_clone(url: &str, path: &Path) -> Result<git2::Repository, anyhow::Error> {
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.proxy_options(Self::proxy_options(url));
+
+        git2::build::RepoBuilder::new()
+            .bare(true)
+            .fetch_options(fetch_options)
+            .clone(url, path)
+            .context("unable to clone repository")
+    }
+
+    /// Uses the proxy of the environment variables, falling back to the one of the git config.
+    fn proxy_options(url: &str) -> git2::ProxyOptions<'static> {
+        let mut proxy_options = git2::ProxyOptions::new();
+        match reqwest::Url::parse(url)
+            .ok()
+            .as_ref()
+            .and_then(http::proxy_for)
+        {
+            Some(proxy) => proxy_options.url(&proxy),
+            None => proxy_options.auto(),
+        };
+        proxy_options
+    }
+
+    /// libgit2 does not support shallow fetches, so they are delegated to the git command line,
+    /// which honors the proxy environment variables by itself.
+    fn shallow_clone(
+        url: &str,
+        path: &Path,
+        clone_depth: u32,
+        git: &str,
+    ) -> Result<git2::Repository, anyhow::Error> {
+        let path_str = path.to_str().context("temp dir path is not valid UTF-8")?;
+        Self::run_git(
+            git,
+            &[
+                "clone",
+                "--bare",
+                "--no-single-branch",
+                "--depth",
+                &clone_depth.to_string(),
+                url,
+                path_str,
+            ],
+            None,
+        )?;
+
+        git2::Repository::open_bare(path).context("unable to open shallow clone")
+    }
+
+    fn run_git(git: &str, args: &[&str], working_dir: Option<&Path>) -> Result<(), anyhow::Error> {
+        let mut command = std::process::Command::new(git);
+        command.args(args);
+        if let Some(working_dir) = working_dir {
+            command.current_dir(working_dir);
+        }
+
+        let output = command
+            .output()
+            .with_context(|| format!("unable to run {git}, is it installed?"))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn is_shallow(&self) -> bool {
+        self.repo.lock().await.is_shallow()
+    }
+
+    async fn deepen(&self, deepen_by: u32) -> Result<(), anyhow::Error> {
+        self.fetch_history(vec!["--deepen".to_string(), deepen_by.to_string()])
+            .await
+    }
+
+    async fn unshallow(&self) -> Result<(), anyhow::Error> {
+        self.fetch_history(vec!["--unshallow".to_string()]).await
+    }
+
+    /// Fetches more of the history of a shallow clone, with the given depth options, along with
+    /// the tags of the newly fetched commits. The repository stays locked until the fetch is done,
+    /// so its fetches run one at a time, as each one takes the lock of the shallow file of git.
+    async fn fetch_history(&self, depth_options: Vec<String>) -> Result<(), anyhow::Error> {
+        let mut repository = self.repo.clone().lock_owned().await;
+        let path = self.temp_dir.path().to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let mut args = vec!["fetch"];
+            args.extend(depth_options.iter().map(String::as_str));
+            args.extend([
+                "origin",
+                "+refs/heads/*:refs/heads/*",
+                "+refs/tags/*:refs/tags/*",
+            ]);
+            Self::run_git(GIT_PROGRAM, &args, Some(&path))?;
+
+            *repository =
+                git2::Repository::open_bare(&path).context("unable to reopen deepened clone")?;
+            Ok(())
+        })
+        .await?
+    }
+
     async fn commits_for_each_tag(&self) -> Result<HashMap<String, Vec<Commit>>, anyhow::Error> {
         let commits_ids = self.commit_ids_for_each_tag().await?;
         let map = commits_ids
@@ -187,9 +605,69 @@ impl Repository {
         Ok(result)
     }
 
+    /// Retrieves the commits of each tag that touch the directory, leaving out the tags without
+    /// any.
+    async fn commits_for_each_tag_in(
+        &self,
+        directory: &str,
+    ) -> Result<HashMap<String, Vec<Commit>>, anyhow::Error> {
+        let commit_ids = self.commit_ids_for_each_tag().await?;
+        let touching = self
+            .commit_ids_touching(directory, commit_ids.values().flatten().cloned().collect())
+            .await?;
+
+        let mut result = HashMap::new();
+        for (tag, commit_ids) in commit_ids {
+            let commits = commit_ids
+                .into_iter()
+                .filter(|commit_id| touching.contains(commit_id))
+                .map(|commit_id| self.commit_from_id(Cow::from(commit_id)))
+                .collect::<FuturesOrdered<_>>()
+                .filter_map(|commit| async { commit.ok() })
+                .collect::<Vec<_>>()
+                .await;
+            if !commits.is_empty() {
+                result.insert(tag, commits);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the commits among the given ones that change a file under the directory, compared
+    /// to their first parent.
+    async fn commit_ids_touching(
+        &self,
+        directory: &str,
+        commit_ids: Vec<String>,
+    ) -> Result<HashSet<String>, anyhow::Error> {
+        let repository = self.repo.clone();
+        let directory = directory.trim_matches('/').to_string();
+        tokio::task::spawn_blocking(move || {
+            let guard = repository.blocking_lock();
+            let mut touching = HashSet::new();
+            for commit_id in commit_ids {
+                let commit = guard.find_commit(Oid::from_str(&commit_id)?)?;
+                let parent_tree = commit.parent(0).and_then(|parent| parent.tree()).ok();
+                let mut options = git2::DiffOptions::new();
+                options.pathspec(&directory);
+                let diff = guard.diff_tree_to_tree(
+                    parent_tree.as_ref(),
+                    Some(&commit.tree()?),
+                    Some(&mut options),
+                )?;
+                if diff.deltas().next().is_some() {
+                    touching.insert(commit_id);
+                }
+            }
+            Ok(touching)
+        })
+        .await?
+    }
+
     #[allow(clippy::cast_sign_loss)]
     async fn all_tags(&self) -> Result<Vec<Tag>, anyhow::Error> {
-        let repository = self.repository.clone();
+        let repository = self.repo.clone();
         tokio::task::spawn_blocking(move || {
             let mut tags = vec![];
             let guard = repository.blocking_lock();
@@ -200,6 +678,7 @@ impl Repository {
                             name: String::from_utf8_lossy(name).replace("refs/tags/", ""),
                             commit_id: commit.id().to_string(),
                             commit_timestamp: commit.time().seconds() as u64,
+                            is_signed: Self::is_signed_tag(&obj),
                         });
                     }
                 }
@@ -212,41 +691,250 @@ impl Repository {
         .expect("unable to get all tags")
     }
 
+    /// Returns the path and the contents of the files under the directory in the default branch.
+    pub async fn files(&self, directory: &str) -> Result<Vec<(String, Vec<u8>)>, anyhow::Error> {
+        let repository = self.repo.clone();
+        let directory = directory.to_string();
+        tokio::task::spawn_blocking(move || {
+            let guard = repository.blocking_lock();
+            let tree = guard
+                .head()
+                .and_then(|head| head.peel_to_tree())
+                .context("unable to find the tree of the default branch")?
+                .get_path(Path::new(&directory))
+                .and_then(|entry| entry.to_object(&guard))
+                .and_then(|object| object.peel_to_tree())
+                .with_context(|| format!("unable to find the directory {directory}"))?;
+
+            let mut files = vec![];
+            tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+                if let Some(blob) = entry
+                    .to_object(&guard)
+                    .ok()
+                    .and_then(|object| object.into_blob().ok())
+                {
+                    files.push((
+                        format!("{directory}/{root}{}", entry.name().unwrap_or_default()),
+                        blob.content().to_vec(),
+                    ));
+                }
+                git2::TreeWalkResult::Ok
+            })?;
+            Ok(files)
+        })
+        .await?
+    }
+
+    /// Whether the object is an annotated tag carrying a GPG or SSH signature, which git appends
+    /// to the message of the tag. Lightweight tags point directly to a commit, so they are never
+    /// signed.
+    fn is_signed_tag(obj: &git2::Object) -> bool {
+        obj.as_tag()
+            .and_then(git2::Tag::message_bytes)
+            .map(String::from_utf8_lossy)
+            .is_some_and(|message| {
+                message.contains("-----BEGIN PGP SIGNATURE-----")
+                    || message.contains("-----BEGIN SSH SIGNATURE-----")
+            })
+    }
+
+    /// Walks the commits between tags, deepening a shallow clone whenever the walk reaches its
+    /// boundary, until the walk succeeds or the whole history has been fetched. As the oldest tag
+    /// is only walked up to the boundary, a shallow clone is not deepened to reach its first commit.
     async fn commit_ids_for_each_tag(&self) -> Result<HashMap<String, Vec<String>>, anyhow::Error> {
-        let mut result = HashMap::new();
+        let mut deepen_by = self.clone_depth;
+        loop {
+            match self.walk_commit_ids_for_each_tag().await {
+                Err(e) if self.is_shallow().await => {
+                    debug!(
+                        "shallow clone is missing commits ({}), deepening it by {}",
+                        e, deepen_by
+                    );
+                    self.deepen(deepen_by).await?;
+                    deepen_by = deepen_by.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+    }
 
+    /// Walks the commits of each tag that are not reachable from the previous one. The tags are
+    /// split between blocking threads, each one with its own handle of the repository, because
+    /// the git2 ones can't be shared between threads.
+    async fn walk_commit_ids_for_each_tag(
+        &self,
+    ) -> Result<HashMap<String, Vec<String>>, anyhow::Error> {
         let tags: Vec<_> = self.all_tags().await?.into_iter().rev().collect();
-        if tags.is_empty() {
-            return Ok(result);
+        let tag_pairs = Self::tag_pairs(&tags)?;
+        if tag_pairs.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let parallelism = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let walks = tag_pairs
+            .chunks(tag_pairs.len().div_ceil(parallelism))
+            .map(|tag_pairs| {
+                let tag_pairs = tag_pairs.to_vec();
+                let path = self.temp_dir.path().to_path_buf();
+                tokio::task::spawn_blocking(move || Self::walk_tag_pairs(&path, &tag_pairs))
+            })
+            .collect::<Vec<_>>();
+
+        let mut result = HashMap::new();
+        for walk in join_all(walks).await {
+            result.extend(walk??);
         }
 
-        let mut commit_buffer = Vec::new();
-        for i in 0..tags.len() - 1 {
-            let first_tag = tags.get(i).context("unable to retrieve first tag")?;
-            let second_tag = tags.get(i + 1).context("unable to retrieve second tag")?;
-            let first_oid = Oid::from_str(&first_tag.commit_id)?;
-            let second_oid = Oid::from_str(&second_tag.commit_id)?;
-
-            let guard = self.repository.lock().await;
-            let mut revwalk = guard.revwalk()?;
-            revwalk.push(first_oid)?;
-            for oid in revwalk.flatten() {
-                if oid == second_oid {
-                    break;
+        Ok(result)
+    }
+
+    /// Pairs each tag, from the newest to the oldest, with the previous one. The oldest tag is
+    /// paired with none so it is walked back to the initial commit, or to the boundary of a
+    /// shallow clone.
+    fn tag_pairs(tags: &[Tag]) -> Result<Vec<(String, Oid, Option<Oid>)>, anyhow::Error> {
+        let mut tag_pairs = tags
+            .windows(2)
+            .map(|pair| {
+                Ok((
+                    pair[0].name.clone(),
+                    Oid::from_str(&pair[0].commit_id)?,
+                    Some(Oid::from_str(&pair[1].commit_id)?),
+                ))
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        if let Some(oldest_tag) = tags.last() {
+            tag_pairs.push((
+                oldest_tag.name.clone(),
+                Oid::from_str(&oldest_tag.commit_id)?,
+                None,
+            ));
+        }
+
+        Ok(tag_pairs)
+    }
+
+    fn walk_tag_pairs(
+        path: &Path,
+        tag_pairs: &[(String, Oid, Option<Oid>)],
+    ) -> Result<Vec<(String, Vec<String>)>, anyhow::Error> {
+        let repository = git2::Repository::open_bare(path).context("unable to open repository")?;
+        let is_shallow = repository.is_shallow();
+
+        tag_pairs
+            .iter()
+            .map(|(tag_name, tag_oid, previous_tag_oid)| {
+                if is_shallow {
+                    let commit_ids =
+                        Self::walk_shallow_tag_pair(&repository, *tag_oid, *previous_tag_oid)?;
+                    return Ok((tag_name.clone(), commit_ids));
+                }
+
+                let mut revwalk = repository.revwalk()?;
+                revwalk.set_sorting(git2::Sort::TIME)?;
+                revwalk.push(*tag_oid)?;
+                if let Some(previous_tag_oid) = previous_tag_oid {
+                    revwalk.hide(*previous_tag_oid)?;
+                }
+
+                let commit_ids = revwalk
+                    .map(|oid| oid.map(|oid| oid.to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((tag_name.clone(), commit_ids))
+            })
+            .collect()
+    }
+
+    /// Walks the commits of a tag that are not reachable from the previous one in a shallow
+    /// clone, as the revwalks of libgit2 fail on the parents missing beyond its boundary. The
+    /// commits are walked from the newest to the oldest, as the revwalks do, until only the ones
+    /// reachable from the previous tag remain. The missing parents of those are ignored, and so are
+    /// the ones of the oldest tag, which is walked up to the boundary instead of the initial
+    /// commit; otherwise they fail the walk so the clone is deepened.
+    fn walk_shallow_tag_pair(
+        repository: &git2::Repository,
+        tag_oid: Oid,
+        previous_tag_oid: Option<Oid>,
+    ) -> Result<Vec<String>, anyhow::Error> {
+        let mut pending = BinaryHeap::new();
+        let mut queued = HashSet::new();
+        let mut hidden = HashSet::new();
+        for oid in std::iter::once(tag_oid).chain(previous_tag_oid) {
+            let commit = repository.find_commit(oid)?;
+            pending.push((commit.time().seconds(), oid));
+            queued.insert(oid);
+        }
+        if let Some(previous_tag_oid) = previous_tag_oid {
+            hidden.insert(previous_tag_oid);
+        }
+
+        let mut commit_ids = Vec::new();
+        while pending.iter().any(|(_, oid)| !hidden.contains(oid)) {
+            let Some((_, oid)) = pending.pop() else {
+                break;
+            };
+            let is_hidden = hidden.contains(&oid);
+            if !is_hidden {
+                commit_ids.push(oid.to_string());
+            }
+
+            for parent_id in repository.find_commit(oid)?.parent_ids() {
+                if is_hidden {
+                    hidden.insert(parent_id);
+                }
+                if !queued.insert(parent_id) {
+                    continue;
+                }
+                match repository.find_commit(parent_id) {
+                    Ok(parent) => pending.push((parent.time().seconds(), parent_id)),
+                    Err(_) if is_hidden || previous_tag_oid.is_none() => {}
+                    Err(e) => return Err(e.into()),
                 }
-                commit_buffer.push(oid.to_string());
             }
-            result.insert(first_tag.name.clone(), commit_buffer.clone());
-            commit_buffer.clear();
         }
 
-        Ok(result)
+        Ok(commit_ids)
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    async fn latest_commit_timestamp(&self) -> Result<u64, anyhow::Error> {
+        let guard = self.repo.lock().await;
+        let head_commit = guard
+            .head()
+            .context("unable to retrieve HEAD")?
+            .peel_to_commit()
+            .context("HEAD does not point to a commit")?;
+
+        Ok(head_commit.time().seconds() as u64)
+    }
+
+    /// Retrieves the timestamp of the oldest commit reachable from HEAD. A shallow clone is
+    /// unshallowed first, otherwise its oldest fetched commit would be taken for the first one.
+    #[allow(clippy::cast_sign_loss)]
+    async fn earliest_commit_timestamp(&self) -> Result<u64, anyhow::Error> {
+        if self.is_shallow().await {
+            self.unshallow().await?;
+        }
+
+        let guard = self.repo.lock().await;
+        let mut revwalk = guard.revwalk().context("unable to walk the commits")?;
+        revwalk.push_head().context("unable to retrieve HEAD")?;
+
+        let mut earliest_timestamp: Option<i64> = None;
+        for commit_id in revwalk {
+            let timestamp = guard.find_commit(commit_id?)?.time().seconds();
+            earliest_timestamp = Some(earliest_timestamp.map_or(timestamp, |t| t.min(timestamp)));
+        }
+
+        earliest_timestamp
+            .map(|timestamp| timestamp as u64)
+            .context("HEAD does not point to a commit")
     }
 
     async fn commit_from_id(&self, commit_id: Cow<'_, str>) -> Result<Commit, anyhow::Error> {
         let oid = Oid::from_str(commit_id.as_ref())?;
 
-        let guard = self.repository.lock().await;
+        let guard = self.repo.lock().await;
         let commit = guard
             .find_object(oid, None)?
             .into_commit()
@@ -275,10 +963,11 @@ impl Repository {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pkg::Repository as PackageRepository;
 
     #[tokio::test]
     async fn it_retrieves_the_tags_of_a_repository() {
-        let repository = Repository::new("https://github.com/libgit2/libgit2")
+        let repository = Repository::new("https://github.com/libgit2/libgit2", 0)
             .await
             .expect("unable to create repository");
 
@@ -288,16 +977,167 @@ mod tests {
             .expect("unable to retrieve all tags");
 
         assert!(tags.len() >= 76);
-        assert!(tags.contains(&Tag {
-            name: "v1.4.2".to_string(),
-            commit_id: "182d0d1ee933de46bf0b5a6ec269bafa77aba9a2".to_string(),
-            commit_timestamp: 1_645_905_004,
-        }));
+        assert!(tags.iter().any(|tag| tag.name == "v1.4.2"
+            && tag.commit_id == "182d0d1ee933de46bf0b5a6ec269bafa77aba9a2"
+            && tag.commit_timestamp == 1_645_905_004));
+    }
+
+    #[test]
+    fn it_considers_signed_only_the_annotated_tags_with_a_signature() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Some Author", "author@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit_id = repo
+            .commit(None, &signature, &signature, "initial commit", &tree, &[])
+            .unwrap();
+        let commit = repo.find_object(commit_id, None).unwrap();
+
+        let signed_tag_id = repo
+            .tag(
+                "v1.0.0",
+                &commit,
+                &signature,
+                "v1.0.0\n-----BEGIN PGP SIGNATURE-----\nc2lnbmF0dXJl\n-----END PGP SIGNATURE-----\n",
+                false,
+            )
+            .unwrap();
+        let annotated_tag_id = repo
+            .tag("v1.0.1", &commit, &signature, "v1.0.1\n", false)
+            .unwrap();
+
+        assert!(Repository::is_signed_tag(
+            &repo.find_object(signed_tag_id, None).unwrap()
+        ));
+        assert!(!Repository::is_signed_tag(
+            &repo.find_object(annotated_tag_id, None).unwrap()
+        ));
+        assert!(!Repository::is_signed_tag(&commit));
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_commits_of_each_tag_touching_a_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let mut tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+        let mut parent_id = None;
+        for (timestamp, (tag, path)) in [
+            ("v1.0.0", "packages/core/index.js"),
+            ("v1.1.0", "packages/core-utils/index.js"),
+            ("v1.2.0", "packages/core/lib/index.js"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let blob_id = repo.blob(tag.as_bytes()).unwrap();
+            let mut update = git2::build::TreeUpdateBuilder::new();
+            update.upsert(path, blob_id, git2::FileMode::Blob);
+            tree_id = update
+                .create_updated(&repo, &repo.find_tree(tree_id).unwrap())
+                .unwrap();
+            let signature = git2::Signature::new(
+                "Some Author",
+                "author@example.com",
+                &git2::Time::new(1_600_000_000 + i64::try_from(timestamp).unwrap(), 0),
+            )
+            .unwrap();
+            let parents = parent_id
+                .map(|parent_id| repo.find_commit(parent_id).unwrap())
+                .into_iter()
+                .collect::<Vec<_>>();
+            let commit_id = repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    tag,
+                    &repo.find_tree(tree_id).unwrap(),
+                    &parents.iter().collect::<Vec<_>>(),
+                )
+                .unwrap();
+            repo.tag_lightweight(tag, &repo.find_object(commit_id, None).unwrap(), false)
+                .unwrap();
+            parent_id = Some(commit_id);
+        }
+        let repository = Repository {
+            repo: Arc::new(Mutex::new(repo)),
+            clone_depth: 0,
+            temp_dir,
+        };
+
+        let commits_for_each_tag = repository
+            .commits_for_each_tag_in("packages/core")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            commits_for_each_tag
+                .keys()
+                .map(String::as_str)
+                .collect::<HashSet<_>>(),
+            HashSet::from(["v1.0.0", "v1.2.0"])
+        );
+        assert!(commits_for_each_tag
+            .values()
+            .all(|commits| commits.len() == 1));
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_files_under_a_directory_of_the_default_branch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Some Author", "author@example.com").unwrap();
+        for (path, contents) in [
+            ("crates/time/RUSTSEC-2020-0071.md", "time"),
+            ("crates/chrono/RUSTSEC-2020-0159.md", "chrono"),
+            ("README.md", "readme"),
+        ] {
+            std::fs::create_dir_all(temp_dir.path().join(path).parent().unwrap()).unwrap();
+            std::fs::write(temp_dir.path().join(path), contents).unwrap();
+        }
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+        drop(tree);
+        let repository = Repository {
+            repo: Arc::new(Mutex::new(repo)),
+            clone_depth: 0,
+            temp_dir,
+        };
+
+        let files = repository.files("crates").await.unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "crates/chrono/RUSTSEC-2020-0159.md".to_string(),
+                    b"chrono".to_vec()
+                ),
+                (
+                    "crates/time/RUSTSEC-2020-0071.md".to_string(),
+                    b"time".to_vec()
+                ),
+            ]
+        );
     }
 
     #[tokio::test]
     async fn it_retrieves_commit_ids_for_each_tag_of_a_repository() {
-        let repository = Repository::new("https://github.com/libgit2/libgit2")
+        let repository = Repository::new("https://github.com/libgit2/libgit2", 0)
             .await
             .unwrap();
 
@@ -320,9 +1160,82 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn it_walks_the_same_commits_of_each_tag_of_libgit2_in_parallel_as_sequentially() {
+        let repository = Repository::new("https://github.com/libgit2/libgit2", 0)
+            .await
+            .unwrap();
+        let tags: Vec<_> = repository
+            .all_tags()
+            .await
+            .unwrap()
+            .into_iter()
+            .rev()
+            .collect();
+        let tag_pairs = Repository::tag_pairs(&tags).unwrap();
+
+        let sequential_result: HashMap<_, _> =
+            Repository::walk_tag_pairs(repository.temp_dir.path(), &tag_pairs)
+                .unwrap()
+                .into_iter()
+                .collect();
+        let parallel_result = repository.walk_commit_ids_for_each_tag().await.unwrap();
+
+        assert_eq!(parallel_result, sequential_result);
+    }
+
+    #[tokio::test]
+    async fn it_walks_only_the_commits_that_are_not_reachable_from_the_previous_tag() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, Some("v0.1.0"));
+        let second = commit_and_tag(&git_repository, &[first], 2_000, None);
+        let branch = commit_and_tag(&git_repository, &[first], 3_000, Some("v0.2.0"));
+        let merge = commit_and_tag(&git_repository, &[second, branch], 4_000, Some("v0.3.0"));
+        let repository = Repository {
+            repo: Arc::new(Mutex::new(git_repository)),
+            clone_depth: 0,
+            temp_dir,
+        };
+
+        let commit_ids_for_each_tag = repository.walk_commit_ids_for_each_tag().await.unwrap();
+
+        assert_eq!(
+            commit_ids_for_each_tag,
+            HashMap::from([
+                ("v0.1.0".to_string(), vec![first.to_string()]),
+                ("v0.2.0".to_string(), vec![branch.to_string()]),
+                (
+                    "v0.3.0".to_string(),
+                    vec![merge.to_string(), second.to_string()]
+                ),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn it_walks_the_commits_of_the_oldest_tag_back_to_the_initial_commit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, None);
+        let second = commit_and_tag(&git_repository, &[first], 2_000, Some("v0.1.0"));
+        let repository = Repository {
+            repo: Arc::new(Mutex::new(git_repository)),
+            clone_depth: 0,
+            temp_dir,
+        };
+
+        let commit_ids_for_each_tag = repository.walk_commit_ids_for_each_tag().await.unwrap();
+
+        assert_eq!(
+            commit_ids_for_each_tag.get("v0.1.0").unwrap(),
+            &[second.to_string(), first.to_string()]
+        );
+    }
+
     #[tokio::test]
     async fn it_retrieves_commit_for_each_tag_of_a_repository() {
-        let repository = Repository::new("https://github.com/libgit2/libgit2")
+        let repository = Repository::new("https://github.com/libgit2/libgit2", 0)
             .await
             .unwrap();
 
@@ -341,11 +1254,165 @@ mod tests {
         assert_eq!(commits_for_each_tag.get("v1.4.2").unwrap().len(), 6_usize);
     }
 
+    #[tokio::test]
+    async fn it_retrieves_the_latest_commit_timestamp_of_a_repository() {
+        let repository = Repository::new("https://github.com/libgit2/libgit2", 1)
+            .await
+            .unwrap();
+
+        let latest_commit_timestamp = repository.latest_commit_timestamp().await.unwrap();
+
+        assert!(latest_commit_timestamp > 1_645_905_004);
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_earliest_commit_timestamp_of_a_repository() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, None);
+        let second = commit_and_tag(&git_repository, &[first], 2_000, None);
+        git_repository
+            .branch("main", &git_repository.find_commit(second).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let repository = Repository {
+            repo: Arc::new(Mutex::new(git_repository)),
+            clone_depth: 0,
+            temp_dir,
+        };
+
+        let earliest_commit_timestamp = repository.earliest_commit_timestamp().await.unwrap();
+
+        assert_eq!(earliest_commit_timestamp, 1_000);
+    }
+
+    #[tokio::test]
+    async fn it_unshallows_a_shallow_clone_to_retrieve_the_earliest_commit_timestamp() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, None);
+        let second = commit_and_tag(&git_repository, &[first], 2_000, None);
+        let third = commit_and_tag(&git_repository, &[second], 3_000, None);
+        git_repository
+            .branch("main", &git_repository.find_commit(third).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let url = format!("file://{}", temp_dir.path().display());
+        let repository = Repository::new(&url, 1).await.unwrap();
+        assert!(repository.is_shallow().await);
+
+        let earliest_commit_timestamp = repository.earliest_commit_timestamp().await.unwrap();
+
+        assert_eq!(earliest_commit_timestamp, 1_000);
+        assert!(!repository.is_shallow().await);
+    }
+
+    #[test]
+    fn it_falls_back_to_a_full_clone_when_git_cannot_be_run() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, Some("v0.1.0"));
+        let second = commit_and_tag(&git_repository, &[first], 2_000, Some("v0.2.0"));
+        git_repository
+            .branch("main", &git_repository.find_commit(second).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let url = format!("file://{}", temp_dir.path().display());
+
+        let repository = Repository::clone_with(&url, 1, "git-that-is-not-installed").unwrap();
+
+        let clone = repository.repo.blocking_lock();
+        assert!(!clone.is_shallow());
+        assert!(clone.find_commit(first).is_ok());
+        assert_eq!(clone.tag_names(None).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_deepens_a_shallow_clone_to_walk_the_commits_between_two_tags() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, Some("v0.1.0"));
+        let second = commit_and_tag(&git_repository, &[first], 2_000, None);
+        let third = commit_and_tag(&git_repository, &[second], 3_000, None);
+        let fourth = commit_and_tag(&git_repository, &[third], 4_000, Some("v0.2.0"));
+        git_repository
+            .branch("main", &git_repository.find_commit(fourth).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let url = format!("file://{}", temp_dir.path().display());
+        let repository = Repository::new(&url, 1).await.unwrap();
+
+        let commit_ids_for_each_tag = repository.commit_ids_for_each_tag().await.unwrap();
+
+        assert_eq!(
+            commit_ids_for_each_tag,
+            HashMap::from([
+                ("v0.1.0".to_string(), vec![first.to_string()]),
+                (
+                    "v0.2.0".to_string(),
+                    vec![fourth.to_string(), third.to_string(), second.to_string()]
+                ),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn it_walks_the_oldest_tag_of_a_shallow_clone_up_to_its_boundary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, None);
+        let second = commit_and_tag(&git_repository, &[first], 2_000, None);
+        let third = commit_and_tag(&git_repository, &[second], 3_000, Some("v0.1.0"));
+        let fourth = commit_and_tag(&git_repository, &[third], 4_000, None);
+        let fifth = commit_and_tag(&git_repository, &[fourth], 5_000, Some("v0.2.0"));
+        git_repository
+            .branch("main", &git_repository.find_commit(fifth).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let url = format!("file://{}", temp_dir.path().display());
+        let repository = Repository::new(&url, 2).await.unwrap();
+
+        let commit_ids_for_each_tag = repository.commit_ids_for_each_tag().await.unwrap();
+
+        assert_eq!(
+            commit_ids_for_each_tag,
+            HashMap::from([
+                (
+                    "v0.1.0".to_string(),
+                    vec![third.to_string(), second.to_string()]
+                ),
+                (
+                    "v0.2.0".to_string(),
+                    vec![fifth.to_string(), fourth.to_string()]
+                ),
+            ])
+        );
+        assert!(repository.is_shallow().await);
+    }
+
+    #[tokio::test]
+    async fn it_deepens_a_shallow_clone_to_retrieve_the_commit_ids_for_each_tag() {
+        let repository = Repository::new("https://github.com/libgit2/libgit2", 1)
+            .await
+            .unwrap();
+
+        let commit_ids_for_each_tag = repository.commit_ids_for_each_tag().await.unwrap();
+
+        assert_eq!(
+            commit_ids_for_each_tag.get("v1.4.2").unwrap().len(),
+            6_usize
+        );
+        assert_eq!(
+            commit_ids_for_each_tag.get("v1.4.0").unwrap().len(),
+            302_usize
+        );
+    }
+
     #[tokio::test]
     async fn it_retrieves_the_contents_of_the_repositories_and_stores_them_in_a_cache() {
         let commit_store: Box<dyn CommitStore> = mock_commit_store();
 
-        let repository_retriever = RepositoryRetriever::new(commit_store);
+        let repository_retriever = RepositoryRetriever::new(commit_store, DEFAULT_CLONE_DEPTH);
         let repository_url = "https://github.com/libgit2/libgit2";
 
         repository_retriever
@@ -397,7 +1464,7 @@ mod tests {
     #[tokio::test]
     async fn it_retrieves_the_tags_for_yocto_queue() {
         let commit_store: Box<dyn CommitStore> = mock_commit_store();
-        let repository_retriever = RepositoryRetriever::new(commit_store);
+        let repository_retriever = RepositoryRetriever::new(commit_store, DEFAULT_CLONE_DEPTH);
         let tags = repository_retriever
             .all_tags("https://github.com/sindresorhus/yocto-queue")
             .await
@@ -406,6 +1473,213 @@ mod tests {
         assert!(tags.len() >= 2_usize);
     }
 
+    #[tokio::test]
+    async fn it_retrieves_the_tags_of_a_gitlab_repository() {
+        let repository_url = crate::pkg::Repository::GitLab {
+            organization: "philbooth".to_string(),
+            name: "bfj".to_string(),
+        }
+        .url()
+        .unwrap();
+        let commit_store: Box<dyn CommitStore> = mock_commit_store();
+        let repository_retriever = RepositoryRetriever::new(commit_store, DEFAULT_CLONE_DEPTH);
+
+        let tags = repository_retriever
+            .all_tags(&repository_url)
+            .await
+            .unwrap();
+        let commits_for_each_tag = repository_retriever
+            .commits_for_each_tag(&repository_url)
+            .await
+            .unwrap();
+
+        assert!(tags.len() >= 2_usize);
+        assert!(!commits_for_each_tag.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_caches_the_repositories_that_are_unavailable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repository_url = temp_dir
+            .path()
+            .join("missing")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let commit_store = {
+            let mut commit_store = Box::new(MockCommitStore::new());
+            commit_store
+                .expect_get_commits_for_each_tag()
+                .once()
+                .return_const(None);
+            commit_store.expect_get_all_tags().once().return_const(None);
+            commit_store
+                .expect_get_latest_commit_timestamp()
+                .once()
+                .return_const(None);
+            commit_store as Box<dyn CommitStore>
+        };
+        let repository_retriever = RepositoryRetriever::new(commit_store, DEFAULT_CLONE_DEPTH);
+
+        let tags_error = repository_retriever
+            .all_tags(&repository_url)
+            .await
+            .unwrap_err();
+        let commits_error = repository_retriever
+            .commits_for_each_tag(&repository_url)
+            .await
+            .unwrap_err();
+
+        for error in [tags_error, commits_error] {
+            let unavailable = error.downcast_ref::<RepositoryUnavailable>().unwrap();
+            assert_eq!(unavailable.repository_url, repository_url);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_earliest_commit_timestamp_without_retrieving_the_tags() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, None);
+        let second = commit_and_tag(&git_repository, &[first], 2_000, None);
+        git_repository
+            .branch("main", &git_repository.find_commit(second).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let repository_url = format!("file://{}", temp_dir.path().display());
+        let commit_store = {
+            let mut commit_store = Box::new(MockCommitStore::new());
+            commit_store
+                .expect_get_earliest_commit_timestamp()
+                .once()
+                .return_const(None);
+            commit_store
+                .expect_save_earliest_commit_timestamp()
+                .withf(|_, timestamp| *timestamp == 1_000)
+                .once()
+                .return_once(|_, _| Ok(()));
+            commit_store as Box<dyn CommitStore>
+        };
+        let repository_retriever = RepositoryRetriever::new(commit_store, DEFAULT_CLONE_DEPTH);
+
+        let earliest_commit_timestamp = repository_retriever
+            .earliest_commit_timestamp(&repository_url)
+            .await
+            .unwrap();
+        let cached_earliest_commit_timestamp = repository_retriever
+            .earliest_commit_timestamp(&repository_url)
+            .await
+            .unwrap();
+
+        assert_eq!(earliest_commit_timestamp, 1_000);
+        assert_eq!(cached_earliest_commit_timestamp, 1_000);
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_tags_of_a_repository_in_any_git_host() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, Some("v0.1.0"));
+        let second = commit_and_tag(&git_repository, &[first], 2_000, Some("v0.2.0"));
+        git_repository
+            .branch("main", &git_repository.find_commit(second).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let repository =
+            PackageRepository::parse_url(&format!("git+file://{}", temp_dir.path().display()));
+        let repository_retriever =
+            RepositoryRetriever::new(mock_commit_store() as Box<dyn CommitStore>, 0);
+
+        let tags = repository_retriever
+            .all_tags(&repository.url().unwrap())
+            .await
+            .unwrap();
+
+        assert!(matches!(repository, PackageRepository::Raw { .. }));
+        assert_eq!(
+            tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>(),
+            ["v0.1.0", "v0.2.0"]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_leaves_out_the_tags_and_commits_created_after_the_as_of_timestamp() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_repository = git2::Repository::init_bare(temp_dir.path()).unwrap();
+        let first = commit_and_tag(&git_repository, &[], 1_000, Some("v0.1.0"));
+        let second = commit_and_tag(&git_repository, &[first], 2_000, None);
+        let third = commit_and_tag(&git_repository, &[second], 3_000, Some("v0.2.0"));
+        git_repository
+            .branch("main", &git_repository.find_commit(third).unwrap(), true)
+            .unwrap();
+        git_repository.set_head("refs/heads/main").unwrap();
+        let repository_url = format!("file://{}", temp_dir.path().display());
+        let repository_retriever =
+            RepositoryRetriever::new(mock_commit_store() as Box<dyn CommitStore>, 0)
+                .with_as_of(2_500);
+
+        let tags = repository_retriever
+            .all_tags(&repository_url)
+            .await
+            .unwrap();
+        let commits_for_each_tag = repository_retriever
+            .commits_for_each_tag(&repository_url)
+            .await
+            .unwrap();
+        let latest_commit_timestamp = repository_retriever
+            .latest_commit_timestamp(&repository_url)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>(),
+            ["v0.1.0"]
+        );
+        assert_eq!(
+            commits_for_each_tag.keys().collect::<Vec<_>>(),
+            [&"v0.1.0".to_string()]
+        );
+        assert_eq!(latest_commit_timestamp, 1_000);
+    }
+
+    fn commit_and_tag(
+        repository: &git2::Repository,
+        parents: &[Oid],
+        time: i64,
+        tag: Option<&str>,
+    ) -> Oid {
+        let signature =
+            git2::Signature::new("SomeName", "some@email.com", &git2::Time::new(time, 0)).unwrap();
+        let tree = repository
+            .find_tree(repository.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let parents = parents
+            .iter()
+            .map(|parent| repository.find_commit(*parent).unwrap())
+            .collect::<Vec<_>>();
+        let commit_id = repository
+            .commit(
+                None,
+                &signature,
+                &signature,
+                "some message",
+                &tree,
+                &parents.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+
+        if let Some(tag) = tag {
+            repository
+                .tag_lightweight(
+                    tag,
+                    &repository.find_object(commit_id, None).unwrap(),
+                    false,
+                )
+                .unwrap();
+        }
+        commit_id
+    }
+
     fn mock_commit_store() -> Box<MockCommitStore> {
         let mut commit_store = Box::new(MockCommitStore::new());
         commit_store
@@ -420,6 +1694,13 @@ mod tests {
             .expect_save_all_tags()
             .once()
             .return_once(|_, _| Ok(()));
+        commit_store
+            .expect_get_latest_commit_timestamp()
+            .return_const(None);
+        commit_store
+            .expect_save_latest_commit_timestamp()
+            .once()
+            .return_once(|_, _| Ok(()));
 
         commit_store
     }
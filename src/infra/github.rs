@@ -1,143 +1,78 @@
 use std::error::Error;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 
 use anyhow::Context;
-use async_recursion::async_recursion;
 use async_trait::async_trait;
 use futures::StreamExt;
-use log::{debug, trace};
-use reqwest::header::{HeaderMap, HeaderValue};
+use log::debug;
+use reqwest::header::HeaderMap;
+use reqwest::RequestBuilder;
 use serde_json::Value;
 use tokio_stream::Stream;
 
-use crate::infra::cached_issue_client::IssueClient;
+use crate::infra::cached_issue_client::{take_window, IssueClient};
+use crate::infra::paginator::{Pagination, Paginator};
+use crate::pkg::policy::IssueWindow;
+
+const RATE_LIMIT_REMAINING_THRESHOLD: u64 = 10;
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
 
 #[derive(Clone)]
 pub enum Authentication {
     None,
     Basic(String, Option<String>),
+    Token(String),
+}
+
+impl Authentication {
+    fn authenticate(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Authentication::None => request,
+            Authentication::Basic(user, passwd) => request.basic_auth(user, passwd.as_ref()),
+            Authentication::Token(token) => request.bearer_auth(token),
+        }
+    }
 }
 
 pub struct Client {
-    client: Arc<reqwest::Client>,
+    http: Arc<reqwest::Client>,
     auth: Authentication,
+    api_url: String,
 }
 
-pub struct IssuePullRequestStream {
-    client: Arc<reqwest::Client>,
-    next_page: Option<String>,
-    buffer: Vec<Value>,
+/// Pages of the issues and pull requests of the REST API, which tells the rate limit left in the
+/// `x-ratelimit-*` headers and answers with a 403 once it's exhausted.
+pub struct IssuePages {
     auth: Authentication,
 }
 
-impl IssuePullRequestStream {
-    #[async_recursion]
-    async fn update_buffer(&mut self) -> Result<(), Box<dyn Error>> {
-        if self.next_page.is_none() {
-            return Ok(());
-        }
+impl Pagination for IssuePages {
+    const LOG_TARGET: &'static str = "dean::github_client";
+    const RATE_LIMITED_STATUS: u16 = 403;
+    const RATE_LIMIT_RESET_HEADER: &'static str = "x-ratelimit-reset";
 
-        let url = self.next_page.as_ref().unwrap().clone();
-
-        debug!(target: "dean::github_client", "Fetching issues from {}", url);
-        let mut request = self
-            .client
-            .get(&url)
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/81.0.4044.138 Safari/537.36")
+    fn request(&self, client: &reqwest::Client, url: &str) -> RequestBuilder {
+        let request = client
+            .get(url)
             .header("Accept", "application/vnd.github.v3+json");
-
-        if let Authentication::Basic(user, passwd) = &self.auth {
-            request = request.basic_auth(user, passwd.as_ref());
-        }
-
-        trace!(target: "dean::github_client", "Request: {:?}", request);
-        let response = request.send().await.context("Failed to get issues")?;
-        self.next_page.take();
-        trace!(target: "dean::github_client", "Response: {:?}", response);
-
-        if response.status().as_u16() == 403 {
-            self.next_page = Some(url);
-
-            let rate_limit_timestamp_seconds = response
-                .headers()
-                .get("x-ratelimit-reset")
-                .unwrap_or(&HeaderValue::from(0))
-                .to_str()
-                .context("unable to convert rate limit reset header to str")?
-                .parse::<u64>()?;
-
-            let rate_limit_sleep_duration = Duration::from_secs(rate_limit_timestamp_seconds)
-                .checked_sub(SystemTime::now().duration_since(UNIX_EPOCH)?)
-                .context("unable to substract dates")?
-                .checked_add(Duration::from_secs(5))
-                .context("unable to add duration increment")?;
-
-            tokio::time::sleep(rate_limit_sleep_duration).await;
-            return self.update_buffer().await;
-        }
-
-        if let Some(next_page) = Self::extract_next_page_from_headers(response.headers()) {
-            self.next_page = Some(next_page.to_string());
-        }
-
-        let response_json = response
-            .json::<Value>()
-            .await
-            .context("Failed to parse issues")?;
-
-        let issues = response_json
-            .as_array()
-            .context("the response is not an array")?
-            .clone();
-
-        self.buffer.extend_from_slice(&issues);
-
-        Ok(())
+        self.auth.authenticate(request)
     }
 
-    fn extract_next_page_from_headers(headers: &HeaderMap) -> Option<&str> {
-        let link_header = headers.get("link")?;
-        let link_header_as_str = link_header.to_str().ok()?;
-        let next_rel_link = link_header_as_str
-            .split(',')
-            .find(|link| link.contains("rel=\"next\""))?;
-        let first_next_rel_link = next_rel_link.split(';').next()?;
-        let first_next_rel_link_without_weird_characters = first_next_rel_link
-            .trim()
-            .trim_start_matches('<')
-            .trim_end_matches('>');
-
-        if first_next_rel_link_without_weird_characters.is_empty() {
-            None
-        } else {
-            Some(first_next_rel_link_without_weird_characters)
+    /// When the remaining quota is low, spreads the remaining requests over what is left of the
+    /// rate limit window instead of exhausting it and waiting for a 403.
+    fn throttle_duration(&self, headers: &HeaderMap, now: Duration) -> Option<Duration> {
+        let header_as_u64 = |name: &str| headers.get(name)?.to_str().ok()?.parse::<u64>().ok();
+
+        let remaining = header_as_u64("x-ratelimit-remaining")?;
+        debug!(target: "dean::github_client", "Rate limit remaining: {}", remaining);
+        if remaining >= RATE_LIMIT_REMAINING_THRESHOLD {
+            return None;
         }
-    }
-}
 
-async fn fetch_value_from_stream(
-    mut stream: IssuePullRequestStream,
-) -> Option<(Value, IssuePullRequestStream)> {
-    trace!(target: "dean::github_client::func", "Polling stream");
-    if stream.buffer.is_empty() {
-        trace!(target: "dean::github_client::func", "Buffer is empty, updating it");
-        match stream.update_buffer().await {
-            Ok(_) => {
-                trace!(target: "dean::github_client::func", "Buffer update is ready");
-            }
-            Err(e) => {
-                trace!(target: "dean::github_client", "Failed to update buffer: {}", e);
-                return None;
-            }
-        };
-    };
-    if let Some(value_from_buffer) = stream.buffer.pop() {
-        trace!(target: "dean::github_client::func", "Returning issue from buffer");
-        Some((value_from_buffer, stream))
-    } else {
-        trace!(target: "dean::github_client::func", "Buffer is empty, stopping");
-        None
+        let reset = Duration::from_secs(header_as_u64("x-ratelimit-reset")?);
+        let window_left = reset.checked_sub(now)?;
+        Some(window_left / u32::try_from(remaining + 1).ok()?)
     }
 }
 
@@ -147,26 +82,24 @@ impl IssueClient for Client {
         &self,
         organization: &str,
         repo: &str,
+        window: IssueWindow,
     ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
-        let stream = self.all_issues_iterator(organization, repo);
-
-        let values_from_the_stream = futures::stream::unfold(stream, fetch_value_from_stream);
+        let values_from_the_stream = self.all_issues_iterator(organization, repo).into_stream();
         let issues = values_from_the_stream
             .filter(|value| futures::future::ready(value.get("pull_request").is_none()));
-        Box::new(Box::pin(issues))
+        take_window(issues, window)
     }
 
     async fn get_last_pull_requests(
         &self,
         organization: &str,
         repo: &str,
+        window: IssueWindow,
     ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
-        let stream = self.all_issues_iterator(organization, repo);
-
-        let values_from_the_stream = futures::stream::unfold(stream, fetch_value_from_stream);
+        let values_from_the_stream = self.all_issues_iterator(organization, repo).into_stream();
         let pull_requests = values_from_the_stream
             .filter(|value| futures::future::ready(value.get("pull_request").is_some()));
-        Box::new(Box::pin(pull_requests))
+        take_window(pull_requests, window)
     }
 }
 
@@ -176,38 +109,114 @@ impl Client {
         C: Into<Arc<reqwest::Client>>,
     {
         Self {
-            client: client.into(),
+            http: client.into(),
             auth,
+            api_url: DEFAULT_API_URL.to_string(),
         }
     }
 
-    fn all_issues_iterator(&self, organization: &str, repo: &str) -> IssuePullRequestStream {
-        IssuePullRequestStream {
-            client: self.client.clone(),
-            next_page: Some(format!(
-                "https://api.github.com/repos/{organization}/{repo}/issues?state=all&direction=asc&sort=created&per_page=100&page=1"
-            )),
-            buffer: vec![],
-            auth: self.auth.clone(),
+    /// Sends the requests to the given API base URL instead of the public GitHub one, as in
+    /// `https://github.mycorp.com/api/v3` for GitHub Enterprise.
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub async fn get_repository(
+        &self,
+        organization: &str,
+        repo: &str,
+    ) -> Result<Value, Box<dyn Error>> {
+        let url = format!("{}/repos/{organization}/{repo}", self.api_url);
+
+        debug!(target: "dean::github_client", "Fetching repository from {}", url);
+        let request = self
+            .http
+            .get(&url)
+            .header("Accept", "application/vnd.github.v3+json");
+
+        let request = self.auth.authenticate(request);
+
+        let response = request
+            .send()
+            .await
+            .context("Failed to get repository")?
+            .error_for_status()
+            .context("Failed to get repository")?;
+
+        let repository = response
+            .json::<Value>()
+            .await
+            .context("Failed to parse repository")?;
+
+        Ok(repository)
+    }
+
+    /// Returns whether the file exists in the default branch of the repository.
+    pub async fn has_file(
+        &self,
+        organization: &str,
+        repo: &str,
+        path: &str,
+    ) -> Result<bool, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{organization}/{repo}/contents/{path}",
+            self.api_url
+        );
+
+        debug!(target: "dean::github_client", "Checking the file {}", url);
+        let request = self
+            .http
+            .get(&url)
+            .header("Accept", "application/vnd.github.v3+json");
+
+        let request = self.auth.authenticate(request);
+
+        let response = request.send().await.context("Failed to get file")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
         }
+        response.error_for_status().context("Failed to get file")?;
+
+        Ok(true)
+    }
+
+    /// Iterates over the issues and pull requests, the most recently created first, so that the
+    /// pages are only requested until enough of them are retrieved.
+    fn all_issues_iterator(&self, organization: &str, repo: &str) -> Paginator<IssuePages> {
+        Paginator::new(
+            self.http.clone(),
+            IssuePages {
+                auth: self.auth.clone(),
+            },
+            self.all_issues_url(organization, repo),
+        )
+    }
+
+    fn all_issues_url(&self, organization: &str, repo: &str) -> String {
+        format!(
+            "{}/repos/{organization}/{repo}/issues?state=all&direction=desc&sort=created&per_page=100&page=1",
+            self.api_url
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use log::info;
+    use reqwest::header::HeaderValue;
     use time::format_description::well_known::Rfc3339;
 
     use super::*;
+    use crate::infra::http;
 
     #[tokio::test]
     async fn it_retrieves_the_issues_from_dean_from_newer_to_older() {
-        let client = Client::new(reqwest::Client::new(), authentication());
+        let client = Client::new(http::default_client(), authentication());
 
         let issues = client
-            .get_last_issues("StaticDependencyAnalyzer", "dean")
+            .get_last_issues("StaticDependencyAnalyzer", "dean", IssueWindow::Last(100))
             .await
-            .take(100)
             .collect::<Vec<_>>()
             .await;
 
@@ -217,12 +226,11 @@ mod tests {
 
     #[tokio::test]
     async fn it_retrieves_the_pull_requests_from_dean_from_newer_to_older() {
-        let client = Client::new(reqwest::Client::new(), authentication());
+        let client = Client::new(http::default_client(), authentication());
 
         let prs = client
-            .get_last_pull_requests("StaticDependencyAnalyzer", "dean")
+            .get_last_pull_requests("StaticDependencyAnalyzer", "dean", IssueWindow::Last(100))
             .await
-            .take(100)
             .collect::<Vec<_>>()
             .await;
 
@@ -232,15 +240,19 @@ mod tests {
 
     #[tokio::test]
     async fn it_retrieves_150_issues_from_rust_lang() {
-        let client = Client::new(reqwest::Client::new(), authentication());
+        let client = Client::new(http::default_client(), authentication());
 
-        let issues = client.get_last_issues("rust-lang", "rust").await.take(150);
-        let issue_count = issues.take(150).count().await;
+        let issues = client
+            .get_last_issues("rust-lang", "rust", IssueWindow::Last(150))
+            .await;
+        let issue_count = issues.count().await;
         assert!(issue_count > 0);
         assert!(issue_count <= 150);
 
         info!("New issues!");
-        let mut issues = client.get_last_issues("rust-lang", "rust").await.take(150);
+        let mut issues = client
+            .get_last_issues("rust-lang", "rust", IssueWindow::Last(150))
+            .await;
         assert_eq!(
             issues
                 .next()
@@ -255,6 +267,72 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn it_retrieves_the_repository_of_dean() {
+        let client = Client::new(http::default_client(), authentication());
+
+        let repository = client
+            .get_repository("StaticDependencyAnalyzer", "dean")
+            .await
+            .unwrap();
+
+        assert_eq!(repository["full_name"], "StaticDependencyAnalyzer/dean");
+    }
+
+    #[test]
+    fn it_builds_the_request_urls_from_the_configured_api_url() {
+        let client = Client::new(http::default_client(), Authentication::None)
+            .with_api_url("https://github.mycorp.com/api/v3/");
+
+        assert_eq!(
+            client.all_issues_url("some_org", "some_repo"),
+            "https://github.mycorp.com/api/v3/repos/some_org/some_repo/issues?state=all&direction=desc&sort=created&per_page=100&page=1"
+        );
+    }
+
+    #[test]
+    fn token_authentication_sets_a_bearer_authorization_header() {
+        let request = Authentication::Token("some_token".to_string())
+            .authenticate(reqwest::Client::new().get("https://api.github.com"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.headers()["Authorization"],
+            HeaderValue::from_static("Bearer some_token")
+        );
+    }
+
+    #[test]
+    fn it_throttles_when_the_remaining_rate_limit_is_low() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(4));
+        headers.insert("x-ratelimit-reset", HeaderValue::from(1_100));
+
+        let throttle_duration =
+            issue_pages().throttle_duration(&headers, Duration::from_secs(1_000));
+
+        assert_eq!(throttle_duration, Some(Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn it_does_not_throttle_while_there_is_enough_rate_limit() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(4_000));
+        headers.insert("x-ratelimit-reset", HeaderValue::from(1_100));
+
+        let throttle_duration =
+            issue_pages().throttle_duration(&headers, Duration::from_secs(1_000));
+
+        assert_eq!(throttle_duration, None);
+    }
+
+    fn issue_pages() -> IssuePages {
+        IssuePages {
+            auth: Authentication::None,
+        }
+    }
+
     fn creation_timestamp(issue_or_pr: &Value) -> i64 {
         let created_at_str = issue_or_pr["created_at"].as_str().unwrap();
         time::OffsetDateTime::parse(created_at_str, &Rfc3339)
@@ -263,6 +341,10 @@ mod tests {
     }
 
     fn authentication() -> Authentication {
+        if let Ok(github_token) = std::env::var("GITHUB_TOKEN") {
+            return Authentication::Token(github_token);
+        }
+
         let github_username = std::env::var("GITHUB_USERNAME").ok();
         let github_password = std::env::var("GITHUB_PASSWORD").ok();
 
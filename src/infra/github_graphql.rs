@@ -0,0 +1,274 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use log::{debug, trace};
+use serde_json::{json, Value};
+use tokio_stream::Stream;
+
+use crate::infra::cached_issue_client::{take_window, IssueClient};
+use crate::pkg::policy::IssueWindow;
+
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Retrieves the issues and pull requests through the GitHub GraphQL API, which returns only the
+/// fields the policies need and takes fewer requests than the REST API. It requires a token.
+pub struct Client {
+    http: Arc<reqwest::Client>,
+    token: String,
+    graphql_url: String,
+}
+
+impl Client {
+    pub fn new<C>(client: C, token: &str) -> Self
+    where
+        C: Into<Arc<reqwest::Client>>,
+    {
+        Self {
+            http: client.into(),
+            token: token.to_string(),
+            graphql_url: graphql_url(super::github::DEFAULT_API_URL),
+        }
+    }
+
+    /// Sends the requests to the GraphQL API next to the given REST API base URL, as in
+    /// `https://github.mycorp.com/api/graphql` for `https://github.mycorp.com/api/v3`.
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.graphql_url = graphql_url(api_url);
+        self
+    }
+
+    /// Streams the nodes in the window of the `issues` or `pullRequests` connection of the
+    /// repository, the most recently created first, requesting the following page when the
+    /// previous one is consumed.
+    fn nodes(
+        &self,
+        organization: &str,
+        repo: &str,
+        connection: &'static str,
+        window: IssueWindow,
+    ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
+        let pages = Pages {
+            http: self.http.clone(),
+            token: self.token.clone(),
+            graphql_url: self.graphql_url.clone(),
+            organization: organization.to_string(),
+            repo: repo.to_string(),
+            connection,
+            page_size: window.page_size(MAX_PAGE_SIZE),
+            cursor: None,
+            has_next_page: true,
+            buffer: VecDeque::new(),
+        };
+
+        let nodes = futures::stream::unfold(pages, |mut pages| async move {
+            if pages.buffer.is_empty() && pages.has_next_page {
+                if let Err(e) = pages.fetch_next_page().await {
+                    debug!(target: "dean::github_graphql_client", "Failed to fetch {}: {}", pages.connection, e);
+                    return None;
+                }
+            }
+            let node = pages.buffer.pop_front()?;
+            Some((node, pages))
+        });
+        take_window(nodes, window)
+    }
+}
+
+#[async_trait]
+impl IssueClient for Client {
+    async fn get_last_issues(
+        &self,
+        organization: &str,
+        repo: &str,
+        window: IssueWindow,
+    ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
+        self.nodes(organization, repo, "issues", window)
+    }
+
+    async fn get_last_pull_requests(
+        &self,
+        organization: &str,
+        repo: &str,
+        window: IssueWindow,
+    ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
+        self.nodes(organization, repo, "pullRequests", window)
+    }
+}
+
+struct Pages {
+    http: Arc<reqwest::Client>,
+    token: String,
+    graphql_url: String,
+    organization: String,
+    repo: String,
+    connection: &'static str,
+    page_size: usize,
+    cursor: Option<String>,
+    has_next_page: bool,
+    buffer: VecDeque<Value>,
+}
+
+impl Pages {
+    async fn fetch_next_page(&mut self) -> Result<(), Box<dyn Error>> {
+        let query = format!(
+            "query($owner: String!, $name: String!, $cursor: String) {{ repository(owner: $owner, name: $name) {{ items: {}(first: {}, after: $cursor, orderBy: {{field: CREATED_AT, direction: DESC}}) {{ pageInfo {{ hasNextPage endCursor }} nodes {{ __typename createdAt closedAt state }} }} }} }}",
+            self.connection, self.page_size
+        );
+        let body = json!({
+            "query": query,
+            "variables": {
+                "owner": self.organization,
+                "name": self.repo,
+                "cursor": self.cursor,
+            },
+        });
+
+        debug!(target: "dean::github_graphql_client", "Fetching {} of {}/{}", self.connection, self.organization, self.repo);
+        let response = self
+            .http
+            .post(&self.graphql_url)
+            .bearer_auth(&self.token)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to query the GraphQL API")?
+            .error_for_status()
+            .context("Failed to query the GraphQL API")?
+            .json::<Value>()
+            .await
+            .context("Failed to parse the GraphQL response")?;
+        trace!(target: "dean::github_graphql_client", "Response: {:?}", response);
+
+        let (nodes, next_cursor) = parse_page(&response)?;
+        self.buffer.extend(nodes);
+        self.has_next_page = next_cursor.is_some();
+        self.cursor = next_cursor;
+
+        Ok(())
+    }
+}
+
+/// Extracts the nodes of a page, converted to the fields of the REST API, and the cursor of the
+/// next page if there is one.
+fn parse_page(response: &Value) -> Result<(Vec<Value>, Option<String>), Box<dyn Error>> {
+    if let Some(errors) = response.get("errors") {
+        return Err(format!("the GraphQL API returned errors: {errors}").into());
+    }
+
+    let items = response
+        .pointer("/data/repository/items")
+        .context("the GraphQL response doesn't contain the repository items")?;
+    let nodes = items
+        .get("nodes")
+        .and_then(Value::as_array)
+        .context("the GraphQL response doesn't contain the nodes")?
+        .iter()
+        .map(|node| {
+            json!({
+                "state": node.get("state").and_then(Value::as_str).unwrap_or_default().to_lowercase(),
+                "created_at": node.get("createdAt"),
+                "closed_at": node.get("closedAt"),
+                "type": node.get("__typename"),
+            })
+        })
+        .collect();
+
+    let has_next_page = items
+        .pointer("/pageInfo/hasNextPage")
+        .and_then(Value::as_bool)
+        .unwrap_or_default();
+    let next_cursor = items
+        .pointer("/pageInfo/endCursor")
+        .and_then(Value::as_str)
+        .filter(|_| has_next_page)
+        .map(ToString::to_string);
+
+    Ok((nodes, next_cursor))
+}
+
+/// The GraphQL API is served next to the REST one: at `/graphql` of the public API, and at
+/// `/api/graphql` in GitHub Enterprise, whose REST API is at `/api/v3`.
+fn graphql_url(api_url: &str) -> String {
+    let api_url = api_url.trim_end_matches('/');
+    match api_url.strip_suffix("/v3") {
+        Some(base_url) => format!("{base_url}/graphql"),
+        None => format!("{api_url}/graphql"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_the_nodes_of_a_page_to_the_fields_of_the_rest_api() {
+        let response = json!({
+            "data": {
+                "repository": {
+                    "items": {
+                        "pageInfo": {"hasNextPage": true, "endCursor": "Y3Vyc29yOjI="},
+                        "nodes": [
+                            {"__typename": "PullRequest", "createdAt": "2022-01-01T00:00:00Z", "closedAt": "2022-01-02T00:00:00Z", "state": "MERGED"},
+                            {"__typename": "PullRequest", "createdAt": "2022-01-03T00:00:00Z", "closedAt": null, "state": "OPEN"},
+                        ],
+                    },
+                },
+            },
+        });
+
+        let (nodes, next_cursor) = parse_page(&response).unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![
+                json!({"state": "merged", "created_at": "2022-01-01T00:00:00Z", "closed_at": "2022-01-02T00:00:00Z", "type": "PullRequest"}),
+                json!({"state": "open", "created_at": "2022-01-03T00:00:00Z", "closed_at": null, "type": "PullRequest"}),
+            ]
+        );
+        assert_eq!(next_cursor, Some("Y3Vyc29yOjI=".to_string()));
+    }
+
+    #[test]
+    fn it_stops_at_the_last_page() {
+        let response = json!({
+            "data": {
+                "repository": {
+                    "items": {
+                        "pageInfo": {"hasNextPage": false, "endCursor": "Y3Vyc29yOjI="},
+                        "nodes": [],
+                    },
+                },
+            },
+        });
+
+        let (nodes, next_cursor) = parse_page(&response).unwrap();
+
+        assert!(nodes.is_empty());
+        assert_eq!(next_cursor, None);
+    }
+
+    #[test]
+    fn it_fails_if_the_response_has_errors() {
+        let response = json!({
+            "data": {"repository": null},
+            "errors": [{"type": "NOT_FOUND", "message": "Could not resolve to a Repository"}],
+        });
+
+        assert!(parse_page(&response).is_err());
+    }
+
+    #[test]
+    fn it_builds_the_graphql_url_from_the_rest_api_url() {
+        assert_eq!(
+            graphql_url("https://api.github.com"),
+            "https://api.github.com/graphql"
+        );
+        assert_eq!(
+            graphql_url("https://github.mycorp.com/api/v3/"),
+            "https://github.mycorp.com/api/graphql"
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::RequestBuilder;
+use serde_json::Value;
+use tokio_stream::Stream;
+
+use crate::infra::cached_issue_client::{take_window, IssueClient};
+use crate::infra::paginator::{Pagination, Paginator};
+use crate::pkg::policy::IssueWindow;
+
+pub const DEFAULT_API_URL: &str = "https://gitlab.com/api/v4";
+const MAX_PER_PAGE: usize = 100;
+
+#[allow(clippy::struct_field_names)]
+pub struct Client {
+    client: Arc<reqwest::Client>,
+    api_url: String,
+    token: Option<String>,
+}
+
+/// Pages of the issues and merge requests of the REST API, which answers with a 429 once the rate
+/// limit is exhausted.
+pub struct ProjectPages {
+    token: Option<String>,
+}
+
+impl Pagination for ProjectPages {
+    const LOG_TARGET: &'static str = "dean::gitlab_client";
+    const RATE_LIMITED_STATUS: u16 = 429;
+    const RATE_LIMIT_RESET_HEADER: &'static str = "ratelimit-reset";
+
+    /// Builds the request of the page, authenticated with the token if any, which is required to
+    /// read the issues of private projects.
+    fn request(&self, client: &reqwest::Client, url: &str) -> RequestBuilder {
+        let request = client.get(url);
+        match &self.token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    }
+}
+
+#[async_trait]
+impl IssueClient for Client {
+    async fn get_last_issues(
+        &self,
+        organization: &str,
+        repo: &str,
+        window: IssueWindow,
+    ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
+        let issues = self
+            .project_resource_iterator(organization, repo, "issues", window)
+            .into_stream();
+        take_window(issues, window)
+    }
+
+    async fn get_last_pull_requests(
+        &self,
+        organization: &str,
+        repo: &str,
+        window: IssueWindow,
+    ) -> Box<dyn Stream<Item = Value> + Unpin + Send> {
+        let merge_requests = self
+            .project_resource_iterator(organization, repo, "merge_requests", window)
+            .into_stream();
+        take_window(merge_requests, window)
+    }
+}
+
+impl Client {
+    pub fn new<C>(client: C) -> Self
+    where
+        C: Into<Arc<reqwest::Client>>,
+    {
+        Self {
+            client: client.into(),
+            api_url: DEFAULT_API_URL.to_string(),
+            token: None,
+        }
+    }
+
+    /// Authenticates the requests with a personal, project or group access token.
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Sends the requests to the given API base URL instead of the public GitLab one, as in
+    /// `https://gitlab.mycorp.com/api/v4` for a self-hosted instance.
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Iterates over the issues or merge requests, the most recently created first, in pages no
+    /// bigger than the number of them retrieved, if the window is bounded by a number.
+    fn project_resource_iterator(
+        &self,
+        organization: &str,
+        repo: &str,
+        resource: &str,
+        window: IssueWindow,
+    ) -> Paginator<ProjectPages> {
+        Paginator::new(
+            self.client.clone(),
+            ProjectPages {
+                token: self.token.clone(),
+            },
+            self.project_resource_url(organization, repo, resource, window),
+        )
+    }
+
+    /// The project is identified by its full path, including the subgroups of its organization,
+    /// as in `group%2Fsubgroup%2Frepo`.
+    fn project_resource_url(
+        &self,
+        organization: &str,
+        repo: &str,
+        resource: &str,
+        window: IssueWindow,
+    ) -> String {
+        let per_page = window.page_size(MAX_PER_PAGE);
+        format!(
+            "{}/projects/{}/{resource}?scope=all&state=all&order_by=created_at&sort=desc&per_page={per_page}&page=1",
+            self.api_url,
+            encode_project_path(&format!("{organization}/{repo}"))
+        )
+    }
+}
+
+/// Percent-encodes every character of the path of the project but the unreserved ones, so that it
+/// is a single segment of the URL.
+fn encode_project_path(path: &str) -> String {
+    path.bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use time::format_description::well_known::Rfc3339;
+
+    use super::*;
+    use crate::infra::http;
+
+    #[tokio::test]
+    async fn it_retrieves_the_issues_from_bfj_from_newer_to_older() {
+        let client = Client::new(http::default_client());
+
+        let issues = client
+            .get_last_issues("philbooth", "bfj", IssueWindow::Last(100))
+            .await
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(issues.len() >= 2);
+        assert!(creation_timestamp(&issues[0]) > creation_timestamp(&issues[1]));
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_merge_requests_from_bfj_from_newer_to_older() {
+        let client = Client::new(http::default_client());
+
+        let merge_requests = client
+            .get_last_pull_requests("philbooth", "bfj", IssueWindow::Last(100))
+            .await
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(merge_requests.len() >= 2);
+        assert!(creation_timestamp(&merge_requests[0]) > creation_timestamp(&merge_requests[1]));
+    }
+
+    #[test]
+    fn it_builds_the_request_urls_from_the_configured_api_url() {
+        let client =
+            Client::new(http::default_client()).with_api_url("https://gitlab.mycorp.com/api/v4");
+
+        let url =
+            client.project_resource_url("some_org", "some_repo", "issues", IssueWindow::Last(10));
+
+        assert_eq!(
+            url,
+            "https://gitlab.mycorp.com/api/v4/projects/some_org%2Fsome_repo/issues?scope=all&state=all&order_by=created_at&sort=desc&per_page=10&page=1"
+        );
+    }
+
+    #[test]
+    fn it_encodes_the_full_path_of_the_projects_of_subgroups() {
+        let client = Client::new(http::default_client());
+
+        let url = client.project_resource_url(
+            "some_group/some_subgroup",
+            "some_repo",
+            "merge_requests",
+            IssueWindow::Last(10),
+        );
+
+        assert_eq!(
+            url,
+            "https://gitlab.com/api/v4/projects/some_group%2Fsome_subgroup%2Fsome_repo/merge_requests?scope=all&state=all&order_by=created_at&sort=desc&per_page=10&page=1"
+        );
+    }
+
+    #[test]
+    fn it_authenticates_the_requests_with_the_token_if_any() {
+        let url = "https://gitlab.com/api/v4/projects/philbooth%2Fbfj/issues";
+        let client = http::default_client();
+
+        let authenticated_request = ProjectPages {
+            token: Some("some_token".to_string()),
+        }
+        .request(&client, url)
+        .build()
+        .unwrap();
+        let anonymous_request = ProjectPages { token: None }
+            .request(&client, url)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            authenticated_request.headers()["private-token"],
+            "some_token"
+        );
+        assert!(anonymous_request.headers().get("private-token").is_none());
+    }
+
+    fn creation_timestamp(issue_or_merge_request: &Value) -> i64 {
+        let created_at_str = issue_or_merge_request["created_at"].as_str().unwrap();
+        time::OffsetDateTime::parse(created_at_str, &Rfc3339)
+            .unwrap()
+            .unix_timestamp()
+    }
+}
@@ -0,0 +1,112 @@
+/// User-Agent sent in every request to the package registries and the GitHub and GitLab APIs,
+/// unless the config file sets another one.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "dean/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/StaticDependencyAnalyzer/dean)"
+);
+
+/// Builds a client sending the default User-Agent, for the retrievers built without the factory.
+pub fn default_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(DEFAULT_USER_AGENT)
+        .build()
+        .expect("unable to create the reqwest client")
+}
+
+/// Proxy of the requests to the URL, taken from the `HTTPS_PROXY` environment variable, or
+/// `HTTP_PROXY` for plain HTTP URLs, unless the host of the URL is listed in `NO_PROXY`.
+pub fn proxy_for(url: &reqwest::Url) -> Option<String> {
+    let proxy_var = match url.scheme() {
+        "https" => "HTTPS_PROXY",
+        "http" => "HTTP_PROXY",
+        _ => return None,
+    };
+    let proxy = env_var(proxy_var)?;
+    let no_proxy = env_var("NO_PROXY").unwrap_or_default();
+
+    (!is_excluded_from_proxy(url, &no_proxy)).then_some(proxy)
+}
+
+/// Reads the environment variable, accepting its lowercase name too, as curl and git do.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .or_else(|_| std::env::var(name.to_lowercase()))
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+/// Whether the host of the URL matches any of the comma separated domains of `no_proxy`,
+/// including their subdomains, or `no_proxy` is `*`.
+fn is_excluded_from_proxy(url: &reqwest::Url, no_proxy: &str) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+
+    no_proxy
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            let domain = entry.trim_start_matches('.');
+            entry == "*" || host == domain || host.ends_with(&format!(".{domain}"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_excludes_the_hosts_listed_in_no_proxy_and_their_subdomains() {
+        let no_proxy = "localhost, .mycorp.com,gitlab.internal";
+
+        assert!(is_excluded_from_proxy(
+            &url("https://localhost:8080/api"),
+            no_proxy
+        ));
+        assert!(is_excluded_from_proxy(
+            &url("https://github.mycorp.com/api/v3"),
+            no_proxy
+        ));
+        assert!(is_excluded_from_proxy(
+            &url("https://gitlab.internal/api/v4"),
+            no_proxy
+        ));
+        assert!(!is_excluded_from_proxy(
+            &url("https://api.github.com/repos"),
+            no_proxy
+        ));
+        assert!(!is_excluded_from_proxy(
+            &url("https://notmycorp.com"),
+            no_proxy
+        ));
+        assert!(!is_excluded_from_proxy(
+            &url("https://registry.npmjs.org"),
+            ""
+        ));
+    }
+
+    #[test]
+    fn it_excludes_every_host_when_no_proxy_is_a_wildcard() {
+        assert!(is_excluded_from_proxy(
+            &url("https://registry.npmjs.org"),
+            "*"
+        ));
+    }
+
+    fn url(url: &str) -> reqwest::Url {
+        reqwest::Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn the_default_user_agent_identifies_dean_and_its_version() {
+        assert_eq!(
+            DEFAULT_USER_AGENT,
+            format!(
+                "dean/{} (+https://github.com/StaticDependencyAnalyzer/dean)",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use log::warn;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+use crate::pkg::PackageNotFound;
+
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Sends the request, retrying it up to `max_retries` times with an exponential backoff when the
+/// connection fails, times out, or the server answers with a 5xx or a 429. The `Retry-After`
+/// header of the response is honored when present.
+pub async fn send_with_retries(
+    request: RequestBuilder,
+    max_retries: u32,
+) -> reqwest::Result<Response> {
+    let mut attempt = 0;
+    loop {
+        let Some(attempt_request) = request.try_clone() else {
+            return request.send().await;
+        };
+
+        let result = attempt_request.send().await;
+        let delay = match &result {
+            Ok(response) if is_retryable(response.status()) => {
+                retry_delay(attempt, retry_after(response.headers()))
+            }
+            Err(e) if e.is_connect() || e.is_timeout() => retry_delay(attempt, None),
+            _ => return result,
+        };
+
+        if attempt >= max_retries {
+            return result;
+        }
+
+        warn!(
+            target: "dean::http_retry",
+            "request failed ({}), retrying in {:?}",
+            result.as_ref().map_or_else(ToString::to_string, |r| r.status().to_string()),
+            delay
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Fails with `PackageNotFound` when the registry answers with a 404, which is cached unlike the
+/// other errors, as the package won't appear during the scan.
+pub fn error_if_not_found(response: Response, package_name: &str) -> anyhow::Result<Response> {
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(anyhow::Error::new(PackageNotFound {
+            package_name: package_name.to_string(),
+        }));
+    }
+    Ok(response)
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn retry_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after.unwrap_or_else(|| BASE_DELAY * 2_u32.saturating_pow(attempt))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn it_retries_only_server_errors_and_rate_limits() {
+        assert!(is_retryable(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable(StatusCode::OK));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn it_backs_off_exponentially_unless_the_server_says_when_to_retry() {
+        assert_eq!(retry_delay(0, None), Duration::from_millis(500));
+        assert_eq!(retry_delay(1, None), Duration::from_secs(1));
+        assert_eq!(retry_delay(2, None), Duration::from_secs(2));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(
+            retry_delay(2, retry_after(&headers)),
+            Duration::from_secs(7)
+        );
+    }
+}
@@ -1,26 +1,39 @@
 use std::error::Error;
-use std::sync::{Arc, Mutex};
 
 use serde_json::Value;
 
+use crate::infra::cache_store::{self, CacheStore, Pool, TableStats};
 use crate::infra::cached_issue_client::IssueStore;
+use crate::pkg::policy;
+
+const TABLES: [&str; 2] = ["issuestore_issue", "issuestore_pullrequest"];
 
 pub struct Sqlite {
-    connection: Arc<Mutex<rusqlite::Connection>>,
+    pool: Pool,
+    ttl_in_seconds: u64,
+    clock: Box<dyn policy::Clock>,
 }
 
 impl Sqlite {
-    pub fn new<C>(connection: C) -> Self
-    where
-        C: Into<Arc<Mutex<rusqlite::Connection>>>,
-    {
+    pub fn new(pool: Pool, ttl_in_seconds: u64, clock: Box<dyn policy::Clock>) -> Self {
         Self {
-            connection: connection.into(),
+            pool,
+            ttl_in_seconds,
+            clock,
         }
     }
 
+    fn now_timestamp(&self) -> u64 {
+        self.clock.now_timestamp()
+    }
+
+    fn oldest_valid_timestamp(&self) -> u64 {
+        self.now_timestamp().saturating_sub(self.ttl_in_seconds)
+    }
+
     pub fn init(&self) -> Result<(), Box<dyn Error>> {
-        let conn = self.connection.lock().map_err(|e| e.to_string())?;
+        let conn = self.pool.get()?;
+        cache_store::enable_wal(&conn)?;
         conn.execute_batch(
             r#"
 CREATE TABLE IF NOT EXISTS issuestore_issue (
@@ -28,6 +41,7 @@ CREATE TABLE IF NOT EXISTS issuestore_issue (
     organization TEXT NOT NULL,
     repo TEXT NOT NULL,
     issue_body TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL DEFAULT 0,
     PRIMARY KEY (provider, organization, repo, issue_body)
 );
 
@@ -36,28 +50,57 @@ CREATE TABLE IF NOT EXISTS issuestore_pullrequest (
     organization TEXT NOT NULL,
     repo TEXT NOT NULL,
     pullrequest_body TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL DEFAULT 0,
     PRIMARY KEY (provider, organization, repo, pullrequest_body)
 );
 "#,
         )?;
+
+        for table in TABLES {
+            cache_store::add_fetched_at_column_if_missing(&conn, table)?;
+        }
+
         Ok(())
     }
 }
 
+impl CacheStore for Sqlite {
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        cache_store::clear_tables(&conn, &TABLES)?;
+        Ok(())
+    }
+
+    fn stats(&self) -> Result<Vec<TableStats>, Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        let stats = cache_store::table_stats(&conn, &TABLES, self.oldest_valid_timestamp())?;
+        Ok(stats)
+    }
+
+    fn export(&self) -> Result<Vec<Value>, Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        let rows = cache_store::export_tables(&conn, &TABLES, &["issue_body", "pullrequest_body"])?;
+        Ok(rows)
+    }
+}
+
 impl IssueStore for Sqlite {
     fn get_issues(&self, provider: &str, organization: &str, repo: &str) -> Option<Vec<Value>> {
-        let conn = self.connection.lock().unwrap();
+        let conn = self.pool.get().ok()?;
         let mut stmt = conn.prepare(
-            "SELECT issue_body FROM issuestore_issue WHERE provider = ? AND organization = ? AND repo = ?",
+            "SELECT issue_body FROM issuestore_issue WHERE provider = ? AND organization = ? AND repo = ? AND fetched_at >= ?",
         ).unwrap();
 
         let rows = stmt
-            .query_map([provider, organization, repo], |row| {
-                let value_str: String = row.get(0)?;
-                let value: Value = serde_json::from_str(&value_str)
-                    .map_err(|e| rusqlite::Error::InvalidParameterName(e.to_string()))?;
-                Ok(value)
-            })
+            .query_map(
+                rusqlite::params![provider, organization, repo, self.oldest_valid_timestamp()],
+                |row| {
+                    let value_str: String = row.get(0)?;
+                    let value: Value = serde_json::from_str(&value_str)
+                        .map_err(|e| rusqlite::Error::InvalidParameterName(e.to_string()))?;
+                    Ok(value)
+                },
+            )
             .unwrap()
             .flatten();
 
@@ -76,12 +119,19 @@ impl IssueStore for Sqlite {
         repo: &str,
         issues: &[Value],
     ) -> Result<(), Box<dyn Error>> {
-        let mut conn = self.connection.lock().unwrap();
+        let mut conn = self.pool.get()?;
+
+        let fetched_at = self.now_timestamp();
 
         let tx = conn.transaction()?;
         {
+            tx.execute(
+                "DELETE FROM issuestore_issue WHERE provider = ? AND organization = ? AND repo = ?",
+                [provider, organization, repo],
+            )?;
+
             let mut stmt = tx.prepare(
-                "INSERT OR IGNORE INTO issuestore_issue (provider, organization, repo, issue_body) VALUES (?, ?, ?, ?)",
+                "INSERT OR IGNORE INTO issuestore_issue (provider, organization, repo, issue_body, fetched_at) VALUES (?, ?, ?, ?, ?)",
             ).unwrap();
             for issue in issues {
                 stmt.execute([
@@ -89,6 +139,7 @@ impl IssueStore for Sqlite {
                     organization,
                     repo,
                     serde_json::to_string(issue).unwrap().as_str(),
+                    fetched_at.to_string().as_str(),
                 ])?;
             }
         }
@@ -103,18 +154,21 @@ impl IssueStore for Sqlite {
         organization: &str,
         repo: &str,
     ) -> Option<Vec<Value>> {
-        let conn = self.connection.lock().unwrap();
+        let conn = self.pool.get().ok()?;
         let mut stmt = conn.prepare(
-            "SELECT pullrequest_body FROM issuestore_pullrequest WHERE provider = ? AND organization = ? AND repo = ?",
+            "SELECT pullrequest_body FROM issuestore_pullrequest WHERE provider = ? AND organization = ? AND repo = ? AND fetched_at >= ?",
         ).unwrap();
 
         let rows = stmt
-            .query_map([provider, organization, repo], |row| {
-                let value_str: String = row.get(0)?;
-                let value: Value = serde_json::from_str(&value_str)
-                    .map_err(|e| rusqlite::Error::InvalidParameterName(e.to_string()))?;
-                Ok(value)
-            })
+            .query_map(
+                rusqlite::params![provider, organization, repo, self.oldest_valid_timestamp()],
+                |row| {
+                    let value_str: String = row.get(0)?;
+                    let value: Value = serde_json::from_str(&value_str)
+                        .map_err(|e| rusqlite::Error::InvalidParameterName(e.to_string()))?;
+                    Ok(value)
+                },
+            )
             .unwrap()
             .flatten();
 
@@ -133,12 +187,19 @@ impl IssueStore for Sqlite {
         repo: &str,
         pull_requests: &[Value],
     ) -> Result<(), Box<dyn Error>> {
-        let mut conn = self.connection.lock().unwrap();
+        let mut conn = self.pool.get()?;
+
+        let fetched_at = self.now_timestamp();
 
         let tx = conn.transaction()?;
         {
+            tx.execute(
+                "DELETE FROM issuestore_pullrequest WHERE provider = ? AND organization = ? AND repo = ?",
+                [provider, organization, repo],
+            )?;
+
             let mut stmt = tx.prepare(
-                "INSERT OR IGNORE INTO issuestore_pullrequest (provider, organization, repo, pullrequest_body) VALUES (?, ?, ?, ?)",
+                "INSERT OR IGNORE INTO issuestore_pullrequest (provider, organization, repo, pullrequest_body, fetched_at) VALUES (?, ?, ?, ?, ?)",
             ).unwrap();
             for pr in pull_requests {
                 stmt.execute([
@@ -146,6 +207,7 @@ impl IssueStore for Sqlite {
                     organization,
                     repo,
                     serde_json::to_string(pr).unwrap().as_str(),
+                    fetched_at.to_string().as_str(),
                 ])?;
             }
         }
@@ -157,14 +219,18 @@ impl IssueStore for Sqlite {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Mutex;
-
     use super::*;
+    use crate::infra::clock::FixedClock;
+
+    const NOW: u64 = 1_700_000_000;
 
     #[test]
     fn it_stores_and_retrieves_the_issues() {
-        let connection = Mutex::new(rusqlite::Connection::open_in_memory().unwrap());
-        let issue_store = Sqlite::new(connection);
+        let issue_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
         issue_store.init().unwrap();
 
         issue_store
@@ -179,8 +245,11 @@ mod tests {
 
     #[test]
     fn it_stores_and_retrieves_the_pull_requests() {
-        let connection = Mutex::new(rusqlite::Connection::open_in_memory().unwrap());
-        let issue_store = Sqlite::new(connection);
+        let issue_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
         issue_store.init().unwrap();
 
         issue_store
@@ -195,8 +264,11 @@ mod tests {
 
     #[test]
     fn if_there_are_no_issues_it_returns_none() {
-        let connection = Mutex::new(rusqlite::Connection::open_in_memory().unwrap());
-        let issue_store = Sqlite::new(connection);
+        let issue_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
         issue_store.init().unwrap();
 
         let issues = issue_store.get_issues("github", "unknown", "unknown");
@@ -206,8 +278,11 @@ mod tests {
 
     #[test]
     fn if_there_are_no_pull_requests_it_returns_none() {
-        let connection = Mutex::new(rusqlite::Connection::open_in_memory().unwrap());
-        let issue_store = Sqlite::new(connection);
+        let issue_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
         issue_store.init().unwrap();
 
         let pull_requests = issue_store.get_pull_requests("github", "unknown", "unknown");
@@ -215,6 +290,93 @@ mod tests {
         assert_eq!(pull_requests, None);
     }
 
+    #[test]
+    fn if_the_issues_are_expired_it_returns_none() {
+        let issue_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
+        issue_store.init().unwrap();
+
+        issue_store
+            .save_issues("github", "rust-lang", "rust", &issues_in_repo())
+            .unwrap();
+        issue_store
+            .pool
+            .get()
+            .unwrap()
+            .execute("UPDATE issuestore_issue SET fetched_at = 0", [])
+            .unwrap();
+        let issues = issue_store.get_issues("github", "rust-lang", "rust");
+
+        assert_eq!(issues, None);
+    }
+
+    #[test]
+    fn it_expires_the_pull_requests_once_the_ttl_has_elapsed() {
+        let pool = cache_store::in_memory_pool().unwrap();
+        let issue_store = Sqlite::new(pool.clone(), 3600, Box::new(FixedClock::new(NOW)));
+        issue_store.init().unwrap();
+        issue_store
+            .save_pull_requests("github", "rust-lang", "rust", &pull_requests_in_repo())
+            .unwrap();
+
+        let issue_store_within_ttl =
+            Sqlite::new(pool.clone(), 3600, Box::new(FixedClock::new(NOW + 3600)));
+        let issue_store_after_ttl = Sqlite::new(pool, 3600, Box::new(FixedClock::new(NOW + 3601)));
+
+        assert_eq!(
+            issue_store_within_ttl.get_pull_requests("github", "rust-lang", "rust"),
+            Some(pull_requests_in_repo())
+        );
+        assert_eq!(
+            issue_store_after_ttl.get_pull_requests("github", "rust-lang", "rust"),
+            None
+        );
+    }
+
+    #[test]
+    fn it_reports_the_stats_and_clears_the_stored_issues() {
+        let issue_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
+        issue_store.init().unwrap();
+        issue_store
+            .save_issues("github", "rust-lang", "rust", &issues_in_repo())
+            .unwrap();
+
+        let stats_before_clearing = issue_store.stats().unwrap();
+        issue_store.clear().unwrap();
+        let stats_after_clearing = issue_store.stats().unwrap();
+
+        assert_eq!(stats_before_clearing[0].rows, 2);
+        assert_eq!(stats_after_clearing[0].rows, 0);
+    }
+
+    #[test]
+    fn it_exports_the_stored_issues_with_their_repository() {
+        let issue_store = Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(FixedClock::new(NOW)),
+        );
+        issue_store.init().unwrap();
+        issue_store
+            .save_issues("github", "rust-lang", "rust", &issues_in_repo())
+            .unwrap();
+
+        let rows = issue_store.export().unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["table"], "issuestore_issue");
+        assert_eq!(rows[0]["organization"], "rust-lang");
+        assert_eq!(rows[0]["repo"], "rust");
+        assert_eq!(rows[0]["issue_body"], "issue1");
+    }
+
     fn issues_in_repo() -> Vec<Value> {
         vec![
             Value::String("issue1".to_string()),
@@ -0,0 +1,111 @@
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use serde_json::{Map, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Target of the records of the evaluations of the dependencies, whose message is a JSON object
+/// with their fields when the logs are formatted as JSON.
+pub const EVALUATION_LOG_TARGET: &str = "dean::evaluation";
+
+/// Writes every record to stderr as a JSON object per line, for log aggregation tools. As the
+/// text logger, it logs the records of dean up to the given level, and only the errors of the
+/// dependencies.
+pub struct JsonLogger {
+    level: LevelFilter,
+}
+
+impl JsonLogger {
+    pub fn new(level: LevelFilter) -> Self {
+        Self { level }
+    }
+
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        log::set_max_level(self.level.max(LevelFilter::Error));
+        log::set_boxed_logger(Box::new(self))
+    }
+
+    /// Turns the record into an object with its timestamp, level, target and message, or with the
+    /// fields of the evaluation instead of the message for the records of the evaluations.
+    fn to_json(record: &Record, timestamp: OffsetDateTime) -> Value {
+        let message = record.args().to_string();
+        let mut fields = match serde_json::from_str::<Map<String, Value>>(&message) {
+            Ok(fields) if record.target() == EVALUATION_LOG_TARGET => fields,
+            _ => Map::from_iter([("message".to_string(), Value::from(message))]),
+        };
+        fields.insert(
+            "timestamp".to_string(),
+            Value::from(timestamp.format(&Rfc3339).unwrap_or_default()),
+        );
+        fields.insert("level".to_string(), Value::from(record.level().as_str()));
+        fields.insert("target".to_string(), Value::from(record.target()));
+        Value::Object(fields)
+    }
+}
+
+impl Log for JsonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if metadata.target().starts_with("dean") {
+            metadata.level() <= self.level
+        } else {
+            metadata.level() <= LevelFilter::Error
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", Self::to_json(record, OffsetDateTime::now_utc()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_formats_the_records_with_their_message() {
+        let record = Record::builder()
+            .args(format_args!("unable to save the scan"))
+            .level(Level::Error)
+            .target("dean")
+            .build();
+
+        assert_eq!(
+            JsonLogger::to_json(&record, OffsetDateTime::UNIX_EPOCH),
+            json!({
+                "timestamp": "1970-01-01T00:00:00Z",
+                "level": "ERROR",
+                "target": "dean",
+                "message": "unable to save the scan",
+            })
+        );
+    }
+
+    #[test]
+    fn it_formats_the_records_of_the_evaluations_with_their_fields() {
+        let record = Record::builder()
+            .args(format_args!(
+                r#"{{"name":"serde","policy":"max_versions_behind","result":"pass"}}"#
+            ))
+            .level(Level::Info)
+            .target(EVALUATION_LOG_TARGET)
+            .build();
+
+        assert_eq!(
+            JsonLogger::to_json(&record, OffsetDateTime::UNIX_EPOCH),
+            json!({
+                "timestamp": "1970-01-01T00:00:00Z",
+                "level": "INFO",
+                "target": "dean::evaluation",
+                "name": "serde",
+                "policy": "max_versions_behind",
+                "result": "pass",
+            })
+        );
+    }
+}
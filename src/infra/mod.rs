@@ -1,8 +1,23 @@
+pub mod cache_store;
+pub mod cached_info_retriever;
 pub mod cached_issue_client;
 pub mod clock;
 pub mod commit_store;
 pub mod git;
 pub mod github;
+pub mod github_graphql;
+pub mod gitlab;
+pub mod http;
+pub mod http_retry;
 pub mod issue_store;
+pub mod json_logger;
+pub mod offline_info_retriever;
+pub mod osv;
 pub mod package_manager;
+pub mod paginator;
 pub mod repo_contribution;
+pub mod repository_status;
+pub mod rustsec;
+pub mod scan_state;
+pub mod scan_store;
+pub mod vulnerability_store;
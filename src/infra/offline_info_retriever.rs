@@ -0,0 +1,26 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::pkg::{InfoRetriever, PackageInfo, Repository};
+use crate::Result;
+
+/// Retriever that doesn't request the registries, so the packages have neither a latest version
+/// nor a repository.
+pub struct OfflineInfoRetriever;
+
+#[async_trait]
+impl InfoRetriever for OfflineInfoRetriever {
+    async fn latest_version(&self, package_name: &str) -> Result<String> {
+        Err(anyhow!(
+            "the latest version of {package_name} is not retrieved in offline mode"
+        ))
+    }
+
+    async fn repository(&self, _package_name: &str) -> Result<Repository> {
+        Ok(Repository::Unknown)
+    }
+
+    async fn info(&self, _package_name: &str) -> Result<PackageInfo> {
+        Ok(PackageInfo::default())
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use log::{debug, warn};
+use serde_json::{json, Value};
+
+use crate::infra::http_retry::{send_with_retries, DEFAULT_MAX_RETRIES};
+use crate::pkg::policy::{Vulnerability, VulnerabilityRetriever};
+use crate::Dependency;
+
+pub const DEFAULT_API_URL: &str = "https://api.osv.dev";
+
+/// Cache of the vulnerabilities of each version, kept even when there are none, as most versions
+/// have no vulnerabilities.
+pub trait VulnerabilityStore: Send + Sync {
+    fn get_vulnerabilities(
+        &self,
+        ecosystem: &str,
+        name: &str,
+        version: &str,
+    ) -> Option<Vec<Vulnerability>>;
+
+    fn save_vulnerabilities(
+        &self,
+        ecosystem: &str,
+        name: &str,
+        version: &str,
+        vulnerabilities: &[Vulnerability],
+    ) -> Result<(), Box<dyn Error>>;
+}
+
+/// Client of the OSV API, which aggregates the security advisories of GitHub, Go, and the Rust and
+/// Python advisory databases among others.
+pub struct Client {
+    http: Arc<reqwest::Client>,
+    api_url: String,
+    store: Arc<dyn VulnerabilityStore>,
+    max_retries: u32,
+}
+
+impl Client {
+    pub fn new<S>(http: Arc<reqwest::Client>, store: S) -> Self
+    where
+        S: Into<Arc<dyn VulnerabilityStore>>,
+    {
+        Self {
+            http,
+            api_url: DEFAULT_API_URL.to_string(),
+            store: store.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    async fn query(
+        &self,
+        ecosystem: &str,
+        name: &str,
+        version: &str,
+    ) -> Result<Vec<Vulnerability>, Box<dyn Error>> {
+        debug!(target: "dean::osv_client", "querying the vulnerabilities of {}@{}", name, version);
+        let request = self
+            .http
+            .post(format!("{}/v1/query", self.api_url))
+            .json(&json!({
+                "package": {"name": name, "ecosystem": ecosystem},
+                "version": version,
+            }));
+        let response = send_with_retries(request, self.max_retries)
+            .await?
+            .error_for_status()?;
+        let body: Value = response.json().await?;
+
+        Ok(vulnerabilities_from_response(&body))
+    }
+}
+
+#[async_trait]
+impl VulnerabilityRetriever for Client {
+    async fn vulnerabilities(
+        &self,
+        dependency: &Dependency,
+    ) -> Result<Option<Vec<Vulnerability>>, Box<dyn Error>> {
+        let Some(ecosystem) = dependency.ecosystem.and_then(osv_ecosystem) else {
+            return Ok(None);
+        };
+        if let Some(vulnerabilities) =
+            self.store
+                .get_vulnerabilities(ecosystem, &dependency.name, &dependency.version)
+        {
+            return Ok(Some(vulnerabilities));
+        }
+
+        let vulnerabilities = self
+            .query(ecosystem, &dependency.name, &dependency.version)
+            .await?;
+        if let Err(e) = self.store.save_vulnerabilities(
+            ecosystem,
+            &dependency.name,
+            &dependency.version,
+            &vulnerabilities,
+        ) {
+            warn!(
+                "unable to save the vulnerabilities of {}: {}",
+                dependency.name, e
+            );
+        }
+        Ok(Some(vulnerabilities))
+    }
+}
+
+/// Returns the name OSV gives to the ecosystem of a dependency.
+fn osv_ecosystem(ecosystem: &str) -> Option<&'static str> {
+    match ecosystem {
+        "npm" => Some("npm"),
+        "cargo" => Some("crates.io"),
+        "pypi" => Some("PyPI"),
+        "go" => Some("Go"),
+        "rubygems" => Some("RubyGems"),
+        "packagist" => Some("Packagist"),
+        _ => None,
+    }
+}
+
+fn vulnerabilities_from_response(body: &Value) -> Vec<Vulnerability> {
+    body["vulns"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|vulnerability| {
+            let id = vulnerability["id"].as_str()?;
+            let cvss_score = vulnerability["severity"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|severity| severity["type"] == "CVSS_V3")
+                .filter_map(|severity| cvss_v3_base_score(severity["score"].as_str()?))
+                .reduce(f64::max);
+            Some(Vulnerability {
+                id: id.to_string(),
+                cvss_score,
+            })
+        })
+        .collect()
+}
+
+/// Computes the base score of a CVSS 3.x vector like `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`,
+/// as OSV only publishes the vectors.
+pub fn cvss_v3_base_score(vector: &str) -> Option<f64> {
+    let metrics: HashMap<&str, &str> = vector
+        .split('/')
+        .skip(1)
+        .filter_map(|metric| metric.split_once(':'))
+        .collect();
+    let scope_changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+
+    let attack_vector: f64 = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let attack_complexity = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let privileges_required = match (*metrics.get("PR")?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let user_interaction = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact_metric = |name| match *metrics.get(name)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let impact_sub_score: f64 = 1.0
+        - (1.0 - impact_metric("C")?) * (1.0 - impact_metric("I")?) * (1.0 - impact_metric("A")?);
+
+    let impact = if scope_changed {
+        7.52 * (impact_sub_score - 0.029) - 3.25 * (impact_sub_score - 0.02).powi(15)
+    } else {
+        6.42 * impact_sub_score
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability =
+        8.22 * attack_vector * attack_complexity * privileges_required * user_interaction;
+
+    Some(if scope_changed {
+        round_up((1.08 * (impact + exploitability)).min(10.0))
+    } else {
+        round_up((impact + exploitability).min(10.0))
+    })
+}
+
+/// Rounds up to one decimal as the CVSS 3.1 specification does, avoiding the floating point
+/// errors of a plain ceiling.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn round_up(value: f64) -> f64 {
+    let integer = (value * 100_000.0).round() as i64;
+    if integer % 10_000 == 0 {
+        integer as f64 / 100_000.0
+    } else {
+        ((integer / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::cache_store;
+    use crate::infra::clock::Clock;
+    use crate::infra::http::default_client;
+    use crate::infra::vulnerability_store;
+
+    #[test]
+    fn it_computes_the_base_score_of_the_cvss_vectors() {
+        assert_eq!(
+            cvss_v3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            cvss_v3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H"),
+            Some(10.0)
+        );
+        assert_eq!(
+            cvss_v3_base_score("CVSS:3.1/AV:N/AC:H/PR:N/UI:R/S:U/C:L/I:N/A:N"),
+            Some(3.1)
+        );
+        assert_eq!(
+            cvss_v3_base_score("CVSS:3.0/AV:N/AC:L/PR:H/UI:N/S:U/C:H/I:H/A:H"),
+            Some(7.2)
+        );
+        assert_eq!(
+            cvss_v3_base_score("CVSS:3.1/AV:L/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N"),
+            Some(0.0)
+        );
+        assert_eq!(cvss_v3_base_score("CVSS:3.1/AV:N/AC:L"), None);
+    }
+
+    #[test]
+    fn it_takes_the_highest_cvss_v3_score_of_each_vulnerability() {
+        let body = json!({
+            "vulns": [
+                {
+                    "id": "GHSA-35jh-r3h4-6jhm",
+                    "severity": [
+                        {"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:H/UI:N/S:U/C:H/I:H/A:H"},
+                        {"type": "CVSS_V4", "score": "CVSS:4.0/AV:N/AC:L/AT:N/PR:H/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"}
+                    ]
+                },
+                {"id": "GHSA-29mw-wpgm-hmr9"}
+            ]
+        });
+
+        assert_eq!(
+            vulnerabilities_from_response(&body),
+            vec![
+                Vulnerability {
+                    id: "GHSA-35jh-r3h4-6jhm".to_string(),
+                    cvss_score: Some(7.2),
+                },
+                Vulnerability {
+                    id: "GHSA-29mw-wpgm-hmr9".to_string(),
+                    cvss_score: None,
+                },
+            ]
+        );
+        assert_eq!(vulnerabilities_from_response(&json!({})), vec![]);
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_vulnerabilities_of_lodash_4_17_20() {
+        let store = vulnerability_store::Sqlite::new(
+            cache_store::in_memory_pool().unwrap(),
+            3600,
+            Box::new(Clock {}),
+        );
+        store.init().unwrap();
+        let client = Client::new(
+            Arc::new(default_client()),
+            Box::new(store) as Box<dyn VulnerabilityStore>,
+        );
+        let lodash = Dependency {
+            name: "lodash".to_string(),
+            version: "4.17.20".to_string(),
+            ecosystem: Some("npm"),
+            ..Dependency::default()
+        };
+
+        let vulnerabilities = client.vulnerabilities(&lodash).await.unwrap().unwrap();
+
+        assert!(vulnerabilities
+            .iter()
+            .any(|vulnerability| vulnerability.id == "GHSA-35jh-r3h4-6jhm"));
+    }
+}
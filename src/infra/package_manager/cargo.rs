@@ -2,14 +2,24 @@ use std::sync::Arc;
 
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use moka::future::{Cache, CacheBuilder};
 use serde_json::{Map, Value};
 
-use crate::pkg::Repository;
+use crate::infra::http;
+use crate::infra::http_retry::{error_if_not_found, send_with_retries, DEFAULT_MAX_RETRIES};
+use crate::pkg::{PackageInfo, Repository};
 use crate::Result;
 
-#[derive(Default)]
 pub struct InfoRetriever {
     client: Arc<reqwest::Client>,
+    response_cache: Cache<String, Map<String, Value>>,
+    max_retries: u32,
+}
+
+impl Default for InfoRetriever {
+    fn default() -> Self {
+        Self::new(http::default_client())
+    }
 }
 
 impl InfoRetriever {
@@ -19,16 +29,37 @@ impl InfoRetriever {
     {
         Self {
             client: client.into(),
+            response_cache: CacheBuilder::default().build(),
+            max_retries: DEFAULT_MAX_RETRIES,
         }
     }
 
+    /// Sets the number of times a request failing with a transient error is retried.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Retrieves the crates.io response of the dependency, requesting it only once so it can be
+    /// shared between the latest version, the repository and the info lookups.
+    async fn crate_response(&self, dependency: &str) -> Result<Map<String, Value>> {
+        self.response_cache
+            .try_get_with(dependency.to_string(), self.make_request(dependency))
+            .await
+            .map_err(|e| anyhow!(e))
+    }
+
     async fn make_request(&self, dependency: &str) -> Result<Map<String, Value>> {
-        let result: Value = self
+        let request = self
             .client
-            .get(&format!("https://crates.io/api/v1/crates/{dependency}"))
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/80.0.3987.149 Safari/537.36")
-            .send().await.context("unable to request crates.io")?
-            .json().await.context("unable to parse crates.io response")?;
+            .get(&format!("https://crates.io/api/v1/crates/{dependency}"));
+        let response = send_with_retries(request, self.max_retries)
+            .await
+            .context("unable to request crates.io")?;
+        let result: Value = error_if_not_found(response, dependency)?
+            .json()
+            .await
+            .context("unable to parse crates.io response")?;
 
         if !result.is_object() {
             return Err(anyhow!(
@@ -39,17 +70,14 @@ impl InfoRetriever {
 
         Ok(result.as_object().unwrap().clone())
     }
-}
 
-#[async_trait]
-impl crate::pkg::InfoRetriever for InfoRetriever {
-    async fn latest_version(&self, dependency: &str) -> Result<String> {
-        let response_object = self.make_request(dependency).await?;
-
-        let crate_info = response_object
+    fn crate_info_from(response_object: &Map<String, Value>) -> Result<&Value> {
+        response_object
             .get("crate")
-            .context("crate key is not present in the API response")?;
+            .context("crate key is not present in the API response")
+    }
 
+    fn newest_version_from(crate_info: &Value) -> Result<String> {
         let newest_version = crate_info
             .get("newest_version")
             .context("newest_version key is not present in the API response")?;
@@ -60,13 +88,7 @@ impl crate::pkg::InfoRetriever for InfoRetriever {
             .map(std::string::ToString::to_string)
     }
 
-    async fn repository(&self, dependency: &str) -> Result<Repository> {
-        let response_object = self.make_request(dependency).await?;
-
-        let crate_info = response_object
-            .get("crate")
-            .context("crate key is not present in the API response")?;
-
+    fn repository_from(crate_info: &Value) -> Result<Repository> {
         let repository_info = crate_info
             .get("repository")
             .context("repository key is not present in the API response")?;
@@ -77,6 +99,74 @@ impl crate::pkg::InfoRetriever for InfoRetriever {
 
         Ok(Repository::parse_url(repository))
     }
+
+    fn downloads_from(crate_info: &Value) -> Result<u64> {
+        crate_info
+            .get("downloads")
+            .context("downloads key is not present in the API response")?
+            .as_u64()
+            .context("downloads is not a number")
+    }
+
+    fn yanked_from(response_object: &Map<String, Value>, version: &str) -> Result<Option<String>> {
+        let versions = response_object
+            .get("versions")
+            .and_then(Value::as_array)
+            .context("versions key is not present in the API response")?;
+
+        let Some(version_info) = versions.iter().find(|v| v["num"] == version) else {
+            return Ok(None);
+        };
+
+        if version_info["yanked"].as_bool() != Some(true) {
+            return Ok(None);
+        }
+
+        Ok(Some(match version_info["yank_message"].as_str() {
+            Some(message) if !message.is_empty() => {
+                format!("version {version} is yanked: {message}")
+            }
+            _ => format!("version {version} is yanked"),
+        }))
+    }
+}
+
+#[async_trait]
+impl crate::pkg::InfoRetriever for InfoRetriever {
+    async fn latest_version(&self, dependency: &str) -> Result<String> {
+        let response_object = self.crate_response(dependency).await?;
+
+        Self::newest_version_from(Self::crate_info_from(&response_object)?)
+    }
+
+    async fn repository(&self, dependency: &str) -> Result<Repository> {
+        let response_object = self.crate_response(dependency).await?;
+
+        Self::repository_from(Self::crate_info_from(&response_object)?)
+    }
+
+    async fn info(&self, dependency: &str) -> Result<PackageInfo> {
+        let response_object = self.crate_response(dependency).await?;
+        let crate_info = Self::crate_info_from(&response_object)?;
+
+        Ok(PackageInfo {
+            latest_version: Self::newest_version_from(crate_info).ok(),
+            repository: Self::repository_from(crate_info).unwrap_or(Repository::Unknown),
+            repository_directory: None,
+        })
+    }
+
+    async fn deprecation(&self, dependency: &str, version: &str) -> Result<Option<String>> {
+        let response_object = self.crate_response(dependency).await?;
+
+        Self::yanked_from(&response_object, version)
+    }
+
+    async fn downloads(&self, dependency: &str) -> Result<Option<u64>> {
+        let response_object = self.crate_response(dependency).await?;
+
+        Self::downloads_from(Self::crate_info_from(&response_object)?).map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -84,6 +174,41 @@ mod tests {
     use super::*;
     use crate::pkg::InfoRetriever as _;
 
+    #[test]
+    fn it_reads_whether_the_version_is_yanked() {
+        let response = serde_json::json!({
+            "versions": [
+                {"num": "1.0.1", "yanked": false},
+                {"num": "1.0.0", "yanked": true, "yank_message": "security issue"},
+                {"num": "0.9.0", "yanked": true, "yank_message": null}
+            ]
+        });
+        let response_object = response.as_object().unwrap();
+
+        assert_eq!(
+            InfoRetriever::yanked_from(response_object, "1.0.0").unwrap(),
+            Some("version 1.0.0 is yanked: security issue".to_string())
+        );
+        assert_eq!(
+            InfoRetriever::yanked_from(response_object, "0.9.0").unwrap(),
+            Some("version 0.9.0 is yanked".to_string())
+        );
+        assert_eq!(
+            InfoRetriever::yanked_from(response_object, "1.0.1").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn it_reads_the_downloads_of_the_crate() {
+        let crate_info = serde_json::json!({"name": "serde", "downloads": 262_515_913});
+
+        assert_eq!(
+            InfoRetriever::downloads_from(&crate_info).unwrap(),
+            262_515_913
+        );
+    }
+
     #[tokio::test]
     async fn it_retrieves_the_latest_version_of_yaml_rust() {
         let retriever = InfoRetriever::default();
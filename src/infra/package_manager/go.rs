@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::pkg::{PackageInfo, Repository};
+use crate::Result;
+
+#[derive(Default)]
+pub struct InfoRetriever {
+    client: Arc<reqwest::Client>,
+}
+
+impl InfoRetriever {
+    pub fn new<C>(client: C) -> Self
+    where
+        C: Into<Arc<reqwest::Client>>,
+    {
+        Self {
+            client: client.into(),
+        }
+    }
+
+    async fn make_request(&self, module_path: &str) -> Result<Value> {
+        let response: Value = self
+            .client
+            .get(
+                format!(
+                    "https://proxy.golang.org/{}/@latest",
+                    Self::escape(module_path)
+                )
+                .as_str(),
+            )
+            .send()
+            .await
+            .context("unable to request proxy.golang.org")?
+            .error_for_status()
+            .context("unable to find the module in proxy.golang.org")?
+            .json()
+            .await
+            .context("unable to parse proxy.golang.org response")?;
+
+        Ok(response)
+    }
+
+    /// Escapes the module path as the Go proxy expects, replacing every uppercase letter with an
+    /// exclamation mark followed by the lowercase letter.
+    fn escape(module_path: &str) -> String {
+        module_path
+            .chars()
+            .map(|c| {
+                if c.is_ascii_uppercase() {
+                    format!("!{}", c.to_ascii_lowercase())
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect()
+    }
+
+    fn latest_version_from(response: &Value) -> Result<String> {
+        let version = response["Version"]
+            .as_str()
+            .context("version is not a string")?;
+
+        Ok(version.strip_prefix('v').unwrap_or(version).to_string())
+    }
+
+    fn repository_from(module_path: &str, response: &Value) -> Repository {
+        response["Origin"]["URL"].as_str().map_or_else(
+            || Self::repository_from_module_path(module_path),
+            Repository::parse_url,
+        )
+    }
+
+    /// Derives the repository from the module path, removing the major version suffix of
+    /// modules like `github.com/foo/bar/v2`.
+    fn repository_from_module_path(module_path: &str) -> Repository {
+        lazy_static! {
+            static ref MAJOR_VERSION_SUFFIX_REGEX: Regex = Regex::new("/v[0-9]+$").unwrap();
+        }
+
+        let module_path = MAJOR_VERSION_SUFFIX_REGEX.replace(module_path, "");
+        Repository::parse_url(&format!("https://{module_path}"))
+    }
+}
+
+#[async_trait]
+impl crate::pkg::InfoRetriever for InfoRetriever {
+    async fn latest_version(&self, package_name: &str) -> Result<String> {
+        let response = self.make_request(package_name).await?;
+
+        Self::latest_version_from(&response)
+    }
+
+    async fn repository(&self, package_name: &str) -> Result<Repository> {
+        let response = self.make_request(package_name).await?;
+
+        Ok(Self::repository_from(package_name, &response))
+    }
+
+    async fn info(&self, package_name: &str) -> Result<PackageInfo> {
+        let response = self.make_request(package_name).await?;
+
+        Ok(PackageInfo {
+            latest_version: Self::latest_version_from(&response).ok(),
+            repository: Self::repository_from(package_name, &response),
+            repository_directory: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InfoRetriever;
+    use crate::pkg::InfoRetriever as _;
+    use crate::pkg::Repository;
+
+    #[tokio::test]
+    async fn retrieves_the_latest_version_of_testify() {
+        let retriever = InfoRetriever::default();
+
+        let result = retriever
+            .latest_version("github.com/stretchr/testify")
+            .await;
+
+        assert!(!result.unwrap().starts_with('v'));
+    }
+
+    #[tokio::test]
+    async fn retrieves_the_repository_of_chi() {
+        let retriever = InfoRetriever::default();
+
+        let result = retriever.repository("github.com/go-chi/chi/v5").await;
+
+        assert_eq!(
+            result.unwrap(),
+            Repository::GitHub {
+                organization: "go-chi".into(),
+                name: "chi".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_strips_the_major_version_suffix_from_the_module_path() {
+        assert_eq!(
+            InfoRetriever::repository_from_module_path("go.uber.org/zap/v2"),
+            Repository::Raw {
+                address: "https://go.uber.org/zap".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_escapes_the_uppercase_letters_of_the_module_path() {
+        assert_eq!(
+            InfoRetriever::escape("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+    }
+}
@@ -1,2 +1,7 @@
 pub mod cargo;
+pub mod go;
 pub mod npm;
+pub mod npmrc;
+pub mod packagist;
+pub mod pypi;
+pub mod rubygems;
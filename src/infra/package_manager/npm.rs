@@ -2,14 +2,25 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use async_trait::async_trait;
+use reqwest::RequestBuilder;
 use serde_json::Value;
 
-use crate::pkg::Repository;
+use crate::infra::http;
+use crate::infra::http_retry::{error_if_not_found, send_with_retries, DEFAULT_MAX_RETRIES};
+use crate::infra::package_manager::npmrc::Registries;
+use crate::pkg::{PackageInfo, Repository};
 use crate::Result;
 
-#[derive(Default)]
 pub struct InfoRetriever {
     client: Arc<reqwest::Client>,
+    max_retries: u32,
+    registries: Registries,
+}
+
+impl Default for InfoRetriever {
+    fn default() -> Self {
+        Self::new(http::default_client())
+    }
 }
 
 impl InfoRetriever {
@@ -19,55 +30,171 @@ impl InfoRetriever {
     {
         Self {
             client: client.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            registries: Registries::default(),
         }
     }
-}
 
-#[async_trait]
-impl crate::pkg::InfoRetriever for InfoRetriever {
-    async fn latest_version(&self, package_name: &str) -> Result<String> {
-        let response: Value = self
-            .client
-            .get(format!("https://registry.npmjs.org/{package_name}").as_str())
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/80.0.3987.149 Safari/537.36")
-            .send().await.context("unable to request npmjs.org")?
-            .json().await.context("unable to parse npmjs.org response")?;
+    /// Sets the registries the packages are requested from, instead of the public one.
+    pub fn with_registries(mut self, registries: Registries) -> Self {
+        self.registries = registries;
+        self
+    }
+
+    /// Sets the number of times a request failing with a transient error is retried.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
 
+    /// Builds the request of the package to its registry, authenticated with the token of the
+    /// registry, if any. The slash of the scoped packages is escaped as the registries expect.
+    fn request(&self, package_name: &str) -> RequestBuilder {
+        let registry = self.registries.registry_for(package_name);
+        let request = self.client.get(format!(
+            "{registry}{}",
+            package_name.replacen('/', "%2f", 1)
+        ));
+
+        match self.registries.token_for(registry) {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn make_request(&self, package_name: &str) -> Result<Value> {
+        let response = send_with_retries(self.request(package_name), self.max_retries)
+            .await
+            .context("unable to request the npm registry")?;
+        let response: Value = error_if_not_found(response, package_name)?
+            .json()
+            .await
+            .context("unable to parse the npm registry response")?;
+
+        Ok(response)
+    }
+
+    async fn make_weekly_downloads_request(&self, package_name: &str) -> Result<Value> {
+        let request = self.client.get(
+            format!("https://api.npmjs.org/downloads/point/last-week/{package_name}").as_str(),
+        );
+        let response: Value = send_with_retries(request, self.max_retries)
+            .await
+            .context("unable to request api.npmjs.org")?
+            .json()
+            .await
+            .context("unable to parse api.npmjs.org response")?;
+
+        Ok(response)
+    }
+
+    fn latest_version_from(response: &Value) -> Result<String> {
         Ok(response["dist-tags"]["latest"]
             .as_str()
             .context("latest is not a string")?
             .to_string())
     }
 
-    async fn repository(&self, package_name: &str) -> Result<Repository> {
-        let response: Value = self
-            .client
-            .get(format!("https://registry.npmjs.org/{package_name}").as_str())
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/80.0.3987.149 Safari/537.36")
-            .send().await.context("unable to request npmjs.org")?
-            .json().await.context("unable to parse npmjs.org response")?;
-
-        let possible_repository = response["repository"]["url"]
+    fn repository_from(response: &Value) -> Repository {
+        response["repository"]["url"]
             .as_str()
             .or_else(|| response["homepage"].as_str())
-            .map(ToString::to_string);
+            .map_or(Repository::Unknown, Repository::parse_url)
+    }
 
-        if possible_repository.is_none() {
-            return Ok(Repository::Unknown);
-        }
+    /// Returns the directory of the package in its repository, which the packages of a monorepo
+    /// publish.
+    fn repository_directory_from(response: &Value) -> Option<String> {
+        response["repository"]["directory"]
+            .as_str()
+            .map(|directory| directory.trim_matches('/').to_string())
+            .filter(|directory| !directory.is_empty())
+    }
+
+    fn deprecation_from(response: &Value, version: &str) -> Option<String> {
+        response["versions"][version]["deprecated"]
+            .as_str()
+            .map(ToString::to_string)
+    }
+
+    fn weekly_downloads_from(response: &Value) -> Result<u64> {
+        response["downloads"]
+            .as_u64()
+            .context("downloads is not a number")
+    }
+}
+
+#[async_trait]
+impl crate::pkg::InfoRetriever for InfoRetriever {
+    async fn latest_version(&self, package_name: &str) -> Result<String> {
+        let response = self.make_request(package_name).await?;
+
+        Self::latest_version_from(&response)
+    }
+
+    async fn repository(&self, package_name: &str) -> Result<Repository> {
+        let response = self.make_request(package_name).await?;
+
+        Ok(Self::repository_from(&response))
+    }
+
+    async fn info(&self, package_name: &str) -> Result<PackageInfo> {
+        let response = self.make_request(package_name).await?;
+
+        Ok(PackageInfo {
+            latest_version: Self::latest_version_from(&response).ok(),
+            repository: Self::repository_from(&response),
+            repository_directory: Self::repository_directory_from(&response),
+        })
+    }
 
-        let repository = possible_repository.as_ref().unwrap();
+    async fn deprecation(&self, package_name: &str, version: &str) -> Result<Option<String>> {
+        let response = self.make_request(package_name).await?;
 
-        Ok(Repository::parse_url(repository))
+        Ok(Self::deprecation_from(&response, version))
+    }
+
+    async fn weekly_downloads(&self, package_name: &str) -> Result<Option<u64>> {
+        let response = self.make_weekly_downloads_request(package_name).await?;
+
+        Self::weekly_downloads_from(&response).map(Some)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::InfoRetriever;
+    use crate::infra::http;
+    use crate::infra::package_manager::npmrc::Registries;
     use crate::pkg::InfoRetriever as _;
     use crate::pkg::Repository;
 
+    #[test]
+    fn it_requests_the_scoped_packages_to_their_registry_with_its_token() {
+        let mut registries = Registries::default();
+        registries.merge_npmrc(
+            "@mycorp:registry=https://npm.mycorp.com/\n//npm.mycorp.com/:_authToken=some_token",
+        );
+        let retriever = InfoRetriever::new(http::default_client()).with_registries(registries);
+
+        let private_request = retriever.request("@mycorp/some_package").build().unwrap();
+        let public_request = retriever.request("colors").build().unwrap();
+
+        assert_eq!(
+            private_request.url().as_str(),
+            "https://npm.mycorp.com/@mycorp%2fsome_package"
+        );
+        assert_eq!(
+            private_request.headers()["authorization"],
+            "Bearer some_token"
+        );
+        assert_eq!(
+            public_request.url().as_str(),
+            "https://registry.npmjs.org/colors"
+        );
+        assert!(public_request.headers().get("authorization").is_none());
+    }
+
     #[tokio::test]
     async fn retrieves_the_latest_version_of_colors() {
         let retriever = InfoRetriever::default();
@@ -136,6 +263,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reads_the_deprecation_message_of_the_version() {
+        let response = serde_json::json!({
+            "versions": {
+                "1.0.0": {"deprecated": "use 2.0.0 instead"},
+                "2.0.0": {}
+            }
+        });
+
+        assert_eq!(
+            InfoRetriever::deprecation_from(&response, "1.0.0"),
+            Some("use 2.0.0 instead".to_string())
+        );
+        assert_eq!(InfoRetriever::deprecation_from(&response, "2.0.0"), None);
+    }
+
+    #[test]
+    fn reads_the_directory_of_the_package_in_its_repository() {
+        let response = serde_json::json!({
+            "repository": {
+                "type": "git",
+                "url": "https://github.com/babel/babel.git",
+                "directory": "packages/babel-core/"
+            }
+        });
+
+        assert_eq!(
+            InfoRetriever::repository_directory_from(&response),
+            Some("packages/babel-core".to_string())
+        );
+        assert_eq!(
+            InfoRetriever::repository_directory_from(&serde_json::json!({"repository": {}})),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_the_weekly_downloads_of_the_package() {
+        let response = serde_json::json!({
+            "downloads": 23_513_215,
+            "start": "2023-01-01",
+            "end": "2023-01-07",
+            "package": "colors"
+        });
+
+        assert_eq!(
+            InfoRetriever::weekly_downloads_from(&response).unwrap(),
+            23_513_215
+        );
+    }
+
     #[tokio::test]
     async fn retrieves_unknown_repository_of_json5() {
         let retriever = InfoRetriever::default();
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org/";
+
+/// The registries the npm packages are requested from, and the tokens to authenticate to them, as
+/// configured in the `.npmrc` files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Registries {
+    default: String,
+    /// Registry of the packages of each scope, as in `@mycorp:registry=https://npm.mycorp.com/`.
+    by_scope: HashMap<String, String>,
+    /// Token of each registry, by its URL without the scheme, as in
+    /// `//npm.mycorp.com/:_authToken=...`.
+    tokens: HashMap<String, String>,
+}
+
+impl Default for Registries {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_REGISTRY.to_string(),
+            by_scope: HashMap::new(),
+            tokens: HashMap::new(),
+        }
+    }
+}
+
+impl Registries {
+    /// Reads the `.npmrc` of the home directory and then the one of the current directory, which
+    /// takes precedence. The `NPM_CONFIG_REGISTRY` and `NPM_TOKEN` environment variables override
+    /// the default registry and its token.
+    pub fn from_environment() -> Self {
+        let mut registries = Self::default();
+
+        let npmrc_files = [
+            dirs_next::home_dir().map(|home| home.join(".npmrc")),
+            Some(PathBuf::from(".npmrc")),
+        ];
+        for npmrc in npmrc_files.into_iter().flatten() {
+            if let Ok(contents) = std::fs::read_to_string(npmrc) {
+                registries.merge_npmrc(&contents);
+            }
+        }
+
+        if let Ok(registry) = std::env::var("NPM_CONFIG_REGISTRY") {
+            registries.default = with_trailing_slash(&registry);
+        }
+        if let Ok(token) = std::env::var("NPM_TOKEN") {
+            registries
+                .tokens
+                .insert(without_scheme(&registries.default), token);
+        }
+
+        registries
+    }
+
+    /// Adds the registries and tokens of the contents of an `.npmrc` file, replacing the ones
+    /// already set. `${VAR}` references to environment variables are expanded.
+    pub fn merge_npmrc(&mut self, contents: &str) {
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), expand_env(value.trim()));
+
+            if key == "registry" {
+                self.default = with_trailing_slash(&value);
+            } else if let Some(scope) = key.strip_suffix(":registry") {
+                self.by_scope
+                    .insert(scope.to_string(), with_trailing_slash(&value));
+            } else if let Some(registry) = key.strip_suffix(":_authToken") {
+                self.tokens.insert(registry.to_string(), value);
+            }
+        }
+    }
+
+    /// Returns the registry of the package, which is the one of its scope, if any.
+    pub fn registry_for(&self, package_name: &str) -> &str {
+        package_name
+            .split_once('/')
+            .and_then(|(scope, _)| self.by_scope.get(scope))
+            .unwrap_or(&self.default)
+    }
+
+    /// Returns the token of the registry, matching the longest configured URL it starts with.
+    pub fn token_for(&self, registry: &str) -> Option<&str> {
+        let registry = without_scheme(registry);
+        self.tokens
+            .iter()
+            .filter(|(url, _)| registry.starts_with(url.as_str()))
+            .max_by_key(|(url, _)| url.len())
+            .map(|(_, token)| token.as_str())
+    }
+}
+
+fn with_trailing_slash(url: &str) -> String {
+    format!("{}/", url.trim_end_matches('/'))
+}
+
+/// Turns `https://npm.mycorp.com/` into `//npm.mycorp.com/`, as the tokens are keyed in `.npmrc`.
+fn without_scheme(url: &str) -> String {
+    url.split_once(':')
+        .map_or(url, |(_, rest)| rest)
+        .to_string()
+}
+
+fn expand_env(value: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let variable = &rest[start + 2..start + end];
+        expanded.push_str(&std::env::var(variable).unwrap_or_default());
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NPMRC: &str = r"
+# private packages
+@mycorp:registry=https://npm.mycorp.com/repository/npm/
+//npm.mycorp.com/:_authToken=some_token
+; public ones
+registry=https://mirror.example.com
+";
+
+    #[test]
+    fn it_uses_the_registry_of_the_scope_of_the_package() {
+        let mut registries = Registries::default();
+        registries.merge_npmrc(NPMRC);
+
+        assert_eq!(
+            registries.registry_for("@mycorp/some_package"),
+            "https://npm.mycorp.com/repository/npm/"
+        );
+        assert_eq!(
+            registries.registry_for("@other/some_package"),
+            "https://mirror.example.com/"
+        );
+        assert_eq!(
+            registries.registry_for("colors"),
+            "https://mirror.example.com/"
+        );
+    }
+
+    #[test]
+    fn it_uses_the_token_of_the_registry() {
+        let mut registries = Registries::default();
+        registries.merge_npmrc(NPMRC);
+
+        assert_eq!(
+            registries.token_for("https://npm.mycorp.com/repository/npm/"),
+            Some("some_token")
+        );
+        assert_eq!(registries.token_for("https://mirror.example.com/"), None);
+    }
+
+    #[test]
+    fn it_uses_the_public_registry_without_npmrc() {
+        let registries = Registries::default();
+
+        assert_eq!(
+            registries.registry_for("@mycorp/some_package"),
+            DEFAULT_REGISTRY
+        );
+        assert_eq!(registries.token_for(DEFAULT_REGISTRY), None);
+    }
+}
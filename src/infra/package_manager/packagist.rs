@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::infra::http;
+use crate::infra::http_retry::{error_if_not_found, send_with_retries, DEFAULT_MAX_RETRIES};
+use crate::pkg::{PackageInfo, Repository};
+use crate::Result;
+
+pub struct InfoRetriever {
+    client: Arc<reqwest::Client>,
+    max_retries: u32,
+}
+
+impl Default for InfoRetriever {
+    fn default() -> Self {
+        Self::new(http::default_client())
+    }
+}
+
+impl InfoRetriever {
+    pub fn new<C>(client: C) -> Self
+    where
+        C: Into<Arc<reqwest::Client>>,
+    {
+        Self {
+            client: client.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// Sets the number of times a request failing with a transient error is retried.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Requests the tagged versions of the package, the most recent first. Only the first one
+    /// has all the fields, as the rest only have the ones that changed from the previous version.
+    async fn make_request(&self, package_name: &str) -> Result<Vec<Value>> {
+        let request = self
+            .client
+            .get(format!("https://repo.packagist.org/p2/{package_name}.json"));
+        let response = send_with_retries(request, self.max_retries)
+            .await
+            .context("unable to request repo.packagist.org")?;
+        let mut response: Value = error_if_not_found(response, package_name)?
+            .json()
+            .await
+            .context("unable to parse repo.packagist.org response")?;
+
+        let versions = response["packages"][package_name].take();
+        match versions {
+            Value::Array(versions) => Ok(versions),
+            _ => Err(anyhow!(
+                "no versions of {} found in repo.packagist.org",
+                package_name
+            )),
+        }
+    }
+
+    /// Returns the most recent stable version, or the most recent one if none is stable, without
+    /// the `v` prefix of the tags, as in the lock files.
+    fn latest_version_from(versions: &[Value]) -> Result<String> {
+        let is_stable = |version: &&Value| {
+            version["version_normalized"]
+                .as_str()
+                .is_some_and(|normalized| !normalized.contains('-'))
+        };
+
+        versions
+            .iter()
+            .find(is_stable)
+            .or_else(|| versions.first())
+            .and_then(|version| version["version"].as_str())
+            .map(|version| version.strip_prefix('v').unwrap_or(version).to_string())
+            .context("version is not a string")
+    }
+
+    fn repository_from(versions: &[Value]) -> Repository {
+        versions
+            .first()
+            .and_then(|version| {
+                version["source"]["url"]
+                    .as_str()
+                    .or_else(|| version["homepage"].as_str())
+            })
+            .map_or(Repository::Unknown, Repository::parse_url)
+    }
+}
+
+#[async_trait]
+impl crate::pkg::InfoRetriever for InfoRetriever {
+    async fn latest_version(&self, package_name: &str) -> Result<String> {
+        let versions = self.make_request(package_name).await?;
+
+        Self::latest_version_from(&versions)
+    }
+
+    async fn repository(&self, package_name: &str) -> Result<Repository> {
+        let versions = self.make_request(package_name).await?;
+
+        Ok(Self::repository_from(&versions))
+    }
+
+    async fn info(&self, package_name: &str) -> Result<PackageInfo> {
+        let versions = self.make_request(package_name).await?;
+
+        Ok(PackageInfo {
+            latest_version: Self::latest_version_from(&versions).ok(),
+            repository: Self::repository_from(&versions),
+            repository_directory: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_reads_the_latest_stable_version_and_the_repository() {
+        let versions = vec![
+            json!({
+                "version": "v7.0.0-RC1",
+                "version_normalized": "7.0.0.0-RC1",
+                "source": {"url": "https://github.com/symfony/console.git", "type": "git"},
+            }),
+            json!({"version": "v6.3.4", "version_normalized": "6.3.4.0"}),
+            json!({"version": "v6.3.3", "version_normalized": "6.3.3.0"}),
+        ];
+
+        assert_eq!(
+            InfoRetriever::latest_version_from(&versions).unwrap(),
+            "6.3.4"
+        );
+        assert_eq!(
+            InfoRetriever::repository_from(&versions),
+            Repository::GitHub {
+                organization: "symfony".into(),
+                name: "console".into(),
+            }
+        );
+    }
+}
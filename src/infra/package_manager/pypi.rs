@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::pkg::{PackageInfo, Repository};
+use crate::Result;
+
+#[derive(Default)]
+pub struct InfoRetriever {
+    client: Arc<reqwest::Client>,
+}
+
+impl InfoRetriever {
+    pub fn new<C>(client: C) -> Self
+    where
+        C: Into<Arc<reqwest::Client>>,
+    {
+        Self {
+            client: client.into(),
+        }
+    }
+
+    async fn make_request(&self, package_name: &str) -> Result<Value> {
+        let response: Value = self
+            .client
+            .get(format!("https://pypi.org/pypi/{package_name}/json").as_str())
+            .send()
+            .await
+            .context("unable to request pypi.org")?
+            .json()
+            .await
+            .context("unable to parse pypi.org response")?;
+
+        Ok(response)
+    }
+
+    fn latest_version_from(response: &Value) -> Result<String> {
+        Ok(response["info"]["version"]
+            .as_str()
+            .context("version is not a string")?
+            .to_string())
+    }
+
+    fn repository_from(response: &Value) -> Repository {
+        let project_urls = &response["info"]["project_urls"];
+
+        project_urls["Source"]
+            .as_str()
+            .or_else(|| project_urls["Homepage"].as_str())
+            .map_or(Repository::Unknown, Repository::parse_url)
+    }
+}
+
+#[async_trait]
+impl crate::pkg::InfoRetriever for InfoRetriever {
+    async fn latest_version(&self, package_name: &str) -> Result<String> {
+        let response = self.make_request(package_name).await?;
+
+        Self::latest_version_from(&response)
+    }
+
+    async fn repository(&self, package_name: &str) -> Result<Repository> {
+        let response = self.make_request(package_name).await?;
+
+        Ok(Self::repository_from(&response))
+    }
+
+    async fn info(&self, package_name: &str) -> Result<PackageInfo> {
+        let response = self.make_request(package_name).await?;
+
+        Ok(PackageInfo {
+            latest_version: Self::latest_version_from(&response).ok(),
+            repository: Self::repository_from(&response),
+            repository_directory: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InfoRetriever;
+    use crate::pkg::InfoRetriever as _;
+    use crate::pkg::Repository;
+
+    #[tokio::test]
+    async fn retrieves_the_latest_version_of_urllib3() {
+        let retriever = InfoRetriever::default();
+
+        let result = retriever.latest_version("urllib3").await;
+
+        assert!(!result.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn retrieves_the_repository_of_requests() {
+        let retriever = InfoRetriever::default();
+
+        let result = retriever.repository("requests").await;
+
+        assert_eq!(
+            result.unwrap(),
+            Repository::GitHub {
+                organization: "psf".into(),
+                name: "requests".into(),
+            }
+        );
+    }
+}
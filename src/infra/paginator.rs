@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use log::{debug, trace};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::RequestBuilder;
+use serde_json::Value;
+use tokio_stream::Stream;
+
+/// How the pages of a REST API are requested and chained, which is what differs between the
+/// GitHub and GitLab APIs.
+pub trait Pagination: Send + Sync + 'static {
+    /// Target of the logs of the requests.
+    const LOG_TARGET: &'static str;
+    /// Status of the responses to the requests made once the rate limit is exhausted.
+    const RATE_LIMITED_STATUS: u16;
+    /// Header telling the timestamp, in seconds, the rate limit is reset at.
+    const RATE_LIMIT_RESET_HEADER: &'static str;
+
+    /// Builds the request of the page, with the headers and the authentication of the API.
+    fn request(&self, client: &reqwest::Client, url: &str) -> RequestBuilder;
+
+    /// Returns how long to wait after the response before requesting the next page, to avoid
+    /// exhausting the rate limit. Doesn't wait by default.
+    fn throttle_duration(&self, _headers: &HeaderMap, _now: Duration) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the URL of the next page, from the `next` link of the `Link` header by default.
+    fn next_page(&self, headers: &HeaderMap) -> Option<String> {
+        next_page_from_link_header(headers).map(str::to_string)
+    }
+}
+
+/// Requests the pages of a JSON array one after the other, starting by the given URL, as they are
+/// consumed. When the rate limit is exceeded, it waits until it's reset and requests the page
+/// again.
+pub struct Paginator<P> {
+    client: Arc<reqwest::Client>,
+    pagination: P,
+    next_page: Option<String>,
+    buffer: VecDeque<Value>,
+}
+
+impl<P: Pagination> Paginator<P> {
+    pub fn new(client: Arc<reqwest::Client>, pagination: P, first_page: String) -> Self {
+        Self {
+            client,
+            pagination,
+            next_page: Some(first_page),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Streams the items of every page, stopping at the last one or at the first error.
+    pub fn into_stream(self) -> impl Stream<Item = Value> + Send {
+        futures::stream::unfold(self, Self::next_value)
+    }
+
+    async fn next_value(mut self) -> Option<(Value, Self)> {
+        trace!(target: P::LOG_TARGET, "Polling stream");
+        if self.buffer.is_empty() {
+            trace!(target: P::LOG_TARGET, "Buffer is empty, updating it");
+            if let Err(e) = self.update_buffer().await {
+                trace!(target: P::LOG_TARGET, "Failed to update buffer: {}", e);
+                return None;
+            }
+        }
+        if let Some(value_from_buffer) = self.buffer.pop_front() {
+            trace!(target: P::LOG_TARGET, "Returning item from buffer");
+            Some((value_from_buffer, self))
+        } else {
+            trace!(target: P::LOG_TARGET, "Buffer is empty, stopping");
+            None
+        }
+    }
+
+    async fn update_buffer(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(url) = self.next_page.take() else {
+            return Ok(());
+        };
+
+        let response = loop {
+            debug!(target: P::LOG_TARGET, "Fetching page {}", url);
+            let request = self.pagination.request(&self.client, &url);
+
+            trace!(target: P::LOG_TARGET, "Request: {:?}", request);
+            let response = request.send().await.context("Failed to get page")?;
+            trace!(target: P::LOG_TARGET, "Response: {:?}", response);
+
+            if response.status().as_u16() != P::RATE_LIMITED_STATUS {
+                break response;
+            }
+            let rate_limit_sleep_duration = Self::rate_limit_sleep_duration(response.headers())?;
+            debug!(target: P::LOG_TARGET, "Rate limit exceeded, waiting for {:?}", rate_limit_sleep_duration);
+            tokio::time::sleep(rate_limit_sleep_duration).await;
+        };
+
+        if let Some(throttle_duration) = self.pagination.throttle_duration(
+            response.headers(),
+            SystemTime::now().duration_since(UNIX_EPOCH)?,
+        ) {
+            debug!(target: P::LOG_TARGET, "Rate limit almost exhausted, throttling for {:?}", throttle_duration);
+            tokio::time::sleep(throttle_duration).await;
+        }
+
+        self.next_page = self.pagination.next_page(response.headers());
+
+        let response_json = response
+            .json::<Value>()
+            .await
+            .context("Failed to parse page")?;
+
+        let items = response_json
+            .as_array()
+            .context("the response is not an array")?
+            .clone();
+
+        self.buffer.extend(items);
+
+        Ok(())
+    }
+
+    /// Returns the time left until the rate limit is reset, plus a margin.
+    fn rate_limit_sleep_duration(headers: &HeaderMap) -> Result<Duration, Box<dyn Error>> {
+        let rate_limit_timestamp_seconds = headers
+            .get(P::RATE_LIMIT_RESET_HEADER)
+            .unwrap_or(&HeaderValue::from(0))
+            .to_str()
+            .context("unable to convert rate limit reset header to str")?
+            .parse::<u64>()?;
+
+        Ok(Duration::from_secs(rate_limit_timestamp_seconds)
+            .checked_sub(SystemTime::now().duration_since(UNIX_EPOCH)?)
+            .context("unable to substract dates")?
+            .checked_add(Duration::from_secs(5))
+            .context("unable to add duration increment")?)
+    }
+}
+
+/// Returns the URL of the `next` link of the `Link` header, as in
+/// `<https://api.github.com/...&page=2>; rel="next", <...>; rel="last"`.
+pub fn next_page_from_link_header(headers: &HeaderMap) -> Option<&str> {
+    let link_header = headers.get("link")?;
+    let link_header_as_str = link_header.to_str().ok()?;
+    let next_rel_link = link_header_as_str
+        .split(',')
+        .find(|link| link.contains("rel=\"next\""))?;
+    let first_next_rel_link = next_rel_link.split(';').next()?;
+    let first_next_rel_link_without_weird_characters = first_next_rel_link
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+
+    if first_next_rel_link_without_weird_characters.is_empty() {
+        None
+    } else {
+        Some(first_next_rel_link_without_weird_characters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_extracts_the_next_page_from_the_link_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "link",
+            HeaderValue::from_static(
+                "<https://gitlab.com/api/v4/projects/1/issues?page=1>; rel=\"prev\", <https://gitlab.com/api/v4/projects/1/issues?page=3>; rel=\"next\"",
+            ),
+        );
+
+        assert_eq!(
+            next_page_from_link_header(&headers),
+            Some("https://gitlab.com/api/v4/projects/1/issues?page=3")
+        );
+    }
+
+    #[test]
+    fn it_has_no_next_page_on_the_last_page() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "link",
+            HeaderValue::from_static(
+                "<https://api.github.com/repos/some_org/some_repo/issues?page=1>; rel=\"first\"",
+            ),
+        );
+
+        assert_eq!(next_page_from_link_header(&headers), None);
+        assert_eq!(next_page_from_link_header(&HeaderMap::new()), None);
+    }
+}
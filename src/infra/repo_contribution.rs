@@ -2,88 +2,219 @@ use std::error::Error;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use serde_json::Value;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
-use tokio_stream::StreamExt;
+use tokio_stream::{Stream, StreamExt};
 
 use crate::infra::cached_issue_client::{CachedClient, IssueClient, IssueStore};
-use crate::infra::github;
-use crate::pkg::policy::ContributionDataRetriever;
+use crate::infra::gitlab;
+use crate::pkg::policy::{Clock, ContributionDataRetriever, IssueWindow};
+use crate::pkg::timings::Timings;
 use crate::pkg::Repository;
 
+const DAYS_TO_SECONDS: u64 = 24 * 60 * 60;
+
 pub struct Retriever {
     github_cached_client: Box<CachedClient>,
+    gitlab_cached_client: Box<CachedClient>,
+    clock: Box<dyn Clock>,
 }
 
 impl Retriever {
-    pub fn new<C, S>(github_client: C, issue_store: S) -> Self
+    pub fn new<C, L, S>(
+        github_client: C,
+        gitlab_client: L,
+        issue_store: S,
+        clock: Box<dyn Clock>,
+    ) -> Self
     where
-        C: Into<Arc<github::Client>>,
+        C: Into<Arc<dyn IssueClient>>,
+        L: Into<Arc<gitlab::Client>>,
         S: Into<Arc<dyn IssueStore>>,
     {
-        let client = CachedClient::new(
-            "github",
-            github_client.into() as Arc<dyn IssueClient>,
-            issue_store.into(),
+        let issue_store = issue_store.into();
+        let github_client = CachedClient::new("github", github_client.into(), issue_store.clone());
+        let gitlab_client = CachedClient::new(
+            "gitlab",
+            gitlab_client.into() as Arc<dyn IssueClient>,
+            issue_store,
         );
         Self {
-            github_cached_client: Box::new(client),
+            github_cached_client: Box::new(github_client),
+            gitlab_cached_client: Box::new(gitlab_client),
+            clock,
         }
     }
 
-    async fn get_github_issue_lifespan(
-        &self,
+    /// Records the durations of the retrievals of the issues and pull requests.
+    pub fn with_timings(mut self, timings: &Arc<Timings>) -> Self {
+        self.github_cached_client =
+            Box::new((*self.github_cached_client).with_timings(timings.clone()));
+        self.gitlab_cached_client =
+            Box::new((*self.gitlab_cached_client).with_timings(timings.clone()));
+        self
+    }
+
+    async fn get_cached_issue_lifespan(
+        cached_client: &CachedClient,
         organization: &str,
         repo: &str,
-        last_issues: usize,
+        window: IssueWindow,
     ) -> f64 {
-        let mut issues = self
-            .github_cached_client
-            .get_last_issues(organization, repo, last_issues)
+        let issues = cached_client
+            .get_last_issues(organization, repo, window)
             .await;
 
-        let mut closed_issues = Vec::new();
-        while let Some(issue) = issues.next().await {
-            if issue.get("state").unwrap().as_str().unwrap() == "closed" {
-                closed_issues.push(issue);
-            }
-        }
+        Self::mean_lifespan_of_closed(issues).await
+    }
 
-        let lifespan_per_issue = closed_issues.into_iter().map(|issue| {
-            let created_at_str = issue.get("created_at").unwrap().as_str().unwrap();
-            let closed_at_str = issue.get("closed_at").unwrap().as_str().unwrap();
+    async fn get_cached_pull_request_lifespan(
+        cached_client: &CachedClient,
+        organization: &str,
+        repo: &str,
+        window: IssueWindow,
+    ) -> f64 {
+        let prs = cached_client
+            .get_pull_requests(organization, repo, window)
+            .await;
 
-            let created_at = OffsetDateTime::parse(created_at_str, &Rfc3339).unwrap();
-            let closed_at = OffsetDateTime::parse(closed_at_str, &Rfc3339).unwrap();
-            let lifespan = closed_at - created_at;
+        Self::mean_lifespan_of_closed(prs).await
+    }
 
-            lifespan.as_seconds_f64()
-        });
+    async fn get_cached_open_issue_ratio(
+        cached_client: &CachedClient,
+        organization: &str,
+        repo: &str,
+        window: IssueWindow,
+    ) -> f64 {
+        let issues = cached_client
+            .get_last_issues(organization, repo, window)
+            .await;
 
-        lifespan_per_issue.mean()
+        Self::open_ratio(issues).await
     }
 
-    async fn get_github_pull_request_lifespan(
-        &self,
+    async fn get_cached_issue_close_rate(
+        cached_client: &CachedClient,
         organization: &str,
         repo: &str,
-        last_pull_requests: usize,
+        window: IssueWindow,
     ) -> f64 {
-        let mut prs = self
-            .github_cached_client
-            .get_pull_requests(organization, repo, last_pull_requests)
+        let issues = cached_client
+            .get_last_issues(organization, repo, window)
             .await;
 
-        let mut closed_prs = Vec::new();
-        while let Some(pr) = prs.next().await {
-            if pr.get("state").unwrap().as_str().unwrap() == "closed" {
-                closed_prs.push(pr);
+        Self::close_rate(issues).await
+    }
+
+    async fn get_cached_stale_open_pr_count(
+        cached_client: &CachedClient,
+        organization: &str,
+        repo: &str,
+        window: IssueWindow,
+        stale_after_days: u64,
+        now_timestamp: u64,
+    ) -> usize {
+        let prs = cached_client
+            .get_pull_requests(organization, repo, window)
+            .await;
+        let stale_before = now_timestamp.saturating_sub(stale_after_days * DAYS_TO_SECONDS);
+
+        Self::open_count_created_before(prs, stale_before).await
+    }
+
+    /// Counts the open issues or pull requests in the stream created before the timestamp.
+    async fn open_count_created_before(
+        mut issues_or_prs: Box<dyn Stream<Item = Value> + Unpin + Send>,
+        timestamp: u64,
+    ) -> usize {
+        let mut count = 0;
+        while let Some(issue_or_pr) = issues_or_prs.next().await {
+            let state = issue_or_pr
+                .get("state")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let created_at = issue_or_pr
+                .get("created_at")
+                .and_then(Value::as_str)
+                .and_then(|created_at| OffsetDateTime::parse(created_at, &Rfc3339).ok())
+                .and_then(|created_at| u64::try_from(created_at.unix_timestamp()).ok());
+            if (state == "open" || state == "opened")
+                && created_at.is_some_and(|created_at| created_at < timestamp)
+            {
+                count += 1;
             }
         }
+        count
+    }
 
-        let lifespan_per_pr = closed_prs.into_iter().map(|pr| {
-            let created_at_str = pr.get("created_at").unwrap().as_str().unwrap();
-            let closed_at_str = pr.get("closed_at").unwrap().as_str().unwrap();
+    /// Computes the ratio of open issues in the stream. GitHub reports them as `open` while GitLab
+    /// reports them as `opened`.
+    async fn open_ratio(mut issues: Box<dyn Stream<Item = Value> + Unpin + Send>) -> f64 {
+        let mut open = 0_u32;
+        let mut total = 0_u32;
+        while let Some(issue) = issues.next().await {
+            let state = issue
+                .get("state")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if state == "open" || state == "opened" {
+                open += 1;
+            }
+            total += 1;
+        }
+
+        if total == 0 {
+            0.0
+        } else {
+            f64::from(open) / f64::from(total)
+        }
+    }
+
+    /// Computes the ratio of closed to opened issues in the stream, all of them being opened in the
+    /// window. A window without issues has nothing left to close, so its rate is 1.
+    async fn close_rate(mut issues: Box<dyn Stream<Item = Value> + Unpin + Send>) -> f64 {
+        let mut closed = 0_u32;
+        let mut opened = 0_u32;
+        while let Some(issue) = issues.next().await {
+            let state = issue
+                .get("state")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if state == "closed" {
+                closed += 1;
+            }
+            opened += 1;
+        }
+
+        if opened == 0 {
+            1.0
+        } else {
+            f64::from(closed) / f64::from(opened)
+        }
+    }
+
+    /// Computes the mean lifespan of the closed issues or pull requests in the stream. GitLab
+    /// merge requests are considered closed when merged, using `merged_at` as the closing time.
+    async fn mean_lifespan_of_closed(
+        mut issues_or_prs: Box<dyn Stream<Item = Value> + Unpin + Send>,
+    ) -> f64 {
+        let mut closed = Vec::new();
+        while let Some(issue_or_pr) = issues_or_prs.next().await {
+            let state = issue_or_pr.get("state").unwrap().as_str().unwrap();
+            if state == "closed" || state == "merged" {
+                closed.push(issue_or_pr);
+            }
+        }
+
+        let lifespan_per_issue_or_pr = closed.into_iter().map(|issue_or_pr| {
+            let created_at_str = issue_or_pr.get("created_at").unwrap().as_str().unwrap();
+            let closed_at_str = issue_or_pr
+                .get("closed_at")
+                .and_then(Value::as_str)
+                .or_else(|| issue_or_pr.get("merged_at").and_then(Value::as_str))
+                .unwrap();
 
             let created_at = OffsetDateTime::parse(created_at_str, &Rfc3339).unwrap();
             let closed_at = OffsetDateTime::parse(closed_at_str, &Rfc3339).unwrap();
@@ -92,7 +223,7 @@ impl Retriever {
             lifespan.as_seconds_f64()
         });
 
-        lifespan_per_pr.mean()
+        lifespan_per_issue_or_pr.mean()
     }
 }
 
@@ -117,14 +248,25 @@ impl ContributionDataRetriever for Retriever {
     async fn get_issue_lifespan(
         &self,
         repository: &Repository,
-        last_issues: usize,
+        window: IssueWindow,
     ) -> Result<f64, Box<dyn Error>> {
         match repository {
             Repository::Unknown => Err("unknown repository".into()),
-            Repository::GitHub { name, organization } => Ok(self
-                .get_github_issue_lifespan(organization, name, last_issues)
-                .await),
-            Repository::GitLab { .. } | Repository::Raw { .. } => {
+            Repository::GitHub { name, organization } => Ok(Self::get_cached_issue_lifespan(
+                &self.github_cached_client,
+                organization,
+                name,
+                window,
+            )
+            .await),
+            Repository::GitLab { name, organization } => Ok(Self::get_cached_issue_lifespan(
+                &self.gitlab_cached_client,
+                organization,
+                name,
+                window,
+            )
+            .await),
+            Repository::Bitbucket { .. } | Repository::Raw { .. } => {
                 Err("not implemented contribution data retriever".into())
             }
         }
@@ -133,14 +275,109 @@ impl ContributionDataRetriever for Retriever {
     async fn get_pull_request_lifespan(
         &self,
         repository: &Repository,
-        last_pull_requests: usize,
+        window: IssueWindow,
+    ) -> Result<f64, Box<dyn Error>> {
+        match repository {
+            Repository::Unknown => Err("unknown repository".into()),
+            Repository::GitHub { name, organization } => {
+                Ok(Self::get_cached_pull_request_lifespan(
+                    &self.github_cached_client,
+                    organization,
+                    name,
+                    window,
+                )
+                .await)
+            }
+            Repository::GitLab { name, organization } => {
+                Ok(Self::get_cached_pull_request_lifespan(
+                    &self.gitlab_cached_client,
+                    organization,
+                    name,
+                    window,
+                )
+                .await)
+            }
+            Repository::Bitbucket { .. } | Repository::Raw { .. } => Err("not implemented".into()),
+        }
+    }
+
+    async fn get_open_issue_ratio(
+        &self,
+        repository: &Repository,
+        window: IssueWindow,
+    ) -> Result<f64, Box<dyn Error>> {
+        match repository {
+            Repository::Unknown => Err("unknown repository".into()),
+            Repository::GitHub { name, organization } => Ok(Self::get_cached_open_issue_ratio(
+                &self.github_cached_client,
+                organization,
+                name,
+                window,
+            )
+            .await),
+            Repository::GitLab { name, organization } => Ok(Self::get_cached_open_issue_ratio(
+                &self.gitlab_cached_client,
+                organization,
+                name,
+                window,
+            )
+            .await),
+            Repository::Bitbucket { .. } | Repository::Raw { .. } => Err("not implemented".into()),
+        }
+    }
+
+    async fn get_issue_close_rate(
+        &self,
+        repository: &Repository,
+        window: IssueWindow,
     ) -> Result<f64, Box<dyn Error>> {
         match repository {
             Repository::Unknown => Err("unknown repository".into()),
-            Repository::GitHub { name, organization } => Ok(self
-                .get_github_pull_request_lifespan(organization, name, last_pull_requests)
-                .await),
-            Repository::GitLab { .. } | Repository::Raw { .. } => Err("not implemented".into()),
+            Repository::GitHub { name, organization } => Ok(Self::get_cached_issue_close_rate(
+                &self.github_cached_client,
+                organization,
+                name,
+                window,
+            )
+            .await),
+            Repository::GitLab { name, organization } => Ok(Self::get_cached_issue_close_rate(
+                &self.gitlab_cached_client,
+                organization,
+                name,
+                window,
+            )
+            .await),
+            Repository::Bitbucket { .. } | Repository::Raw { .. } => Err("not implemented".into()),
+        }
+    }
+
+    async fn get_stale_open_pr_count(
+        &self,
+        repository: &Repository,
+        window: IssueWindow,
+        stale_after_days: u64,
+    ) -> Result<usize, Box<dyn Error>> {
+        match repository {
+            Repository::Unknown => Err("unknown repository".into()),
+            Repository::GitHub { name, organization } => Ok(Self::get_cached_stale_open_pr_count(
+                &self.github_cached_client,
+                organization,
+                name,
+                window,
+                stale_after_days,
+                self.clock.now_timestamp(),
+            )
+            .await),
+            Repository::GitLab { name, organization } => Ok(Self::get_cached_stale_open_pr_count(
+                &self.gitlab_cached_client,
+                organization,
+                name,
+                window,
+                stale_after_days,
+                self.clock.now_timestamp(),
+            )
+            .await),
+            Repository::Bitbucket { .. } | Repository::Raw { .. } => Err("not implemented".into()),
         }
     }
 }
@@ -149,15 +386,24 @@ impl ContributionDataRetriever for Retriever {
 mod tests {
     use super::*;
     use crate::infra::cached_issue_client::MockIssueStore;
-    use crate::infra::github::Authentication;
+    use crate::infra::clock::FixedClock;
+    use crate::infra::github::{self, Authentication};
+    use crate::infra::http;
     use crate::pkg::Repository;
 
+    const NOW: u64 = 1_700_000_000;
+
     #[tokio::test]
     async fn it_retrieves_the_issue_lifespan_of_dean() {
-        let http_client = reqwest::Client::default();
+        let http_client = http::default_client();
         let github_client = github::Client::new(http_client, authentication());
         let issue_store = mock_issue_store();
-        let retriever = Retriever::new(github_client, issue_store);
+        let retriever = Retriever::new(
+            Box::new(github_client) as Box<dyn IssueClient>,
+            gitlab_client(),
+            issue_store,
+            Box::new(FixedClock::new(NOW)),
+        );
 
         let issue_lifespan: f64 = retriever
             .get_issue_lifespan(
@@ -165,7 +411,7 @@ mod tests {
                     organization: "StaticDependencyAnalyzer".to_string(),
                     name: "dean".to_string(),
                 },
-                10,
+                IssueWindow::Last(10),
             )
             .await
             .unwrap();
@@ -178,10 +424,15 @@ mod tests {
 
     #[tokio::test]
     async fn it_retrieves_the_pull_request_lifespan_of_dean() {
-        let http_client = reqwest::Client::default();
+        let http_client = http::default_client();
         let github_client = github::Client::new(http_client, authentication());
         let issue_store = mock_issue_store();
-        let retriever = Retriever::new(github_client, issue_store);
+        let retriever = Retriever::new(
+            Box::new(github_client) as Box<dyn IssueClient>,
+            gitlab_client(),
+            issue_store,
+            Box::new(FixedClock::new(NOW)),
+        );
 
         let pr_lifespan: f64 = retriever
             .get_pull_request_lifespan(
@@ -189,7 +440,7 @@ mod tests {
                     organization: "StaticDependencyAnalyzer".to_string(),
                     name: "dean".to_string(),
                 },
-                10,
+                IssueWindow::Last(10),
             )
             .await
             .unwrap();
@@ -200,6 +451,145 @@ mod tests {
         assert!(pr_lifespan < a_week_in_seconds);
     }
 
+    #[tokio::test]
+    async fn it_retrieves_the_merge_request_lifespan_of_bfj() {
+        let http_client = http::default_client();
+        let github_client = github::Client::new(http_client, authentication());
+        let issue_store = mock_issue_store();
+        let retriever = Retriever::new(
+            Box::new(github_client) as Box<dyn IssueClient>,
+            gitlab_client(),
+            issue_store,
+            Box::new(FixedClock::new(NOW)),
+        );
+
+        let mr_lifespan: f64 = retriever
+            .get_pull_request_lifespan(
+                &Repository::GitLab {
+                    organization: "philbooth".to_string(),
+                    name: "bfj".to_string(),
+                },
+                IssueWindow::Last(10),
+            )
+            .await
+            .unwrap();
+
+        assert!(mr_lifespan > 0.0);
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_lifespan_of_closed_and_merged_items_only() {
+        let items = vec![
+            serde_json::json!({
+                "state": "closed",
+                "created_at": "2022-01-01T00:00:00Z",
+                "closed_at": "2022-01-01T00:01:40Z",
+            }),
+            serde_json::json!({
+                "state": "merged",
+                "created_at": "2022-01-01T00:00:00.000Z",
+                "closed_at": null,
+                "merged_at": "2022-01-01T00:05:00.000Z",
+            }),
+            serde_json::json!({
+                "state": "opened",
+                "created_at": "2022-01-01T00:00:00Z",
+                "closed_at": null,
+            }),
+        ];
+
+        let lifespan =
+            Retriever::mean_lifespan_of_closed(Box::new(tokio_stream::iter(items))).await;
+
+        assert!((lifespan - 200.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_ratio_of_open_and_opened_issues() {
+        let items = vec![
+            serde_json::json!({"state": "open"}),
+            serde_json::json!({"state": "opened"}),
+            serde_json::json!({"state": "closed"}),
+            serde_json::json!({"state": "closed"}),
+        ];
+
+        let ratio = Retriever::open_ratio(Box::new(tokio_stream::iter(items))).await;
+
+        assert!((ratio - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_ratio_of_closed_to_opened_issues() {
+        let items = vec![
+            serde_json::json!({"state": "open"}),
+            serde_json::json!({"state": "opened"}),
+            serde_json::json!({"state": "opened"}),
+            serde_json::json!({"state": "closed"}),
+        ];
+
+        let rate = Retriever::close_rate(Box::new(tokio_stream::iter(items))).await;
+
+        assert!((rate - 0.25).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn it_counts_the_open_and_opened_items_created_before_the_timestamp() {
+        let items = vec![
+            serde_json::json!({"state": "open", "created_at": "2022-01-01T00:00:00Z"}),
+            serde_json::json!({"state": "opened", "created_at": "2022-01-02T00:00:00Z"}),
+            serde_json::json!({"state": "open", "created_at": "2022-03-01T00:00:00Z"}),
+            serde_json::json!({"state": "closed", "created_at": "2022-01-01T00:00:00Z"}),
+        ];
+        let february_2022 = 1_643_673_600;
+
+        let count = Retriever::open_count_created_before(
+            Box::new(tokio_stream::iter(items)),
+            february_2022,
+        )
+        .await;
+
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn it_counts_the_stale_open_pull_requests_as_of_the_clock() {
+        let mut issue_store = Box::new(MockIssueStore::new());
+        issue_store
+            .expect_get_pull_requests()
+            .return_const(Some(vec![
+                serde_json::json!({"state": "open", "created_at": "2022-01-01T00:00:00Z"}),
+                serde_json::json!({"state": "open", "created_at": "2022-03-01T00:00:00Z"}),
+            ]));
+        let march_15_2022 = 1_647_302_400;
+        let retriever = Retriever::new(
+            Box::new(github::Client::new(
+                http::default_client(),
+                Authentication::None,
+            )) as Box<dyn IssueClient>,
+            gitlab_client(),
+            issue_store as Box<dyn IssueStore>,
+            Box::new(FixedClock::new(march_15_2022)),
+        );
+
+        let count = retriever
+            .get_stale_open_pr_count(
+                &Repository::GitHub {
+                    organization: "some_org".to_string(),
+                    name: "some_repo".to_string(),
+                },
+                IssueWindow::Last(100),
+                30,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(count, 1);
+    }
+
+    fn gitlab_client() -> gitlab::Client {
+        gitlab::Client::new(http::default_client())
+    }
+
     fn mock_issue_store() -> Box<dyn IssueStore> {
         let mut issue_store = Box::new(MockIssueStore::new());
         issue_store.expect_get_issues().return_const(None);
@@ -0,0 +1,157 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use moka::future::{Cache, CacheBuilder};
+
+use crate::infra::github;
+use crate::pkg::policy::RepositoryStatusRetriever;
+use crate::pkg::Repository;
+
+/// Paths where GitHub looks for the security policy of a repository.
+const SECURITY_POLICY_PATHS: [&str; 3] = ["SECURITY.md", ".github/SECURITY.md", "docs/SECURITY.md"];
+
+pub struct Retriever {
+    github_client: Arc<github::Client>,
+    archived_cache: Cache<Repository, bool>,
+    security_policy_cache: Cache<Repository, bool>,
+}
+
+impl Retriever {
+    pub fn new<C>(github_client: C) -> Self
+    where
+        C: Into<Arc<github::Client>>,
+    {
+        Self {
+            github_client: github_client.into(),
+            archived_cache: CacheBuilder::default().build(),
+            security_policy_cache: CacheBuilder::default().build(),
+        }
+    }
+
+    async fn is_github_repository_archived(
+        &self,
+        organization: &str,
+        name: &str,
+    ) -> Result<bool, anyhow::Error> {
+        let repository = self
+            .github_client
+            .get_repository(organization, name)
+            .await
+            .map_err(|e| anyhow!("unable to retrieve repository: {}", e))?;
+
+        repository["archived"]
+            .as_bool()
+            .ok_or_else(|| anyhow!("archived is not a boolean"))
+    }
+
+    async fn has_github_security_policy(
+        &self,
+        organization: &str,
+        name: &str,
+    ) -> Result<bool, anyhow::Error> {
+        for path in SECURITY_POLICY_PATHS {
+            let exists = self
+                .github_client
+                .has_file(organization, name, path)
+                .await
+                .map_err(|e| anyhow!("unable to retrieve {}: {}", path, e))?;
+            if exists {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[async_trait]
+impl RepositoryStatusRetriever for Retriever {
+    async fn is_archived(&self, repository: &Repository) -> Result<bool, Box<dyn Error>> {
+        match repository {
+            Repository::GitHub { organization, name } => self
+                .archived_cache
+                .try_get_with(
+                    repository.clone(),
+                    self.is_github_repository_archived(organization, name),
+                )
+                .await
+                .map_err(|e| anyhow!(e).into()),
+            Repository::Unknown
+            | Repository::GitLab { .. }
+            | Repository::Bitbucket { .. }
+            | Repository::Raw { .. } => Err("not implemented repository status retriever".into()),
+        }
+    }
+
+    async fn has_security_policy(&self, repository: &Repository) -> Result<bool, Box<dyn Error>> {
+        match repository {
+            Repository::GitHub { organization, name } => self
+                .security_policy_cache
+                .try_get_with(
+                    repository.clone(),
+                    self.has_github_security_policy(organization, name),
+                )
+                .await
+                .map_err(|e| anyhow!(e).into()),
+            Repository::Unknown
+            | Repository::GitLab { .. }
+            | Repository::Bitbucket { .. }
+            | Repository::Raw { .. } => Err("not implemented repository status retriever".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::github::Authentication;
+    use crate::infra::http;
+
+    #[tokio::test]
+    async fn it_retrieves_that_dean_is_not_archived() {
+        let github_client = github::Client::new(http::default_client(), Authentication::None);
+        let retriever = Retriever::new(github_client);
+
+        let archived = retriever
+            .is_archived(&Repository::GitHub {
+                organization: "StaticDependencyAnalyzer".to_string(),
+                name: "dean".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert!(!archived);
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_that_tokio_has_a_security_policy() {
+        let github_client = github::Client::new(http::default_client(), Authentication::None);
+        let retriever = Retriever::new(github_client);
+
+        let has_security_policy = retriever
+            .has_security_policy(&Repository::GitHub {
+                organization: "tokio-rs".to_string(),
+                name: "tokio".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert!(has_security_policy);
+    }
+
+    #[tokio::test]
+    async fn it_fails_for_non_github_repositories() {
+        let github_client = github::Client::new(http::default_client(), Authentication::None);
+        let retriever = Retriever::new(github_client);
+
+        let archived = retriever
+            .is_archived(&Repository::Raw {
+                address: "https://example.com/repo.git".to_string(),
+            })
+            .await;
+
+        assert!(archived.is_err());
+    }
+}
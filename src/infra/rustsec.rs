@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use log::{debug, info, warn};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+
+use crate::infra::git;
+use crate::infra::osv::cvss_v3_base_score;
+use crate::pkg::policy::{Advisory, AdvisoryRetriever};
+
+pub const DEFAULT_ADVISORY_DB_URL: &str = "https://github.com/rustsec/advisory-db";
+
+#[derive(Deserialize)]
+struct AdvisoryFile {
+    advisory: AdvisoryMetadata,
+    #[serde(default)]
+    versions: AdvisoryVersions,
+}
+
+#[derive(Deserialize)]
+struct AdvisoryMetadata {
+    id: String,
+    package: String,
+    cvss: Option<String>,
+    informational: Option<String>,
+    withdrawn: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct AdvisoryVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+/// Advisory of the database with the ranges of the versions it doesn't affect.
+struct CrateAdvisory {
+    advisory: Advisory,
+    patched: Vec<VersionReq>,
+    unaffected: Vec<VersionReq>,
+}
+
+impl CrateAdvisory {
+    fn affects(&self, version: &Version) -> bool {
+        !self
+            .patched
+            .iter()
+            .chain(&self.unaffected)
+            .any(|range| range.matches(version))
+    }
+}
+
+/// Advisories of the `RustSec` database, loaded from a shallow clone of its git repository on the
+/// first retrieval and kept by crate for the rest of the run.
+pub struct AdvisoryDatabase {
+    url: String,
+    advisories: OnceCell<HashMap<String, Vec<CrateAdvisory>>>,
+}
+
+impl AdvisoryDatabase {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            advisories: OnceCell::new(),
+        }
+    }
+
+    async fn load(&self) -> Result<HashMap<String, Vec<CrateAdvisory>>, Box<dyn Error>> {
+        info!("loading the RustSec advisory database from {}", self.url);
+        let repository = git::Repository::new(&self.url, 1).await?;
+        let files = repository.files("crates").await?;
+
+        let mut advisories: HashMap<String, Vec<CrateAdvisory>> = HashMap::new();
+        for (path, contents) in files {
+            let extension = Path::new(&path).extension().and_then(OsStr::to_str);
+            if !matches!(extension, Some("md" | "toml")) {
+                continue;
+            }
+            match parse_advisory(&String::from_utf8_lossy(&contents)) {
+                Ok(Some((package, advisory))) => {
+                    advisories.entry(package).or_default().push(advisory);
+                }
+                Ok(None) => debug!("skipping the withdrawn advisory {}", path),
+                Err(e) => warn!("unable to parse the advisory {}: {}", path, e),
+            }
+        }
+        Ok(advisories)
+    }
+}
+
+#[async_trait]
+impl AdvisoryRetriever for AdvisoryDatabase {
+    async fn advisories(&self, name: &str, version: &str) -> Result<Vec<Advisory>, Box<dyn Error>> {
+        let advisories = self.advisories.get_or_try_init(|| self.load()).await?;
+
+        let Ok(version) = Version::parse(version) else {
+            debug!(
+                "the version {} of {} is not a semver version, so no advisory affects it",
+                version, name
+            );
+            return Ok(vec![]);
+        };
+        Ok(advisories
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter(|advisory| advisory.affects(&version))
+            .map(|advisory| advisory.advisory.clone())
+            .collect())
+    }
+}
+
+/// Parses an advisory, either a TOML file or a Markdown one whose front matter is a TOML code
+/// block, returning its package unless it's withdrawn.
+fn parse_advisory(contents: &str) -> Result<Option<(String, CrateAdvisory)>, anyhow::Error> {
+    let toml = match contents.trim_start().strip_prefix("```toml") {
+        Some(front_matter) => front_matter
+            .split_once("\n```")
+            .map(|(toml, _)| toml)
+            .ok_or_else(|| anyhow!("the front matter is not closed"))?,
+        None => contents,
+    };
+    let file: AdvisoryFile = toml::from_str(toml).context("invalid advisory")?;
+    if file.advisory.withdrawn.is_some() {
+        return Ok(None);
+    }
+
+    let parse_ranges = |ranges: &[String]| {
+        ranges
+            .iter()
+            .map(|range| {
+                VersionReq::parse(range).with_context(|| format!("invalid version range {range}"))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+    Ok(Some((
+        file.advisory.package,
+        CrateAdvisory {
+            advisory: Advisory {
+                id: file.advisory.id,
+                cvss_score: file.advisory.cvss.as_deref().and_then(cvss_v3_base_score),
+                informational: file.advisory.informational,
+            },
+            patched: parse_ranges(&file.versions.patched)?,
+            unaffected: parse_ranges(&file.versions.unaffected)?,
+        },
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADVISORY: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2020-0071"
+package = "time"
+date = "2020-11-18"
+cvss = "CVSS:3.1/AV:L/AC:H/PR:N/UI:N/S:U/C:N/I:N/A:H"
+keywords = ["segfault"]
+
+[versions]
+patched = [">= 0.2.23"]
+unaffected = ["=0.2.0", "=0.2.1", "=0.2.2", "=0.2.3", "=0.2.4", "=0.2.5", "=0.2.6"]
+```
+
+# Potential segfault in the time crate
+"#;
+
+    #[test]
+    fn it_parses_the_front_matter_of_an_advisory() {
+        let (package, advisory) = parse_advisory(ADVISORY).unwrap().unwrap();
+
+        assert_eq!(package, "time");
+        assert_eq!(
+            advisory.advisory,
+            Advisory {
+                id: "RUSTSEC-2020-0071".to_string(),
+                cvss_score: Some(5.1),
+                informational: None,
+            }
+        );
+        for (version, affected) in [
+            ("0.1.45", true),
+            ("0.2.3", false),
+            ("0.2.22", true),
+            ("0.3.17", false),
+        ] {
+            assert_eq!(
+                advisory.affects(&Version::parse(version).unwrap()),
+                affected,
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn it_skips_the_withdrawn_advisories() {
+        let advisory = r#"
+[advisory]
+id = "RUSTSEC-2019-0008"
+package = "generic-array"
+withdrawn = "2019-09-04"
+
+[versions]
+patched = []
+"#;
+
+        assert!(parse_advisory(advisory).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_the_advisories_affecting_a_version_of_a_crate() {
+        let database = AdvisoryDatabase::new(DEFAULT_ADVISORY_DB_URL);
+
+        let advisories = database.advisories("time", "0.1.45").await.unwrap();
+
+        assert!(advisories
+            .iter()
+            .any(|advisory| advisory.id == "RUSTSEC-2020-0071"));
+        assert!(database
+            .advisories("time", "0.3.17")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
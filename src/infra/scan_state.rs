@@ -0,0 +1,320 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use clap::ValueEnum;
+
+use crate::infra::cache_store::Pool;
+use crate::pkg::policy::{Measurement, Severity};
+use crate::pkg::recognizer::ECOSYSTEMS;
+use crate::pkg::{DependencyKind, Repository};
+use crate::{Dependency, Evaluation};
+
+/// Name, version and ecosystem of a dependency, as stored in the database.
+pub type DependencyKey = (String, String, String);
+
+/// The dependencies evaluated by a scan that was interrupted, and their evaluations.
+#[derive(Debug, Default)]
+pub struct ScanState {
+    pub evaluated: HashSet<DependencyKey>,
+    pub evaluations: Vec<Evaluation>,
+}
+
+/// Stores the evaluations of the dependencies of the scan in progress as soon as each one is
+/// evaluated, keyed by the hash of the lock files, so that an interrupted scan can be resumed
+/// instead of started over.
+pub struct Sqlite {
+    pool: Pool,
+}
+
+impl Sqlite {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    pub fn init(&self) -> Result<(), Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE IF NOT EXISTS scanstate_dependency (
+    lock_files_hash TEXT NOT NULL,
+    dependency TEXT NOT NULL,
+    version TEXT NOT NULL,
+    ecosystem TEXT NOT NULL,
+    PRIMARY KEY (lock_files_hash, dependency, version, ecosystem)
+);
+
+CREATE TABLE IF NOT EXISTS scanstate_evaluation (
+    lock_files_hash TEXT NOT NULL,
+    dependency TEXT NOT NULL,
+    version TEXT NOT NULL,
+    ecosystem TEXT NOT NULL,
+    latest_version TEXT,
+    repository TEXT NOT NULL,
+    retrieval_error TEXT,
+    policy TEXT NOT NULL,
+    result TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    fail_score REAL NOT NULL,
+    severity TEXT NOT NULL,
+    measurement TEXT,
+    PRIMARY KEY (lock_files_hash, dependency, version, ecosystem, policy)
+);
+",
+        )?;
+
+        Ok(())
+    }
+
+    /// Saves the evaluations of a dependency of the scan of the lock files with the given hash.
+    pub fn save_evaluations(
+        &self,
+        lock_files_hash: &str,
+        dependency: &Dependency,
+        evaluations: &[Evaluation],
+    ) -> Result<(), Box<dyn Error>> {
+        let mut conn = self.pool.get()?;
+        let ecosystem = dependency.ecosystem.unwrap_or_default();
+
+        let tx = conn.transaction()?;
+        {
+            tx.execute(
+                "INSERT OR REPLACE INTO scanstate_dependency (lock_files_hash, dependency, version, ecosystem) VALUES (?, ?, ?, ?)",
+                rusqlite::params![lock_files_hash, &dependency.name, &dependency.version, ecosystem],
+            )?;
+
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO scanstate_evaluation (lock_files_hash, dependency, version, ecosystem, latest_version, repository, retrieval_error, policy, result, reason, fail_score, severity, measurement) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            for evaluation in evaluations {
+                let dependency = evaluation.dependency();
+                let (result, reason, severity, measurement) = match evaluation {
+                    Evaluation::Pass { .. } => ("pass", "", Severity::default(), None),
+                    Evaluation::Fail {
+                        reason,
+                        severity,
+                        measurement,
+                        ..
+                    } => ("fail", reason.as_str(), *severity, measurement.as_ref()),
+                    Evaluation::Skip { reason, .. } => {
+                        ("skip", reason.as_str(), Severity::default(), None)
+                    }
+                };
+                stmt.execute(rusqlite::params![
+                    lock_files_hash,
+                    &dependency.name,
+                    &dependency.version,
+                    ecosystem,
+                    &dependency.latest_version,
+                    dependency.repository.url().unwrap_or_default(),
+                    &dependency.retrieval_error,
+                    evaluation.policy(),
+                    result,
+                    reason,
+                    evaluation.fail_score(),
+                    severity.as_str(),
+                    measurement.map(serde_json::to_string).transpose()?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Retrieves the dependencies evaluated by the scan of the lock files with the given hash, and
+    /// their evaluations.
+    pub fn load(&self, lock_files_hash: &str) -> Result<ScanState, Box<dyn Error>> {
+        let conn = self.pool.get()?;
+
+        let mut select_dependency_stmt = conn.prepare(
+            "SELECT dependency, version, ecosystem FROM scanstate_dependency WHERE lock_files_hash = ?",
+        )?;
+        let evaluated = select_dependency_stmt
+            .query_map([lock_files_hash], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<rusqlite::Result<HashSet<DependencyKey>>>()?;
+
+        let mut select_evaluation_stmt = conn.prepare(
+            "SELECT dependency, version, ecosystem, latest_version, repository, retrieval_error, policy, result, reason, fail_score, severity, measurement FROM scanstate_evaluation WHERE lock_files_hash = ?",
+        )?;
+        let evaluations = select_evaluation_stmt
+            .query_map([lock_files_hash], |row| {
+                let ecosystem = row.get::<_, String>(2)?;
+                let repository = row.get::<_, String>(4)?;
+                let dependency = Dependency {
+                    name: row.get(0)?,
+                    version: row.get(1)?,
+                    latest_version: row.get(3)?,
+                    repository: if repository.is_empty() {
+                        Repository::Unknown
+                    } else {
+                        Repository::parse_url(&repository)
+                    },
+                    retrieval_error: row.get(5)?,
+                    ecosystem: ECOSYSTEMS.into_iter().find(|e| *e == ecosystem),
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
+                };
+                Ok((
+                    dependency,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, String>(7)?,
+                    row.get::<_, String>(8)?,
+                    row.get::<_, f64>(9)?,
+                    row.get::<_, String>(10)?,
+                    row.get::<_, Option<String>>(11)?,
+                ))
+            })?
+            .map(|row| {
+                let (dependency, policy_name, result, reason, fail_score, severity, measurement) =
+                    row?;
+                Ok(match result.as_str() {
+                    "fail" => Evaluation::Fail {
+                        policy_name,
+                        dependency,
+                        reason,
+                        fail_score,
+                        severity: Severity::from_str(&severity, true)?,
+                        measurement: measurement
+                            .map(|measurement| serde_json::from_str::<Measurement>(&measurement))
+                            .transpose()?,
+                    },
+                    "skip" => Evaluation::Skip {
+                        policy_name,
+                        dependency,
+                        reason,
+                    },
+                    _ => Evaluation::Pass {
+                        policy_name,
+                        dependency,
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        Ok(ScanState {
+            evaluated,
+            evaluations,
+        })
+    }
+
+    /// Removes the state of the scan of the lock files with the given hash, once it is completed
+    /// or started over.
+    pub fn clear(&self, lock_files_hash: &str) -> Result<(), Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        for table in ["scanstate_dependency", "scanstate_evaluation"] {
+            conn.execute(
+                &format!("DELETE FROM {table} WHERE lock_files_hash = ?"),
+                [lock_files_hash],
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_saves_and_loads_the_evaluations_of_the_scan() {
+        let scan_state = scan_state();
+        let evaluations = vec![
+            Evaluation::Fail {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep"),
+                reason: "some_reason".to_string(),
+                fail_score: 1.5,
+                severity: Severity::Warn,
+                measurement: Some(Measurement::new(3.0, 2.0).with_detail("author", "someone")),
+            },
+            Evaluation::Skip {
+                policy_name: "policy2".to_string(),
+                dependency: dependency("some_dep"),
+                reason: "some_reason".to_string(),
+            },
+        ];
+
+        scan_state
+            .save_evaluations("some_hash", &dependency("some_dep"), &evaluations)
+            .unwrap();
+        scan_state
+            .save_evaluations("some_hash", &dependency("other_dep"), &[])
+            .unwrap();
+        scan_state
+            .save_evaluations("other_hash", &dependency("another_dep"), &[])
+            .unwrap();
+
+        let state = scan_state.load("some_hash").unwrap();
+
+        assert_eq!(
+            state.evaluated,
+            HashSet::from([key("some_dep"), key("other_dep")])
+        );
+        assert_eq!(state.evaluations.len(), 2);
+        for evaluation in &evaluations {
+            assert!(state.evaluations.contains(evaluation));
+        }
+    }
+
+    #[test]
+    fn it_clears_the_state_of_the_scan() {
+        let scan_state = scan_state();
+        scan_state
+            .save_evaluations(
+                "some_hash",
+                &dependency("some_dep"),
+                &[Evaluation::Pass {
+                    policy_name: "policy1".to_string(),
+                    dependency: dependency("some_dep"),
+                }],
+            )
+            .unwrap();
+        scan_state
+            .save_evaluations("other_hash", &dependency("other_dep"), &[])
+            .unwrap();
+
+        scan_state.clear("some_hash").unwrap();
+
+        let state = scan_state.load("some_hash").unwrap();
+        assert!(state.evaluated.is_empty());
+        assert!(state.evaluations.is_empty());
+        assert_eq!(
+            scan_state.load("other_hash").unwrap().evaluated,
+            HashSet::from([key("other_dep")])
+        );
+    }
+
+    fn scan_state() -> Sqlite {
+        let pool = crate::infra::cache_store::in_memory_pool().unwrap();
+        let scan_state = Sqlite::new(pool);
+        scan_state.init().unwrap();
+        scan_state
+    }
+
+    fn key(name: &str) -> DependencyKey {
+        (name.to_string(), "1.0.0".to_string(), "npm".to_string())
+    }
+
+    fn dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            latest_version: Some("1.1.0".to_string()),
+            repository: Repository::GitHub {
+                organization: "some_org".to_string(),
+                name: name.to_string(),
+            },
+            retrieval_error: None,
+            ecosystem: Some("npm"),
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
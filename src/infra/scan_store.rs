@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::infra::cache_store::Pool;
+use crate::infra::clock::Clock;
+use crate::pkg::history::{Scan, ScannedDependency};
+use crate::pkg::policy;
+use crate::Evaluation;
+
+/// Stores the evaluations of every scan, so that a scan can be compared with the previous one.
+/// Unlike the cache stores, its entries don't expire and are not removed by `dean cache clear`.
+pub struct Sqlite {
+    pool: Pool,
+}
+
+impl Sqlite {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    pub fn init(&self) -> Result<(), Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE IF NOT EXISTS scanstore_scan (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scanned_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS scanstore_evaluation (
+    scan_id INTEGER NOT NULL,
+    dependency TEXT NOT NULL,
+    version TEXT NOT NULL,
+    ecosystem TEXT NOT NULL,
+    policy TEXT NOT NULL,
+    result TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    fail_score REAL NOT NULL,
+    PRIMARY KEY (scan_id, dependency, version, ecosystem, policy)
+);
+",
+        )?;
+
+        Ok(())
+    }
+
+    /// Saves the evaluations of a scan made now.
+    pub fn save_scan(&self, evaluations: &[Evaluation]) -> Result<(), Box<dyn Error>> {
+        let mut conn = self.pool.get()?;
+        let scanned_at = policy::Clock::now_timestamp(&Clock::default());
+
+        let tx = conn.transaction()?;
+        {
+            tx.execute(
+                "INSERT INTO scanstore_scan (scanned_at) VALUES (?)",
+                [scanned_at],
+            )?;
+            let scan_id = tx.last_insert_rowid();
+
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO scanstore_evaluation (scan_id, dependency, version, ecosystem, policy, result, reason, fail_score) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            for evaluation in evaluations {
+                let dependency = evaluation.dependency();
+                let (result, reason) = match evaluation {
+                    Evaluation::Pass { .. } => ("pass", ""),
+                    Evaluation::Fail { reason, .. } => ("fail", reason.as_str()),
+                    Evaluation::Skip { reason, .. } => ("skip", reason.as_str()),
+                };
+                stmt.execute(rusqlite::params![
+                    scan_id,
+                    &dependency.name,
+                    &dependency.version,
+                    dependency.ecosystem.unwrap_or_default(),
+                    evaluation.policy(),
+                    result,
+                    reason,
+                    evaluation.fail_score(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Retrieves the given number of the latest scans, the most recent first.
+    pub fn latest_scans(&self, count: usize) -> Result<Vec<Scan>, Box<dyn Error>> {
+        let conn = self.pool.get()?;
+
+        let mut select_scan_stmt =
+            conn.prepare("SELECT id, scanned_at FROM scanstore_scan ORDER BY id DESC LIMIT ?")?;
+        let mut select_evaluation_stmt = conn.prepare(
+            "SELECT dependency, version, ecosystem, policy, result FROM scanstore_evaluation WHERE scan_id = ?",
+        )?;
+
+        let scans = select_scan_stmt
+            .query_map([count], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, u64>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        scans
+            .into_iter()
+            .map(|(scan_id, scanned_at)| {
+                let mut dependencies: BTreeMap<(String, String, String), Vec<String>> =
+                    BTreeMap::new();
+                let rows = select_evaluation_stmt.query_map([scan_id], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                })?;
+                for row in rows {
+                    let (name, version, ecosystem, policy, result) = row?;
+                    let failed_policies =
+                        dependencies.entry((name, version, ecosystem)).or_default();
+                    if result == "fail" {
+                        failed_policies.push(policy);
+                    }
+                }
+
+                Ok(Scan {
+                    scanned_at,
+                    dependencies: dependencies
+                        .into_iter()
+                        .map(
+                            |((name, version, ecosystem), failed_policies)| ScannedDependency {
+                                name,
+                                version,
+                                ecosystem: Some(ecosystem).filter(|e| !e.is_empty()),
+                                failed_policies,
+                            },
+                        )
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pkg::policy::Severity;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository;
+    use crate::Dependency;
+
+    #[test]
+    fn it_saves_and_retrieves_the_latest_scans() {
+        let scan_store = scan_store();
+
+        scan_store
+            .save_scan(&[Evaluation::Pass {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep"),
+            }])
+            .unwrap();
+        scan_store
+            .save_scan(&[
+                Evaluation::Fail {
+                    policy_name: "policy1".to_string(),
+                    dependency: dependency("some_dep"),
+                    reason: "some_reason".to_string(),
+                    fail_score: 1.0,
+                    severity: Severity::Error,
+                    measurement: None,
+                },
+                Evaluation::Skip {
+                    policy_name: "policy1".to_string(),
+                    dependency: dependency("other_dep"),
+                    reason: "some_reason".to_string(),
+                },
+            ])
+            .unwrap();
+
+        let scans = scan_store.latest_scans(2).unwrap();
+
+        assert_eq!(scans.len(), 2);
+        assert_eq!(
+            scans[0].dependencies,
+            vec![scanned("other_dep", &[]), scanned("some_dep", &["policy1"])]
+        );
+        assert_eq!(scans[1].dependencies, vec![scanned("some_dep", &[])]);
+    }
+
+    #[test]
+    fn it_retrieves_no_scans_when_nothing_was_scanned() {
+        assert!(scan_store().latest_scans(2).unwrap().is_empty());
+    }
+
+    fn scan_store() -> Sqlite {
+        let pool = crate::infra::cache_store::in_memory_pool().unwrap();
+        let scan_store = Sqlite::new(pool);
+        scan_store.init().unwrap();
+        scan_store
+    }
+
+    fn scanned(name: &str, failed_policies: &[&str]) -> ScannedDependency {
+        ScannedDependency {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            ecosystem: Some("npm".to_string()),
+            failed_policies: failed_policies.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            latest_version: None,
+            repository: Repository::Unknown,
+            retrieval_error: None,
+            ecosystem: Some("npm"),
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
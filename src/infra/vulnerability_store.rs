@@ -0,0 +1,185 @@
+use std::error::Error;
+
+use serde_json::Value;
+
+use crate::infra::cache_store::{self, CacheStore, Pool, TableStats};
+use crate::infra::osv::VulnerabilityStore;
+use crate::pkg::policy::{self, Vulnerability};
+
+const TABLES: [&str; 1] = ["vulnerabilitystore_vulnerability"];
+
+pub struct Sqlite {
+    pool: Pool,
+    ttl_in_seconds: u64,
+    clock: Box<dyn policy::Clock>,
+}
+
+impl Sqlite {
+    pub fn new(pool: Pool, ttl_in_seconds: u64, clock: Box<dyn policy::Clock>) -> Self {
+        Self {
+            pool,
+            ttl_in_seconds,
+            clock,
+        }
+    }
+
+    fn now_timestamp(&self) -> u64 {
+        self.clock.now_timestamp()
+    }
+
+    fn oldest_valid_timestamp(&self) -> u64 {
+        self.now_timestamp().saturating_sub(self.ttl_in_seconds)
+    }
+
+    pub fn init(&self) -> Result<(), Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE IF NOT EXISTS vulnerabilitystore_vulnerability (
+    ecosystem TEXT NOT NULL,
+    name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    vulnerabilities TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (ecosystem, name, version)
+);
+",
+        )?;
+
+        Ok(())
+    }
+}
+
+impl CacheStore for Sqlite {
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        cache_store::clear_tables(&conn, &TABLES)?;
+        Ok(())
+    }
+
+    fn stats(&self) -> Result<Vec<TableStats>, Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        let stats = cache_store::table_stats(&conn, &TABLES, self.oldest_valid_timestamp())?;
+        Ok(stats)
+    }
+
+    fn export(&self) -> Result<Vec<Value>, Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        let rows = cache_store::export_tables(&conn, &TABLES, &["vulnerabilities"])?;
+        Ok(rows)
+    }
+}
+
+impl VulnerabilityStore for Sqlite {
+    fn get_vulnerabilities(
+        &self,
+        ecosystem: &str,
+        name: &str,
+        version: &str,
+    ) -> Option<Vec<Vulnerability>> {
+        let conn = self.pool.get().ok()?;
+        let vulnerabilities: String = conn
+            .query_row(
+                "SELECT vulnerabilities FROM vulnerabilitystore_vulnerability WHERE ecosystem = ? AND name = ? AND version = ? AND fetched_at >= ?",
+                rusqlite::params![ecosystem, name, version, self.oldest_valid_timestamp()],
+                |row| row.get(0),
+            )
+            .ok()?;
+
+        serde_json::from_str(&vulnerabilities).ok()
+    }
+
+    fn save_vulnerabilities(
+        &self,
+        ecosystem: &str,
+        name: &str,
+        version: &str,
+        vulnerabilities: &[Vulnerability],
+    ) -> Result<(), Box<dyn Error>> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO vulnerabilitystore_vulnerability (ecosystem, name, version, vulnerabilities, fetched_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![
+                ecosystem,
+                name,
+                version,
+                serde_json::to_string(vulnerabilities)?,
+                self.now_timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::clock::FixedClock;
+
+    #[test]
+    fn it_stores_and_retrieves_the_vulnerabilities_of_a_version() {
+        let vulnerability_store = store();
+
+        vulnerability_store
+            .save_vulnerabilities("npm", "lodash", "4.17.20", &vulnerabilities())
+            .unwrap();
+        vulnerability_store
+            .save_vulnerabilities("npm", "lodash", "4.17.21", &[])
+            .unwrap();
+
+        assert_eq!(
+            vulnerability_store.get_vulnerabilities("npm", "lodash", "4.17.20"),
+            Some(vulnerabilities())
+        );
+        assert_eq!(
+            vulnerability_store.get_vulnerabilities("npm", "lodash", "4.17.21"),
+            Some(vec![])
+        );
+        assert_eq!(
+            vulnerability_store.get_vulnerabilities("npm", "lodash", "4.17.19"),
+            None
+        );
+    }
+
+    #[test]
+    fn if_the_vulnerabilities_are_expired_it_returns_none() {
+        let vulnerability_store = store();
+        vulnerability_store
+            .save_vulnerabilities("npm", "lodash", "4.17.20", &vulnerabilities())
+            .unwrap();
+        vulnerability_store
+            .pool
+            .get()
+            .unwrap()
+            .execute(
+                "UPDATE vulnerabilitystore_vulnerability SET fetched_at = 0",
+                [],
+            )
+            .unwrap();
+
+        assert_eq!(
+            vulnerability_store.get_vulnerabilities("npm", "lodash", "4.17.20"),
+            None
+        );
+    }
+
+    fn store() -> Sqlite {
+        let pool = cache_store::in_memory_pool().unwrap();
+        let vulnerability_store = Sqlite::new(pool, 3600, Box::new(FixedClock::new(1_700_000_000)));
+        vulnerability_store.init().unwrap();
+        vulnerability_store
+    }
+
+    fn vulnerabilities() -> Vec<Vulnerability> {
+        vec![
+            Vulnerability {
+                id: "GHSA-35jh-r3h4-6jhm".to_string(),
+                cvss_score: Some(7.2),
+            },
+            Vulnerability {
+                id: "GHSA-29mw-wpgm-hmr9".to_string(),
+                cvss_score: None,
+            },
+        ]
+    }
+}
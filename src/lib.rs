@@ -0,0 +1,22 @@
+#![deny(clippy::pedantic, clippy::style)]
+#![deny(unused)]
+#![allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+
+// The modules below the scanner were written as internals of the binary, so their public API
+// doesn't document its errors and panics to the level pedantic expects from a library.
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+pub mod cmd;
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+pub mod factory;
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+pub mod infra;
+mod lazy;
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+pub mod pkg;
+pub mod scanner;
+
+pub type Result<T, E = anyhow::Error> = core::result::Result<T, E>;
+
+pub use pkg::policy::{Evaluation, Policy};
+pub use pkg::Dependency;
+pub use scanner::Scanner;
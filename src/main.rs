@@ -1,45 +1,189 @@
 #![deny(clippy::pedantic, clippy::style)]
 #![deny(unused)]
 
-mod cmd;
-mod factory;
-mod infra;
-mod lazy;
-mod pkg;
-
-pub type Result<T, E = anyhow::Error> = core::result::Result<T, E>;
-
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::Context;
-use futures::future::join_all;
-use log::{error, info, warn, LevelFilter};
-use tokio::fs::File;
+use anyhow::{anyhow, Context};
+use dean::cmd::{parse_args, CacheCommands, Commands, ConfigCommands, LogFormat};
+use dean::factory::Factory;
+use dean::infra::git;
+use dean::infra::json_logger::JsonLogger;
+use dean::pkg::config::Config;
+use dean::pkg::history;
+use dean::pkg::policy::{
+    evaluations_of_failing_dependencies, exceeds_fail_threshold, exceeds_risk_threshold,
+    ScanSummary,
+};
+use dean::pkg::timings::Timings;
+use dean::pkg::{DependencyFilter, ResultReporter};
+use dean::{Evaluation, Result, Scanner};
+use log::{error, warn, LevelFilter};
+use regex::RegexSet;
 use tokio_stream::StreamExt;
 
-use crate::cmd::{parse_args, Commands, ConfigCommands};
-use crate::factory::Factory;
-use crate::pkg::config::Config;
-use crate::pkg::policy::{Evaluation, Policy};
-use crate::pkg::{Dependency, ResultReporter};
+const FAIL_THRESHOLD_EXCEEDED_EXIT_CODE: i32 = 2;
+/// Number of dependencies listed in the timings of the scan.
+const SLOWEST_DEPENDENCIES: usize = 10;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<()> {
     let args = parse_args();
-    load_logger(&args.log_level)?;
+    load_logger(&args.log_level, args.log_format)?;
 
-    let config = Rc::new(Config::load_from_default_file_path_or_default().await);
+    let config = Rc::new(match &args.config {
+        Some(path) => Config::load_from_file(Path::new(path)).await?,
+        None => Config::load_from_default_file_path_or_default().await,
+    });
     let mut factory = Factory::new(config.clone());
+    if let Some(cache_dir) = &args.cache_dir {
+        factory.set_cache_dir(PathBuf::from(cache_dir));
+    }
+    factory.set_no_cache(args.no_cache);
 
     match &args.command {
-        Commands::Scan { lock_file } => {
-            scan_lock_file(&mut factory, lock_file).await?;
+        Commands::Scan {
+            lock_file,
+            format,
+            output,
+            only,
+            exclude,
+            prod_only,
+            include_dev: _,
+            failures_only,
+            progress,
+            fail_threshold,
+            fail_severity,
+            risk_score,
+            max_failures,
+            clone_depth,
+            max_retries,
+            dependency_timeout,
+            offline,
+            resume,
+            timings,
+            as_of,
+        } => {
+            factory.set_clone_depth(*clone_depth);
+            if *clone_depth != 0 && !*offline && !git::is_git_installed() {
+                warn!("git is not installed, so the repositories are fully cloned instead of with a depth of {clone_depth}");
+            }
+            factory.set_max_retries(*max_retries);
+            if let Some(as_of) = as_of {
+                factory.set_as_of(*as_of);
+            }
+            let only = RegexSet::new(only).context("invalid --only regex")?;
+            let exclude = RegexSet::new(exclude).context("invalid --exclude regex")?;
+            if *offline {
+                factory.set_offline(true);
+                return list_lock_file_policies(&mut factory, lock_file, only, exclude, *prod_only)
+                    .await;
+            }
+
+            let output_file = output
+                .as_deref()
+                .unwrap_or_else(|| format.default_output_file());
+            let progress = *progress || std::io::stderr().is_terminal();
+            let scan_timings = timings.then(|| Arc::new(Timings::default()));
+            if let Some(scan_timings) = &scan_timings {
+                factory.set_timings(scan_timings.clone());
+            }
+            let factory_scan_store = factory.scan_store();
+            let mut scanner = Scanner::from_factory(factory);
+            scanner.set_only(only);
+            scanner.set_exclude(exclude);
+            scanner.set_prod_only(*prod_only);
+            scanner.set_progress(progress);
+            scanner.set_quiet(args.quiet);
+            scanner.set_log_format(args.log_format);
+            scanner.set_resume(*resume);
+            scanner.set_dependency_timeout(Duration::from_secs(*dependency_timeout));
+            let results = scanner.scan_all(lock_file).await?;
+            if let Some(scan_timings) = &scan_timings {
+                eprint!("{}", scan_timings.summary(SLOWEST_DEPENDENCIES));
+            }
+
+            if let Err(err) = factory_scan_store.save_scan(&results) {
+                error!("unable to save the scan to the history: {}", err);
+            }
+
+            let mut reporter =
+                Factory::result_reporter(*format, output_file, &lock_file[0], config.risk_score)?;
+            let reported_results = if *failures_only {
+                evaluations_of_failing_dependencies(&results)
+            } else {
+                results.clone()
+            };
+            reporter.report_results(reported_results).await?;
+            let summary = ScanSummary::new(&results);
+            eprintln!("{summary}");
+            if let Some(max_failures) = max_failures {
+                if summary.failing > *max_failures {
+                    error!(
+                        "{} dependencies failed, more than the maximum of {max_failures}",
+                        summary.failing
+                    );
+                    std::process::exit(FAIL_THRESHOLD_EXCEEDED_EXIT_CODE);
+                }
+            }
+            let exceeds_threshold = if *risk_score {
+                exceeds_risk_threshold(
+                    &results,
+                    *fail_threshold,
+                    *fail_severity,
+                    &config.risk_score,
+                )
+            } else {
+                exceeds_fail_threshold(&results, *fail_threshold, *fail_severity)
+            };
+            if exceeds_threshold {
+                error!("some dependencies exceed the fail threshold of {fail_threshold}");
+                std::process::exit(FAIL_THRESHOLD_EXCEEDED_EXIT_CODE);
+            }
         }
-        Commands::Config { command } => match command {
-            ConfigCommands::Show => {
-                println!("{}", config.dump_to_string()?);
+        Commands::Explain {
+            dependency,
+            lock_file,
+        } => explain_dependency(&mut factory, lock_file, dependency).await?,
+        Commands::Diff => print_scan_diff(&factory)?,
+        Commands::Config { command } => {
+            run_config_command(&config, args.config.as_deref(), command).await?;
+        }
+        Commands::Cache { command } => match command {
+            CacheCommands::Clear => {
+                for store in factory.cache_stores() {
+                    store
+                        .clear()
+                        .map_err(|e| anyhow!("unable to clear the cache: {}", e))?;
+                }
+                println!("cache cleared");
+            }
+            CacheCommands::Stats => {
+                for store in factory.cache_stores() {
+                    let stats = store
+                        .stats()
+                        .map_err(|e| anyhow!("unable to read the cache stats: {}", e))?;
+                    for table_stats in stats {
+                        println!(
+                            "{}: {} rows ({} expired)",
+                            table_stats.table, table_stats.rows, table_stats.expired_rows
+                        );
+                    }
+                }
+            }
+            CacheCommands::Export => {
+                for store in factory.cache_stores() {
+                    let rows = store
+                        .export()
+                        .map_err(|e| anyhow!("unable to export the cache: {}", e))?;
+                    for row in rows {
+                        println!("{row}");
+                    }
+                }
             }
         },
     }
@@ -47,69 +191,195 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn scan_lock_file(factory: &mut Factory, lock_file_name: &str) -> Result<()> {
-    let lock_file = File::open(lock_file_name)
-        .await
-        .with_context(|| format!("failed to open lock file: {lock_file_name}"))?;
-    let mut reporter = Factory::result_reporter();
-    let mut dependency_reader = factory.dependency_reader(lock_file, lock_file_name).await;
+async fn run_config_command(
+    config: &Config,
+    config_file: Option<&str>,
+    command: &ConfigCommands,
+) -> Result<()> {
+    match command {
+        ConfigCommands::Show => {
+            println!("{}", config.dump_to_string()?);
+        }
+        ConfigCommands::Schema => {
+            println!("{}", Config::json_schema()?);
+        }
+        ConfigCommands::Init { force } => {
+            let path = Config::default_config_file()?;
+            if !force && path.exists() {
+                return Err(anyhow!(
+                    "{} already exists, use --force to overwrite it",
+                    path.display()
+                ));
+            }
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            tokio::fs::write(&path, Config::default().dump_to_commented_string()?)
+                .await
+                .with_context(|| format!("failed to write config file: {}", path.display()))?;
+            println!("{}", path.display());
+        }
+        ConfigCommands::Validate { path } => {
+            let path = match path.as_deref().or(config_file) {
+                Some(path) => PathBuf::from(path),
+                None => Config::default_config_file()?,
+            };
+            let config = Config::load_from_file(&path).await?;
+            config
+                .validate()
+                .with_context(|| format!("invalid config file: {}", path.display()))?;
+            println!("{} is valid", path.display());
+        }
+    }
 
-    let engine = Arc::new(factory.engine()?);
+    Ok(())
+}
+
+/// Prints the policies each dependency would be evaluated with, without evaluating them.
+async fn list_lock_file_policies(
+    factory: &mut Factory,
+    lock_file_names: &[String],
+    only: RegexSet,
+    exclude: RegexSet,
+    prod_only: bool,
+) -> Result<()> {
+    let mut dependency_reader = factory
+        .lock_files_dependency_reader(
+            lock_file_names,
+            &DependencyFilter::new(only, exclude, prod_only),
+        )
+        .await?;
 
-    let mut async_results = Vec::new();
+    let engine = factory.engine()?;
 
     while let Some(dep) = dependency_reader.next().await {
-        let engine = engine.clone();
-        let task = tokio::spawn(async move {
-            let evaluations = engine.evaluate(&dep).await;
-            if let Err(err) = evaluations {
-                error!("error evaluating dependency {}: {}", dep.name, err);
-                return None;
-            }
+        println!(
+            "{}@{}: {}",
+            dep.name,
+            dep.version,
+            engine.policy_names(&dep).join(", ")
+        );
+    }
 
-            for evaluation in evaluations.as_ref().unwrap() {
-                match evaluation {
-                    Evaluation::Pass {
-                        policy_name,
-                        dependency,
-                    } => {
-                        info!(
-                        "dependency [name={}, version={}, latest version={}, repository={}, policy={}] is okay",
-                        dependency.name, dependency.version, dependency.latest_version.as_ref().unwrap_or(&"unknown".to_string()), dependency.repository, policy_name
-                    );
+    Ok(())
+}
+
+/// Prints every policy evaluation of the dependency with the given name, with the observed values
+/// and limits of the failures and the configs the dependency matched.
+async fn explain_dependency(
+    factory: &mut Factory,
+    lock_file_names: &[String],
+    dependency_name: &str,
+) -> Result<()> {
+    let only_the_dependency = DependencyFilter::new(
+        RegexSet::new([format!("^{}$", regex::escape(dependency_name))])?,
+        RegexSet::empty(),
+        false,
+    );
+    let dependencies = factory
+        .lock_files_dependency_reader(lock_file_names, &only_the_dependency)
+        .await?
+        .collect::<Vec<_>>()
+        .await;
+    if dependencies.is_empty() {
+        return Err(anyhow!(
+            "dependency {dependency_name} not found in the lock files"
+        ));
+    }
+
+    let engine = factory.engine()?;
+
+    for dep in dependencies {
+        println!(
+            "{}@{} ({})",
+            dep.name,
+            dep.version,
+            dep.ecosystem.unwrap_or("unknown ecosystem")
+        );
+        println!("  repository: {}", dep.repository);
+        if let Some(retrieval_error) = &dep.retrieval_error {
+            println!("  retrieval error: {retrieval_error}");
+        }
+        let configs = engine
+            .matching_configs(&dep)
+            .into_iter()
+            .map(|regex| match regex {
+                Some(regex) => format!("dependency_config {regex}"),
+                None => "default_policies".to_string(),
+            })
+            .collect::<Vec<_>>();
+        println!("  configs: {}", configs.join(", "));
+
+        for evaluation in engine.evaluate(&dep).await? {
+            match evaluation {
+                Evaluation::Pass { policy_name, .. } => println!("  {policy_name}: passed"),
+                Evaluation::Fail {
+                    policy_name,
+                    reason,
+                    fail_score,
+                    severity,
+                    measurement,
+                    ..
+                } => {
+                    println!("  {policy_name}: failed: {reason}");
+                    if let Some(measurement) = measurement {
+                        println!("    {measurement}");
                     }
-                    Evaluation::Fail {
-                        policy_name,
-                        dependency,
-                        reason,
-                        fail_score,
-                    } => {
-                        warn!(
-                        "dependency [name={}, version={}, latest version={}, repository={}, policy={}] is not okay: {} (score: {})",
-                        dependency.name, dependency.version, dependency.latest_version.as_ref().unwrap_or(&"unknown".to_string()), dependency.repository, policy_name, reason, fail_score,
+                    println!(
+                        "    fail score {fail_score}, severity {}",
+                        severity.as_str()
                     );
-                    }
                 }
+                Evaluation::Skip {
+                    policy_name,
+                    reason,
+                    ..
+                } => println!("  {policy_name}: skipped: {reason}"),
             }
-
-            Some(evaluations.unwrap())
-        });
-        async_results.push(task);
+        }
     }
 
-    let async_results = join_all(async_results).await;
-    let sequential_results = async_results.into_iter().flatten().flatten().flatten();
-    reporter.report_results(sequential_results).await?;
+    Ok(())
+}
+
+/// Prints the changes of the dependencies between the latest two scans.
+fn print_scan_diff(factory: &Factory) -> Result<()> {
+    let scans = factory
+        .scan_store()
+        .latest_scans(2)
+        .map_err(|e| anyhow!("unable to read the scan history: {}", e))?;
+    let [latest, previous] = scans.as_slice() else {
+        return Err(anyhow!("at least two scans are needed to compare them"));
+    };
+
+    let scan_diff = history::diff(&previous.dependencies, &latest.dependencies);
+    for (title, dependencies) in [
+        ("newly failing", &scan_diff.newly_failing),
+        ("newly passing", &scan_diff.newly_passing),
+        ("added", &scan_diff.added),
+        ("removed", &scan_diff.removed),
+    ] {
+        println!("{title}:");
+        for dependency in dependencies {
+            println!("  {dependency}");
+        }
+    }
 
     Ok(())
 }
 
-fn load_logger(level: &str) -> Result<()> {
-    simple_logger::SimpleLogger::new()
-        .with_level(LevelFilter::Error)
-        .with_module_level("dean", LevelFilter::from_str(level)?)
-        .with_colors(true)
-        .env()
-        .init()?;
+fn load_logger(level: &str, log_format: LogFormat) -> Result<()> {
+    let level = LevelFilter::from_str(level)?;
+    match log_format {
+        LogFormat::Text => simple_logger::SimpleLogger::new()
+            .with_level(LevelFilter::Error)
+            .with_module_level("dean", level)
+            .with_colors(true)
+            .env()
+            .init()?,
+        LogFormat::Json => JsonLogger::new(level).init()?,
+    }
     Ok(())
 }
@@ -0,0 +1,30 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails the dependencies that are in the list, whatever the result of the other policies.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default, PartialEq)]
+#[schemars(rename = "BlocklistConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub blocked: Vec<BlockedDependency>,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
+pub struct BlockedDependency {
+    pub name: String,
+    /// Semver range of the blocked versions, e.g. `=3.3.6`, every version if not set.
+    #[serde(default = "any_version")]
+    pub version_range: String,
+    /// Reason of the failures, which names the blocked version if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+fn any_version() -> String {
+    "*".to_string()
+}
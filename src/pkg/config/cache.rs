@@ -0,0 +1,20 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[schemars(rename = "CacheConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub ttl_in_seconds: u64,
+    pub busy_timeout_in_milliseconds: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let week_in_seconds = 60 * 60 * 24 * 7;
+        Self {
+            ttl_in_seconds: week_in_seconds,
+            busy_timeout_in_milliseconds: 5000,
+        }
+    }
+}
@@ -1,10 +1,23 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if a single author made too many of the commits of the latest releases.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "ContributorsRatioConfig", deny_unknown_fields)]
 #[serde(default)]
 pub struct Config {
     pub max_number_of_releases_to_check: usize,
     pub max_contributor_ratio: f64,
+    /// Lowercases the author emails and turns the GitHub noreply ones into the user name.
+    pub normalize_emails: bool,
+    /// Groups of author emails that belong to the same author, by author.
+    pub author_aliases: HashMap<String, Vec<String>>,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
 }
 
 impl Default for Config {
@@ -12,6 +25,9 @@ impl Default for Config {
         Self {
             max_number_of_releases_to_check: 3,
             max_contributor_ratio: 0.5,
+            normalize_emails: false,
+            author_aliases: HashMap::new(),
+            options: PolicyOptions::default(),
         }
     }
 }
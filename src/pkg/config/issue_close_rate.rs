@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if too few of the latest issues are closed.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "IssueCloseRateConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub min_close_rate: f64,
+    pub last_issues: usize,
+    /// Takes precedence over `last_issues` when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_days: Option<u64>,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            min_close_rate: 0.5,
+            last_issues: 300,
+            since_days: None,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if too many of the latest issues are still open.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MaxOpenIssueRatioConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub max_open_issue_ratio: f64,
+    pub last_issues: usize,
+    /// Takes precedence over `last_issues` when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_days: Option<u64>,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_open_issue_ratio: 0.5,
+            last_issues: 300,
+            since_days: None,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -1,10 +1,23 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the pull requests take too long to be closed.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MaxPullRequestLifespanConfig", deny_unknown_fields)]
 #[serde(default)]
 pub struct Config {
     pub max_lifespan_in_seconds: usize,
+    /// Takes precedence over `max_lifespan_in_seconds` when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lifespan_days: Option<u64>,
     pub last_pull_requests: usize,
+    /// Takes precedence over `last_pull_requests` when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_days: Option<u64>,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
 }
 
 impl Default for Config {
@@ -12,7 +25,10 @@ impl Default for Config {
         let month_in_seconds = 60 * 60 * 24 * 30;
         Self {
             max_lifespan_in_seconds: month_in_seconds,
+            max_lifespan_days: None,
             last_pull_requests: 300,
+            since_days: None,
+            options: PolicyOptions::default(),
         }
     }
 }
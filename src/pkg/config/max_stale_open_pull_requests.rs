@@ -0,0 +1,31 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if too many pull requests have been left open for too long.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MaxStaleOpenPullRequestsConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub max_stale_open_pull_requests: usize,
+    pub stale_after_days: u64,
+    pub last_pull_requests: usize,
+    /// Takes precedence over `last_pull_requests` when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_days: Option<u64>,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_stale_open_pull_requests: 10,
+            stale_after_days: 90,
+            last_pull_requests: 300,
+            since_days: None,
+            options: PolicyOptions::default(),
+        }
+    }
+}
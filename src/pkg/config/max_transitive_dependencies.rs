@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the dependency pulls in too many packages.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MaxTransitiveDependenciesConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub max_transitive: usize,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_transitive: 50,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the version is too far behind the latest one.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MaxVersionsBehindConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub max_major_behind: u64,
+    pub max_minor_behind: u64,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_major_behind: 1,
+            max_minor_behind: 10,
+            options: PolicyOptions::default(),
+        }
+    }
+}
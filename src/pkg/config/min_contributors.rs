@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if there are not enough authors in the latest releases.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MinContributorsConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub max_number_of_releases_to_check: usize,
+    pub min_contributors: usize,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_number_of_releases_to_check: 3,
+            min_contributors: 2,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the crate has been downloaded too few times from crates.io.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MinDownloadsConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub min_downloads: u64,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            min_downloads: 10_000,
+            options: PolicyOptions::default(),
+        }
+    }
+}
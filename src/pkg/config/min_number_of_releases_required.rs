@@ -1,10 +1,21 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+use crate::pkg::config::PolicyOptions;
+
+/// Matches the semver tags, with or without a leading `v`, leaving out the ones like `nightly`.
+pub const DEFAULT_TAG_PATTERN: &str = r"^v?\d+\.\d+\.\d+$";
+
+/// Fails if there are not enough releases in a period of time.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MinNumberOfReleasesRequiredConfig", deny_unknown_fields)]
 #[serde(default)]
 pub struct Config {
     pub min_number_of_releases: usize,
     pub days: u64,
+    pub tag_pattern: String,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
 }
 
 impl Default for Config {
@@ -12,6 +23,8 @@ impl Default for Config {
         Self {
             min_number_of_releases: 3,
             days: 365,
+            tag_pattern: DEFAULT_TAG_PATTERN.to_string(),
+            options: PolicyOptions::default(),
         }
     }
 }
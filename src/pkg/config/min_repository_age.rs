@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the repository is too young.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MinRepositoryAgeConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub min_age_days: u64,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            min_age_days: 90,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the package has been downloaded too few times from npm in the last week.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "MinWeeklyDownloadsConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub min_weekly_downloads: u64,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            min_weekly_downloads: 1000,
+            options: PolicyOptions::default(),
+        }
+    }
+}
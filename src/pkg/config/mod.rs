@@ -1,22 +1,129 @@
 use anyhow::{anyhow, Context};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::io::AsyncReadExt;
 
+use crate::pkg::policy::Severity;
+use crate::pkg::Repository;
 use crate::Result;
 
+pub mod blocklist;
+pub mod cache;
 pub mod contributors_ratio;
+pub mod issue_close_rate;
+pub mod max_days_since_last_commit;
 pub mod max_issue_lifespan;
+pub mod max_open_issue_ratio;
 pub mod max_pull_request_lifespan;
+pub mod max_stale_open_pull_requests;
+pub mod max_transitive_dependencies;
+pub mod max_versions_behind;
+pub mod min_contributors;
+pub mod min_downloads;
 pub mod min_number_of_releases_required;
+pub mod min_repository_age;
+pub mod min_weekly_downloads;
+pub mod no_known_vulnerabilities;
+pub mod no_prerelease_version;
+pub mod no_rustsec_advisories;
+pub mod not_deprecated;
+pub mod recent_contributor_diversity;
+pub mod repository_archived;
+pub mod require_recent_patch_release;
+pub mod require_security_policy;
+pub mod risk_score;
+pub mod signed_releases;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Comments written before each field of the dumped config, by the path of the field.
+const FIELD_COMMENTS: &[(&str, &str)] = &[
+    ("default_policies", "Policies evaluated for every dependency. Remove a policy to disable it"),
+    ("default_policies.contributors_ratio", "Fails if a single author made too many of the commits of the latest releases"),
+    ("default_policies.contributors_ratio.max_number_of_releases_to_check", "Number of latest releases whose commits are checked"),
+    ("default_policies.contributors_ratio.max_contributor_ratio", "Maximum ratio of the commits made by a single author, between 0 and 1"),
+    ("default_policies.contributors_ratio.normalize_emails", "Lowercases the author emails and turns the GitHub noreply ones into the user name"),
+    ("default_policies.contributors_ratio.author_aliases", "Emails that belong to the same author, by author"),
+    ("default_policies.min_number_of_releases_required", "Fails if there are not enough releases in a period of time"),
+    ("default_policies.min_number_of_releases_required.min_number_of_releases", "Minimum number of releases in the period"),
+    ("default_policies.min_number_of_releases_required.days", "Period of time, in days"),
+    ("default_policies.min_number_of_releases_required.tag_pattern", "Regex of the names of the tags counted as releases"),
+    ("default_policies.max_issue_lifespan", "Fails if the issues take too long to be closed"),
+    ("default_policies.max_issue_lifespan.max_lifespan_in_seconds", "Maximum mean lifespan of the closed issues, in seconds. Set max_lifespan_days instead to use days"),
+    ("default_policies.max_issue_lifespan.last_issues", "Number of latest issues checked. Set since_days instead to check the issues created in the last days"),
+    ("default_policies.max_pull_request_lifespan", "Fails if the pull requests take too long to be closed"),
+    ("default_policies.max_pull_request_lifespan.max_lifespan_in_seconds", "Maximum mean lifespan of the closed pull requests, in seconds. Set max_lifespan_days instead to use days"),
+    ("default_policies.max_pull_request_lifespan.last_pull_requests", "Number of latest pull requests checked. Set since_days instead to check the pull requests created in the last days"),
+    ("default_policies.repository_archived", "Fails if the repository has been archived"),
+    ("default_policies.max_days_since_last_commit", "Fails if the latest commit is too old"),
+    ("default_policies.max_days_since_last_commit.max_days", "Maximum age of the latest commit, in days"),
+    ("default_policies.max_versions_behind", "Fails if the version is too far behind the latest one"),
+    ("default_policies.max_versions_behind.max_major_behind", "Maximum number of major versions behind"),
+    ("default_policies.max_versions_behind.max_minor_behind", "Maximum number of minor versions behind, within the latest major version"),
+    ("default_policies.min_contributors", "Fails if there are not enough authors in the latest releases"),
+    ("default_policies.min_contributors.max_number_of_releases_to_check", "Number of latest releases whose commits are checked"),
+    ("default_policies.min_contributors.min_contributors", "Minimum number of distinct authors"),
+    ("default_policies.max_open_issue_ratio", "Fails if too many of the latest issues are still open"),
+    ("default_policies.max_open_issue_ratio.max_open_issue_ratio", "Maximum ratio of open issues, between 0 and 1"),
+    ("default_policies.max_open_issue_ratio.last_issues", "Number of latest issues checked. Set since_days instead to check the issues created in the last days"),
+    ("default_policies.not_deprecated", "Fails if the version has been deprecated in npm or yanked in crates.io"),
+    ("default_policies.signed_releases", "Fails if not enough of the latest tags are signed with GPG or SSH. Lightweight tags count as unsigned"),
+    ("default_policies.signed_releases.last_tags", "Number of latest tags checked"),
+    ("default_policies.signed_releases.min_signed_ratio", "Minimum ratio of signed tags, between 0 and 1"),
+    ("default_policies.min_repository_age", "Fails if the oldest tag, or the earliest commit of a repository without tags, is too recent"),
+    ("default_policies.min_repository_age.min_age_days", "Minimum age of the repository, in days"),
+    ("default_policies.min_downloads", "Fails if the crate has been downloaded too few times from crates.io. Other ecosystems pass"),
+    ("default_policies.min_downloads.min_downloads", "Minimum number of total downloads"),
+    ("default_policies.min_weekly_downloads", "Fails if the package has been downloaded too few times from npm in the last week. Other ecosystems pass"),
+    ("default_policies.min_weekly_downloads.min_weekly_downloads", "Minimum number of downloads in the last week"),
+    ("default_policies.recent_contributor_diversity", "Fails if the recent commits come mostly from one author, even when the whole history is diverse"),
+    ("default_policies.recent_contributor_diversity.window_releases", "Number of latest releases making up the recent window, usually fewer than contributors_ratio checks"),
+    ("default_policies.recent_contributor_diversity.window_days", "Only checks the commits of those releases made in the last days"),
+    ("default_policies.recent_contributor_diversity.max_recent_contributor_ratio", "Maximum ratio of the recent commits made by a single author, between 0 and 1"),
+    ("default_policies.max_stale_open_pull_requests", "Fails if too many pull requests have been left open for too long"),
+    ("default_policies.max_stale_open_pull_requests.max_stale_open_pull_requests", "Maximum number of stale open pull requests"),
+    ("default_policies.max_stale_open_pull_requests.stale_after_days", "Age of the open pull requests considered stale, in days"),
+    ("default_policies.max_stale_open_pull_requests.last_pull_requests", "Number of latest pull requests checked. Set since_days instead to check the pull requests created in the last days"),
+    ("default_policies.blocklist", "Fails the dependencies in the list, whatever the result of the other policies"),
+    ("default_policies.blocklist.blocked", "Blocked dependencies, by name and semver range of their versions, with an optional reason"),
+    ("default_policies.require_security_policy", "Fails if the GitHub repository has no SECURITY.md. Other repositories pass"),
+    ("default_policies.max_transitive_dependencies", "Fails if the dependency pulls in too many packages, directly or transitively. Only the npm and yarn lock files tell the number, the dependencies of other lock files pass"),
+    ("default_policies.max_transitive_dependencies.max_transitive", "Maximum number of packages the dependency pulls in"),
+    ("default_policies.no_known_vulnerabilities", "Fails if the version of the dependency has known vulnerabilities in the OSV database. The dependencies of the ecosystems OSV does not cover pass"),
+    ("default_policies.no_rustsec_advisories", "Fails if the version of the crate is affected by advisories of the RustSec database. The dependencies of the other ecosystems pass"),
+    ("default_policies.require_recent_patch_release", "Fails if the repository has not tagged a patch release, like 1.4.2, in the last days. Pre-releases are not counted"),
+    ("default_policies.require_recent_patch_release.max_days_since_patch", "Maximum age of the latest patch release, in days"),
+    ("default_policies.no_prerelease_version", "Fails if the pinned version of the dependency is a pre-release, like 1.0.0-rc.1. The versions that are not semver pass"),
+    ("default_policies.no_prerelease_version.fail_on_prerelease", "Whether the pre-releases fail, to turn the policy off without removing it"),
+    ("default_policies.issue_close_rate", "Fails if the repository closes too few of the issues opened in the window, e.g. fewer than half of them"),
+    ("default_policies.issue_close_rate.min_close_rate", "Minimum ratio of closed to opened issues, between 0 and 1"),
+    ("default_policies.issue_close_rate.last_issues", "Number of latest issues checked. Set since_days instead to check the issues created in the last days"),
+    ("dependency_config", "Policies of the dependencies whose name matches a regex, replacing the default ones unless merge_with_defaults is true"),
+    ("cache", "Local cache of the tags, commits, issues and pull requests of the repositories"),
+    ("cache.ttl_in_seconds", "Time the cached data is valid for, in seconds"),
+    ("cache.busy_timeout_in_milliseconds", "Time a scan waits for another one, or another dean process, writing to the cache before failing"),
+    ("risk_score", "Normalization of the fail scores of each dependency into a risk score between 0 and 100"),
+    ("risk_score.max_contribution", "Maximum contribution of a single weighted failure to the risk score"),
+    ("risk_score.saturation", "Sum of the capped contributions that reaches a risk score of 100"),
+    ("user_agent", "User-Agent of the requests to the package registries and the GitHub and GitLab APIs"),
+];
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+#[schemars(deny_unknown_fields)]
 #[cfg_attr(test, derive(PartialEq))]
 #[serde(default)]
+#[allow(clippy::struct_field_names)]
 pub struct Config {
     #[serde(default)]
     pub default_policies: Policies,
     #[serde(default)]
     pub dependency_config: Vec<DependencyConfiguration>,
+    #[serde(default)]
+    pub cache: cache::Config,
+    #[serde(default)]
+    pub risk_score: risk_score::Config,
+    /// User-Agent of the requests to the package registries and the GitHub and GitLab APIs,
+    /// `dean/<version>` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
 }
 
 impl Default for Config {
@@ -29,21 +136,52 @@ impl Default for Config {
                 contributors_ratio: Some(contributors_ratio::Config::default()),
                 max_issue_lifespan: Some(max_issue_lifespan::Config::default()),
                 max_pull_request_lifespan: Some(max_pull_request_lifespan::Config::default()),
+                repository_archived: Some(repository_archived::Config::default()),
+                max_days_since_last_commit: Some(max_days_since_last_commit::Config::default()),
+                max_versions_behind: Some(max_versions_behind::Config::default()),
+                min_contributors: Some(min_contributors::Config::default()),
+                max_open_issue_ratio: Some(max_open_issue_ratio::Config::default()),
+                not_deprecated: Some(not_deprecated::Config::default()),
+                signed_releases: None,
+                min_repository_age: None,
+                min_downloads: None,
+                min_weekly_downloads: None,
+                recent_contributor_diversity: None,
+                max_stale_open_pull_requests: None,
+                blocklist: None,
+                require_security_policy: None,
+                max_transitive_dependencies: None,
+                no_known_vulnerabilities: None,
+                no_rustsec_advisories: None,
+                require_recent_patch_release: None,
+                no_prerelease_version: None,
+                issue_close_rate: None,
             },
             dependency_config: vec![],
+            cache: cache::Config::default(),
+            risk_score: risk_score::Config::default(),
+            user_agent: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
+#[schemars(deny_unknown_fields)]
 #[cfg_attr(test, derive(PartialEq))]
 #[serde(default)]
 pub struct DependencyConfiguration {
     pub name: String,
     pub policies: Policies,
+    /// Evaluates the default policies too, instead of replacing them with these ones.
+    pub merge_with_defaults: bool,
+    /// URL of the repository of the matching dependencies, replacing the one of their registry,
+    /// e.g. when it's missing or is the one of a monorepo.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository_override: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
+#[schemars(deny_unknown_fields)]
 #[cfg_attr(test, derive(PartialEq))]
 #[serde(default)]
 pub struct Policies {
@@ -51,6 +189,185 @@ pub struct Policies {
     pub min_number_of_releases_required: Option<min_number_of_releases_required::Config>,
     pub max_issue_lifespan: Option<max_issue_lifespan::Config>,
     pub max_pull_request_lifespan: Option<max_pull_request_lifespan::Config>,
+    pub repository_archived: Option<repository_archived::Config>,
+    pub max_days_since_last_commit: Option<max_days_since_last_commit::Config>,
+    pub max_versions_behind: Option<max_versions_behind::Config>,
+    pub min_contributors: Option<min_contributors::Config>,
+    pub max_open_issue_ratio: Option<max_open_issue_ratio::Config>,
+    pub not_deprecated: Option<not_deprecated::Config>,
+    /// Not evaluated by default, as most projects don't sign their tags.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_releases: Option<signed_releases::Config>,
+    /// Not evaluated by default, as it mostly matters for the newly published dependencies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_repository_age: Option<min_repository_age::Config>,
+    /// Not evaluated by default, as the downloads are only a weak signal of the popularity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_downloads: Option<min_downloads::Config>,
+    /// Not evaluated by default, as the downloads are only a weak signal of the popularity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_weekly_downloads: Option<min_weekly_downloads::Config>,
+    /// Not evaluated by default, as it overlaps with `contributors_ratio`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent_contributor_diversity: Option<recent_contributor_diversity::Config>,
+    /// Not evaluated by default, as it overlaps with `max_pull_request_lifespan`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_stale_open_pull_requests: Option<max_stale_open_pull_requests::Config>,
+    /// Not evaluated by default, as it only fails the dependencies in the list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocklist: Option<blocklist::Config>,
+    /// Not evaluated by default, as most repositories have no security policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_security_policy: Option<require_security_policy::Config>,
+    /// Not evaluated by default, as only the npm and yarn lock files record the packages each one requires.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_transitive_dependencies: Option<max_transitive_dependencies::Config>,
+    /// Not evaluated by default, as it queries the OSV database for every dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_known_vulnerabilities: Option<no_known_vulnerabilities::Config>,
+    /// Not evaluated by default, as it clones the `RustSec` advisory database on the first crate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_rustsec_advisories: Option<no_rustsec_advisories::Config>,
+    /// Not evaluated by default, as many stable projects only ship minor releases.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_recent_patch_release: Option<require_recent_patch_release::Config>,
+    /// Not evaluated by default, as a pre-release can be pinned on purpose to get an early fix.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_prerelease_version: Option<no_prerelease_version::Config>,
+    /// Not evaluated by default, as it overlaps with `max_open_issue_ratio` on the same issues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue_close_rate: Option<issue_close_rate::Config>,
+}
+
+impl Policies {
+    /// Returns the options of every configured policy, by policy name.
+    #[allow(clippy::too_many_lines)]
+    pub fn options(&self) -> Vec<(&'static str, &PolicyOptions)> {
+        [
+            (
+                "contributors_ratio",
+                self.contributors_ratio.as_ref().map(|p| &p.options),
+            ),
+            (
+                "min_number_of_releases_required",
+                self.min_number_of_releases_required
+                    .as_ref()
+                    .map(|p| &p.options),
+            ),
+            (
+                "max_issue_lifespan",
+                self.max_issue_lifespan.as_ref().map(|p| &p.options),
+            ),
+            (
+                "max_pull_request_lifespan",
+                self.max_pull_request_lifespan.as_ref().map(|p| &p.options),
+            ),
+            (
+                "repository_archived",
+                self.repository_archived.as_ref().map(|p| &p.options),
+            ),
+            (
+                "max_days_since_last_commit",
+                self.max_days_since_last_commit.as_ref().map(|p| &p.options),
+            ),
+            (
+                "max_versions_behind",
+                self.max_versions_behind.as_ref().map(|p| &p.options),
+            ),
+            (
+                "min_contributors",
+                self.min_contributors.as_ref().map(|p| &p.options),
+            ),
+            (
+                "max_open_issue_ratio",
+                self.max_open_issue_ratio.as_ref().map(|p| &p.options),
+            ),
+            (
+                "not_deprecated",
+                self.not_deprecated.as_ref().map(|p| &p.options),
+            ),
+            (
+                "signed_releases",
+                self.signed_releases.as_ref().map(|p| &p.options),
+            ),
+            (
+                "min_repository_age",
+                self.min_repository_age.as_ref().map(|p| &p.options),
+            ),
+            (
+                "min_downloads",
+                self.min_downloads.as_ref().map(|p| &p.options),
+            ),
+            (
+                "min_weekly_downloads",
+                self.min_weekly_downloads.as_ref().map(|p| &p.options),
+            ),
+            (
+                "recent_contributor_diversity",
+                self.recent_contributor_diversity
+                    .as_ref()
+                    .map(|p| &p.options),
+            ),
+            (
+                "max_stale_open_pull_requests",
+                self.max_stale_open_pull_requests
+                    .as_ref()
+                    .map(|p| &p.options),
+            ),
+            ("blocklist", self.blocklist.as_ref().map(|p| &p.options)),
+            (
+                "require_security_policy",
+                self.require_security_policy.as_ref().map(|p| &p.options),
+            ),
+            (
+                "max_transitive_dependencies",
+                self.max_transitive_dependencies
+                    .as_ref()
+                    .map(|p| &p.options),
+            ),
+            (
+                "no_known_vulnerabilities",
+                self.no_known_vulnerabilities.as_ref().map(|p| &p.options),
+            ),
+            (
+                "no_rustsec_advisories",
+                self.no_rustsec_advisories.as_ref().map(|p| &p.options),
+            ),
+            (
+                "require_recent_patch_release",
+                self.require_recent_patch_release
+                    .as_ref()
+                    .map(|p| &p.options),
+            ),
+            (
+                "no_prerelease_version",
+                self.no_prerelease_version.as_ref().map(|p| &p.options),
+            ),
+            (
+                "issue_close_rate",
+                self.issue_close_rate.as_ref().map(|p| &p.options),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(policy_name, options)| Some((policy_name, options?)))
+        .collect()
+    }
+}
+
+/// Options every policy accepts next to its own ones.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default, PartialEq)]
+#[schemars(deny_unknown_fields)]
+#[serde(default)]
+pub struct PolicyOptions {
+    /// Regexes of the names of the dependencies the policy is skipped for.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skip: Vec<String>,
+    /// Factor the fail scores of the policy are multiplied by, 1 if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    /// Severity of the failures of the policy, error if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
 }
 
 impl Config {
@@ -61,19 +378,176 @@ impl Config {
             return Err(anyhow!("the content of the config file is empty"));
         }
         let result = serde_yaml::from_str(&contents)?;
+        for key in Self::unknown_keys(&contents) {
+            log::warn!("unknown key {key} in the config file is ignored");
+        }
         Ok(result)
     }
 
+    /// Returns the path of every key of the config file that is not a known field, which the
+    /// lenient parsing silently ignores.
+    pub fn unknown_keys(contents: &str) -> Vec<String> {
+        let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(contents) else {
+            return vec![];
+        };
+        let Ok(schema) = serde_json::to_value(schemars::schema_for!(Config)) else {
+            return vec![];
+        };
+
+        let mut unknown_keys = vec![];
+        collect_unknown_keys(&value, &schema, &schema, "", &mut unknown_keys);
+        unknown_keys
+    }
+
+    /// Returns the JSON Schema of the config file, which editors can use to autocomplete and
+    /// validate it.
+    pub fn json_schema() -> Result<String> {
+        let schema = schemars::schema_for!(Config);
+        Ok(serde_json::to_string_pretty(&schema)?)
+    }
+
     pub fn dump_to_string(&self) -> Result<String> {
         let result = serde_yaml::to_string(&self)?;
         Ok(result)
     }
 
-    fn default_config_file() -> Result<std::path::PathBuf> {
+    /// Dumps the config with a comment before each known field explaining it.
+    pub fn dump_to_commented_string(&self) -> Result<String> {
+        let mut path: Vec<&str> = vec![];
+        let mut result = String::new();
+
+        for line in self.dump_to_string()?.lines() {
+            let content = line.trim_start();
+            let indentation = &line[..line.len() - content.len()];
+            if let Some((key, _)) = content
+                .split_once(':')
+                .filter(|_| !content.starts_with('-'))
+            {
+                path.truncate(indentation.len() / 2);
+                path.push(key);
+
+                let field_path = path.join(".");
+                if let Some((_, comment)) = FIELD_COMMENTS.iter().find(|(p, _)| *p == field_path) {
+                    result.push_str(indentation);
+                    result.push_str("# ");
+                    result.push_str(comment);
+                    result.push('\n');
+                }
+            }
+            result.push_str(line);
+            result.push('\n');
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the repositories overriding the ones of the dependencies whose name matches the
+    /// regex, in the order of the dependency configurations. The invalid regexes are skipped, as
+    /// they're reported by the validation.
+    pub fn repository_overrides(&self) -> Vec<(regex::Regex, Repository)> {
+        self.dependency_config
+            .iter()
+            .filter_map(|dependency_config| {
+                let repository = dependency_config.repository_override.as_ref()?;
+                let regex = regex::Regex::new(&dependency_config.name).ok()?;
+                Some((regex, Repository::parse_url(repository)))
+            })
+            .collect()
+    }
+
+    /// Checks that the name of each dependency configuration is a valid regex, reporting all the
+    /// invalid ones.
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = self
+            .dependency_config
+            .iter()
+            .filter_map(|dependency_config| {
+                regex::Regex::new(&dependency_config.name).err().map(|e| {
+                    format!(
+                        "invalid dependency config name {}: {}",
+                        dependency_config.name, e
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let all_policies = std::iter::once(&self.default_policies)
+            .chain(
+                self.dependency_config
+                    .iter()
+                    .map(|dependency_config| &dependency_config.policies),
+            )
+            .collect::<Vec<_>>();
+        for (policy_name, options) in all_policies.iter().copied().flat_map(Policies::options) {
+            errors.extend(options.skip.iter().filter_map(|regex| {
+                regex::Regex::new(regex)
+                    .err()
+                    .map(|e| format!("invalid skip regex {regex} of {policy_name}: {e}"))
+            }));
+            if options.weight.is_some_and(|weight| weight < 0.0) {
+                errors.push(format!("the weight of {policy_name} must not be negative"));
+            }
+        }
+        errors.extend(
+            all_policies
+                .iter()
+                .filter_map(|policies| policies.min_number_of_releases_required.as_ref())
+                .filter_map(|policy| {
+                    regex::Regex::new(&policy.tag_pattern).err().map(|e| {
+                        format!(
+                            "invalid tag_pattern {} of min_number_of_releases_required: {e}",
+                            policy.tag_pattern
+                        )
+                    })
+                }),
+        );
+
+        errors.extend(
+            all_policies
+                .iter()
+                .filter_map(|policies| policies.blocklist.as_ref())
+                .flat_map(|policy| &policy.blocked)
+                .filter_map(|blocked| {
+                    semver::VersionReq::parse(&blocked.version_range)
+                        .err()
+                        .map(|e| {
+                            format!(
+                                "invalid version_range {} of the blocked dependency {}: {e}",
+                                blocked.version_range, blocked.name
+                            )
+                        })
+                }),
+        );
+        if self.risk_score.max_contribution < 0.0 {
+            errors.push("the max_contribution of risk_score must not be negative".to_string());
+        }
+        if self.risk_score.saturation <= 0.0 {
+            errors.push("the saturation of risk_score must be greater than 0".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(errors.join("\n")))
+        }
+    }
+
+    pub fn default_config_file() -> Result<std::path::PathBuf> {
         let home = dirs_next::home_dir().context("Could not find home directory. Please set the environment variable HOME to your home directory.")?;
         Ok(home.join(".config/dean.yaml"))
     }
 
+    /// Loads the config file at the path, failing instead of falling back to the default config
+    /// when it can't be opened or parsed.
+    pub async fn load_from_file(path: &std::path::Path) -> Result<Self> {
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("failed to open config file: {}", path.display()))?;
+        Self::load_from_reader(&mut file)
+            .await
+            .with_context(|| format!("invalid config file: {}", path.display()))
+    }
+
     pub async fn load_from_default_file_path_or_default() -> Self {
         match Self::default_config_file() {
             Ok(config_file) => match tokio::fs::File::open(&config_file).await {
@@ -102,8 +576,77 @@ impl Config {
     }
 }
 
+/// Resolves the references of a schema and the `Option` and `allOf` wrappers around them.
+fn resolve_schema<'a>(
+    schema: &'a serde_json::Value,
+    root: &'a serde_json::Value,
+) -> &'a serde_json::Value {
+    if let Some(name) = schema["$ref"]
+        .as_str()
+        .and_then(|reference| reference.strip_prefix("#/definitions/"))
+    {
+        return resolve_schema(&root["definitions"][name], root);
+    }
+    for wrapper in ["allOf", "anyOf"] {
+        if let Some(inner) = schema[wrapper]
+            .as_array()
+            .and_then(|schemas| schemas.iter().find(|s| s["type"] != "null"))
+        {
+            return resolve_schema(inner, root);
+        }
+    }
+    schema
+}
+
+fn collect_unknown_keys(
+    value: &serde_yaml::Value,
+    schema: &serde_json::Value,
+    root: &serde_json::Value,
+    path: &str,
+    unknown_keys: &mut Vec<String>,
+) {
+    let schema = resolve_schema(schema, root);
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            let Some(properties) = schema["properties"].as_object() else {
+                return;
+            };
+            for (key, value) in mapping {
+                let key = key
+                    .as_str()
+                    .map_or_else(|| format!("{key:?}"), str::to_string);
+                let key_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match properties.get(&key) {
+                    Some(property) => {
+                        collect_unknown_keys(value, property, root, &key_path, unknown_keys);
+                    }
+                    None => unknown_keys.push(key_path),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(sequence) => {
+            for (index, value) in sequence.iter().enumerate() {
+                collect_unknown_keys(
+                    value,
+                    &schema["items"],
+                    root,
+                    &format!("{path}[{index}]"),
+                    unknown_keys,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
 
     #[tokio::test]
@@ -118,23 +661,80 @@ mod tests {
                     contributors_ratio: Some(contributors_ratio::Config {
                         max_number_of_releases_to_check: 3_usize,
                         max_contributor_ratio: 0.5,
+                        normalize_emails: false,
+                        author_aliases: HashMap::new(),
+                        options: PolicyOptions::default(),
                     }),
                     min_number_of_releases_required: Some(
                         min_number_of_releases_required::Config {
                             min_number_of_releases: 3_usize,
                             days: 365_u64,
+                            tag_pattern: min_number_of_releases_required::DEFAULT_TAG_PATTERN
+                                .to_string(),
+                            options: PolicyOptions::default(),
                         }
                     ),
                     max_issue_lifespan: Some(max_issue_lifespan::Config {
                         max_lifespan_in_seconds: 2_592_000_usize,
+                        max_lifespan_days: None,
                         last_issues: 300,
+                        since_days: None,
+                        options: PolicyOptions::default(),
                     }),
                     max_pull_request_lifespan: Some(max_pull_request_lifespan::Config {
                         max_lifespan_in_seconds: 2_592_000_usize,
+                        max_lifespan_days: None,
                         last_pull_requests: 300,
+                        since_days: None,
+                        options: PolicyOptions::default(),
+                    }),
+                    repository_archived: Some(repository_archived::Config {
+                        enabled: true,
+                        options: PolicyOptions::default(),
                     }),
+                    max_days_since_last_commit: Some(max_days_since_last_commit::Config {
+                        max_days: 365,
+                        options: PolicyOptions::default(),
+                    }),
+                    max_versions_behind: Some(max_versions_behind::Config {
+                        max_major_behind: 1,
+                        max_minor_behind: 10,
+                        options: PolicyOptions::default(),
+                    }),
+                    min_contributors: Some(min_contributors::Config {
+                        max_number_of_releases_to_check: 3,
+                        min_contributors: 2,
+                        options: PolicyOptions::default(),
+                    }),
+                    max_open_issue_ratio: Some(max_open_issue_ratio::Config {
+                        max_open_issue_ratio: 0.5,
+                        last_issues: 300,
+                        since_days: None,
+                        options: PolicyOptions::default(),
+                    }),
+                    not_deprecated: Some(not_deprecated::Config {
+                        enabled: true,
+                        options: PolicyOptions::default(),
+                    }),
+                    signed_releases: None,
+                    min_repository_age: None,
+                    min_downloads: None,
+                    min_weekly_downloads: None,
+                    recent_contributor_diversity: None,
+                    max_stale_open_pull_requests: None,
+                    blocklist: None,
+                    require_security_policy: None,
+                    max_transitive_dependencies: None,
+                    no_known_vulnerabilities: None,
+                    no_rustsec_advisories: None,
+                    require_recent_patch_release: None,
+                    no_prerelease_version: None,
+                    issue_close_rate: None,
                 },
                 dependency_config: vec![],
+                cache: cache::Config::default(),
+                risk_score: risk_score::Config::default(),
+                user_agent: None,
             }
         );
     }
@@ -151,23 +751,150 @@ mod tests {
                     contributors_ratio: Some(contributors_ratio::Config {
                         max_number_of_releases_to_check: 3_usize,
                         max_contributor_ratio: 0.8,
+                        normalize_emails: true,
+                        author_aliases: HashMap::from([(
+                            "some_author".to_string(),
+                            vec![
+                                "some_author@example.com".to_string(),
+                                "some.author@example.com".to_string(),
+                            ],
+                        )]),
+                        options: PolicyOptions {
+                            skip: vec!["^internal-".to_string()],
+                            weight: Some(0.5),
+                            severity: Some(Severity::Info),
+                        },
                     }),
                     min_number_of_releases_required: Some(
                         min_number_of_releases_required::Config {
                             min_number_of_releases: 3_usize,
                             days: 180_u64,
+                            tag_pattern: min_number_of_releases_required::DEFAULT_TAG_PATTERN
+                                .to_string(),
+                            options: PolicyOptions::default(),
                         }
                     ),
                     max_issue_lifespan: Some(max_issue_lifespan::Config {
                         max_lifespan_in_seconds: 2_592_000_usize,
+                        max_lifespan_days: None,
                         last_issues: 300,
+                        since_days: None,
+                        options: PolicyOptions::default(),
                     }),
                     max_pull_request_lifespan: Some(max_pull_request_lifespan::Config {
                         max_lifespan_in_seconds: 2_592_000_usize,
+                        max_lifespan_days: Some(15),
                         last_pull_requests: 300,
+                        since_days: None,
+                        options: PolicyOptions::default(),
+                    }),
+                    repository_archived: Some(repository_archived::Config {
+                        enabled: false,
+                        options: PolicyOptions::default(),
+                    }),
+                    max_days_since_last_commit: Some(max_days_since_last_commit::Config {
+                        max_days: 180,
+                        options: PolicyOptions::default(),
+                    }),
+                    max_versions_behind: Some(max_versions_behind::Config {
+                        max_major_behind: 0,
+                        max_minor_behind: 5,
+                        options: PolicyOptions::default(),
+                    }),
+                    min_contributors: Some(min_contributors::Config {
+                        max_number_of_releases_to_check: 5,
+                        min_contributors: 3,
+                        options: PolicyOptions::default(),
+                    }),
+                    max_open_issue_ratio: Some(max_open_issue_ratio::Config {
+                        max_open_issue_ratio: 0.3,
+                        last_issues: 100,
+                        since_days: Some(90),
+                        options: PolicyOptions::default(),
+                    }),
+                    not_deprecated: Some(not_deprecated::Config {
+                        enabled: false,
+                        options: PolicyOptions::default(),
+                    }),
+                    signed_releases: Some(signed_releases::Config {
+                        last_tags: 10,
+                        min_signed_ratio: 0.8,
+                        options: PolicyOptions::default(),
+                    }),
+                    min_repository_age: Some(min_repository_age::Config {
+                        min_age_days: 30,
+                        options: PolicyOptions::default(),
+                    }),
+                    min_downloads: Some(min_downloads::Config {
+                        min_downloads: 5000,
+                        options: PolicyOptions::default(),
+                    }),
+                    min_weekly_downloads: Some(min_weekly_downloads::Config {
+                        min_weekly_downloads: 500,
+                        options: PolicyOptions::default(),
+                    }),
+                    recent_contributor_diversity: Some(recent_contributor_diversity::Config {
+                        window_releases: 2,
+                        window_days: Some(90),
+                        max_recent_contributor_ratio: 0.9,
+                        options: PolicyOptions::default(),
+                    }),
+                    max_stale_open_pull_requests: Some(max_stale_open_pull_requests::Config {
+                        max_stale_open_pull_requests: 5,
+                        stale_after_days: 60,
+                        last_pull_requests: 100,
+                        since_days: None,
+                        options: PolicyOptions::default(),
+                    }),
+                    blocklist: Some(blocklist::Config {
+                        blocked: vec![blocklist::BlockedDependency {
+                            name: "event-stream".to_string(),
+                            version_range: "=3.3.6".to_string(),
+                            reason: Some("compromised release".to_string()),
+                        }],
+                        options: PolicyOptions::default(),
+                    }),
+                    require_security_policy: Some(require_security_policy::Config {
+                        enabled: true,
+                        options: PolicyOptions::default(),
+                    }),
+                    max_transitive_dependencies: Some(max_transitive_dependencies::Config {
+                        max_transitive: 50,
+                        options: PolicyOptions::default(),
+                    }),
+                    no_known_vulnerabilities: Some(no_known_vulnerabilities::Config {
+                        enabled: true,
+                        options: PolicyOptions::default(),
+                    }),
+                    no_rustsec_advisories: Some(no_rustsec_advisories::Config {
+                        enabled: true,
+                        options: PolicyOptions::default(),
+                    }),
+                    require_recent_patch_release: Some(require_recent_patch_release::Config {
+                        max_days_since_patch: 180,
+                        options: PolicyOptions::default(),
+                    }),
+                    no_prerelease_version: Some(no_prerelease_version::Config {
+                        fail_on_prerelease: true,
+                        options: PolicyOptions::default(),
+                    }),
+                    issue_close_rate: Some(issue_close_rate::Config {
+                        min_close_rate: 0.6,
+                        last_issues: 200,
+                        since_days: Some(90),
+                        options: PolicyOptions::default(),
                     }),
                 },
                 dependency_config: vec![],
+                cache: cache::Config {
+                    ttl_in_seconds: 3600,
+                    busy_timeout_in_milliseconds: 5000,
+                },
+                risk_score: risk_score::Config {
+                    max_contribution: 2.0,
+                    saturation: 5.0,
+                },
+                user_agent: Some("my-company-scanner/1.0".to_string()),
             }
         );
     }
@@ -183,16 +910,40 @@ default_policies:
   contributors_ratio:
     max_number_of_releases_to_check: 3
     max_contributor_ratio: 0.5
+    normalize_emails: false
+    author_aliases: {}
   min_number_of_releases_required:
     min_number_of_releases: 3
     days: 365
+    tag_pattern: ^v?\\d+\\.\\d+\\.\\d+$
   max_issue_lifespan:
     max_lifespan_in_seconds: 2592000
     last_issues: 300
   max_pull_request_lifespan:
     max_lifespan_in_seconds: 2592000
     last_pull_requests: 300
+  repository_archived:
+    enabled: true
+  max_days_since_last_commit:
+    max_days: 365
+  max_versions_behind:
+    max_major_behind: 1
+    max_minor_behind: 10
+  min_contributors:
+    max_number_of_releases_to_check: 3
+    min_contributors: 2
+  max_open_issue_ratio:
+    max_open_issue_ratio: 0.5
+    last_issues: 300
+  not_deprecated:
+    enabled: true
 dependency_config: []
+cache:
+  ttl_in_seconds: 604800
+  busy_timeout_in_milliseconds: 5000
+risk_score:
+  max_contribution: 1.0
+  saturation: 3.0
 "
         );
     }
@@ -209,12 +960,38 @@ dependency_config: []
                     contributors_ratio: Some(contributors_ratio::Config {
                         max_number_of_releases_to_check: 3_usize,
                         max_contributor_ratio: 0.5,
+                        normalize_emails: false,
+                        author_aliases: HashMap::new(),
+                        options: PolicyOptions::default(),
                     }),
                     min_number_of_releases_required: None,
                     max_issue_lifespan: None,
                     max_pull_request_lifespan: None,
+                    repository_archived: None,
+                    max_days_since_last_commit: None,
+                    max_versions_behind: None,
+                    min_contributors: None,
+                    max_open_issue_ratio: None,
+                    not_deprecated: None,
+                    signed_releases: None,
+                    min_repository_age: None,
+                    min_downloads: None,
+                    min_weekly_downloads: None,
+                    recent_contributor_diversity: None,
+                    max_stale_open_pull_requests: None,
+                    blocklist: None,
+                    require_security_policy: None,
+                    max_transitive_dependencies: None,
+                    no_known_vulnerabilities: None,
+                    no_rustsec_advisories: None,
+                    require_recent_patch_release: None,
+                    no_prerelease_version: None,
+                    issue_close_rate: None,
                 },
                 dependency_config: vec![],
+                cache: cache::Config::default(),
+                risk_score: risk_score::Config::default(),
+                user_agent: None,
             }
         );
     }
@@ -232,6 +1009,26 @@ dependency_config: []
                     min_number_of_releases_required: None,
                     max_issue_lifespan: None,
                     max_pull_request_lifespan: None,
+                    repository_archived: None,
+                    max_days_since_last_commit: None,
+                    max_versions_behind: None,
+                    min_contributors: None,
+                    max_open_issue_ratio: None,
+                    not_deprecated: None,
+                    signed_releases: None,
+                    min_repository_age: None,
+                    min_downloads: None,
+                    min_weekly_downloads: None,
+                    recent_contributor_diversity: None,
+                    max_stale_open_pull_requests: None,
+                    blocklist: None,
+                    require_security_policy: None,
+                    max_transitive_dependencies: None,
+                    no_known_vulnerabilities: None,
+                    no_rustsec_advisories: None,
+                    require_recent_patch_release: None,
+                    no_prerelease_version: None,
+                    issue_close_rate: None,
                 },
                 dependency_config: vec![
                     DependencyConfiguration {
@@ -240,22 +1037,57 @@ dependency_config: []
                             contributors_ratio: Some(contributors_ratio::Config {
                                 max_number_of_releases_to_check: 3_usize,
                                 max_contributor_ratio: 0.8,
+                                normalize_emails: false,
+                                author_aliases: HashMap::new(),
+                                options: PolicyOptions::default(),
                             }),
                             min_number_of_releases_required: Some(
                                 min_number_of_releases_required::Config {
                                     min_number_of_releases: 3_usize,
                                     days: 180_u64,
+                                    tag_pattern:
+                                        min_number_of_releases_required::DEFAULT_TAG_PATTERN
+                                            .to_string(),
+                                    options: PolicyOptions::default(),
                                 },
                             ),
                             max_issue_lifespan: Some(max_issue_lifespan::Config {
                                 max_lifespan_in_seconds: 2_592_000_usize,
+                                max_lifespan_days: None,
                                 last_issues: 300,
+                                since_days: None,
+                                options: PolicyOptions::default(),
                             }),
                             max_pull_request_lifespan: Some(max_pull_request_lifespan::Config {
                                 max_lifespan_in_seconds: 2_592_000_usize,
+                                max_lifespan_days: None,
                                 last_pull_requests: 300,
+                                since_days: None,
+                                options: PolicyOptions::default(),
                             }),
+                            repository_archived: None,
+                            max_days_since_last_commit: None,
+                            max_versions_behind: None,
+                            min_contributors: None,
+                            max_open_issue_ratio: None,
+                            not_deprecated: None,
+                            signed_releases: None,
+                            min_repository_age: None,
+                            min_downloads: None,
+                            min_weekly_downloads: None,
+                            recent_contributor_diversity: None,
+                            max_stale_open_pull_requests: None,
+                            blocklist: None,
+                            require_security_policy: None,
+                            max_transitive_dependencies: None,
+                            no_known_vulnerabilities: None,
+                            no_rustsec_advisories: None,
+                            require_recent_patch_release: None,
+                            no_prerelease_version: None,
+                            issue_close_rate: None,
                         },
+                        merge_with_defaults: false,
+                        repository_override: None,
                     },
                     DependencyConfiguration {
                         name: "bar".to_string(),
@@ -263,17 +1095,273 @@ dependency_config: []
                             contributors_ratio: Some(contributors_ratio::Config {
                                 max_number_of_releases_to_check: 5_usize,
                                 max_contributor_ratio: 0.5,
+                                normalize_emails: false,
+                                author_aliases: HashMap::new(),
+                                options: PolicyOptions::default(),
                             }),
                             min_number_of_releases_required: None,
                             max_issue_lifespan: None,
                             max_pull_request_lifespan: None,
+                            repository_archived: None,
+                            max_days_since_last_commit: None,
+                            max_versions_behind: None,
+                            min_contributors: None,
+                            max_open_issue_ratio: None,
+                            not_deprecated: None,
+                            signed_releases: None,
+                            min_repository_age: None,
+                            min_downloads: None,
+                            min_weekly_downloads: None,
+                            recent_contributor_diversity: None,
+                            max_stale_open_pull_requests: None,
+                            blocklist: None,
+                            require_security_policy: None,
+                            max_transitive_dependencies: None,
+                            no_known_vulnerabilities: None,
+                            no_rustsec_advisories: None,
+                            require_recent_patch_release: None,
+                            no_prerelease_version: None,
+                            issue_close_rate: None,
                         },
+                        merge_with_defaults: true,
+                        repository_override: None,
                     },
                 ],
+                cache: cache::Config::default(),
+                risk_score: risk_score::Config::default(),
+                user_agent: None,
             }
         );
     }
 
+    #[tokio::test]
+    async fn it_dumps_the_config_with_comments_that_can_be_loaded_back() {
+        let config = Config::default();
+
+        let config_string = config.dump_to_commented_string().unwrap();
+
+        assert!(config_string.contains(
+            "\
+  max_issue_lifespan:
+    # Maximum mean lifespan of the closed issues, in seconds. Set max_lifespan_days instead to use days
+    max_lifespan_in_seconds: 2592000
+"
+        ));
+        assert!(config_string.starts_with(
+            "# Policies evaluated for every dependency. Remove a policy to disable it\n"
+        ));
+        assert_eq!(
+            Config::load_from_reader(&mut config_string.as_bytes())
+                .await
+                .unwrap(),
+            config
+        );
+    }
+
+    #[tokio::test]
+    async fn it_reports_the_line_and_column_of_the_parse_errors() {
+        let result = Config::load_from_reader(
+            &mut "\
+default_policies:
+  contributors_ratio:
+    max_contributor_ratio: high
+"
+            .as_bytes(),
+        )
+        .await;
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("line 3 column 28"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn it_fails_to_load_a_config_file_that_cannot_be_parsed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("dean.yaml");
+        tokio::fs::write(&path, "default_policies: [")
+            .await
+            .unwrap();
+
+        let error = Config::load_from_file(&path).await.unwrap_err();
+
+        assert!(
+            error.to_string().starts_with("invalid config file: "),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_fails_to_load_a_config_file_that_does_not_exist() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let result = Config::load_from_file(&temp_dir.path().join("dean.yaml")).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_validates_the_dependency_config_names() {
+        let config = Config::load_from_reader(&mut config_example_for_specific_policy())
+            .await
+            .unwrap();
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn it_reports_the_dependency_config_names_that_are_not_valid_regexes() {
+        let config = Config {
+            dependency_config: vec![
+                DependencyConfiguration {
+                    name: "foo(".to_string(),
+                    ..DependencyConfiguration::default()
+                },
+                DependencyConfiguration {
+                    name: "^bar$".to_string(),
+                    ..DependencyConfiguration::default()
+                },
+            ],
+            ..Config::default()
+        };
+
+        let error = config.validate().unwrap_err().to_string();
+
+        assert!(error.starts_with("invalid dependency config name foo(:"));
+        assert!(!error.contains("^bar$"));
+    }
+
+    #[tokio::test]
+    async fn it_overrides_the_repository_of_the_matching_dependencies() {
+        let config = Config::load_from_reader(
+            &mut "\
+dependency_config:
+- name: ^@babel/
+  merge_with_defaults: true
+  repository_override: https://github.com/babel/babel
+- name: ^left-pad$
+  merge_with_defaults: true
+"
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+
+        let repository_overrides = config.repository_overrides();
+
+        assert_eq!(repository_overrides.len(), 1);
+        let (regex, repository) = &repository_overrides[0];
+        assert!(regex.is_match("@babel/core"));
+        assert_eq!(
+            repository,
+            &Repository::GitHub {
+                organization: "babel".to_string(),
+                name: "babel".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_reports_the_skip_regexes_that_are_not_valid() {
+        let mut config = Config::default();
+        config.default_policies.min_contributors = Some(min_contributors::Config {
+            options: PolicyOptions {
+                skip: vec!["^internal-".to_string(), "foo(".to_string()],
+                ..PolicyOptions::default()
+            },
+            ..min_contributors::Config::default()
+        });
+
+        let error = config.validate().unwrap_err().to_string();
+
+        assert!(error.starts_with("invalid skip regex foo( of min_contributors:"));
+        assert!(!error.contains("^internal-"));
+    }
+
+    #[test]
+    fn it_reports_the_tag_pattern_that_is_not_valid() {
+        let mut config = Config::default();
+        config.default_policies.min_number_of_releases_required =
+            Some(min_number_of_releases_required::Config {
+                tag_pattern: "v(".to_string(),
+                ..min_number_of_releases_required::Config::default()
+            });
+
+        let error = config.validate().unwrap_err().to_string();
+
+        assert!(error.starts_with("invalid tag_pattern v( of min_number_of_releases_required:"));
+    }
+
+    #[test]
+    fn it_reports_the_blocked_version_ranges_that_are_not_valid() {
+        let mut config = Config::default();
+        config.default_policies.blocklist = Some(blocklist::Config {
+            blocked: vec![blocklist::BlockedDependency {
+                name: "event-stream".to_string(),
+                version_range: "3.3.6.1".to_string(),
+                reason: None,
+            }],
+            ..blocklist::Config::default()
+        });
+
+        let error = config.validate().unwrap_err().to_string();
+
+        assert!(error
+            .starts_with("invalid version_range 3.3.6.1 of the blocked dependency event-stream:"));
+    }
+
+    #[test]
+    fn it_reports_the_risk_score_saturation_that_is_not_positive() {
+        let mut config = Config::default();
+        config.risk_score.saturation = 0.0;
+
+        let error = config.validate().unwrap_err().to_string();
+
+        assert_eq!(error, "the saturation of risk_score must be greater than 0");
+    }
+
+    #[test]
+    fn it_exports_the_json_schema_of_the_config_file() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&Config::json_schema().unwrap()).unwrap();
+
+        assert_eq!(schema["additionalProperties"], false);
+        let contributors_ratio = &schema["definitions"]["ContributorsRatioConfig"];
+        assert_eq!(contributors_ratio["additionalProperties"], false);
+        assert!(contributors_ratio["properties"]["max_contributor_ratio"].is_object());
+        assert!(contributors_ratio["properties"]["skip"].is_object());
+    }
+
+    #[test]
+    fn it_reports_the_unknown_keys_of_the_config_file() {
+        let contents = "\
+default_policies:
+  contributors_ratio:
+    max_contributr_ratio: 0.8
+    skip:
+    - ^internal-
+  max_open_issues_ratio:
+    max_open_issue_ratio: 0.3
+dependency_config:
+- name: foo
+  policies:
+    max_pull_request_lifespan:
+      last_issues: 300
+caches:
+  ttl_in_seconds: 3600
+";
+
+        assert_eq!(
+            Config::unknown_keys(contents),
+            vec![
+                "default_policies.contributors_ratio.max_contributr_ratio",
+                "default_policies.max_open_issues_ratio",
+                "dependency_config[0].policies.max_pull_request_lifespan.last_issues",
+                "caches",
+            ]
+        );
+        assert!(Config::unknown_keys(&Config::default().dump_to_string().unwrap()).is_empty());
+    }
+
     fn config_example_for_specific_policy() -> &'static [u8] {
         "\
 dependency_config:
@@ -292,6 +1380,7 @@ dependency_config:
       max_lifespan_in_seconds: 2592000
       last_issues: 300
 - name: bar
+  merge_with_defaults: true
   policies:
     contributors_ratio:
       max_number_of_releases_to_check: 5
@@ -316,6 +1405,15 @@ default_policies:
   contributors_ratio:
     max_number_of_releases_to_check: 3
     max_contributor_ratio: 0.8
+    normalize_emails: true
+    author_aliases:
+      some_author:
+      - some_author@example.com
+      - some.author@example.com
+    skip:
+    - ^internal-
+    weight: 0.5
+    severity: info
   min_number_of_releases_required:
     min_number_of_releases: 3
     days: 180
@@ -323,8 +1421,68 @@ default_policies:
     max_lifespan_in_seconds: 2592000
     last_issues: 300
   max_pull_request_lifespan:
-    max_lifespan_in_seconds: 2592000
+    max_lifespan_days: 15
     last_issues: 300
+  repository_archived:
+    enabled: false
+  max_days_since_last_commit:
+    max_days: 180
+  max_versions_behind:
+    max_major_behind: 0
+    max_minor_behind: 5
+  min_contributors:
+    max_number_of_releases_to_check: 5
+    min_contributors: 3
+  max_open_issue_ratio:
+    max_open_issue_ratio: 0.3
+    last_issues: 100
+    since_days: 90
+  not_deprecated:
+    enabled: false
+  signed_releases:
+    last_tags: 10
+    min_signed_ratio: 0.8
+  min_repository_age:
+    min_age_days: 30
+  min_downloads:
+    min_downloads: 5000
+  min_weekly_downloads:
+    min_weekly_downloads: 500
+  recent_contributor_diversity:
+    window_releases: 2
+    window_days: 90
+    max_recent_contributor_ratio: 0.9
+  max_stale_open_pull_requests:
+    max_stale_open_pull_requests: 5
+    stale_after_days: 60
+    last_pull_requests: 100
+  blocklist:
+    blocked:
+    - name: event-stream
+      version_range: =3.3.6
+      reason: compromised release
+  require_security_policy:
+    enabled: true
+  max_transitive_dependencies:
+    max_transitive: 50
+  no_known_vulnerabilities:
+    enabled: true
+  no_rustsec_advisories:
+    enabled: true
+  require_recent_patch_release:
+    max_days_since_patch: 180
+  no_prerelease_version:
+    fail_on_prerelease: true
+  issue_close_rate:
+    min_close_rate: 0.6
+    last_issues: 200
+    since_days: 90
+cache:
+  ttl_in_seconds: 3600
+risk_score:
+  max_contribution: 2.0
+  saturation: 5.0
+user_agent: my-company-scanner/1.0
 "
         .as_bytes()
     }
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the pinned version of the dependency is a pre-release.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "NoPrereleaseVersionConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub fail_on_prerelease: bool,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            fail_on_prerelease: true,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the version has been deprecated in npm or yanked in crates.io.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "NotDeprecatedConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if a single author made too many of the recent commits.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "RecentContributorDiversityConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    /// Number of latest releases making up the recent window.
+    pub window_releases: usize,
+    /// Narrows the commits of the latest releases to the ones made in the last days when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_days: Option<u64>,
+    pub max_recent_contributor_ratio: f64,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            window_releases: 1,
+            window_days: None,
+            max_recent_contributor_ratio: 0.8,
+            options: PolicyOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if the latest patch release is too old.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "RequireRecentPatchReleaseConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub max_days_since_patch: u64,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_days_since_patch: 180,
+            options: PolicyOptions::default(),
+        }
+    }
+}
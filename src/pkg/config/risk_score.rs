@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Normalization of the fail scores of a dependency into a risk score between 0 and 100.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq)]
+#[schemars(rename = "RiskScoreConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    /// Maximum contribution of a single failure, so a policy failing by far does not outweigh
+    /// the rest.
+    pub max_contribution: f64,
+    /// Sum of the capped contributions that reaches a risk score of 100.
+    pub saturation: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_contribution: 1.0,
+            saturation: 3.0,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::config::PolicyOptions;
+
+/// Fails if not enough of the latest tags are signed.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "SignedReleasesConfig", deny_unknown_fields)]
+#[serde(default)]
+pub struct Config {
+    pub last_tags: usize,
+    pub min_signed_ratio: f64,
+    #[serde(flatten)]
+    pub options: PolicyOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            last_tags: 5,
+            min_signed_ratio: 1.0,
+            options: PolicyOptions::default(),
+        }
+    }
+}
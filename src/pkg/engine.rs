@@ -1,20 +1,27 @@
 use std::cmp::Ordering;
 use std::sync::Arc;
 
+use anyhow::anyhow;
 use futures::future::join_all;
 use itertools::Itertools;
+use moka::future::{Cache, CacheBuilder};
 
+use crate::pkg::policy::RepositoryUnavailable;
+use crate::pkg::timings::Timings;
+use crate::pkg::Repository;
 use crate::{Dependency, Evaluation, Policy, Result};
 
 pub struct ExecutionConfig {
     regex: Option<regex::Regex>,
     policies: Vec<Arc<dyn Policy>>,
+    merge_with_defaults: bool,
 }
 
 impl ExecutionConfig {
     pub fn new(
         policies: Vec<Box<dyn Policy>>,
         dependency_name_regex: Option<&str>,
+        merge_with_defaults: bool,
     ) -> Result<Self> {
         Ok(Self {
             regex: match dependency_name_regex {
@@ -22,12 +29,15 @@ impl ExecutionConfig {
                 None => None,
             },
             policies: policies.into_iter().map(std::convert::Into::into).collect(),
+            merge_with_defaults,
         })
     }
 }
 
 pub struct PolicyExecutor {
     execution_configs: Vec<ExecutionConfig>,
+    repository_evaluations: Cache<(usize, Repository, Option<String>), Evaluation>,
+    timings: Option<Arc<Timings>>,
 }
 
 fn some_options_first<T>(a: &Option<T>, b: &Option<T>) -> Ordering {
@@ -45,46 +55,178 @@ impl PolicyExecutor {
                 .into_iter()
                 .sorted_by(|a, b| some_options_first(&a.regex, &b.regex))
                 .collect(),
+            repository_evaluations: CacheBuilder::default().build(),
+            timings: None,
         }
     }
 
-    pub async fn evaluate(&self, dependency: &Dependency) -> Result<Vec<Evaluation>> {
-        let mut has_matched_regex_previously = false;
-        let mut evaluations = vec![];
+    /// Records the duration of the evaluation of each dependency.
+    pub fn with_timings(mut self, timings: Arc<Timings>) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
+    /// Returns the configurations a dependency is evaluated with: every one whose regex matches
+    /// its name, plus the default ones unless any of those replaces them.
+    fn execution_configs_for(&self, dependency: &Dependency) -> Vec<&ExecutionConfig> {
+        let mut should_skip_default_policies = false;
+        let mut execution_configs = vec![];
 
         for execution_config in &self.execution_configs {
             if let Some(regex) = &execution_config.regex {
                 if !regex.is_match(&dependency.name) {
                     continue;
                 }
-                has_matched_regex_previously = true;
-            } else if has_matched_regex_previously {
+                should_skip_default_policies |= !execution_config.merge_with_defaults;
+            } else if should_skip_default_policies {
                 continue;
             }
 
-            for policy in &execution_config.policies {
+            execution_configs.push(execution_config);
+        }
+
+        execution_configs
+    }
+
+    fn policies_for(&self, dependency: &Dependency) -> Vec<&Arc<dyn Policy>> {
+        self.execution_configs_for(dependency)
+            .into_iter()
+            .flat_map(|execution_config| &execution_config.policies)
+            .collect()
+    }
+
+    /// Returns the dependency name regexes of the configurations a dependency is evaluated with,
+    /// `None` standing for the default one.
+    pub fn matching_configs(&self, dependency: &Dependency) -> Vec<Option<&str>> {
+        self.execution_configs_for(dependency)
+            .into_iter()
+            .map(|execution_config| execution_config.regex.as_ref().map(regex::Regex::as_str))
+            .collect()
+    }
+
+    /// Returns the names of the policies a dependency would be evaluated with.
+    pub fn policy_names(&self, dependency: &Dependency) -> Vec<&'static str> {
+        self.policies_for(dependency)
+            .into_iter()
+            .map(|policy| policy.name())
+            .collect()
+    }
+
+    /// Returns the names of the policies of every configuration, which are all the policies any
+    /// dependency could be evaluated with.
+    pub fn all_policy_names(&self) -> Vec<&'static str> {
+        self.execution_configs
+            .iter()
+            .flat_map(|execution_config| &execution_config.policies)
+            .map(|policy| policy.name())
+            .unique()
+            .collect()
+    }
+
+    pub async fn evaluate(&self, dependency: &Dependency) -> Result<Vec<Evaluation>> {
+        Timings::measure_evaluation(
+            self.timings.as_deref(),
+            dependency,
+            self.evaluate_policies(dependency),
+        )
+        .await
+    }
+
+    async fn evaluate_policies(&self, dependency: &Dependency) -> Result<Vec<Evaluation>> {
+        let evaluations = self
+            .policies_for(dependency)
+            .into_iter()
+            .map(|policy| {
                 let policy = policy.clone();
                 let dependency = dependency.clone();
-                evaluations.push(tokio::spawn(
-                    async move { policy.evaluate(&dependency).await },
-                ));
-            }
-        }
+                let repository_evaluations = self.repository_evaluations.clone();
+                tokio::spawn(async move {
+                    if !policy.is_repository_level() || dependency.repository == Repository::Unknown
+                    {
+                        return Self::evaluate_policy(&*policy, &dependency).await;
+                    }
+
+                    // The policies are identified by their address, as the same policy may be
+                    // configured with different parameters for different dependencies. The
+                    // packages in different directories of a monorepo are evaluated apart.
+                    let key = (
+                        Arc::as_ptr(&policy).cast::<()>() as usize,
+                        dependency.repository.clone(),
+                        dependency.repository_directory.clone(),
+                    );
+                    let evaluation = repository_evaluations
+                        .try_get_with(key, Self::evaluate_policy(&*policy, &dependency))
+                        .await
+                        .map_err(|e| anyhow!("{}", e))?;
+                    Ok(Self::attribute_to(evaluation, &dependency))
+                })
+            })
+            .collect::<Vec<_>>();
 
         let evaluations_resolved = join_all(evaluations).await;
         let mut evaluations = vec![];
-        for evaluation in &evaluations_resolved {
-            evaluations.push(evaluation.as_ref().unwrap().as_ref().unwrap().clone());
+        for evaluation in evaluations_resolved {
+            evaluations.push(evaluation??);
         }
 
         Ok(evaluations)
     }
+
+    /// Evaluates the policy, skipping it when the repository of the dependency is unavailable.
+    async fn evaluate_policy(policy: &dyn Policy, dependency: &Dependency) -> Result<Evaluation> {
+        match policy.evaluate(dependency).await {
+            Err(e) => match e.downcast_ref::<RepositoryUnavailable>() {
+                Some(unavailable) => Ok(Evaluation::Skip {
+                    policy_name: policy.name().to_string(),
+                    dependency: dependency.clone(),
+                    reason: unavailable.to_string(),
+                }),
+                None => Err(e),
+            },
+            evaluation => evaluation,
+        }
+    }
+
+    /// Attributes the evaluation of another dependency with the same repository to the dependency.
+    fn attribute_to(evaluation: Evaluation, dependency: &Dependency) -> Evaluation {
+        match evaluation {
+            Evaluation::Pass { policy_name, .. } => Evaluation::Pass {
+                policy_name,
+                dependency: dependency.clone(),
+            },
+            Evaluation::Fail {
+                policy_name,
+                reason,
+                fail_score,
+                severity,
+                measurement,
+                ..
+            } => Evaluation::Fail {
+                policy_name,
+                dependency: dependency.clone(),
+                reason,
+                fail_score,
+                severity,
+                measurement,
+            },
+            Evaluation::Skip {
+                policy_name,
+                reason,
+                ..
+            } => Evaluation::Skip {
+                policy_name,
+                dependency: dependency.clone(),
+                reason,
+            },
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::pkg::policy::MockPolicy;
+    use crate::pkg::policy::{MockPolicy, Severity};
+    use crate::pkg::DependencyKind;
     use crate::pkg::Repository;
     use crate::{Dependency, Evaluation, Policy};
 
@@ -112,7 +254,7 @@ mod tests {
                 policy as Box<dyn Policy>
             },
         ];
-        let config = vec![ExecutionConfig::new(policies, None).unwrap()];
+        let config = vec![ExecutionConfig::new(policies, None, false).unwrap()];
         let policy_executor = PolicyExecutor::new(config);
 
         let evaluation = policy_executor.evaluate(&dependency()).await.unwrap();
@@ -143,6 +285,8 @@ mod tests {
                         dependency: dep.clone(),
                         reason: "some_reason".into(),
                         fail_score: 1.0,
+                        severity: Severity::Error,
+                        measurement: None,
                     })
                 });
                 policy as Box<dyn Policy>
@@ -158,7 +302,7 @@ mod tests {
                 policy as Box<dyn Policy>
             },
         ];
-        let config = vec![ExecutionConfig::new(policies, None).unwrap()];
+        let config = vec![ExecutionConfig::new(policies, None, false).unwrap()];
 
         let policy_executor = PolicyExecutor::new(config);
 
@@ -171,13 +315,14 @@ mod tests {
                 dependency: dep,
                 reason,
                 fail_score,
+                ..
             } => {
                 assert_eq!(policy_name, "some_policy_name");
                 assert_eq!(dep, &dependency());
                 assert_eq!(reason, "some_reason");
                 assert!((fail_score - 1.0).abs() < f64::EPSILON);
             }
-            Evaluation::Pass { .. } => {
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
                 unreachable!()
             }
         }
@@ -189,7 +334,7 @@ mod tests {
                 assert_eq!(policy, "some_policy_name2");
                 assert_eq!(dep, &dependency());
             }
-            Evaluation::Fail { .. } => {
+            Evaluation::Fail { .. } | Evaluation::Skip { .. } => {
                 unreachable!()
             }
         };
@@ -209,8 +354,8 @@ mod tests {
             policy as Box<dyn Policy>
         }];
         let config = vec![
-            ExecutionConfig::new(matching_policies, Some("foo")).unwrap(),
-            ExecutionConfig::new(non_matching_policies, Some("bar")).unwrap(),
+            ExecutionConfig::new(matching_policies, Some("foo"), false).unwrap(),
+            ExecutionConfig::new(non_matching_policies, Some("bar"), false).unwrap(),
         ];
 
         let policy_executor = PolicyExecutor::new(config);
@@ -236,6 +381,8 @@ mod tests {
                     dependency: dep.clone(),
                     reason: "some_reason".into(),
                     fail_score: 1.0,
+                    severity: Severity::Error,
+                    measurement: None,
                 })
             });
             policy as Box<dyn Policy>
@@ -246,8 +393,8 @@ mod tests {
             policy as Box<dyn Policy>
         }];
         let config = vec![
-            ExecutionConfig::new(non_matching_policies, Some("bar")).unwrap(),
-            ExecutionConfig::new(default_policies, None).unwrap(),
+            ExecutionConfig::new(non_matching_policies, Some("bar"), false).unwrap(),
+            ExecutionConfig::new(default_policies, None, false).unwrap(),
         ];
         let policy_executor = PolicyExecutor::new(config);
 
@@ -260,13 +407,14 @@ mod tests {
                 dependency: dep,
                 reason,
                 fail_score,
+                ..
             } => {
                 assert_eq!(policy_name, "some_policy_name");
                 assert_eq!(dep, &dependency());
                 assert_eq!(reason, "some_reason");
                 assert!((fail_score - 1.0) < f64::EPSILON);
             }
-            Evaluation::Pass { .. } => {
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
                 unreachable!()
             }
         }
@@ -287,8 +435,8 @@ mod tests {
             policy as Box<dyn Policy>
         }];
         let config = vec![
-            ExecutionConfig::new(matching_policies, Some("foo")).unwrap(),
-            ExecutionConfig::new(default_policies, None).unwrap(),
+            ExecutionConfig::new(matching_policies, Some("foo"), false).unwrap(),
+            ExecutionConfig::new(default_policies, None, false).unwrap(),
         ];
         let policy_executor = PolicyExecutor::new(config);
 
@@ -303,6 +451,163 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn if_the_dependency_matches_a_config_merged_with_defaults_it_is_evaluated_with_both_policies(
+    ) {
+        let default_policies = vec![{
+            let mut policy = mock_policy();
+            policy.expect_evaluate().once().return_once(|dep| {
+                Ok(Evaluation::Pass {
+                    policy_name: "some_policy_name".to_string(),
+                    dependency: dep.clone(),
+                })
+            });
+            policy as Box<dyn Policy>
+        }];
+        let matching_policies = vec![{
+            let mut policy = mock_policy();
+            policy.expect_evaluate().once().return_once(|dep| {
+                Ok(Evaluation::Pass {
+                    policy_name: "some_policy_name2".to_string(),
+                    dependency: dep.clone(),
+                })
+            });
+            policy as Box<dyn Policy>
+        }];
+        let config = vec![
+            ExecutionConfig::new(default_policies, None, false).unwrap(),
+            ExecutionConfig::new(matching_policies, Some("foo"), true).unwrap(),
+        ];
+        let policy_executor = PolicyExecutor::new(config);
+
+        let evaluation = policy_executor.evaluate(&dependency()).await.unwrap();
+
+        assert_eq!(
+            evaluation,
+            &[
+                Evaluation::Pass {
+                    policy_name: "some_policy_name2".to_string(),
+                    dependency: dependency()
+                },
+                Evaluation::Pass {
+                    policy_name: "some_policy_name".to_string(),
+                    dependency: dependency()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn if_the_dependency_also_matches_a_config_that_replaces_defaults_the_default_policies_are_skipped(
+    ) {
+        let default_policies = vec![{ mock_policy() as Box<dyn Policy> }];
+        let merging_policies = vec![{
+            let mut policy = mock_policy();
+            policy.expect_evaluate().once().return_once(|dep| {
+                Ok(Evaluation::Pass {
+                    policy_name: "some_policy_name2".to_string(),
+                    dependency: dep.clone(),
+                })
+            });
+            policy as Box<dyn Policy>
+        }];
+        let replacing_policies = vec![{
+            let mut policy = mock_policy();
+            policy.expect_evaluate().once().return_once(|dep| {
+                Ok(Evaluation::Pass {
+                    policy_name: "some_policy_name3".to_string(),
+                    dependency: dep.clone(),
+                })
+            });
+            policy as Box<dyn Policy>
+        }];
+        let config = vec![
+            ExecutionConfig::new(merging_policies, Some("foo"), true).unwrap(),
+            ExecutionConfig::new(replacing_policies, Some("fo+"), false).unwrap(),
+            ExecutionConfig::new(default_policies, None, false).unwrap(),
+        ];
+        let policy_executor = PolicyExecutor::new(config);
+
+        let evaluation = policy_executor.evaluate(&dependency()).await.unwrap();
+
+        assert_eq!(
+            evaluation,
+            &[
+                Evaluation::Pass {
+                    policy_name: "some_policy_name2".to_string(),
+                    dependency: dependency()
+                },
+                Evaluation::Pass {
+                    policy_name: "some_policy_name3".to_string(),
+                    dependency: dependency()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_returns_the_names_of_the_policies_that_match_the_dependency() {
+        let named_policy = |name: &'static str| {
+            let mut policy = mock_policy();
+            policy.expect_name().return_const(name);
+            policy as Box<dyn Policy>
+        };
+        let config = vec![
+            ExecutionConfig::new(vec![named_policy("some_policy_name")], None, false).unwrap(),
+            ExecutionConfig::new(vec![named_policy("some_policy_name2")], Some("foo"), true)
+                .unwrap(),
+            ExecutionConfig::new(vec![named_policy("some_policy_name3")], Some("bar"), false)
+                .unwrap(),
+        ];
+        let policy_executor = PolicyExecutor::new(config);
+
+        let policy_names = policy_executor.policy_names(&dependency());
+
+        assert_eq!(policy_names, ["some_policy_name2", "some_policy_name"]);
+    }
+
+    #[test]
+    fn it_returns_the_names_of_the_policies_of_every_config_once() {
+        let named_policy = |name: &'static str| {
+            let mut policy = mock_policy();
+            policy.expect_name().return_const(name);
+            policy as Box<dyn Policy>
+        };
+        let config = vec![
+            ExecutionConfig::new(
+                vec![
+                    named_policy("some_policy_name"),
+                    named_policy("some_policy_name2"),
+                ],
+                None,
+                false,
+            )
+            .unwrap(),
+            ExecutionConfig::new(vec![named_policy("some_policy_name2")], Some("foo"), true)
+                .unwrap(),
+        ];
+        let policy_executor = PolicyExecutor::new(config);
+
+        assert_eq!(
+            policy_executor.all_policy_names(),
+            ["some_policy_name2", "some_policy_name"]
+        );
+    }
+
+    #[test]
+    fn it_returns_the_configs_that_match_the_dependency() {
+        let config = vec![
+            ExecutionConfig::new(vec![], None, false).unwrap(),
+            ExecutionConfig::new(vec![], Some("^fo"), true).unwrap(),
+            ExecutionConfig::new(vec![], Some("bar"), false).unwrap(),
+        ];
+        let policy_executor = PolicyExecutor::new(config);
+
+        let matching_configs = policy_executor.matching_configs(&dependency());
+
+        assert_eq!(matching_configs, [Some("^fo"), None]);
+    }
+
     fn dependency() -> Dependency {
         Dependency {
             name: "foo".to_string(),
@@ -312,10 +617,108 @@ mod tests {
                 name: "some_name".to_string(),
             },
             latest_version: Some("1.0.1".to_string()),
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
         }
     }
 
+    #[tokio::test]
+    async fn it_evaluates_once_the_repository_level_policies_of_dependencies_sharing_a_repository()
+    {
+        let mut policy = Box::new(MockPolicy::new());
+        policy.expect_is_repository_level().return_const(true);
+        policy.expect_evaluate().once().return_once(|dep| {
+            Ok(Evaluation::Fail {
+                policy_name: "some_policy_name".to_string(),
+                dependency: dep.clone(),
+                reason: "some_reason".into(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            })
+        });
+        let config =
+            vec![ExecutionConfig::new(vec![policy as Box<dyn Policy>], None, false).unwrap()];
+        let policy_executor = PolicyExecutor::new(config);
+        let other_dependency = Dependency {
+            name: "bar".to_string(),
+            ..dependency()
+        };
+
+        let evaluation = policy_executor.evaluate(&dependency()).await.unwrap();
+        let other_evaluation = policy_executor.evaluate(&other_dependency).await.unwrap();
+
+        assert_eq!(evaluation[0].dependency(), &dependency());
+        assert_eq!(
+            other_evaluation,
+            &[Evaluation::Fail {
+                policy_name: "some_policy_name".to_string(),
+                dependency: other_dependency,
+                reason: "some_reason".into(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_skips_the_policies_whose_repository_is_unavailable() {
+        let policies = vec![
+            {
+                let mut policy = mock_policy();
+                policy.expect_name().return_const("some_policy_name");
+                policy.expect_evaluate().once().return_once(|_| {
+                    Err(anyhow::Error::new(RepositoryUnavailable {
+                        repository_url: "https://github.com/some_org/some_repo".to_string(),
+                        reason: "not found".to_string(),
+                    })
+                    .context("error retrieving all tags"))
+                });
+                policy as Box<dyn Policy>
+            },
+            {
+                let mut policy = mock_policy();
+                policy.expect_name().return_const("some_policy_name2");
+                policy.expect_evaluate().once().return_once(|dep| {
+                    Ok(Evaluation::Pass {
+                        policy_name: "some_policy_name2".to_string(),
+                        dependency: dep.clone(),
+                    })
+                });
+                policy as Box<dyn Policy>
+            },
+        ];
+        let config = vec![ExecutionConfig::new(policies, None, false).unwrap()];
+        let policy_executor = PolicyExecutor::new(config);
+
+        let evaluation = policy_executor.evaluate(&dependency()).await.unwrap();
+
+        assert_eq!(
+            evaluation,
+            &[
+                Evaluation::Skip {
+                    policy_name: "some_policy_name".to_string(),
+                    dependency: dependency(),
+                    reason:
+                        "repository https://github.com/some_org/some_repo is unavailable: not found"
+                            .to_string(),
+                },
+                Evaluation::Pass {
+                    policy_name: "some_policy_name2".to_string(),
+                    dependency: dependency()
+                },
+            ]
+        );
+    }
+
     fn mock_policy() -> Box<MockPolicy> {
-        Box::new(MockPolicy::new())
+        let mut policy = Box::new(MockPolicy::new());
+        policy.expect_is_repository_level().return_const(false);
+        policy
     }
 }
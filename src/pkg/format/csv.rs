@@ -2,18 +2,22 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use async_trait::async_trait;
-use itertools::Itertools;
 use tokio::io::AsyncWrite;
 use tokio::sync::Mutex;
 
+use crate::pkg::config::risk_score;
+use crate::pkg::format::group_by_dependency;
+use crate::pkg::policy;
 use crate::pkg::ResultReporter;
-use crate::{Evaluation, Result};
+use crate::{Dependency, Evaluation, Result};
 
 pub struct Reporter<T>
 where
     T: AsyncWrite,
 {
     writer: Arc<Mutex<T>>,
+    risk_score: risk_score::Config,
+    policy_names: Option<Vec<String>>,
 }
 
 impl<T> Reporter<T>
@@ -21,14 +25,93 @@ where
     T: AsyncWrite,
 {
     pub fn new(writer: Arc<Mutex<T>>) -> Self {
-        Self { writer }
+        Self {
+            writer,
+            risk_score: risk_score::Config::default(),
+            policy_names: None,
+        }
+    }
+
+    /// Sets the normalization of the risk score reported for each dependency.
+    pub fn with_risk_score(mut self, risk_score: risk_score::Config) -> Self {
+        self.risk_score = risk_score;
+        self
+    }
+
+    /// Sets the policy columns up front, e.g. with `PolicyExecutor::all_policy_names`, so that each
+    /// row is written as soon as the evaluations of its dependency are read instead of buffering
+    /// all of them. The evaluations of a dependency must be consecutive, as the scanner returns
+    /// them, and the ones of policies missing from the columns are not reported.
+    pub fn with_policy_names(mut self, policy_names: Vec<String>) -> Self {
+        self.policy_names = Some(policy_names);
+        self
     }
 
     fn headers<'a>(policies: &[&'a str]) -> Vec<&'a str> {
-        let mut headers = ["name", "version", "latest_version", "repository", "score"].to_vec();
+        let mut headers = [
+            "name",
+            "ecosystem",
+            "version",
+            "latest_version",
+            "repository",
+            "score",
+            "risk_score",
+            "retrieval_error",
+        ]
+        .to_vec();
         headers.extend_from_slice(policies);
         headers
     }
+
+    fn row(
+        &self,
+        dependency: &Dependency,
+        evaluations: &[&Evaluation],
+        policy_names: &[&str],
+    ) -> Vec<String> {
+        let mut row = [
+            dependency.name.to_string(),
+            dependency.ecosystem.unwrap_or_default().to_string(),
+            dependency.version.to_string(),
+            dependency
+                .latest_version
+                .as_ref()
+                .unwrap_or(&"unknown".to_string())
+                .clone(),
+            dependency.repository.url().unwrap_or_default().to_string(),
+            evaluations
+                .iter()
+                .map(|e| e.fail_score())
+                .sum::<f64>()
+                .to_string(),
+            policy::risk_score(evaluations.iter().copied(), &self.risk_score).to_string(),
+            dependency.retrieval_error.clone().unwrap_or_default(),
+        ]
+        .to_vec();
+
+        for policy in policy_names {
+            let policy_evaluation_for_dependency =
+                evaluations.iter().find(|e| e.policy() == *policy);
+
+            if let Some(evaluation) = policy_evaluation_for_dependency {
+                match evaluation {
+                    Evaluation::Pass { .. } => {
+                        row.push("OK".to_string());
+                    }
+                    Evaluation::Fail { reason, .. } => {
+                        row.push(reason.clone());
+                    }
+                    Evaluation::Skip { reason, .. } => {
+                        row.push(format!("Skipped: {reason}"));
+                    }
+                }
+            } else {
+                row.push("Not evaluated".to_string());
+            }
+        }
+
+        row
+    }
 }
 
 #[async_trait]
@@ -39,73 +122,53 @@ where
     async fn report_results<T>(&mut self, result: T) -> Result<()>
     where
         T: IntoIterator<Item = Evaluation> + Send,
+        T::IntoIter: Send,
     {
         let arc = self.writer.clone();
         let wtr = &mut *arc.lock().await;
 
         let mut writer = csv_async::AsyncWriter::from_writer(wtr);
 
+        if let Some(policy_names) = &self.policy_names {
+            let policy_names = policy_names.iter().map(String::as_str).collect::<Vec<_>>();
+            writer
+                .write_record(Self::headers(&policy_names))
+                .await
+                .context("unable to write record")?;
+
+            let mut evaluations = result.into_iter().peekable();
+            while let Some(evaluation) = evaluations.next() {
+                let mut dependency_evaluations = vec![evaluation];
+                while let Some(evaluation) = evaluations
+                    .next_if(|e| e.dependency() == dependency_evaluations[0].dependency())
+                {
+                    dependency_evaluations.push(evaluation);
+                }
+
+                let row = self.row(
+                    dependency_evaluations[0].dependency(),
+                    &dependency_evaluations.iter().collect::<Vec<_>>(),
+                    &policy_names,
+                );
+                writer
+                    .write_record(row)
+                    .await
+                    .context("unable to write record")?;
+            }
+            return Ok(());
+        }
+
         let evaluations: Vec<Evaluation> = result.into_iter().collect();
-        let policy_names: Vec<_> = evaluations
-            .iter()
-            .map(Evaluation::policy)
-            .unique()
-            .collect();
-
-        let dependencies = evaluations
-            .iter()
-            .map(Evaluation::dependency)
-            .unique()
-            .collect::<Vec<_>>();
+        let (policy_names, dependencies) = group_by_dependency(&evaluations);
 
         writer
             .write_record(Self::headers(&policy_names))
             .await
             .context("unable to write record")?;
 
-        for dependency in dependencies {
-            let evaluations = evaluations
-                .iter()
-                .filter(|e| e.dependency() == dependency)
-                .collect::<Vec<_>>();
-
-            let mut row = [
-                dependency.name.to_string(),
-                dependency.version.to_string(),
-                dependency
-                    .latest_version
-                    .as_ref()
-                    .unwrap_or(&"unknown".to_string())
-                    .clone(),
-                dependency.repository.url().unwrap_or_default().to_string(),
-                evaluations
-                    .iter()
-                    .map(|e| e.fail_score())
-                    .sum::<f64>()
-                    .to_string(),
-            ]
-            .to_vec();
-
-            for policy in &policy_names {
-                let policy_evaluation_for_dependency =
-                    evaluations.iter().find(|e| e.policy() == *policy);
-
-                if let Some(evaluation) = policy_evaluation_for_dependency {
-                    match evaluation {
-                        Evaluation::Pass { .. } => {
-                            row.push("OK".to_string());
-                        }
-                        Evaluation::Fail { reason, .. } => {
-                            row.push(reason.clone());
-                        }
-                    }
-                } else {
-                    row.push("Not evaluated".to_string());
-                }
-            }
-
+        for (dependency, evaluations) in dependencies {
             writer
-                .write_record(row)
+                .write_record(self.row(dependency, &evaluations, &policy_names))
                 .await
                 .context("unable to write record")?;
         }
@@ -119,6 +182,8 @@ mod tests {
     use std::io::Cursor;
 
     use super::*;
+    use crate::pkg::policy::Severity;
+    use crate::pkg::DependencyKind;
     use crate::pkg::Repository::GitHub;
     use crate::{Dependency, Evaluation};
 
@@ -127,7 +192,36 @@ mod tests {
         let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
         let mut reporter = Reporter::new(buffer.clone());
 
-        let evaluations = vec![
+        reporter.report_results(evaluations()).await.unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(buffer.lock().await.get_ref()),
+            r#"name,ecosystem,version,latest_version,repository,score,risk_score,retrieval_error,policy1,policy2
+some_dep1,cargo,1.2.3,1.2.3,https://github.com/some_org/some_repo,0,0,unable to request crates.io,OK,Not evaluated
+some_dep2,cargo,2.3.4,2.4.5,https://github.com/some_org/some_repo,2.5,66.7,,failed because a reason,failed because a reason
+"#
+        );
+    }
+
+    #[tokio::test]
+    async fn it_streams_the_rows_with_the_policy_names_set_up_front() {
+        let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+        let mut reporter = Reporter::new(buffer.clone())
+            .with_policy_names(vec!["policy1".to_string(), "policy2".to_string()]);
+
+        reporter.report_results(evaluations()).await.unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(buffer.lock().await.get_ref()),
+            r#"name,ecosystem,version,latest_version,repository,score,risk_score,retrieval_error,policy1,policy2
+some_dep1,cargo,1.2.3,1.2.3,https://github.com/some_org/some_repo,0,0,unable to request crates.io,OK,Not evaluated
+some_dep2,cargo,2.3.4,2.4.5,https://github.com/some_org/some_repo,2.5,66.7,,failed because a reason,failed because a reason
+"#
+        );
+    }
+
+    fn evaluations() -> Vec<Evaluation> {
+        vec![
             Evaluation::Pass {
                 policy_name: "policy1".to_string(),
                 dependency: Dependency {
@@ -138,6 +232,12 @@ mod tests {
                         organization: "some_org".to_string(),
                         name: "some_repo".to_string(),
                     },
+                    retrieval_error: Some("unable to request crates.io".to_string()),
+                    ecosystem: Some("cargo"),
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
                 },
             },
             Evaluation::Fail {
@@ -150,9 +250,17 @@ mod tests {
                         organization: "some_org".to_string(),
                         name: "some_repo".to_string(),
                     },
+                    retrieval_error: None,
+                    ecosystem: Some("cargo"),
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
                 },
                 reason: "failed because a reason".into(),
                 fail_score: 1.5,
+                severity: Severity::Error,
+                measurement: None,
             },
             Evaluation::Fail {
                 policy_name: "policy2".to_string(),
@@ -164,20 +272,18 @@ mod tests {
                         organization: "some_org".to_string(),
                         name: "some_repo".to_string(),
                     },
+                    retrieval_error: None,
+                    ecosystem: Some("cargo"),
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
                 },
                 reason: "failed because a reason".into(),
                 fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
             },
-        ];
-
-        reporter.report_results(evaluations).await.unwrap();
-
-        assert_eq!(
-            String::from_utf8_lossy(buffer.lock().await.get_ref()),
-            r#"name,version,latest_version,repository,score,policy1,policy2
-some_dep1,1.2.3,1.2.3,https://github.com/some_org/some_repo,0,OK,Not evaluated
-some_dep2,2.3.4,2.4.5,https://github.com/some_org/some_repo,2.5,failed because a reason,failed because a reason
-"#
-        );
+        ]
     }
 }
@@ -0,0 +1,307 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::pkg::config::risk_score;
+use crate::pkg::policy;
+use crate::pkg::ResultReporter;
+use crate::{Dependency, Evaluation, Result};
+
+const WARNING_FAIL_SCORE: f64 = 0.0;
+const ERROR_FAIL_SCORE: f64 = 1.5;
+
+const STYLE: &str = r"body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.4em 0.6em; text-align: left; vertical-align: top; }
+th { background: #eee; cursor: pointer; user-select: none; }
+tr.ok { background: #e6f4ea; }
+tr.warning { background: #fef7e0; }
+tr.error { background: #fce8e6; }
+td.pass { color: #137333; }
+td.fail { color: #a50e0e; }
+td.skip, td.not-evaluated { color: #5f6368; font-style: italic; }";
+
+const SCRIPT: &str = r"document.querySelectorAll('th').forEach((header, column) => {
+  header.addEventListener('click', () => {
+    const body = header.closest('table').tBodies[0];
+    const ascending = header.dataset.order !== 'asc';
+    header.dataset.order = ascending ? 'asc' : 'desc';
+    const value = (row) => row.cells[column].dataset.value ?? row.cells[column].textContent;
+    const rows = Array.from(body.rows).sort((a, b) => {
+      const [x, y] = [value(a), value(b)];
+      const order = isNaN(x) || isNaN(y) ? x.localeCompare(y) : x - y;
+      return ascending ? order : -order;
+    });
+    rows.forEach((row) => body.appendChild(row));
+  });
+});";
+
+pub struct Reporter<T>
+where
+    T: AsyncWrite,
+{
+    writer: Arc<Mutex<T>>,
+    risk_score: risk_score::Config,
+}
+
+impl<T> Reporter<T>
+where
+    T: AsyncWrite,
+{
+    pub fn new(writer: Arc<Mutex<T>>) -> Self {
+        Self {
+            writer,
+            risk_score: risk_score::Config::default(),
+        }
+    }
+
+    /// Sets the normalization of the risk score reported for each dependency.
+    pub fn with_risk_score(mut self, risk_score: risk_score::Config) -> Self {
+        self.risk_score = risk_score;
+        self
+    }
+
+    fn row_class(total_score: f64) -> &'static str {
+        if total_score > ERROR_FAIL_SCORE {
+            "error"
+        } else if total_score > WARNING_FAIL_SCORE {
+            "warning"
+        } else {
+            "ok"
+        }
+    }
+
+    /// Renders a self-contained page with one row per dependency and one column per policy.
+    fn render(
+        evaluations: &[Evaluation],
+        normalization: &risk_score::Config,
+    ) -> std::result::Result<String, std::fmt::Error> {
+        let policy_names: Vec<_> = evaluations
+            .iter()
+            .map(Evaluation::policy)
+            .unique()
+            .collect();
+
+        let dependencies = evaluations
+            .iter()
+            .map(Evaluation::dependency)
+            .unique()
+            .collect::<Vec<_>>();
+
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>")?;
+        writeln!(html, "<html>")?;
+        writeln!(html, "<head>")?;
+        writeln!(html, "<meta charset=\"utf-8\">")?;
+        writeln!(html, "<title>dean report</title>")?;
+        writeln!(html, "<style>\n{STYLE}\n</style>")?;
+        writeln!(html, "</head>")?;
+        writeln!(html, "<body>")?;
+        writeln!(html, "<table>")?;
+        write!(html, "<thead><tr>")?;
+        for header in [
+            "name",
+            "ecosystem",
+            "version",
+            "latest_version",
+            "repository",
+            "score",
+            "risk_score",
+            "retrieval_error",
+        ]
+        .iter()
+        .chain(&policy_names)
+        {
+            write!(html, "<th>{}</th>", escape(header))?;
+        }
+        writeln!(html, "</tr></thead>")?;
+        writeln!(html, "<tbody>")?;
+
+        for dependency in dependencies {
+            let evaluations = evaluations
+                .iter()
+                .filter(|e| e.dependency() == dependency)
+                .collect::<Vec<_>>();
+            let total_score: f64 = evaluations.iter().map(|e| e.fail_score()).sum();
+            let risk_score = policy::risk_score(evaluations.iter().copied(), normalization);
+
+            Self::render_dependency(&mut html, dependency, total_score, risk_score)?;
+
+            for policy in &policy_names {
+                match evaluations.iter().find(|e| e.policy() == *policy) {
+                    Some(Evaluation::Pass { .. }) => write!(html, "<td class=\"pass\">OK</td>")?,
+                    Some(Evaluation::Fail {
+                        reason, fail_score, ..
+                    }) => write!(
+                        html,
+                        "<td class=\"fail\" title=\"fail score: {fail_score}\">{}</td>",
+                        escape(reason)
+                    )?,
+                    Some(Evaluation::Skip { reason, .. }) => {
+                        write!(html, "<td class=\"skip\">Skipped: {}</td>", escape(reason))?;
+                    }
+                    None => write!(html, "<td class=\"not-evaluated\">Not evaluated</td>")?,
+                }
+            }
+            writeln!(html, "</tr>")?;
+        }
+
+        writeln!(html, "</tbody>")?;
+        writeln!(html, "</table>")?;
+        writeln!(html, "<script>\n{SCRIPT}\n</script>")?;
+        writeln!(html, "</body>")?;
+        writeln!(html, "</html>")?;
+
+        Ok(html)
+    }
+
+    fn render_dependency(
+        html: &mut String,
+        dependency: &Dependency,
+        total_score: f64,
+        risk_score: f64,
+    ) -> std::fmt::Result {
+        write!(html, "<tr class=\"{}\">", Self::row_class(total_score))?;
+        write!(html, "<td>{}</td>", escape(&dependency.name))?;
+        write!(
+            html,
+            "<td>{}</td>",
+            escape(dependency.ecosystem.unwrap_or_default())
+        )?;
+        write!(html, "<td>{}</td>", escape(&dependency.version))?;
+        write!(
+            html,
+            "<td>{}</td>",
+            escape(dependency.latest_version.as_deref().unwrap_or("unknown"))
+        )?;
+        match dependency.repository.url() {
+            Some(url) => write!(
+                html,
+                "<td><a href=\"{url}\">{url}</a></td>",
+                url = escape(&url)
+            )?,
+            None => write!(html, "<td></td>")?,
+        }
+        write!(html, "<td data-value=\"{total_score}\">{total_score}</td>")?;
+        write!(html, "<td data-value=\"{risk_score}\">{risk_score}</td>")?;
+        write!(
+            html,
+            "<td>{}</td>",
+            escape(dependency.retrieval_error.as_deref().unwrap_or_default())
+        )
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[async_trait]
+impl<F> ResultReporter for Reporter<F>
+where
+    F: AsyncWrite + Unpin + Send,
+{
+    async fn report_results<T>(&mut self, result: T) -> Result<()>
+    where
+        T: IntoIterator<Item = Evaluation> + Send,
+        T::IntoIter: Send,
+    {
+        let evaluations: Vec<Evaluation> = result.into_iter().collect();
+
+        let contents =
+            Self::render(&evaluations, &self.risk_score).context("unable to render results")?;
+
+        let arc = self.writer.clone();
+        let wtr = &mut *arc.lock().await;
+        wtr.write_all(contents.as_bytes())
+            .await
+            .context("unable to write results")?;
+        wtr.flush().await.context("unable to flush results")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pkg::policy::Severity;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository::GitHub;
+    use crate::{Dependency, Evaluation};
+
+    #[tokio::test]
+    async fn it_reports_to_html_the_results() {
+        let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+        let mut reporter = Reporter::new(buffer.clone());
+
+        let evaluations = vec![
+            Evaluation::Pass {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep1"),
+            },
+            Evaluation::Fail {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep2"),
+                reason: "failed because <reason>".into(),
+                fail_score: 1.5,
+                severity: Severity::Error,
+                measurement: None,
+            },
+            Evaluation::Fail {
+                policy_name: "policy2".to_string(),
+                dependency: dependency("some_dep2"),
+                reason: "failed because a reason".into(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            },
+        ];
+
+        reporter.report_results(evaluations).await.unwrap();
+
+        let html = String::from_utf8_lossy(buffer.lock().await.get_ref()).to_string();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<th>policy1</th><th>policy2</th></tr></thead>"));
+        assert!(html.contains(
+            "<tr class=\"ok\"><td>some_dep1</td><td>cargo</td><td>1.2.3</td><td>1.2.3</td>\
+            <td><a href=\"https://github.com/some_org/some_repo\">https://github.com/some_org/some_repo</a></td>\
+            <td data-value=\"0\">0</td><td data-value=\"0\">0</td><td></td>\
+            <td class=\"pass\">OK</td><td class=\"not-evaluated\">Not evaluated</td></tr>"
+        ));
+        assert!(html.contains("<tr class=\"error\"><td>some_dep2</td>"));
+        assert!(html.contains("<td data-value=\"2.5\">2.5</td><td data-value=\"66.7\">66.7</td>"));
+        assert!(html.contains(
+            "<td class=\"fail\" title=\"fail score: 1.5\">failed because &lt;reason&gt;</td>"
+        ));
+    }
+
+    fn dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.2.3".to_string(),
+            latest_version: Some("1.2.3".to_string()),
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem: Some("cargo"),
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
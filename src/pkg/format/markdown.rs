@@ -0,0 +1,310 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::pkg::config::risk_score;
+use crate::pkg::format::group_by_dependency;
+use crate::pkg::policy;
+use crate::pkg::ResultReporter;
+use crate::{Dependency, Evaluation, Result};
+
+/// Maximum number of dependencies in the table, so that the report fits in a pull request
+/// comment.
+const MAX_DEPENDENCIES: usize = 50;
+
+/// Writes a GitHub-flavored Markdown summary of the results, meant to be posted as a pull request
+/// comment.
+pub struct Reporter<T>
+where
+    T: AsyncWrite,
+{
+    writer: Arc<Mutex<T>>,
+    risk_score: risk_score::Config,
+}
+
+impl<T> Reporter<T>
+where
+    T: AsyncWrite,
+{
+    pub fn new(writer: Arc<Mutex<T>>) -> Self {
+        Self {
+            writer,
+            risk_score: risk_score::Config::default(),
+        }
+    }
+
+    /// Sets the normalization of the risk score reported for each dependency.
+    pub fn with_risk_score(mut self, risk_score: risk_score::Config) -> Self {
+        self.risk_score = risk_score;
+        self
+    }
+
+    /// Renders a table with one row per dependency and one column per policy, the highest fail
+    /// scores first, followed by the reasons of the failures in a collapsible section.
+    fn render(
+        evaluations: &[Evaluation],
+        normalization: &risk_score::Config,
+    ) -> std::result::Result<String, std::fmt::Error> {
+        let (policy_names, mut dependencies) = group_by_dependency(evaluations);
+        let total_dependencies = dependencies.len();
+        let failing_dependencies = dependencies
+            .iter()
+            .filter(|(_, evaluations)| {
+                evaluations
+                    .iter()
+                    .any(|e| matches!(e, Evaluation::Fail { .. }))
+            })
+            .count();
+
+        dependencies.sort_by(|(_, a), (_, b)| total_score(b).total_cmp(&total_score(a)));
+        let omitted = dependencies.len().saturating_sub(MAX_DEPENDENCIES);
+        dependencies.truncate(MAX_DEPENDENCIES);
+
+        let mut markdown = String::new();
+        writeln!(markdown, "### dean report")?;
+        writeln!(markdown)?;
+        writeln!(
+            markdown,
+            "{failing_dependencies} of {total_dependencies} dependencies failed any policy."
+        )?;
+        writeln!(markdown)?;
+
+        write!(markdown, "| dependency | version | score | risk score |")?;
+        for policy in &policy_names {
+            write!(markdown, " {} |", escape(policy))?;
+        }
+        writeln!(markdown)?;
+        write!(markdown, "| --- | --- | ---: | ---: |")?;
+        for _ in &policy_names {
+            write!(markdown, " :---: |")?;
+        }
+        writeln!(markdown)?;
+
+        for (dependency, evaluations) in &dependencies {
+            write!(
+                markdown,
+                "| {} | {} | {} | {} |",
+                escape(&dependency_name(dependency)),
+                escape(&dependency.version),
+                total_score(evaluations),
+                policy::risk_score(evaluations.iter().copied(), normalization)
+            )?;
+            for policy in &policy_names {
+                let status = match evaluations.iter().find(|e| e.policy() == *policy) {
+                    Some(Evaluation::Pass { .. }) => "✅",
+                    Some(Evaluation::Fail { .. }) => "❌",
+                    Some(Evaluation::Skip { .. }) => "⏭️",
+                    None => "➖",
+                };
+                write!(markdown, " {status} |")?;
+            }
+            writeln!(markdown)?;
+        }
+
+        if omitted > 0 {
+            writeln!(markdown)?;
+            writeln!(
+                markdown,
+                "_{omitted} more dependencies with lower fail scores were omitted._"
+            )?;
+        }
+
+        Self::render_failure_reasons(&mut markdown, &dependencies)?;
+
+        Ok(markdown)
+    }
+
+    fn render_failure_reasons(
+        markdown: &mut String,
+        dependencies: &[(&Dependency, Vec<&Evaluation>)],
+    ) -> std::fmt::Result {
+        let failures = dependencies
+            .iter()
+            .flat_map(|(dependency, evaluations)| {
+                evaluations.iter().filter_map(move |e| match e {
+                    Evaluation::Fail {
+                        policy_name,
+                        reason,
+                        ..
+                    } => Some((dependency, policy_name, reason)),
+                    Evaluation::Pass { .. } | Evaluation::Skip { .. } => None,
+                })
+            })
+            .collect::<Vec<_>>();
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(markdown)?;
+        writeln!(markdown, "<details>")?;
+        writeln!(markdown, "<summary>Reasons of the failures</summary>")?;
+        writeln!(markdown)?;
+        for (dependency, policy_name, reason) in failures {
+            writeln!(
+                markdown,
+                "- {}@{} `{}`: {}",
+                escape(&dependency_name(dependency)),
+                escape(&dependency.version),
+                policy_name,
+                escape(reason)
+            )?;
+        }
+        writeln!(markdown)?;
+        writeln!(markdown, "</details>")
+    }
+}
+
+fn total_score(evaluations: &[&Evaluation]) -> f64 {
+    evaluations.iter().map(|e| e.fail_score()).sum()
+}
+
+fn dependency_name(dependency: &Dependency) -> String {
+    match dependency.ecosystem {
+        Some(ecosystem) => format!("{} ({ecosystem})", dependency.name),
+        None => dependency.name.clone(),
+    }
+}
+
+/// Keeps the text in a single table cell, and prevents it from being rendered as HTML.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(['\r', '\n'], " ")
+}
+
+#[async_trait]
+impl<F> ResultReporter for Reporter<F>
+where
+    F: AsyncWrite + Unpin + Send,
+{
+    async fn report_results<T>(&mut self, result: T) -> Result<()>
+    where
+        T: IntoIterator<Item = Evaluation> + Send,
+        T::IntoIter: Send,
+    {
+        let evaluations: Vec<Evaluation> = result.into_iter().collect();
+
+        let contents =
+            Self::render(&evaluations, &self.risk_score).context("unable to render results")?;
+
+        let arc = self.writer.clone();
+        let wtr = &mut *arc.lock().await;
+        wtr.write_all(contents.as_bytes())
+            .await
+            .context("unable to write results")?;
+        wtr.flush().await.context("unable to flush results")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pkg::policy::Severity;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository::GitHub;
+    use crate::{Dependency, Evaluation};
+
+    #[tokio::test]
+    async fn it_reports_to_markdown_the_results() {
+        let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+        let mut reporter = Reporter::new(buffer.clone());
+
+        let evaluations = vec![
+            Evaluation::Pass {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep1"),
+            },
+            Evaluation::Fail {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep2"),
+                reason: "failed because <reason>".into(),
+                fail_score: 1.5,
+                severity: Severity::Error,
+                measurement: None,
+            },
+            Evaluation::Fail {
+                policy_name: "policy2".to_string(),
+                dependency: dependency("some_dep2"),
+                reason: "failed because a | reason".into(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            },
+        ];
+
+        reporter.report_results(evaluations).await.unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(buffer.lock().await.get_ref()),
+            r"### dean report
+
+1 of 2 dependencies failed any policy.
+
+| dependency | version | score | risk score | policy1 | policy2 |
+| --- | --- | ---: | ---: | :---: | :---: |
+| some_dep2 (cargo) | 1.2.3 | 2.5 | 66.7 | ❌ | ❌ |
+| some_dep1 (cargo) | 1.2.3 | 0 | 0 | ✅ | ➖ |
+
+<details>
+<summary>Reasons of the failures</summary>
+
+- some_dep2 (cargo)@1.2.3 `policy1`: failed because &lt;reason&gt;
+- some_dep2 (cargo)@1.2.3 `policy2`: failed because a \| reason
+
+</details>
+"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_omits_the_dependencies_with_the_lowest_fail_scores_from_the_table() {
+        let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+        let mut reporter = Reporter::new(buffer.clone());
+
+        let evaluations = (0..MAX_DEPENDENCIES + 2).map(|i| Evaluation::Fail {
+            policy_name: "policy1".to_string(),
+            dependency: dependency(&format!("some_dep{i}")),
+            reason: "failed because a reason".into(),
+            #[allow(clippy::cast_precision_loss)]
+            fail_score: i as f64,
+            severity: Severity::Error,
+            measurement: None,
+        });
+
+        reporter.report_results(evaluations).await.unwrap();
+
+        let markdown = String::from_utf8_lossy(buffer.lock().await.get_ref()).to_string();
+        assert!(markdown.contains("52 of 52 dependencies failed any policy."));
+        assert!(markdown.contains("| some_dep51 (cargo) | 1.2.3 | 51 | 33.3 | ❌ |"));
+        assert!(!markdown.contains("| some_dep1 (cargo) |"));
+        assert!(markdown.contains("_2 more dependencies with lower fail scores were omitted._"));
+        assert_eq!(markdown.matches("- some_dep").count(), MAX_DEPENDENCIES);
+    }
+
+    fn dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.2.3".to_string(),
+            latest_version: Some("1.2.3".to_string()),
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem: Some("cargo"),
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
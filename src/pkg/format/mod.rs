@@ -1 +1,74 @@
+use async_trait::async_trait;
+use itertools::Itertools;
+use tokio::io::AsyncWrite;
+
+use crate::pkg::ResultReporter;
+use crate::{Dependency, Evaluation, Result};
+
 pub mod csv;
+pub mod html;
+pub mod json;
+pub mod markdown;
+pub mod sarif;
+pub mod toml;
+
+/// Reporter for any of the supported output formats.
+pub enum Reporter<W>
+where
+    W: AsyncWrite,
+{
+    Csv(csv::Reporter<W>),
+    Json(json::Reporter<W>),
+    Sarif(sarif::Reporter<W>),
+    Toml(toml::Reporter<W>),
+    Html(html::Reporter<W>),
+    Markdown(markdown::Reporter<W>),
+}
+
+#[async_trait]
+impl<W> ResultReporter for Reporter<W>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    async fn report_results<T>(&mut self, result: T) -> Result<()>
+    where
+        T: IntoIterator<Item = Evaluation> + Send,
+        T::IntoIter: Send,
+    {
+        match self {
+            Reporter::Csv(reporter) => reporter.report_results(result).await,
+            Reporter::Json(reporter) => reporter.report_results(result).await,
+            Reporter::Sarif(reporter) => reporter.report_results(result).await,
+            Reporter::Toml(reporter) => reporter.report_results(result).await,
+            Reporter::Html(reporter) => reporter.report_results(result).await,
+            Reporter::Markdown(reporter) => reporter.report_results(result).await,
+        }
+    }
+}
+
+/// Groups the evaluations by dependency, in the order the dependencies were first evaluated, and
+/// returns them with the names of all the evaluated policies.
+fn group_by_dependency(
+    evaluations: &[Evaluation],
+) -> (Vec<&str>, Vec<(&Dependency, Vec<&Evaluation>)>) {
+    let policy_names = evaluations
+        .iter()
+        .map(Evaluation::policy)
+        .unique()
+        .collect();
+
+    let dependencies = evaluations
+        .iter()
+        .map(Evaluation::dependency)
+        .unique()
+        .map(|dependency| {
+            let evaluations = evaluations
+                .iter()
+                .filter(|e| e.dependency() == dependency)
+                .collect();
+            (dependency, evaluations)
+        })
+        .collect();
+
+    (policy_names, dependencies)
+}
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::pkg::policy::Severity;
+use crate::pkg::ResultReporter;
+use crate::{Dependency, Evaluation, Result};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const ERROR_LEVEL_FAIL_SCORE: f64 = 1.5;
+
+pub struct Reporter<T>
+where
+    T: AsyncWrite,
+{
+    writer: Arc<Mutex<T>>,
+    /// Location of the results whose dependency doesn't tell the lock file it was read from.
+    artifact_location: String,
+}
+
+impl<T> Reporter<T>
+where
+    T: AsyncWrite,
+{
+    pub fn new(writer: Arc<Mutex<T>>, artifact_location: &str) -> Self {
+        Self {
+            writer,
+            artifact_location: artifact_location.to_string(),
+        }
+    }
+
+    fn level(fail_score: f64, severity: Severity) -> &'static str {
+        match severity {
+            Severity::Info => "note",
+            Severity::Error if fail_score > ERROR_LEVEL_FAIL_SCORE => "error",
+            Severity::Warn | Severity::Error => "warning",
+        }
+    }
+
+    /// Points the result at the lock file listing the dependency.
+    fn locations(&self, dependency: &Dependency) -> Value {
+        let lock_file = dependency
+            .lock_file
+            .as_deref()
+            .unwrap_or(&self.artifact_location);
+        json!([{
+            "physicalLocation": {
+                "artifactLocation": {"uri": lock_file},
+            },
+        }])
+    }
+
+    fn sarif_log(&self, evaluations: &[Evaluation]) -> Value {
+        let rules = evaluations
+            .iter()
+            .map(Evaluation::policy)
+            .unique()
+            .map(|policy| {
+                json!({
+                    "id": policy,
+                    "name": policy,
+                    "shortDescription": {"text": format!("dean policy {policy}")},
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let results = evaluations
+            .iter()
+            .filter_map(|evaluation| match evaluation {
+                Evaluation::Pass { .. } => None,
+                Evaluation::Fail {
+                    policy_name,
+                    dependency,
+                    reason,
+                    fail_score,
+                    severity,
+                    ..
+                } => Some(json!({
+                    "ruleId": policy_name,
+                    "level": Self::level(*fail_score, *severity),
+                    "message": {
+                        "text": format!("dependency {}@{} failed policy {}: {}", dependency.name, dependency.version, policy_name, reason),
+                    },
+                    "locations": self.locations(dependency),
+                    "properties": {"fail_score": fail_score},
+                })),
+                Evaluation::Skip {
+                    policy_name,
+                    dependency,
+                    reason,
+                } => Some(json!({
+                    "ruleId": policy_name,
+                    "level": "note",
+                    "message": {
+                        "text": format!("dependency {}@{} skipped policy {}: {}", dependency.name, dependency.version, policy_name, reason),
+                    },
+                    "locations": self.locations(dependency),
+                })),
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        })
+    }
+}
+
+#[async_trait]
+impl<F> ResultReporter for Reporter<F>
+where
+    F: AsyncWrite + Unpin + Send,
+{
+    async fn report_results<T>(&mut self, result: T) -> Result<()>
+    where
+        T: IntoIterator<Item = Evaluation> + Send,
+        T::IntoIter: Send,
+    {
+        let evaluations: Vec<Evaluation> = result.into_iter().collect();
+
+        let mut contents = serde_json::to_vec_pretty(&self.sarif_log(&evaluations))
+            .context("unable to serialize results")?;
+        contents.push(b'\n');
+
+        let arc = self.writer.clone();
+        let wtr = &mut *arc.lock().await;
+        wtr.write_all(&contents)
+            .await
+            .context("unable to write results")?;
+        wtr.flush().await.context("unable to flush results")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository::GitHub;
+
+    #[tokio::test]
+    async fn it_reports_the_failures_as_sarif_results() {
+        let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+        let mut reporter = Reporter::new(buffer.clone(), "path/to/Cargo.lock");
+
+        let evaluations = vec![
+            Evaluation::Pass {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep1"),
+            },
+            Evaluation::Fail {
+                policy_name: "policy1".to_string(),
+                dependency: dependency("some_dep2"),
+                reason: "failed because a reason".into(),
+                fail_score: 2.0,
+                severity: Severity::Error,
+                measurement: None,
+            },
+            Evaluation::Fail {
+                policy_name: "policy2".to_string(),
+                dependency: dependency("some_dep2"),
+                reason: "failed because another reason".into(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            },
+        ];
+
+        reporter.report_results(evaluations).await.unwrap();
+
+        let result: Value = serde_json::from_slice(buffer.lock().await.get_ref()).unwrap();
+        assert_eq!(result["version"], "2.1.0");
+        let run = &result["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "policy1");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "policy2");
+        assert_eq!(
+            run["results"],
+            json!([
+                {
+                    "ruleId": "policy1",
+                    "level": "error",
+                    "message": {"text": "dependency some_dep2@1.2.3 failed policy policy1: failed because a reason"},
+                    "locations": [{"physicalLocation": {"artifactLocation": {"uri": "path/to/Cargo.lock"}}}],
+                    "properties": {"fail_score": 2.0},
+                },
+                {
+                    "ruleId": "policy2",
+                    "level": "warning",
+                    "message": {"text": "dependency some_dep2@1.2.3 failed policy policy2: failed because another reason"},
+                    "locations": [{"physicalLocation": {"artifactLocation": {"uri": "path/to/Cargo.lock"}}}],
+                    "properties": {"fail_score": 1.0},
+                },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn it_points_each_result_at_the_lock_file_of_its_dependency() {
+        let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+        let mut reporter = Reporter::new(buffer.clone(), "backend/Cargo.lock");
+        let failure = |dependency| Evaluation::Fail {
+            policy_name: "policy1".to_string(),
+            dependency,
+            reason: "failed because a reason".into(),
+            fail_score: 2.0,
+            severity: Severity::Error,
+            measurement: None,
+        };
+
+        reporter
+            .report_results(vec![
+                failure(Dependency {
+                    lock_file: Some("backend/Cargo.lock".to_string()),
+                    ..dependency("some_dep1")
+                }),
+                failure(Dependency {
+                    lock_file: Some("frontend/package-lock.json".to_string()),
+                    ..dependency("some_dep2")
+                }),
+                failure(dependency("some_dep3")),
+            ])
+            .await
+            .unwrap();
+
+        let result: Value = serde_json::from_slice(buffer.lock().await.get_ref()).unwrap();
+        let uris = result["runs"][0]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| {
+                result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].clone()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            uris,
+            vec![
+                "backend/Cargo.lock",
+                "frontend/package-lock.json",
+                "backend/Cargo.lock"
+            ]
+        );
+    }
+
+    fn dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.2.3".to_string(),
+            latest_version: Some("1.2.3".to_string()),
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
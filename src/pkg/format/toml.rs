@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+use serde::Serialize;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::pkg::config::risk_score;
+use crate::pkg::policy::{self, Measurement, Severity};
+use crate::pkg::ResultReporter;
+use crate::{Evaluation, Result};
+
+pub struct Reporter<T>
+where
+    T: AsyncWrite,
+{
+    writer: Arc<Mutex<T>>,
+    risk_score: risk_score::Config,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    dependency: Vec<DependencyResult<'a>>,
+}
+
+/// TOML requires the values of a table to be written before its sub-tables, so the policies
+/// must be the last field.
+#[derive(Serialize)]
+struct DependencyResult<'a> {
+    name: &'a str,
+    ecosystem: Option<&'a str>,
+    version: &'a str,
+    latest_version: Option<&'a str>,
+    repository: Option<String>,
+    retrieval_error: Option<&'a str>,
+    total_score: f64,
+    risk_score: f64,
+    policies: BTreeMap<&'a str, PolicyResult<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+enum PolicyResult<'a> {
+    Pass,
+    Fail {
+        reason: &'a str,
+        fail_score: f64,
+        severity: Severity,
+        #[serde(flatten, skip_serializing_if = "Option::is_none")]
+        measurement: Option<&'a Measurement>,
+    },
+    Skipped {
+        reason: &'a str,
+    },
+}
+
+impl<T> Reporter<T>
+where
+    T: AsyncWrite,
+{
+    pub fn new(writer: Arc<Mutex<T>>) -> Self {
+        Self {
+            writer,
+            risk_score: risk_score::Config::default(),
+        }
+    }
+
+    /// Sets the normalization of the risk score reported for each dependency.
+    pub fn with_risk_score(mut self, risk_score: risk_score::Config) -> Self {
+        self.risk_score = risk_score;
+        self
+    }
+}
+
+#[async_trait]
+impl<F> ResultReporter for Reporter<F>
+where
+    F: AsyncWrite + Unpin + Send,
+{
+    async fn report_results<T>(&mut self, result: T) -> Result<()>
+    where
+        T: IntoIterator<Item = Evaluation> + Send,
+        T::IntoIter: Send,
+    {
+        let evaluations: Vec<Evaluation> = result.into_iter().collect();
+
+        let dependencies = evaluations
+            .iter()
+            .map(Evaluation::dependency)
+            .unique()
+            .map(|dependency| {
+                let evaluations = evaluations
+                    .iter()
+                    .filter(|e| e.dependency() == dependency)
+                    .collect::<Vec<_>>();
+
+                DependencyResult {
+                    name: &dependency.name,
+                    ecosystem: dependency.ecosystem,
+                    version: &dependency.version,
+                    latest_version: dependency.latest_version.as_deref(),
+                    repository: dependency.repository.url(),
+                    retrieval_error: dependency.retrieval_error.as_deref(),
+                    total_score: evaluations.iter().map(|e| e.fail_score()).sum(),
+                    risk_score: policy::risk_score(evaluations.iter().copied(), &self.risk_score),
+                    policies: evaluations
+                        .iter()
+                        .map(|evaluation| {
+                            let policy_result = match evaluation {
+                                Evaluation::Pass { .. } => PolicyResult::Pass,
+                                Evaluation::Fail {
+                                    reason,
+                                    fail_score,
+                                    severity,
+                                    measurement,
+                                    ..
+                                } => PolicyResult::Fail {
+                                    reason,
+                                    fail_score: *fail_score,
+                                    severity: *severity,
+                                    measurement: measurement.as_ref(),
+                                },
+                                Evaluation::Skip { reason, .. } => PolicyResult::Skipped { reason },
+                            };
+                            (evaluation.policy(), policy_result)
+                        })
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let contents = toml::to_string(&Report {
+            dependency: dependencies,
+        })
+        .context("unable to serialize results")?;
+
+        let arc = self.writer.clone();
+        let wtr = &mut *arc.lock().await;
+        wtr.write_all(contents.as_bytes())
+            .await
+            .context("unable to write results")?;
+        wtr.flush().await.context("unable to flush results")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository::GitHub;
+    use crate::{Dependency, Evaluation};
+
+    #[tokio::test]
+    async fn it_reports_to_toml_the_results() {
+        let buffer = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+        let mut reporter = Reporter::new(buffer.clone());
+
+        let evaluations = vec![
+            Evaluation::Pass {
+                policy_name: "policy1".to_string(),
+                dependency: Dependency {
+                    name: "some_dep1".to_string(),
+                    version: "1.2.3".to_string(),
+                    latest_version: None,
+                    repository: GitHub {
+                        organization: "some_org".to_string(),
+                        name: "some_repo".to_string(),
+                    },
+                    retrieval_error: None,
+                    ecosystem: Some("cargo"),
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
+                },
+            },
+            Evaluation::Fail {
+                policy_name: "policy1".to_string(),
+                dependency: Dependency {
+                    name: "some_dep2".to_string(),
+                    version: "2.3.4".to_string(),
+                    latest_version: Some("2.4.5".to_string()),
+                    repository: GitHub {
+                        organization: "some_org".to_string(),
+                        name: "some_repo".to_string(),
+                    },
+                    retrieval_error: None,
+                    ecosystem: Some("cargo"),
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
+                },
+                reason: "failed because a reason".into(),
+                fail_score: 1.5,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(0.75, 0.5)),
+            },
+            Evaluation::Pass {
+                policy_name: "policy2".to_string(),
+                dependency: Dependency {
+                    name: "some_dep2".to_string(),
+                    version: "2.3.4".to_string(),
+                    latest_version: Some("2.4.5".to_string()),
+                    repository: GitHub {
+                        organization: "some_org".to_string(),
+                        name: "some_repo".to_string(),
+                    },
+                    retrieval_error: None,
+                    ecosystem: Some("cargo"),
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
+                },
+            },
+        ];
+
+        reporter.report_results(evaluations).await.unwrap();
+
+        let result = String::from_utf8(buffer.lock().await.get_ref().clone()).unwrap();
+        assert_eq!(
+            result,
+            "\
+[[dependency]]
+name = \"some_dep1\"
+ecosystem = \"cargo\"
+version = \"1.2.3\"
+repository = \"https://github.com/some_org/some_repo\"
+total_score = 0.0
+risk_score = 0.0
+[dependency.policies.policy1]
+result = \"pass\"
+
+[[dependency]]
+name = \"some_dep2\"
+ecosystem = \"cargo\"
+version = \"2.3.4\"
+latest_version = \"2.4.5\"
+repository = \"https://github.com/some_org/some_repo\"
+total_score = 1.5
+risk_score = 33.3
+[dependency.policies.policy1]
+result = \"fail\"
+reason = \"failed because a reason\"
+fail_score = 1.5
+severity = \"error\"
+observed = 0.75
+limit = 0.5
+
+[dependency.policies.policy2]
+result = \"pass\"
+"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// The outcome of a scan for a dependency, as stored in the scan history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScannedDependency {
+    pub name: String,
+    pub version: String,
+    pub ecosystem: Option<String>,
+    pub failed_policies: Vec<String>,
+}
+
+impl ScannedDependency {
+    pub fn has_failed(&self) -> bool {
+        !self.failed_policies.is_empty()
+    }
+
+    /// Identifies the dependency across scans, where its version may have changed.
+    fn key(&self) -> (&str, Option<&str>) {
+        (&self.name, self.ecosystem.as_deref())
+    }
+}
+
+impl Display for ScannedDependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)?;
+        if let Some(ecosystem) = &self.ecosystem {
+            write!(f, " ({ecosystem})")?;
+        }
+        if self.has_failed() {
+            write!(f, ": {}", self.failed_policies.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A stored scan, with the timestamp it was made at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scan {
+    pub scanned_at: u64,
+    pub dependencies: Vec<ScannedDependency>,
+}
+
+/// The changes of the dependencies between two scans.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ScanDiff {
+    pub newly_failing: Vec<ScannedDependency>,
+    pub newly_passing: Vec<ScannedDependency>,
+    pub added: Vec<ScannedDependency>,
+    pub removed: Vec<ScannedDependency>,
+}
+
+/// Compares the latest scan with the previous one.
+pub fn diff(previous: &[ScannedDependency], latest: &[ScannedDependency]) -> ScanDiff {
+    let previous_by_key = previous
+        .iter()
+        .map(|dependency| (dependency.key(), dependency))
+        .collect::<BTreeMap<_, _>>();
+    let latest_by_key = latest
+        .iter()
+        .map(|dependency| (dependency.key(), dependency))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut scan_diff = ScanDiff::default();
+    for (key, dependency) in &latest_by_key {
+        match previous_by_key.get(key) {
+            None => scan_diff.added.push((*dependency).clone()),
+            Some(previous) if dependency.has_failed() && !previous.has_failed() => {
+                scan_diff.newly_failing.push((*dependency).clone());
+            }
+            Some(previous) if !dependency.has_failed() && previous.has_failed() => {
+                scan_diff.newly_passing.push((*dependency).clone());
+            }
+            Some(_) => {}
+        }
+    }
+    scan_diff.removed = previous_by_key
+        .iter()
+        .filter(|(key, _)| !latest_by_key.contains_key(*key))
+        .map(|(_, dependency)| (*dependency).clone())
+        .collect();
+
+    scan_diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reports_the_dependencies_that_changed_between_two_scans() {
+        let previous = vec![
+            scanned("failing", "1.0.0", &[]),
+            scanned("passing", "1.0.0", &["policy1"]),
+            scanned("unchanged", "1.0.0", &["policy1"]),
+            scanned("removed", "1.0.0", &[]),
+        ];
+        let latest = vec![
+            scanned("failing", "1.1.0", &["policy1"]),
+            scanned("passing", "2.0.0", &[]),
+            scanned("unchanged", "1.0.0", &["policy1", "policy2"]),
+            scanned("added", "1.0.0", &[]),
+        ];
+
+        assert_eq!(
+            diff(&previous, &latest),
+            ScanDiff {
+                newly_failing: vec![scanned("failing", "1.1.0", &["policy1"])],
+                newly_passing: vec![scanned("passing", "2.0.0", &[])],
+                added: vec![scanned("added", "1.0.0", &[])],
+                removed: vec![scanned("removed", "1.0.0", &[])],
+            }
+        );
+    }
+
+    fn scanned(name: &str, version: &str, failed_policies: &[&str]) -> ScannedDependency {
+        ScannedDependency {
+            name: name.to_string(),
+            version: version.to_string(),
+            ecosystem: Some("cargo".to_string()),
+            failed_policies: failed_policies.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
@@ -3,35 +3,74 @@ use std::fmt::{Display, Formatter};
 use async_trait::async_trait;
 use futures::Stream;
 use lazy_static::lazy_static;
-use regex::Regex;
+use log::debug;
+use regex::{Regex, RegexSet};
 
 use crate::{Evaluation, Result};
 
 pub mod config;
 pub mod engine;
 pub mod format;
+pub mod history;
 pub mod package_manager;
 pub mod policy;
 pub mod recognizer;
+pub mod timings;
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait InfoRetriever: Sync + Send {
     async fn latest_version(&self, dependency: &str) -> Result<String>;
     async fn repository(&self, dependency: &str) -> Result<Repository>;
+
+    /// Retrieves both the latest version and the repository of the dependency, which
+    /// implementations can override to serve both from a single request.
+    async fn info(&self, dependency: &str) -> Result<PackageInfo> {
+        let (latest_version, repository) =
+            futures::future::join(self.latest_version(dependency), self.repository(dependency))
+                .await;
+
+        Ok(PackageInfo {
+            latest_version: latest_version.ok(),
+            repository: repository.unwrap_or(Repository::Unknown),
+            repository_directory: None,
+        })
+    }
+
+    /// Retrieves why the given version of the dependency is deprecated or yanked, if it is. The
+    /// registries that don't flag their versions never report them as deprecated.
+    async fn deprecation(&self, _dependency: &str, _version: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Retrieves the total number of downloads of the dependency. The registries that don't
+    /// publish it return none.
+    async fn downloads(&self, _dependency: &str) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Retrieves the number of downloads of the dependency in the last week. The registries that
+    /// don't publish it return none.
+    async fn weekly_downloads(&self, _dependency: &str) -> Result<Option<u64>> {
+        Ok(None)
+    }
 }
 
 #[async_trait]
 pub trait DependencyRetriever {
     type Itr: Stream<Item = Dependency> + Unpin + Send;
-    async fn dependencies(&self) -> Result<Self::Itr>;
+    /// Reads the dependencies, skipping the ones rejected by the filter before retrieving their
+    /// package info. The dependencies of the lock files that don't record their kind are checked
+    /// as production ones.
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr>;
 }
 
 #[async_trait]
 pub trait ResultReporter {
     async fn report_results<T>(&mut self, result: T) -> Result<()>
     where
-        T: IntoIterator<Item = Evaluation> + Send;
+        T: IntoIterator<Item = Evaluation> + Send,
+        T::IntoIter: Send;
 }
 
 #[derive(Clone, PartialEq, Debug, Eq, Hash)]
@@ -39,15 +78,136 @@ pub enum Repository {
     Unknown,
     GitHub { organization: String, name: String },
     GitLab { organization: String, name: String },
+    Bitbucket { workspace: String, name: String },
     Raw { address: String },
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackageInfo {
+    pub latest_version: Option<String>,
+    pub repository: Repository,
+    /// Directory of the package in its repository, when the registry tells it, e.g. for the
+    /// packages of a monorepo.
+    pub repository_directory: Option<String>,
+}
+
+/// The error returned by an `InfoRetriever` when the registry doesn't have the package, so that it
+/// is not requested again for the rest of the scan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackageNotFound {
+    pub package_name: String,
+}
+
+impl Display for PackageNotFound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "package {} not found in the registry", self.package_name)
+    }
+}
+
+impl std::error::Error for PackageNotFound {}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Dependency {
     pub name: String,
     pub version: String,
     pub latest_version: Option<String>,
     pub repository: Repository,
+    /// The error found while retrieving the package info from its registry, if any.
+    pub retrieval_error: Option<String>,
+    /// The ecosystem of the lock file the dependency was read from, which tells apart
+    /// dependencies with the same name in different registries.
+    pub ecosystem: Option<&'static str>,
+    /// The number of packages the dependency pulls in, directly or transitively, when the lock
+    /// file records which packages each one requires.
+    pub transitive_dependencies: Option<usize>,
+    /// Whether the dependency is needed in production, as far as the lock file tells.
+    pub kind: DependencyKind,
+    /// Directory of the package in its repository, which scopes the commits the contributor
+    /// policies check to the ones touching it.
+    pub repository_directory: Option<String>,
+    /// Path of the lock file the dependency was read from, the first one when several lock files
+    /// list it.
+    pub lock_file: Option<String>,
+}
+
+/// Kind of dependency recorded by the lock file. The dependencies of the lock files that don't
+/// record it are production ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+    #[default]
+    Prod,
+    /// Only needed to develop the project, like `devDependencies`.
+    Dev,
+    /// Installed in production unless it fails to, like `optionalDependencies`.
+    Optional,
+}
+
+/// Selects the dependencies of the lock files to scan. The readers apply it before retrieving
+/// the package info, so the registries are never queried for the dependencies it drops.
+#[derive(Clone, Debug)]
+pub struct DependencyFilter {
+    only: RegexSet,
+    exclude: RegexSet,
+    prod_only: bool,
+}
+
+impl Default for DependencyFilter {
+    fn default() -> Self {
+        Self::new(RegexSet::empty(), RegexSet::empty(), false)
+    }
+}
+
+impl DependencyFilter {
+    /// Keeps the dependencies matched by any of the `only` regexes, or all of them if the set is
+    /// empty, except the ones matched by any of the `exclude` regexes, which wins over `only`, and
+    /// the dev ones when `prod_only`.
+    pub fn new(only: RegexSet, exclude: RegexSet, prod_only: bool) -> Self {
+        Self {
+            only,
+            exclude,
+            prod_only,
+        }
+    }
+
+    pub fn accepts(&self, name: &str, kind: DependencyKind) -> bool {
+        if self.prod_only && kind == DependencyKind::Dev {
+            debug!("skipping dev dependency {} with --prod-only", name);
+            return false;
+        }
+        if self.exclude.is_match(name) {
+            debug!("skipping dependency {} matched by --exclude", name);
+            return false;
+        }
+        if !self.only.is_empty() && !self.only.is_match(name) {
+            debug!("skipping dependency {} not matched by --only", name);
+            return false;
+        }
+        true
+    }
+}
+
+impl Dependency {
+    /// Builds the dependency from the result of retrieving its package info, keeping the error
+    /// so that a failed lookup can be told apart from an up to date dependency.
+    pub fn new(name: String, version: String, info: Result<PackageInfo>) -> Self {
+        let (info, retrieval_error) = match info {
+            Ok(info) => (info, None),
+            Err(e) => (PackageInfo::default(), Some(e.to_string())),
+        };
+
+        Self {
+            name,
+            version,
+            latest_version: info.latest_version,
+            repository: info.repository,
+            retrieval_error,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: info.repository_directory,
+            lock_file: None,
+        }
+    }
 }
 
 impl Default for Repository {
@@ -56,27 +216,98 @@ impl Default for Repository {
     }
 }
 
+pub const DEFAULT_GITHUB_HOST: &str = "github.com";
+pub const DEFAULT_GITLAB_HOST: &str = "gitlab.com";
+
+lazy_static! {
+    pub static ref HOSTS: Hosts = Hosts::from_env();
+}
+
+/// Hosts of the GitHub and GitLab instances whose repositories are recognized, which point to the
+/// public instances unless overridden with the `GITHUB_HOST` and `GITLAB_HOST` environment
+/// variables for self-hosted ones.
+pub struct Hosts {
+    github: String,
+    gitlab: String,
+    github_regex: Regex,
+    gitlab_regex: Regex,
+}
+
+impl Hosts {
+    pub fn new(github: &str, gitlab: &str) -> Self {
+        Self {
+            github: github.to_string(),
+            gitlab: gitlab.to_string(),
+            github_regex: Self::repository_regex(github),
+            gitlab_regex: Self::namespaced_repository_regex(gitlab),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let github = std::env::var("GITHUB_HOST").unwrap_or_else(|_| DEFAULT_GITHUB_HOST.into());
+        let gitlab = std::env::var("GITLAB_HOST").unwrap_or_else(|_| DEFAULT_GITLAB_HOST.into());
+        Self::new(&github, &gitlab)
+    }
+
+    pub fn github(&self) -> &str {
+        &self.github
+    }
+
+    pub fn gitlab(&self) -> &str {
+        &self.gitlab
+    }
+
+    fn repository_regex(host: &str) -> Regex {
+        Regex::new(&format!(
+            ".*?{}[:/](?P<organization>.*?)/(?P<name>.*?)(?:$|\\.git|/)",
+            regex::escape(host)
+        ))
+        .unwrap()
+    }
+
+    /// Like the `repository_regex`, but the organization is the whole namespace of the repository,
+    /// as in `group/subgroup` for the subgroups of GitLab. The paths of the pages of a repository
+    /// follow a `/-/`, as in `group/repo/-/tree/main`.
+    fn namespaced_repository_regex(host: &str) -> Regex {
+        Regex::new(&format!(
+            ".*?{}[:/](?P<organization>[^?#]+?)/(?P<name>[^/?#]+?)(?:\\.git)?(?:/-/[^?#]*|/)?(?:[?#].*)?$",
+            regex::escape(host)
+        ))
+        .unwrap()
+    }
+}
+
 impl Repository {
     pub fn url(&self) -> Option<String> {
+        self.url_in(&HOSTS)
+    }
+
+    pub fn url_in(&self, hosts: &Hosts) -> Option<String> {
         match self {
             Repository::GitHub { name, organization } => {
-                Some(format!("https://github.com/{organization}/{name}"))
+                Some(format!("https://{}/{organization}/{name}", hosts.github()))
             }
             Repository::GitLab { name, organization } => {
-                Some(format!("https://gitlab.com/{organization}/{name}"))
+                Some(format!("https://{}/{organization}/{name}", hosts.gitlab()))
+            }
+            Repository::Bitbucket { workspace, name } => {
+                Some(format!("https://bitbucket.org/{workspace}/{name}"))
+            }
+            Repository::Raw { address } => {
+                Some(address.strip_prefix("git+").unwrap_or(address).to_string())
             }
-            Repository::Raw { address } => Some(address.clone()),
             Repository::Unknown => None,
         }
     }
 
     pub fn parse_url(repository: &str) -> Self {
+        Self::parse_url_in(repository, &HOSTS)
+    }
+
+    pub fn parse_url_in(repository: &str, hosts: &Hosts) -> Self {
         lazy_static! {
-            static ref GITHUB_REGISTRY_REGEX: Regex =
-                Regex::new(".*?github.com[:/](?P<organization>.*?)/(?P<name>.*?)(?:$|\\.git|/)")
-                    .unwrap();
-            static ref GITLAB_REGISTRY_REGEX: Regex =
-                Regex::new(".*?gitlab.com[:/](?P<organization>.*?)/(?P<name>.*?)(?:$|\\.git|/)")
+            static ref BITBUCKET_REGISTRY_REGEX: Regex =
+                Regex::new(".*?bitbucket.org[:/](?P<workspace>.*?)/(?P<name>.*?)(?:$|\\.git|/)")
                     .unwrap();
         }
 
@@ -84,24 +315,27 @@ impl Repository {
             return Repository::Unknown;
         }
 
-        if GITHUB_REGISTRY_REGEX.is_match(repository) {
-            let captures = GITHUB_REGISTRY_REGEX.captures(repository).unwrap();
-
+        if let Some(captures) = hosts.github_regex.captures(repository) {
             return Repository::GitHub {
                 organization: captures["organization"].to_string(),
                 name: captures["name"].to_string(),
             };
         }
 
-        if GITLAB_REGISTRY_REGEX.is_match(repository) {
-            let captures = GITLAB_REGISTRY_REGEX.captures(repository).unwrap();
-
+        if let Some(captures) = hosts.gitlab_regex.captures(repository) {
             return Repository::GitLab {
                 organization: captures["organization"].to_string(),
                 name: captures["name"].to_string(),
             };
         }
 
+        if let Some(captures) = BITBUCKET_REGISTRY_REGEX.captures(repository) {
+            return Repository::Bitbucket {
+                workspace: captures["workspace"].to_string(),
+                name: captures["name"].to_string(),
+            };
+        }
+
         Repository::Raw {
             address: repository.to_string(),
         }
@@ -116,3 +350,168 @@ impl Display for Repository {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_excludes_the_dependencies_even_if_they_are_matched_by_only() {
+        let filter = DependencyFilter::new(
+            RegexSet::new(["^serde", "^internal-"]).unwrap(),
+            RegexSet::new(["_json$", "^internal-"]).unwrap(),
+            false,
+        );
+
+        let accepted = ["serde", "serde_json", "regex", "internal-client"]
+            .into_iter()
+            .filter(|name| filter.accepts(name, DependencyKind::Prod))
+            .collect::<Vec<_>>();
+
+        assert_eq!(accepted, vec!["serde"]);
+    }
+
+    #[test]
+    fn it_skips_the_dev_dependencies_when_scanning_the_prod_ones_only() {
+        let filter = DependencyFilter::new(RegexSet::empty(), RegexSet::empty(), true);
+
+        let accepted = [
+            ("react", DependencyKind::Prod),
+            ("jest", DependencyKind::Dev),
+            ("fsevents", DependencyKind::Optional),
+        ]
+        .into_iter()
+        .filter(|(name, kind)| filter.accepts(name, *kind))
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+
+        assert_eq!(accepted, vec!["react", "fsevents"]);
+    }
+
+    #[test]
+    fn it_parses_the_repositories_of_the_public_instances_by_default() {
+        let hosts = Hosts::new(DEFAULT_GITHUB_HOST, DEFAULT_GITLAB_HOST);
+
+        assert_eq!(
+            Repository::parse_url_in("git+https://github.com/some_org/some_repo.git", &hosts),
+            Repository::GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            }
+        );
+        assert_eq!(
+            Repository::parse_url_in("https://gitlab.com/some_org/some_repo", &hosts),
+            Repository::GitLab {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_the_whole_namespace_of_the_gitlab_repositories_of_subgroups() {
+        let expected = Repository::GitLab {
+            organization: "some_group/some_subgroup".to_string(),
+            name: "some_repo".to_string(),
+        };
+
+        assert_eq!(
+            Repository::parse_url("git+https://gitlab.com/some_group/some_subgroup/some_repo.git"),
+            expected
+        );
+        assert_eq!(
+            Repository::parse_url(
+                "https://gitlab.com/some_group/some_subgroup/some_repo/-/tree/main"
+            ),
+            expected
+        );
+        assert_eq!(
+            expected.url(),
+            Some("https://gitlab.com/some_group/some_subgroup/some_repo".to_string())
+        );
+    }
+
+    #[test]
+    fn it_parses_the_repositories_of_a_self_hosted_instance() {
+        let hosts = Hosts::new("github.mycorp.com", "gitlab.mycorp.com");
+
+        assert_eq!(
+            Repository::parse_url_in("git@github.mycorp.com:some_org/some_repo.git", &hosts),
+            Repository::GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            }
+        );
+        assert_eq!(
+            Repository::parse_url_in("https://gitlab.mycorp.com/some_org/some_repo", &hosts),
+            Repository::GitLab {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            }
+        );
+        assert_eq!(
+            Repository::parse_url_in("https://githubXmycorp.com/some_org/some_repo", &hosts),
+            Repository::Raw {
+                address: "https://githubXmycorp.com/some_org/some_repo".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_the_repositories_of_bitbucket() {
+        let expected = Repository::Bitbucket {
+            workspace: "foo".to_string(),
+            name: "bar".to_string(),
+        };
+
+        assert_eq!(
+            Repository::parse_url("https://bitbucket.org/foo/bar.git"),
+            expected
+        );
+        assert_eq!(
+            Repository::parse_url("git@bitbucket.org:foo/bar.git"),
+            expected
+        );
+        assert_eq!(
+            expected.url(),
+            Some("https://bitbucket.org/foo/bar".to_string())
+        );
+    }
+
+    #[test]
+    fn it_keeps_the_address_of_the_repositories_of_other_hosts_to_clone_them() {
+        let repository =
+            Repository::parse_url("git+https://dev.azure.com/some_org/some_project/_git/some_repo");
+
+        assert_eq!(
+            repository,
+            Repository::Raw {
+                address: "git+https://dev.azure.com/some_org/some_project/_git/some_repo"
+                    .to_string(),
+            }
+        );
+        assert_eq!(
+            repository.url(),
+            Some("https://dev.azure.com/some_org/some_project/_git/some_repo".to_string())
+        );
+        assert_eq!(
+            Repository::parse_url("ssh://git@git.mycorp.com/some_repo.git").url(),
+            Some("ssh://git@git.mycorp.com/some_repo.git".to_string())
+        );
+    }
+
+    #[test]
+    fn it_builds_the_url_of_the_repository_in_a_self_hosted_instance() {
+        let hosts = Hosts::new("github.mycorp.com", "gitlab.mycorp.com");
+
+        let repository = Repository::GitHub {
+            organization: "some_org".to_string(),
+            name: "some_repo".to_string(),
+        };
+
+        assert_eq!(
+            repository.url_in(&hosts),
+            Some("https://github.mycorp.com/some_org/some_repo".to_string())
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::Stream;
+use itertools::Itertools;
+use log::warn;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+
+use crate::pkg::{DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever};
+use crate::{Dependency, Result};
+
+const GEM_SECTION: &str = "GEM";
+const SPECS_LINE: &str = "  specs:";
+const SPEC_INDENTATION: &str = "    ";
+
+pub struct DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin,
+{
+    rubygems_info_retriever: Arc<dyn InfoRetriever>,
+    reader: Mutex<T>,
+}
+
+impl<T> DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin,
+{
+    pub fn new<R>(reader: T, retriever: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            reader: reader.into(),
+            rubygems_info_retriever: retriever.into(),
+        }
+    }
+
+    /// Returns the specs of the `GEM` section. Only the ones with four spaces of indentation are
+    /// the pinned gems, the more indented ones are their dependencies.
+    fn specs_from(content: &str) -> Vec<&str> {
+        let mut specs = vec![];
+        let mut is_inside_gem_section = false;
+        let mut is_inside_specs = false;
+
+        for line in content.lines() {
+            if !line.starts_with(' ') {
+                is_inside_gem_section = line.trim_end() == GEM_SECTION;
+                is_inside_specs = false;
+            } else if is_inside_gem_section && line.trim_end() == SPECS_LINE {
+                is_inside_specs = true;
+            } else if is_inside_specs {
+                if let Some(spec) = line.strip_prefix(SPEC_INDENTATION) {
+                    if !spec.starts_with(' ') {
+                        specs.push(spec.trim_end());
+                    }
+                }
+            }
+        }
+
+        specs
+    }
+
+    /// Extracts the name and the version from a spec like `nokogiri (1.13.10-x86_64-linux)`,
+    /// without the platform suffix.
+    fn name_and_version_from_spec(spec: &str) -> Option<(String, String)> {
+        let (name, version) = spec.split_once(' ')?;
+        let version = version.strip_prefix('(')?.strip_suffix(')')?;
+        let version = version
+            .split_once('-')
+            .map_or(version, |(version, _)| version);
+        if name.is_empty() || version.is_empty() {
+            return None;
+        }
+
+        Some((name.to_string(), version.to_string()))
+    }
+}
+
+#[async_trait]
+impl<T> DependencyRetriever for DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
+
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
+        let content = self.content_from_reader().await?;
+
+        let dependency_info_tuples = Self::specs_from(&content)
+            .into_iter()
+            .filter_map(|spec| {
+                let name_and_version = Self::name_and_version_from_spec(spec);
+                if name_and_version.is_none() {
+                    warn!("skipping invalid gem spec: {}", spec);
+                }
+                name_and_version
+            })
+            .unique()
+            .collect_vec();
+
+        let futures = dependency_info_tuples
+            .into_iter()
+            .filter(|(name, _)| filter.accepts(name, DependencyKind::Prod))
+            .map(|(name, version)| {
+                let retriever = self.rubygems_info_retriever.clone();
+
+                tokio::spawn(async move {
+                    let info = retriever.info(&name).await;
+
+                    Dependency::new(name, version, info)
+                })
+            })
+            .collect_vec();
+
+        let unfold =
+            futures::stream::unfold(futures, |mut name_and_versions_to_retrieve| async move {
+                let next = name_and_versions_to_retrieve.pop();
+                let dependency = next?.await.ok()?;
+                Some((dependency, name_and_versions_to_retrieve))
+            });
+        Ok(Box::new(Box::pin(unfold)))
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: Unpin + tokio::io::AsyncRead + Send,
+{
+    async fn content_from_reader(&self) -> Result<String> {
+        let mut bytes = Vec::new();
+        self.reader.lock().await.read_to_end(&mut bytes).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
+
+    #[tokio::test]
+    async fn it_retrieves_only_the_pinned_gems() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever.expect_info().with(eq("rack")).return_once(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("3.0.4".into()),
+                    repository: Repository::GitHub {
+                        organization: "rack".into(),
+                        name: "rack".into(),
+                    },
+                    repository_directory: None,
+                })
+            });
+            retriever.expect_info().returning(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("1.0.0".into()),
+                    repository: Repository::Unknown,
+                    repository_directory: None,
+                })
+            });
+            retriever
+        };
+
+        let dependency_reader = DependencyReader::new(gemfile_lock_file(), retriever);
+        let deps = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(deps.len(), 4);
+        assert_eq!(
+            deps.iter().find(|dep| dep.name == "rack").unwrap(),
+            &Dependency {
+                name: "rack".to_string(),
+                version: "2.2.6".to_string(),
+                latest_version: Some("3.0.4".to_string()),
+                repository: Repository::GitHub {
+                    organization: "rack".to_string(),
+                    name: "rack".to_string(),
+                },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+        assert_eq!(
+            deps.iter()
+                .find(|dep| dep.name == "nokogiri")
+                .unwrap()
+                .version,
+            "1.13.10"
+        );
+        assert!(deps.iter().any(|dep| dep.name == "racc"));
+        assert!(deps.iter().any(|dep| dep.name == "rack-test"));
+        assert!(!deps.iter().any(|dep| dep.name == "ruby"));
+    }
+
+    fn gemfile_lock_file() -> &'static [u8] {
+        "\
+GEM
+  remote: https://rubygems.org/
+  specs:
+    nokogiri (1.13.10-x86_64-linux)
+      racc (~> 1.4)
+    nokogiri (1.13.10-arm64-darwin)
+      racc (~> 1.4)
+    racc (1.6.2)
+    rack (2.2.6)
+    rack-test (2.0.2)
+      rack (>= 1.3)
+
+PLATFORMS
+  ruby
+  x86_64-linux
+
+DEPENDENCIES
+  nokogiri
+  rack-test
+
+BUNDLED WITH
+   2.4.3
+"
+        .as_bytes()
+    }
+}
@@ -3,15 +3,23 @@ use std::sync::Arc;
 use anyhow::Context;
 use async_trait::async_trait;
 use itertools::Itertools;
-use log::error;
+use log::{debug, error, info, warn};
 use tokio::io::AsyncReadExt;
 use tokio::sync::Mutex;
 use tokio_stream::Stream;
 use toml::Value;
 
-use crate::pkg::{Dependency, DependencyRetriever, InfoRetriever, Repository};
+use crate::pkg::{
+    Dependency, DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever,
+};
 use crate::Result;
 
+/// Sources of the packages of crates.io, through its git index and its sparse index.
+const CRATES_IO_SOURCES: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
 pub struct DependencyReader<T>
 where
     T: tokio::io::AsyncRead + Unpin + Send,
@@ -26,18 +34,31 @@ where
     T: tokio::io::AsyncRead + Unpin + Send,
 {
     type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
-    async fn dependencies(&self) -> Result<Self::Itr> {
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
         let contents = self.contents_from_reader().await?;
         let result: Value = toml::from_slice(&contents)?;
 
-        let packages = result.get("package").context("no package section found")?;
+        match Self::lock_file_version(&result) {
+            version @ 1..=4 => debug!("reading a Cargo.lock of version {}", version),
+            version => warn!(
+                "the Cargo.lock version {} is not known, reading its packages as the latest one",
+                version
+            ),
+        }
 
-        let package_list = packages
-            .as_array()
-            .context("packages section is not an array")?;
+        // A lock file of a crate without dependencies has no packages, like the version 1 ones
+        // that only have the `[root]` section.
+        let no_packages = Vec::new();
+        let package_list = match result.get("package") {
+            Some(packages) => packages
+                .as_array()
+                .context("packages section is not an array")?,
+            None => &no_packages,
+        };
 
         let name_and_version_from_packages = package_list
             .iter()
+            .filter(|package| Self::is_from_crates_io(package))
             .map(|package| {
                 let name = package
                     .get("name")
@@ -61,21 +82,13 @@ where
             });
 
         let futures = name_and_version_from_packages
+            .filter(|(name, _)| filter.accepts(name, DependencyKind::Prod))
             .map(|(name, version)| {
                 let retriever = self.cargo_info_retriever.clone();
                 tokio::spawn(async move {
-                    let (latest_version, repository) = futures::future::join(
-                        retriever.latest_version(&name),
-                        retriever.repository(&name),
-                    )
-                    .await;
-
-                    Dependency {
-                        name: name.clone(),
-                        version: version.clone(),
-                        latest_version: latest_version.ok(),
-                        repository: repository.unwrap_or(Repository::Unknown),
-                    }
+                    let info = retriever.info(&name).await;
+
+                    Dependency::new(name, version, info)
                 })
             })
             .collect_vec();
@@ -94,6 +107,53 @@ impl<T> DependencyReader<T>
 where
     T: Unpin + tokio::io::AsyncRead + Send,
 {
+    /// The `version` header was added in the version 3 of the format. Before it, the version 1
+    /// kept the root package in `[root]` and the checksums of the packages in `[metadata]`, which
+    /// the version 2 moved to each package.
+    fn lock_file_version(lock_file: &Value) -> i64 {
+        if let Some(version) = lock_file.get("version").and_then(Value::as_integer) {
+            return version;
+        }
+
+        let has_metadata_checksums = lock_file
+            .get("metadata")
+            .and_then(Value::as_table)
+            .is_some_and(|metadata| metadata.keys().any(|key| key.starts_with("checksum ")));
+        if has_metadata_checksums || lock_file.get("root").is_some() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Workspace members and path dependencies have no `source`, and git dependencies are not
+    /// published in a registry, so only the packages coming from crates.io are analyzed. The
+    /// packages of alternate registries are skipped too, as their info is not in crates.io.
+    fn is_from_crates_io(package: &Value) -> bool {
+        let name = package
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+
+        match package.get("source").and_then(Value::as_str) {
+            Some(source) if CRATES_IO_SOURCES.contains(&source) => true,
+            Some(source) if source.starts_with("registry+") || source.starts_with("sparse+") => {
+                info!(
+                    "skipping package {} as it comes from the alternate registry {}, which is not supported",
+                    name, source
+                );
+                false
+            }
+            _ => {
+                debug!(
+                    "skipping package {} as it does not come from a registry",
+                    name
+                );
+                false
+            }
+        }
+    }
+
     async fn contents_from_reader(&self) -> Result<Vec<u8>> {
         let mut contents = Vec::new();
         self.reader
@@ -127,7 +187,7 @@ mod tests {
     use tokio_stream::StreamExt;
 
     use super::*;
-    use crate::pkg::{MockInfoRetriever, Repository};
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
     use crate::Dependency;
 
     #[tokio::test]
@@ -135,17 +195,16 @@ mod tests {
         let retriever = {
             let mut retriever = Box::new(MockInfoRetriever::new());
             retriever
-                .expect_latest_version()
-                .with(eq("serde"))
-                .return_once(|_| Ok("1.0.138".into()))
-                .times(1);
-            retriever
-                .expect_repository()
+                .expect_info()
                 .with(eq("serde"))
                 .return_once(|_| {
-                    Ok(Repository::GitHub {
-                        organization: "serde-rs".into(),
-                        name: "serde".into(),
+                    Ok(PackageInfo {
+                        latest_version: Some("1.0.138".into()),
+                        repository: Repository::GitHub {
+                            organization: "serde-rs".into(),
+                            name: "serde".into(),
+                        },
+                        repository_directory: None,
                     })
                 })
                 .times(1);
@@ -153,7 +212,10 @@ mod tests {
         };
 
         let dependency_reader = DependencyReader::new(cargo_lock_file_contents(), retriever);
-        let mut dependencies = dependency_reader.dependencies().await.unwrap();
+        let mut dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap();
 
         assert_eq!(
             dependencies.next().await.unwrap(),
@@ -165,10 +227,181 @@ mod tests {
                     organization: "serde-rs".into(),
                     name: "serde".into(),
                 },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_skips_the_packages_that_do_not_come_from_crates_io() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .with(eq("serde"))
+                .return_once(|_| Ok(PackageInfo::default()))
+                .times(1);
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let dependency_reader =
+            DependencyReader::new(cargo_lock_file_with_local_packages(), retriever);
+        let dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            dependencies,
+            vec![Dependency {
+                name: "serde".into(),
+                version: "1.0.137".into(),
+                ..Dependency::default()
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_keeps_the_error_when_the_package_info_cannot_be_retrieved() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .with(eq("serde"))
+                .return_once(|_| Err(anyhow::anyhow!("unable to request crates.io")));
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let dependency_reader = DependencyReader::new(cargo_lock_file_contents(), retriever);
+        let mut dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            dependencies.next().await.unwrap(),
+            Dependency {
+                name: "serde".into(),
+                version: "1.0.137".into(),
+                latest_version: None,
+                repository: Repository::Unknown,
+                retrieval_error: Some("unable to request crates.io".into()),
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
             }
         );
     }
 
+    #[tokio::test]
+    async fn it_retrieves_the_dependencies_of_a_version_1_lock_file() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .returning(|_| Ok(PackageInfo::default()))
+                .times(4);
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let dependency_reader = DependencyReader::new(cargo_v1_lock_file_contents(), retriever);
+        let mut dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .map(|dependency| format!("{}@{}", dependency.name, dependency.version))
+            .collect::<Vec<_>>()
+            .await;
+        dependencies.sort();
+
+        assert_eq!(
+            dependencies,
+            vec!["cfg-if@0.1.2", "log@0.3.9", "log@0.4.1", "serde@1.0.27"]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_no_dependencies_from_a_lock_file_without_packages() {
+        let dependency_reader = DependencyReader::new(
+            "[root]\nname = \"legacy_app\"\nversion = \"0.1.0\"\n".as_bytes(),
+            Box::new(MockInfoRetriever::new()) as Box<dyn InfoRetriever>,
+        );
+
+        let dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(dependencies.is_empty());
+    }
+
+    #[test]
+    fn it_detects_the_version_of_the_lock_file() {
+        let version_of = |contents: &[u8]| {
+            DependencyReader::<&[u8]>::lock_file_version(&toml::from_slice(contents).unwrap())
+        };
+
+        assert_eq!(version_of(cargo_lock_file_contents()), 3);
+        assert_eq!(version_of(cargo_v1_lock_file_contents()), 1);
+        assert_eq!(
+            version_of(b"[[package]]\nname = \"serde\"\nversion = \"1.0.137\"\n"),
+            2
+        );
+    }
+
+    fn cargo_v1_lock_file_contents() -> &'static [u8] {
+        include_bytes!("../../../tests/fixtures/Cargo.v1.lock")
+    }
+
+    fn cargo_lock_file_with_local_packages() -> &'static [u8] {
+        "\
+version = 3
+
+[[package]]
+name = \"dean\"
+version = \"0.0.1\"
+dependencies = [
+ \"serde\",
+]
+
+[[package]]
+name = \"local_utils\"
+version = \"0.1.0\"
+
+[[package]]
+name = \"forked_crate\"
+version = \"0.2.0\"
+source = \"git+https://github.com/some_org/forked_crate#0123456789abcdef\"
+
+[[package]]
+name = \"private_crate\"
+version = \"0.3.0\"
+source = \"registry+https://my-registry.example.com/git/index\"
+
+[[package]]
+name = \"sparse_private_crate\"
+version = \"0.4.0\"
+source = \"sparse+https://my-registry.example.com/index/\"
+
+[[package]]
+name = \"serde\"
+version = \"1.0.137\"
+source = \"registry+https://github.com/rust-lang/crates.io-index\"
+"
+        .as_bytes()
+    }
+
     fn cargo_lock_file_contents() -> &'static [u8] {
         r#"# This file is automatically @generated by Cargo.
 # It is not intended for manual editing.
@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::info;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+use tokio_stream::Stream;
+use toml::Value;
+
+use crate::pkg::{
+    Dependency, DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever,
+};
+use crate::Result;
+
+/// Sections of the manifest declaring the dependencies, with the kind of their dependencies.
+const DEPENDENCY_SECTIONS: [(&str, DependencyKind); 3] = [
+    ("dependencies", DependencyKind::Prod),
+    ("dev-dependencies", DependencyKind::Dev),
+    ("build-dependencies", DependencyKind::Prod),
+];
+
+/// Reads the direct dependencies declared in a `Cargo.toml`, instead of the resolved tree of a
+/// `Cargo.lock`. Their version is the requirement of the manifest, e.g. `1.0` or `>=0.4, <0.6`,
+/// so the policies that need an exact version, like the versions behind one, pass the ones that
+/// are not. An exact requirement, like `=1.2.3`, is read as its version.
+pub struct DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    cargo_info_retriever: Arc<dyn InfoRetriever>,
+    reader: Mutex<T>,
+}
+
+#[async_trait]
+impl<T> DependencyRetriever for DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
+        let contents = self.contents_from_reader().await?;
+        let manifest: Value = toml::from_slice(&contents)?;
+
+        let mut declared_dependencies = Vec::new();
+        for (section, kind) in DEPENDENCY_SECTIONS {
+            let Some(dependencies) = manifest.get(section) else {
+                continue;
+            };
+            let dependencies = dependencies
+                .as_table()
+                .with_context(|| format!("the {section} section is not a table"))?;
+            declared_dependencies.extend(
+                dependencies
+                    .iter()
+                    .filter_map(|(key, declaration)| Self::name_and_requirement(key, declaration))
+                    .map(|(name, requirement)| (name, requirement, kind)),
+            );
+        }
+
+        let futures = declared_dependencies
+            .into_iter()
+            .filter(|(name, _, kind)| filter.accepts(name, *kind))
+            .map(|(name, requirement, kind)| {
+                let retriever = self.cargo_info_retriever.clone();
+                tokio::spawn(async move {
+                    let info = retriever.info(&name).await;
+
+                    let mut dependency = Dependency::new(name, requirement, info);
+                    dependency.kind = kind;
+                    dependency
+                })
+            })
+            .collect_vec();
+
+        let unfold =
+            futures::stream::unfold(futures, |mut name_and_versions_to_retrieve| async move {
+                let next = name_and_versions_to_retrieve.pop();
+                let dependency = next?.await.ok()?;
+                Some((dependency, name_and_versions_to_retrieve))
+            });
+        Ok(Box::new(Box::pin(unfold)))
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: Unpin + tokio::io::AsyncRead + Send,
+{
+    /// Returns the name of the crate and its version requirement, either the string of the
+    /// declaration or its `version` key, using the `package` key of the renamed dependencies. The
+    /// path, git and alternate registry dependencies are skipped, as they are not published in
+    /// crates.io, and so are the ones inheriting their requirement from the workspace.
+    fn name_and_requirement(key: &str, declaration: &Value) -> Option<(String, String)> {
+        let (name, requirement) = match declaration {
+            Value::String(requirement) => (key, requirement.as_str()),
+            Value::Table(table) => {
+                let name = table.get("package").and_then(Value::as_str).unwrap_or(key);
+                if let Some(source) = ["path", "git", "registry", "workspace"]
+                    .into_iter()
+                    .find(|source| table.contains_key(*source))
+                {
+                    info!(
+                        "skipping dependency {} as it's declared with {}, which is not supported",
+                        name, source
+                    );
+                    return None;
+                }
+                let Some(requirement) = table.get("version").and_then(Value::as_str) else {
+                    info!("skipping dependency {} as it has no version", name);
+                    return None;
+                };
+                (name, requirement)
+            }
+            _ => {
+                info!(
+                    "skipping dependency {} as its declaration is not valid",
+                    key
+                );
+                return None;
+            }
+        };
+
+        let requirement = requirement.trim();
+        let requirement = requirement
+            .strip_prefix('=')
+            .filter(|version| semver::Version::parse(version.trim()).is_ok())
+            .map_or(requirement, str::trim);
+        Some((name.to_string(), requirement.to_string()))
+    }
+
+    async fn contents_from_reader(&self) -> Result<Vec<u8>> {
+        let mut contents = Vec::new();
+        self.reader
+            .lock()
+            .await
+            .read_to_end(&mut contents)
+            .await
+            .context("error reading from reader")?;
+        Ok(contents)
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    pub fn new<R>(reader: T, retriever: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            reader: reader.into(),
+            cargo_info_retriever: retriever.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::pkg::{MockInfoRetriever, PackageInfo, Repository};
+
+    #[tokio::test]
+    async fn it_retrieves_the_declared_dependencies_with_their_requirements() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever.expect_info().with(eq("serde")).return_once(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("1.0.138".into()),
+                    repository: Repository::GitHub {
+                        organization: "serde-rs".into(),
+                        name: "serde".into(),
+                    },
+                    repository_directory: None,
+                })
+            });
+            retriever
+                .expect_info()
+                .returning(|_| Ok(PackageInfo::default()));
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let dependency_reader = DependencyReader::new(cargo_manifest_contents(), retriever);
+        let mut dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        dependencies.sort_by_key(|dependency| dependency.name.clone());
+
+        assert_eq!(
+            dependencies
+                .iter()
+                .map(|dependency| (
+                    dependency.name.as_str(),
+                    dependency.version.as_str(),
+                    dependency.kind
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("cc", "1.0.73", DependencyKind::Prod),
+                ("mockall", "0.11", DependencyKind::Dev),
+                ("serde", "1.0", DependencyKind::Prod),
+                ("tokio", ">=1.21, <2", DependencyKind::Prod),
+                ("toml", "0.5.9", DependencyKind::Prod),
+            ]
+        );
+        assert_eq!(dependencies[2].latest_version, Some("1.0.138".to_string()));
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_no_dependencies_from_a_manifest_without_them() {
+        let dependency_reader = DependencyReader::new(
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n".as_bytes(),
+            Box::new(MockInfoRetriever::new()) as Box<dyn InfoRetriever>,
+        );
+
+        let dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(dependencies.is_empty());
+    }
+
+    fn cargo_manifest_contents() -> &'static [u8] {
+        r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+tokio = ">=1.21, <2"
+toml_parser = { package = "toml", version = "=0.5.9" }
+local_utils = { path = "../local_utils" }
+forked_crate = { git = "https://github.com/some_org/forked_crate" }
+private_crate = { version = "0.3", registry = "my-registry" }
+shared = { workspace = true }
+
+[dev-dependencies]
+mockall = "0.11"
+
+[build-dependencies]
+cc = "1.0.73"
+"#
+        .as_bytes()
+    }
+}
@@ -0,0 +1,277 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use futures::Stream;
+use itertools::Itertools;
+use log::error;
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::Mutex;
+
+use crate::pkg::{
+    Dependency, DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever, PackageInfo,
+    Repository,
+};
+use crate::Result;
+
+/// Sections of the lock file listing the packages, with the kind of their packages.
+const PACKAGE_SECTIONS: [(&str, DependencyKind); 2] = [
+    ("packages", DependencyKind::Prod),
+    ("packages-dev", DependencyKind::Dev),
+];
+
+pub struct DependencyReader<T>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    packagist_info_retriever: Arc<dyn InfoRetriever>,
+    reader: Mutex<T>,
+}
+
+#[async_trait]
+impl<T> DependencyRetriever for DependencyReader<T>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
+        let content = {
+            let mut content = String::new();
+            self.reader
+                .lock()
+                .await
+                .read_to_string(&mut content)
+                .await
+                .context("unable to read contents from reader")?;
+            content
+        };
+        let result: Value =
+            serde_json::from_str(&content).context("unable to retrieve json from string")?;
+
+        if !result["packages"].is_array() {
+            return Err(anyhow!("packages not found in lock file"));
+        }
+
+        let packages = PACKAGE_SECTIONS
+            .iter()
+            .filter_map(|(section, kind)| Some((result[section].as_array()?, *kind)))
+            .flat_map(|(packages, kind)| packages.iter().map(move |package| (package, kind)))
+            .filter_map(|(package, kind)| {
+                let name = package["name"].as_str()?.to_string();
+                let Some(version) = package["version"].as_str() else {
+                    error!("no version found for dependency {}", &name);
+                    return None;
+                };
+                let repository = package["source"]["url"]
+                    .as_str()
+                    .map(Repository::parse_url)
+                    .filter(|repository| *repository != Repository::Unknown);
+                let version = version.strip_prefix('v').unwrap_or(version);
+                Some((name, version.to_string(), repository, kind))
+            });
+
+        let futures = packages
+            .filter(|(name, _, _, kind)| filter.accepts(name, *kind))
+            .map(|(name, version, repository, kind)| {
+                let retriever = self.packagist_info_retriever.clone();
+
+                tokio::spawn(async move {
+                    let Some(repository) = repository else {
+                        let info = retriever.info(&name).await;
+                        let mut dependency = Dependency::new(name, version, info);
+                        dependency.kind = kind;
+                        return dependency;
+                    };
+
+                    // The repository of the lock file is used as is, so only the latest version
+                    // is requested to the registry.
+                    let latest_version = retriever.latest_version(&name).await;
+                    let mut dependency = Dependency::new(
+                        name,
+                        version,
+                        latest_version.map(|latest_version| PackageInfo {
+                            latest_version: Some(latest_version),
+                            repository: Repository::Unknown,
+                            repository_directory: None,
+                        }),
+                    );
+                    dependency.repository = repository;
+                    dependency.kind = kind;
+                    dependency
+                })
+            })
+            .collect_vec();
+
+        let unfold =
+            futures::stream::unfold(futures, |mut name_and_versions_to_retrieve| async move {
+                let next = name_and_versions_to_retrieve.pop();
+                let dependency = next?.await.ok()?;
+                Some((dependency, name_and_versions_to_retrieve))
+            });
+
+        Ok(Box::new(Box::pin(unfold)))
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    pub fn new<R>(reader: T, retriever: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            reader: reader.into(),
+            packagist_info_retriever: retriever.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever};
+
+    #[tokio::test]
+    async fn retrieves_all_dependencies_with_the_repository_of_the_lock_file() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_latest_version()
+                .with(eq("symfony/console"))
+                .return_once(|_| Ok("6.3.4".into()))
+                .times(1);
+            retriever
+                .expect_info()
+                .with(eq("phpunit/phpunit"))
+                .return_once(|_| {
+                    Ok(PackageInfo {
+                        latest_version: Some("10.3.2".into()),
+                        repository: Repository::GitHub {
+                            organization: "sebastianbergmann".into(),
+                            name: "phpunit".into(),
+                        },
+                        repository_directory: None,
+                    })
+                })
+                .times(1);
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let dependency_reader = DependencyReader::new(composer_lock(), retriever);
+        let mut dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(
+            dependencies,
+            vec![
+                Dependency {
+                    name: "phpunit/phpunit".into(),
+                    version: "10.3.1".into(),
+                    latest_version: Some("10.3.2".into()),
+                    repository: Repository::GitHub {
+                        organization: "sebastianbergmann".into(),
+                        name: "phpunit".into(),
+                    },
+                    retrieval_error: None,
+                    ecosystem: None,
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Dev,
+                    repository_directory: None,
+                    lock_file: None,
+                },
+                Dependency {
+                    name: "symfony/console".into(),
+                    version: "6.3.2".into(),
+                    latest_version: Some("6.3.4".into()),
+                    repository: Repository::GitHub {
+                        organization: "symfony".into(),
+                        name: "console".into(),
+                    },
+                    retrieval_error: None,
+                    ecosystem: None,
+                    transitive_dependencies: None,
+                    kind: DependencyKind::Prod,
+                    repository_directory: None,
+                    lock_file: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_skips_the_dev_packages_with_prod_only() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_latest_version()
+                .with(eq("symfony/console"))
+                .return_once(|_| Ok("6.3.4".into()))
+                .times(1);
+            retriever.expect_info().never();
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let filter =
+            DependencyFilter::new(regex::RegexSet::empty(), regex::RegexSet::empty(), true);
+
+        let dependency_reader = DependencyReader::new(composer_lock(), retriever);
+        let dependencies = dependency_reader
+            .dependencies(&filter)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            dependencies
+                .iter()
+                .map(|dependency| (dependency.name.as_str(), dependency.kind))
+                .collect::<Vec<_>>(),
+            [("symfony/console", DependencyKind::Prod)]
+        );
+    }
+
+    fn composer_lock() -> &'static [u8] {
+        r#"{
+    "_readme": [
+        "This file locks the dependencies of your project to a known state"
+    ],
+    "content-hash": "a3b1c7f0d1e2",
+    "packages": [
+        {
+            "name": "symfony/console",
+            "version": "v6.3.2",
+            "source": {
+                "type": "git",
+                "url": "https://github.com/symfony/console.git",
+                "reference": "aa5d64ad3f63f2e48964fc81ee45cb318a723898"
+            },
+            "type": "library"
+        }
+    ],
+    "packages-dev": [
+        {
+            "name": "phpunit/phpunit",
+            "version": "10.3.1",
+            "dist": {
+                "type": "zip",
+                "url": "https://api.github.com/repos/sebastianbergmann/phpunit/zipball/d442ce7c4104d5683c12e67e4dcb5058159e9804"
+            },
+            "type": "library"
+        }
+    ]
+}"#
+        .as_bytes()
+    }
+}
@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::Stream;
+use itertools::Itertools;
+use log::warn;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+
+use crate::pkg::{DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever};
+use crate::{Dependency, Result};
+
+pub struct DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin,
+{
+    go_info_retriever: Arc<dyn InfoRetriever>,
+    reader: Mutex<T>,
+}
+
+impl<T> DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin,
+{
+    pub fn new<R>(reader: T, retriever: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            reader: reader.into(),
+            go_info_retriever: retriever.into(),
+        }
+    }
+
+    /// Extracts the module path and the version from a requirement like
+    /// `github.com/foo/bar v1.2.3 // indirect`, without the leading `v` of the version.
+    fn module_and_version_from_requirement(requirement: &str) -> Option<(String, String)> {
+        let mut parts = requirement.split_whitespace();
+        let module = parts.next()?;
+        let version = parts.next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let version = version.strip_prefix('v').unwrap_or(version);
+        Some((module.to_string(), version.to_string()))
+    }
+
+    /// Returns the requirements declared in both `require (...)` blocks and single line
+    /// `require` directives.
+    fn requirements_from(content: &str) -> Vec<&str> {
+        let mut requirements = vec![];
+        let mut is_inside_require_block = false;
+
+        let lines = content
+            .lines()
+            .map(|line| line.split_once("//").map_or(line, |(line, _)| line).trim())
+            .filter(|line| !line.is_empty());
+
+        for line in lines {
+            if is_inside_require_block {
+                if line == ")" {
+                    is_inside_require_block = false;
+                } else {
+                    requirements.push(line);
+                }
+            } else if let Some(directive) = line.strip_prefix("require") {
+                let directive = directive.trim();
+                if directive == "(" {
+                    is_inside_require_block = true;
+                } else if !directive.is_empty() {
+                    requirements.push(directive);
+                }
+            }
+        }
+
+        requirements
+    }
+}
+
+#[async_trait]
+impl<T> DependencyRetriever for DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
+
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
+        let content = self.content_from_reader().await?;
+
+        let module_and_version_tuples = Self::requirements_from(&content)
+            .into_iter()
+            .filter_map(|requirement| {
+                let module_and_version = Self::module_and_version_from_requirement(requirement);
+                if module_and_version.is_none() {
+                    warn!("skipping invalid requirement: {}", requirement);
+                }
+                module_and_version
+            })
+            .collect_vec();
+
+        let futures = module_and_version_tuples
+            .into_iter()
+            .filter(|(name, _)| filter.accepts(name, DependencyKind::Prod))
+            .map(|(name, version)| {
+                let retriever = self.go_info_retriever.clone();
+
+                tokio::spawn(async move {
+                    let info = retriever.info(&name).await;
+
+                    Dependency::new(name, version, info)
+                })
+            })
+            .collect_vec();
+
+        let unfold =
+            futures::stream::unfold(futures, |mut name_and_versions_to_retrieve| async move {
+                let next = name_and_versions_to_retrieve.pop();
+                let dependency = next?.await.ok()?;
+                Some((dependency, name_and_versions_to_retrieve))
+            });
+        Ok(Box::new(Box::pin(unfold)))
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: Unpin + tokio::io::AsyncRead + Send,
+{
+    async fn content_from_reader(&self) -> Result<String> {
+        let mut bytes = Vec::new();
+        self.reader.lock().await.read_to_end(&mut bytes).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
+
+    #[tokio::test]
+    async fn it_retrieves_the_required_modules() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .with(eq("github.com/stretchr/testify"))
+                .return_once(|_| {
+                    Ok(PackageInfo {
+                        latest_version: Some("1.8.2".into()),
+                        repository: Repository::GitHub {
+                            organization: "stretchr".into(),
+                            name: "testify".into(),
+                        },
+                        repository_directory: None,
+                    })
+                });
+            retriever.expect_info().returning(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("1.0.0".into()),
+                    repository: Repository::Unknown,
+                    repository_directory: None,
+                })
+            });
+            retriever
+        };
+
+        let dependency_reader = DependencyReader::new(go_mod_file(), retriever);
+        let deps = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(deps.len(), 4);
+        assert_eq!(
+            deps.iter()
+                .find(|dep| dep.name == "github.com/stretchr/testify")
+                .unwrap(),
+            &Dependency {
+                name: "github.com/stretchr/testify".to_string(),
+                version: "1.8.1".to_string(),
+                latest_version: Some("1.8.2".to_string()),
+                repository: Repository::GitHub {
+                    organization: "stretchr".to_string(),
+                    name: "testify".to_string(),
+                },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+        assert_eq!(
+            deps.iter()
+                .find(|dep| dep.name == "github.com/davecgh/go-spew")
+                .unwrap()
+                .version,
+            "1.1.1"
+        );
+        assert!(deps
+            .iter()
+            .any(|dep| dep.name == "github.com/go-chi/chi/v5"));
+        assert!(deps.iter().any(|dep| dep.name == "golang.org/x/net"));
+    }
+
+    fn go_mod_file() -> &'static [u8] {
+        "\
+module github.com/some_org/some_module
+
+go 1.19
+
+require github.com/go-chi/chi/v5 v5.0.8
+
+require (
+\tgithub.com/stretchr/testify v1.8.1
+\tgolang.org/x/net v0.5.0 // the http2 client
+)
+
+require (
+\tgithub.com/davecgh/go-spew v1.1.1 // indirect
+)
+
+replace golang.org/x/net => ../net
+"
+        .as_bytes()
+    }
+}
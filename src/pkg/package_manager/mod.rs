@@ -1,3 +1,32 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+pub mod bundler;
 pub mod cargo;
+pub mod cargo_manifest;
+pub mod composer;
+pub mod go;
 pub mod npm;
+pub mod pip;
+pub mod pnpm;
+pub mod poetry;
 pub mod yarn;
+
+/// Counts the packages that the root pulls in, directly or transitively, given the packages that
+/// each package requires. A package required through several paths is counted once.
+fn count_transitive_dependencies<K>(root: &K, requires: &HashMap<K, Vec<K>>) -> usize
+where
+    K: Eq + Hash,
+{
+    let mut visited = HashSet::from([root]);
+    let mut pending = vec![root];
+    while let Some(package) = pending.pop() {
+        for required in requires.get(package).into_iter().flatten() {
+            if visited.insert(required) {
+                pending.push(required);
+            }
+        }
+    }
+
+    visited.len() - 1
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context};
@@ -5,11 +6,14 @@ use async_trait::async_trait;
 use futures::Stream;
 use itertools::Itertools;
 use log::error;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::sync::Mutex;
 
-use crate::pkg::{Dependency, DependencyRetriever, InfoRetriever, Repository};
+use crate::pkg::package_manager::count_transitive_dependencies;
+use crate::pkg::{
+    Dependency, DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever,
+};
 use crate::Result;
 
 pub struct DependencyReader<T>
@@ -26,7 +30,7 @@ where
     T: AsyncRead + Unpin + Send,
 {
     type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
-    async fn dependencies(&self) -> Result<Self::Itr> {
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
         let content = {
             let mut content = String::new();
             self.reader
@@ -49,13 +53,17 @@ where
             .as_object()
             .context("unable to extract dependency as object")?
             .clone();
+        let requires = resolve_requires(&dependencies);
 
         let deps = dependencies
             .into_iter()
-            .map(|(name, value)| (name, value["version"].as_str().map(ToString::to_string)))
-            .filter_map(|(name, version)| {
+            .map(|(name, value)| {
+                let version = value["version"].as_str().map(ToString::to_string);
+                (name, version, dependency_kind(&value))
+            })
+            .filter_map(|(name, version, kind)| {
                 if let Some(version) = version {
-                    Some((name, version))
+                    Some((name, version, kind))
                 } else {
                     error!("no version found for dependency {}", &name);
                     None
@@ -63,21 +71,19 @@ where
             });
 
         let futures = deps
-            .map(|(name, version)| {
+            .filter(|(name, _, kind)| filter.accepts(name, *kind))
+            .map(|(name, version, kind)| {
                 let retriever = self.npm_info_retriever.clone();
+                let transitive_dependencies =
+                    count_transitive_dependencies(&vec![name.clone()], &requires);
 
                 tokio::spawn(async move {
-                    let (latest_version, repository) = futures::future::join(
-                        retriever.latest_version(&name),
-                        retriever.repository(&name),
-                    )
-                    .await;
+                    let info = retriever.info(&name).await;
 
                     Dependency {
-                        name: name.clone(),
-                        version: version.clone(),
-                        latest_version: latest_version.ok(),
-                        repository: repository.unwrap_or(Repository::Unknown),
+                        transitive_dependencies: Some(transitive_dependencies),
+                        kind,
+                        ..Dependency::new(name, version, info)
                     }
                 })
             })
@@ -109,13 +115,69 @@ where
     }
 }
 
+/// Returns the kind of a package of the lock file, flagged as `dev` when only the dev dependencies
+/// pull it in and as `optional` when only the optional ones do.
+fn dependency_kind(value: &Value) -> DependencyKind {
+    if value["dev"].as_bool() == Some(true) {
+        DependencyKind::Dev
+    } else if value["optional"].as_bool() == Some(true) {
+        DependencyKind::Optional
+    } else {
+        DependencyKind::Prod
+    }
+}
+
+/// Returns the packages each package of the tree of the lock file requires, identified by their
+/// path of nested names, e.g. `["a", "b"]` for the copy of `b` nested in `a`. A required package
+/// is the copy nested in the closest package of the path, as node resolves them.
+fn resolve_requires(dependencies: &Map<String, Value>) -> HashMap<Vec<String>, Vec<Vec<String>>> {
+    let mut required_names = HashMap::new();
+    collect_required_names(dependencies, &[], &mut required_names);
+
+    required_names
+        .iter()
+        .map(|(path, names)| {
+            let resolved = names
+                .iter()
+                .filter_map(|name| {
+                    (0..=path.len()).rev().find_map(|depth| {
+                        let mut candidate = path[..depth].to_vec();
+                        candidate.push(name.clone());
+                        required_names.contains_key(&candidate).then_some(candidate)
+                    })
+                })
+                .collect();
+            (path.clone(), resolved)
+        })
+        .collect()
+}
+
+fn collect_required_names(
+    dependencies: &Map<String, Value>,
+    parent: &[String],
+    required_names: &mut HashMap<Vec<String>, Vec<String>>,
+) {
+    for (name, value) in dependencies {
+        let mut path = parent.to_vec();
+        path.push(name.clone());
+        let names = value["requires"]
+            .as_object()
+            .map(|requires| requires.keys().cloned().collect())
+            .unwrap_or_default();
+        if let Some(nested) = value["dependencies"].as_object() {
+            collect_required_names(nested, &path, required_names);
+        }
+        required_names.insert(path, names);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use futures::StreamExt;
     use mockall::predicate::eq;
 
     use super::*;
-    use crate::pkg::{MockInfoRetriever, Repository};
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
     use crate::Dependency;
 
     #[tokio::test]
@@ -123,17 +185,16 @@ mod tests {
         let retriever = {
             let mut retriever = Box::new(MockInfoRetriever::new());
             retriever
-                .expect_latest_version()
-                .with(eq("colors"))
-                .return_once(|_| Ok("1.4.1".into()))
-                .times(1);
-            retriever
-                .expect_repository()
+                .expect_info()
                 .with(eq("colors"))
                 .return_once(|_| {
-                    Ok(Repository::GitHub {
-                        organization: "org".into(),
-                        name: "name".into(),
+                    Ok(PackageInfo {
+                        latest_version: Some("1.4.1".into()),
+                        repository: Repository::GitHub {
+                            organization: "org".into(),
+                            name: "name".into(),
+                        },
+                        repository_directory: None,
                     })
                 })
                 .times(1);
@@ -141,7 +202,9 @@ mod tests {
         };
 
         let dependency_reader = DependencyReader::new(npm_package_lock(), retriever);
-        let dependencies = dependency_reader.dependencies().await;
+        let dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await;
 
         assert_eq!(
             dependencies.unwrap().next().await.unwrap(),
@@ -153,10 +216,104 @@ mod tests {
                     organization: "org".into(),
                     name: "name".into(),
                 },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: Some(0),
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
             }
         );
     }
 
+    #[tokio::test]
+    async fn it_does_not_retrieve_the_info_of_the_filtered_out_dependencies() {
+        let queried_names = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let retriever = {
+            let queried_names = queried_names.clone();
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever.expect_info().returning(move |name| {
+                queried_names.lock().unwrap().push(name.to_string());
+                Ok(PackageInfo::default())
+            });
+            retriever as Box<dyn InfoRetriever>
+        };
+        let lock_file = br#"{
+  "dependencies": {
+    "react": {"version": "18.2.0"},
+    "jest": {"version": "29.3.1", "dev": true},
+    "internal-client": {"version": "1.0.0"},
+    "left-pad": {"version": "1.3.0"}
+  }
+}"#
+        .as_slice();
+        let filter = DependencyFilter::new(
+            regex::RegexSet::empty(),
+            regex::RegexSet::new(["^internal-"]).unwrap(),
+            true,
+        );
+
+        let dependency_reader = DependencyReader::new(lock_file, retriever);
+        let mut names = dependency_reader
+            .dependencies(&filter)
+            .await
+            .unwrap()
+            .map(|dependency| dependency.name)
+            .collect::<Vec<_>>()
+            .await;
+        names.sort();
+
+        assert_eq!(names, vec!["left-pad", "react"]);
+        let mut queried_names = queried_names.lock().unwrap().clone();
+        queried_names.sort();
+        assert_eq!(queried_names, vec!["left-pad", "react"]);
+    }
+
+    #[test]
+    fn it_reads_the_kind_of_the_dependencies_from_their_flags() {
+        assert_eq!(
+            dependency_kind(&serde_json::json!({"version": "29.3.1", "dev": true})),
+            DependencyKind::Dev
+        );
+        assert_eq!(
+            dependency_kind(&serde_json::json!({"version": "2.3.2", "optional": true})),
+            DependencyKind::Optional
+        );
+        assert_eq!(
+            dependency_kind(&serde_json::json!({"version": "1.4.0"})),
+            DependencyKind::Prod
+        );
+    }
+
+    #[test]
+    fn it_counts_the_packages_each_dependency_pulls_in_resolving_the_nested_copies_first() {
+        let lock_file: Value = serde_json::from_str(
+            r#"{
+  "dependencies": {
+    "express": {
+      "version": "4.18.2",
+      "requires": { "body-parser": "1.20.1", "debug": "2.6.9" },
+      "dependencies": {
+        "debug": { "version": "2.6.9", "requires": { "ms": "2.0.0" } }
+      }
+    },
+    "body-parser": { "version": "1.20.1", "requires": { "debug": "4.3.4", "ms": "2.1.2" } },
+    "debug": { "version": "4.3.4", "requires": { "ms": "2.1.2" } },
+    "ms": { "version": "2.1.2" },
+    "left-pad": { "version": "1.3.0" }
+  }
+}"#,
+        )
+        .unwrap();
+        let requires = resolve_requires(lock_file["dependencies"].as_object().unwrap());
+        let count = |name: &str| count_transitive_dependencies(&vec![name.to_string()], &requires);
+
+        assert_eq!(count("express"), 4);
+        assert_eq!(count("body-parser"), 2);
+        assert_eq!(count("debug"), 1);
+        assert_eq!(count("left-pad"), 0);
+    }
+
     fn npm_package_lock() -> &'static [u8] {
         r#"{
   "name": "foo",
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::Stream;
+use itertools::Itertools;
+use log::warn;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+
+use crate::pkg::{DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever};
+use crate::{Dependency, Result};
+
+pub struct DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin,
+{
+    pypi_info_retriever: Arc<dyn InfoRetriever>,
+    reader: Mutex<T>,
+}
+
+impl<T> DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin,
+{
+    pub fn new<R>(reader: T, retriever: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            reader: reader.into(),
+            pypi_info_retriever: retriever.into(),
+        }
+    }
+
+    /// Extracts the name and the pinned version from a requirement line like
+    /// `requests[security]==2.28.1 ; python_version >= "3.7"`.
+    fn name_and_version_from_line(line: &str) -> Option<(String, String)> {
+        let requirement = line
+            .split_once(';')
+            .map_or(line, |(requirement, _)| requirement);
+        let (name, version) = requirement.split_once("==")?;
+
+        let name = name.split_once('[').map_or(name, |(name, _)| name).trim();
+        let version = version.trim();
+        if name.is_empty() || version.is_empty() {
+            return None;
+        }
+
+        Some((name.to_string(), version.to_string()))
+    }
+}
+
+#[async_trait]
+impl<T> DependencyRetriever for DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
+
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
+        let content = self.content_from_reader().await?;
+
+        let requirement_lines = content
+            .lines()
+            .map(|line| line.split_once('#').map_or(line, |(line, _)| line).trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('-'));
+
+        let dependency_info_tuples = requirement_lines
+            .filter_map(|line| {
+                let name_and_version = Self::name_and_version_from_line(line);
+                if name_and_version.is_none() {
+                    warn!("skipping requirement without a pinned version: {}", line);
+                }
+                name_and_version
+            })
+            .collect_vec();
+
+        let futures = dependency_info_tuples
+            .into_iter()
+            .filter(|(name, _)| filter.accepts(name, DependencyKind::Prod))
+            .map(|(name, version)| {
+                let retriever = self.pypi_info_retriever.clone();
+
+                tokio::spawn(async move {
+                    let info = retriever.info(&name).await;
+
+                    Dependency::new(name, version, info)
+                })
+            })
+            .collect_vec();
+
+        let unfold =
+            futures::stream::unfold(futures, |mut name_and_versions_to_retrieve| async move {
+                let next = name_and_versions_to_retrieve.pop();
+                let dependency = next?.await.ok()?;
+                Some((dependency, name_and_versions_to_retrieve))
+            });
+        Ok(Box::new(Box::pin(unfold)))
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: Unpin + tokio::io::AsyncRead + Send,
+{
+    async fn content_from_reader(&self) -> Result<String> {
+        let mut bytes = Vec::new();
+        self.reader.lock().await.read_to_end(&mut bytes).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
+
+    #[tokio::test]
+    async fn it_retrieves_only_the_pinned_dependencies() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .with(eq("requests"))
+                .return_once(|_| {
+                    Ok(PackageInfo {
+                        latest_version: Some("2.28.2".into()),
+                        repository: Repository::GitHub {
+                            organization: "psf".into(),
+                            name: "requests".into(),
+                        },
+                        repository_directory: None,
+                    })
+                });
+            retriever.expect_info().returning(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("1.0.0".into()),
+                    repository: Repository::Unknown,
+                    repository_directory: None,
+                })
+            });
+            retriever
+        };
+
+        let dependency_reader = DependencyReader::new(requirements_file(), retriever);
+        let deps = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(deps.len(), 3);
+        assert_eq!(
+            deps.iter().find(|dep| dep.name == "requests").unwrap(),
+            &Dependency {
+                name: "requests".to_string(),
+                version: "2.28.1".to_string(),
+                latest_version: Some("2.28.2".to_string()),
+                repository: Repository::GitHub {
+                    organization: "psf".to_string(),
+                    name: "requests".to_string(),
+                },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+        assert_eq!(
+            deps.iter()
+                .find(|dep| dep.name == "importlib-metadata")
+                .unwrap()
+                .version,
+            "4.12.0"
+        );
+        assert!(deps.iter().any(|dep| dep.name == "uvicorn"));
+        assert!(!deps.iter().any(|dep| dep.name == "flask"));
+    }
+
+    fn requirements_file() -> &'static [u8] {
+        "\
+# This is a comment
+-r base.txt
+--index-url https://pypi.org/simple
+
+requests==2.28.1  # the http client
+importlib-metadata==4.12.0 ; python_version < \"3.8\"
+uvicorn[standard]==0.20.0
+flask>=2.0
+"
+        .as_bytes()
+    }
+}
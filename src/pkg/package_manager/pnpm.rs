@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::Stream;
+use itertools::Itertools;
+use log::error;
+use serde_yaml::Value;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::Mutex;
+
+use crate::pkg::{
+    Dependency, DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever,
+};
+use crate::Result;
+
+pub struct DependencyReader<T>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    npm_info_retriever: Arc<dyn InfoRetriever>,
+    reader: Mutex<T>,
+}
+
+#[async_trait]
+impl<T> DependencyRetriever for DependencyReader<T>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
+        let content = {
+            let mut content = String::new();
+            self.reader
+                .lock()
+                .await
+                .read_to_string(&mut content)
+                .await
+                .context("unable to read contents from reader")?;
+            content
+        };
+        let result: Value =
+            serde_yaml::from_str(&content).context("unable to retrieve yaml from string")?;
+
+        let packages = result
+            .get("packages")
+            .context("packages not found in lock file")?
+            .as_mapping()
+            .context("packages section is not a map")?
+            .clone();
+
+        let deps = packages
+            .into_iter()
+            .filter_map(|(key, value)| Some((key.as_str()?.to_string(), value)))
+            .filter_map(|(key, value)| {
+                let name_and_version = Self::name_and_version_from_key(&key);
+                if name_and_version.is_none() {
+                    error!(
+                        "unable to parse the name and version of the package {}",
+                        &key
+                    );
+                }
+                let (name, version) = name_and_version?;
+                Some((name, version, Self::dependency_kind(&value)))
+            });
+
+        let futures = deps
+            .filter(|(name, _, kind)| filter.accepts(name, *kind))
+            .map(|(name, version, kind)| {
+                let retriever = self.npm_info_retriever.clone();
+
+                tokio::spawn(async move {
+                    let info = retriever.info(&name).await;
+
+                    Dependency {
+                        kind,
+                        ..Dependency::new(name, version, info)
+                    }
+                })
+            })
+            .collect_vec();
+
+        let unfold =
+            futures::stream::unfold(futures, |mut name_and_versions_to_retrieve| async move {
+                let next = name_and_versions_to_retrieve.pop();
+                let dependency = next?.await.ok()?;
+                Some((dependency, name_and_versions_to_retrieve))
+            });
+
+        Ok(Box::new(Box::pin(unfold)))
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    pub fn new<R>(reader: T, retriever: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            reader: reader.into(),
+            npm_info_retriever: retriever.into(),
+        }
+    }
+
+    /// Returns the kind of a package, flagged as `dev` when only the dev dependencies pull it in and
+    /// as `optional` when only the optional ones do.
+    fn dependency_kind(value: &Value) -> DependencyKind {
+        if value.get("dev").and_then(Value::as_bool) == Some(true) {
+            DependencyKind::Dev
+        } else if value.get("optional").and_then(Value::as_bool) == Some(true) {
+            DependencyKind::Optional
+        } else {
+            DependencyKind::Prod
+        }
+    }
+
+    /// Extracts the name and the version from a package key, like `/@babel/core@7.0.0` or
+    /// `react-dom@18.2.0(react@18.2.0)`, removing the peer dependency suffix from the version.
+    fn name_and_version_from_key(key: &str) -> Option<(String, String)> {
+        let key = key.trim_start_matches('/');
+        let key = key.split_once('(').map_or(key, |(key, _)| key);
+
+        let separator = key.rfind('@').filter(|&index| index > 0)?;
+        let (name, version) = (&key[..separator], &key[separator + 1..]);
+        if name.is_empty() || version.is_empty() {
+            return None;
+        }
+
+        Some((name.to_string(), version.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
+    use crate::Dependency;
+
+    #[tokio::test]
+    async fn retrieves_all_dependencies() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever.expect_info().with(eq("react")).return_once(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("18.2.0".into()),
+                    repository: Repository::GitHub {
+                        organization: "facebook".into(),
+                        name: "react".into(),
+                    },
+                    repository_directory: None,
+                })
+            });
+            retriever.expect_info().returning(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("1.0.0".into()),
+                    repository: Repository::Unknown,
+                    repository_directory: None,
+                })
+            });
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let dependency_reader = DependencyReader::new(pnpm_lock_file(), retriever);
+        let deps = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(deps.len(), 4);
+        assert_eq!(
+            deps.iter().find(|dep| dep.name == "react").unwrap(),
+            &Dependency {
+                name: "react".into(),
+                version: "18.2.0".into(),
+                latest_version: Some("18.2.0".into()),
+                repository: Repository::GitHub {
+                    organization: "facebook".into(),
+                    name: "react".into(),
+                },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+        assert_eq!(
+            deps.iter().find(|dep| dep.name == "@babel/core").unwrap(),
+            &Dependency {
+                name: "@babel/core".into(),
+                version: "7.0.0".into(),
+                latest_version: Some("1.0.0".into()),
+                repository: Repository::Unknown,
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Dev,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_the_name_and_version_of_the_package_keys() {
+        assert_eq!(
+            DependencyReader::<&[u8]>::name_and_version_from_key("/react@18.2.0"),
+            Some(("react".into(), "18.2.0".into()))
+        );
+        assert_eq!(
+            DependencyReader::<&[u8]>::name_and_version_from_key("/@babel/core@7.0.0"),
+            Some(("@babel/core".into(), "7.0.0".into()))
+        );
+        assert_eq!(
+            DependencyReader::<&[u8]>::name_and_version_from_key(
+                "react-redux@8.0.5(react@18.2.0)(redux@4.0.0)"
+            ),
+            Some(("react-redux".into(), "8.0.5".into()))
+        );
+        assert_eq!(
+            DependencyReader::<&[u8]>::name_and_version_from_key("/@babel/core"),
+            None
+        );
+    }
+
+    fn pnpm_lock_file() -> &'static [u8] {
+        "\
+lockfileVersion: '6.0'
+
+dependencies:
+  react:
+    specifier: ^18.2.0
+    version: 18.2.0
+
+packages:
+
+  /@babel/core@7.0.0:
+    resolution: {integrity: sha512-aaaa}
+    dev: true
+
+  /loose-envify@1.4.0:
+    resolution: {integrity: sha512-bbbb}
+    hasBin: true
+    dev: false
+
+  /react@18.2.0:
+    resolution: {integrity: sha512-cccc}
+    engines: {node: '>=0.10.0'}
+    dependencies:
+      loose-envify: 1.4.0
+    dev: false
+
+  /react-redux@8.0.5(react@18.2.0)(redux@4.0.0):
+    resolution: {integrity: sha512-dddd}
+    dev: false
+"
+        .as_bytes()
+    }
+}
@@ -0,0 +1,241 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::error;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+use tokio_stream::Stream;
+use toml::Value;
+
+use crate::pkg::{
+    Dependency, DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever,
+};
+use crate::Result;
+
+pub struct DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    pypi_info_retriever: Arc<dyn InfoRetriever>,
+    reader: Mutex<T>,
+}
+
+#[async_trait]
+impl<T> DependencyRetriever for DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
+        let contents = self.contents_from_reader().await?;
+        let result: Value = toml::from_slice(&contents)?;
+
+        let packages = result.get("package").context("no package section found")?;
+
+        let package_list = packages
+            .as_array()
+            .context("packages section is not an array")?;
+
+        let name_and_version_from_packages = package_list
+            .iter()
+            .map(|package| {
+                let name = package
+                    .get("name")
+                    .context("no name found")?
+                    .as_str()
+                    .context("name is not a string")?
+                    .to_string();
+
+                let version = package
+                    .get("version")
+                    .context("no version found")?
+                    .as_str()
+                    .context("version is not a string")?
+                    .to_string();
+
+                Ok((name, version, dependency_kind(package)))
+            })
+            .filter_map(|result: Result<(String, String, DependencyKind)>| {
+                result.map_err(|e| error!("{}", e)).ok()
+            });
+
+        let futures = name_and_version_from_packages
+            .filter(|(name, _, kind)| filter.accepts(name, *kind))
+            .map(|(name, version, kind)| {
+                let retriever = self.pypi_info_retriever.clone();
+                tokio::spawn(async move {
+                    let info = retriever.info(&name).await;
+
+                    let mut dependency = Dependency::new(name, version, info);
+                    dependency.kind = kind;
+                    dependency
+                })
+            })
+            .collect_vec();
+
+        let unfold =
+            futures::stream::unfold(futures, |mut name_and_versions_to_retrieve| async move {
+                let next = name_and_versions_to_retrieve.pop();
+                let dependency = next?.await.ok()?;
+                Some((dependency, name_and_versions_to_retrieve))
+            });
+        Ok(Box::new(Box::pin(unfold)))
+    }
+}
+
+/// Returns the kind of a package, flagged as `dev` by its category. The lock files of the recent
+/// versions of Poetry no longer record it, so their packages are all production ones.
+fn dependency_kind(package: &Value) -> DependencyKind {
+    match package.get("category").and_then(Value::as_str) {
+        Some("dev") => DependencyKind::Dev,
+        _ => DependencyKind::Prod,
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: Unpin + tokio::io::AsyncRead + Send,
+{
+    async fn contents_from_reader(&self) -> Result<Vec<u8>> {
+        let mut contents = Vec::new();
+        self.reader
+            .lock()
+            .await
+            .read_to_end(&mut contents)
+            .await
+            .context("error reading from reader")?;
+        Ok(contents)
+    }
+}
+
+impl<T> DependencyReader<T>
+where
+    T: tokio::io::AsyncRead + Unpin + Send,
+{
+    pub fn new<R>(reader: T, retriever: R) -> Self
+    where
+        R: Into<Arc<dyn InfoRetriever>>,
+    {
+        Self {
+            reader: reader.into(),
+            pypi_info_retriever: retriever.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
+    use crate::Dependency;
+
+    #[tokio::test]
+    async fn retrieves_all_dependencies_from_poetry() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .with(eq("certifi"))
+                .return_once(|_| {
+                    Ok(PackageInfo {
+                        latest_version: Some("2022.12.7".into()),
+                        repository: Repository::GitHub {
+                            organization: "certifi".into(),
+                            name: "python-certifi".into(),
+                        },
+                        repository_directory: None,
+                    })
+                })
+                .times(1);
+            retriever as Box<dyn InfoRetriever>
+        };
+
+        let dependency_reader = DependencyReader::new(poetry_lock_file_contents(), retriever);
+        let mut dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            dependencies.next().await.unwrap(),
+            Dependency {
+                name: "certifi".into(),
+                version: "2022.9.24".into(),
+                latest_version: Some("2022.12.7".into()),
+                repository: Repository::GitHub {
+                    organization: "certifi".into(),
+                    name: "python-certifi".into(),
+                },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: None,
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_reads_the_packages_of_the_dev_category_as_dev_dependencies() {
+        let retriever = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .returning(|_| Ok(PackageInfo::default()))
+                .times(2);
+            retriever as Box<dyn InfoRetriever>
+        };
+        let lock_file = br#"[[package]]
+name = "certifi"
+version = "2022.9.24"
+category = "main"
+
+[[package]]
+name = "pytest"
+version = "7.2.0"
+category = "dev"
+"#
+        .as_slice();
+
+        let dependency_reader = DependencyReader::new(lock_file, retriever);
+        let mut kinds = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await
+            .unwrap()
+            .map(|dependency| (dependency.name, dependency.kind))
+            .collect::<Vec<_>>()
+            .await;
+        kinds.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("certifi".to_string(), DependencyKind::Prod),
+                ("pytest".to_string(), DependencyKind::Dev),
+            ]
+        );
+    }
+
+    fn poetry_lock_file_contents() -> &'static [u8] {
+        r#"[[package]]
+name = "certifi"
+version = "2022.9.24"
+description = "Python package for providing Mozilla's CA Bundle."
+category = "main"
+optional = false
+python-versions = ">=3.6"
+
+[metadata]
+lock-version = "1.1"
+python-versions = "^3.10"
+content-hash = "8a1b5d4e6f"
+"#
+        .as_bytes()
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -6,7 +7,8 @@ use itertools::Itertools;
 use tokio::io::AsyncReadExt;
 use tokio::sync::Mutex;
 
-use crate::pkg::{DependencyRetriever, InfoRetriever, Repository};
+use crate::pkg::package_manager::count_transitive_dependencies;
+use crate::pkg::{DependencyFilter, DependencyKind, DependencyRetriever, InfoRetriever};
 use crate::{Dependency, Result};
 
 pub struct DependencyReader<T>
@@ -39,64 +41,27 @@ where
 {
     type Itr = Box<dyn Stream<Item = Dependency> + Unpin + Send>;
 
-    async fn dependencies(&self) -> Result<Self::Itr> {
+    async fn dependencies(&self, filter: &DependencyFilter) -> Result<Self::Itr> {
         let content = self.content_from_reader().await?;
 
-        let not_comment_lines = content.lines().filter(|line| !line.trim().starts_with('#'));
-
-        let dependency_lines_grouped = not_comment_lines.group_by(|line| line.trim().is_empty());
-        let dependency_lines_grouped =
-            dependency_lines_grouped
-                .into_iter()
-                .filter_map(|(bool, group)| {
-                    if bool {
-                        None
-                    } else {
-                        Some(group.collect_vec())
-                    }
-                });
-
-        let dependency_info_tuples = dependency_lines_grouped.into_iter().map(|lines| {
-            let dependency_line: String = lines.first().unwrap().replace('\"', "");
-            let mut dependency_name = dependency_line.split_once('@').unwrap().0.to_owned();
-            if dependency_name.is_empty() {
-                dependency_name = format!(
-                    "@{}",
-                    dependency_line
-                        .replacen('@', "", 1)
-                        .split_once('@')
-                        .unwrap()
-                        .0
-                );
-            }
-
-            let dependency_version = lines.get(1).unwrap();
-            let dependency_version: String = dependency_version
-                .trim()
-                .split_once(' ')
-                .unwrap()
-                .1
-                .replace('\"', "");
-
-            (dependency_name, dependency_version)
-        });
+        let entries = if is_berry_lock_file(&content) {
+            berry_entries(&content)?
+        } else {
+            classic_entries(&content)
+        };
 
-        let futures = dependency_info_tuples
-            .map(|(name, version)| {
+        let futures = with_transitive_dependencies(entries)
+            .into_iter()
+            .filter(|(name, _, _)| filter.accepts(name, DependencyKind::Prod))
+            .map(|(name, version, transitive_dependencies)| {
                 let retriever = self.npm_info_retriever.clone();
 
                 tokio::spawn(async move {
-                    let (latest_version, repository) = futures::future::join(
-                        retriever.latest_version(&name),
-                        retriever.repository(&name),
-                    )
-                    .await;
+                    let info = retriever.info(&name).await;
 
                     Dependency {
-                        name: name.clone(),
-                        version: version.clone(),
-                        latest_version: latest_version.ok(),
-                        repository: repository.unwrap_or(Repository::Unknown),
+                        transitive_dependencies: Some(transitive_dependencies),
+                        ..Dependency::new(name, version, info)
                     }
                 })
             })
@@ -123,42 +88,225 @@ where
     }
 }
 
+/// Package of the lock file, with the descriptors like `lodash@^4.17.0` that resolve to it and the
+/// descriptors of the packages it requires.
+struct Entry {
+    name: String,
+    version: String,
+    descriptors: Vec<String>,
+    requires: Vec<String>,
+}
+
+/// Returns the name and version of each entry with the number of packages it pulls in.
+fn with_transitive_dependencies(entries: Vec<Entry>) -> Vec<(String, String, usize)> {
+    let entries_by_descriptor: HashMap<&str, usize> = entries
+        .iter()
+        .enumerate()
+        .flat_map(|(index, entry)| {
+            entry
+                .descriptors
+                .iter()
+                .map(move |descriptor| (descriptor.as_str(), index))
+        })
+        .collect();
+    let requires: HashMap<usize, Vec<usize>> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let required = entry
+                .requires
+                .iter()
+                .filter_map(|descriptor| entries_by_descriptor.get(descriptor.as_str()).copied())
+                .collect();
+            (index, required)
+        })
+        .collect();
+
+    let counts = (0..entries.len())
+        .map(|index| count_transitive_dependencies(&index, &requires))
+        .collect_vec();
+    entries
+        .into_iter()
+        .zip(counts)
+        .map(|(entry, count)| (entry.name, entry.version, count))
+        .collect()
+}
+
+/// Yarn berry (v2+) lock files are YAML documents starting with a `__metadata` entry.
+fn is_berry_lock_file(content: &str) -> bool {
+    content.lines().any(|line| line.starts_with("__metadata:"))
+}
+
+/// Parses the blocks of the classic (v1) lock file, separated by blank lines.
+fn classic_entries(content: &str) -> Vec<Entry> {
+    let not_comment_lines = content.lines().filter(|line| !line.trim().starts_with('#'));
+
+    let dependency_lines_grouped = not_comment_lines.group_by(|line| line.trim().is_empty());
+    let dependency_lines_grouped =
+        dependency_lines_grouped
+            .into_iter()
+            .filter_map(|(bool, group)| {
+                if bool {
+                    None
+                } else {
+                    Some(group.collect_vec())
+                }
+            });
+
+    dependency_lines_grouped
+        .into_iter()
+        .filter_map(|lines| {
+            let header = lines.first()?;
+            let dependency_name = package_name(header)?;
+
+            let dependency_version = lines.get(1)?;
+            let dependency_version: String = dependency_version
+                .trim()
+                .split_once(' ')?
+                .1
+                .replace('\"', "");
+
+            Some(Entry {
+                name: dependency_name,
+                version: dependency_version,
+                descriptors: header
+                    .trim()
+                    .trim_end_matches(':')
+                    .split(',')
+                    .map(|descriptor| descriptor.trim().trim_matches('"').to_string())
+                    .collect(),
+                requires: classic_requires(&lines),
+            })
+        })
+        .collect()
+}
+
+/// Returns the descriptors of the packages listed under the `dependencies` and
+/// `optionalDependencies` of a block, indented below them like `    "@babel/types" "^7.18.6"`.
+fn classic_requires(lines: &[&str]) -> Vec<String> {
+    let mut requires = Vec::new();
+    let mut in_dependencies = false;
+    for line in lines {
+        if line.starts_with("    ") {
+            if in_dependencies {
+                if let Some((name, range)) = line.trim().split_once(' ') {
+                    requires.push(format!(
+                        "{}@{}",
+                        name.trim_matches('"'),
+                        range.trim().trim_matches('"')
+                    ));
+                }
+            }
+        } else {
+            in_dependencies = matches!(line.trim(), "dependencies:" | "optionalDependencies:");
+        }
+    }
+    requires
+}
+
+/// Returns the package name of a header line like `"@babel/core@^7.0.0, @babel/core@^7.1.0":`,
+/// whose comma-separated descriptors all share the same name. The name is separated from the range
+/// by the first `@` that is not the prefix of a scope.
+fn package_name(header: &str) -> Option<String> {
+    let descriptor = header
+        .trim()
+        .trim_end_matches(':')
+        .split(',')
+        .next()?
+        .trim()
+        .trim_matches('"');
+    let separator = descriptor
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == '@')?
+        .0;
+
+    Some(descriptor[..separator].to_string())
+}
+
+/// Parses the entries of the berry lock file, taking the name from the npm resolution of each one
+/// and skipping the workspaces, patches and other non-npm resolutions.
+fn berry_entries(content: &str) -> Result<Vec<Entry>> {
+    let entries: serde_yaml::Mapping = serde_yaml::from_str(content)?;
+
+    Ok(entries
+        .iter()
+        .filter(|(key, _)| key.as_str() != Some("__metadata"))
+        .filter_map(|(key, entry)| {
+            let (name, _) = entry.get("resolution")?.as_str()?.split_once("@npm:")?;
+            let version = entry.get("version")?.as_str()?;
+            Some(Entry {
+                name: name.to_string(),
+                version: version.to_string(),
+                descriptors: key
+                    .as_str()?
+                    .split(',')
+                    .map(|descriptor| descriptor.trim().to_string())
+                    .collect(),
+                requires: berry_requires(entry),
+            })
+        })
+        .collect())
+}
+
+/// Returns the descriptors of the `dependencies` of an entry, whose ranges without protocol are
+/// resolved from npm.
+fn berry_requires(entry: &serde_yaml::Value) -> Vec<String> {
+    entry
+        .get("dependencies")
+        .and_then(serde_yaml::Value::as_mapping)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, range)| {
+            let (name, range) = (name.as_str()?, range.as_str()?);
+            Some(if range.contains(':') {
+                format!("{name}@{range}")
+            } else {
+                format!("{name}@npm:{range}")
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use mockall::predicate::eq;
     use tokio_stream::StreamExt;
 
     use super::*;
-    use crate::pkg::{MockInfoRetriever, Repository};
+    use crate::pkg::{DependencyKind, MockInfoRetriever, PackageInfo, Repository};
 
     #[tokio::test]
     async fn it_retrieves_all_the_dependencies() {
         let retriever: Box<dyn InfoRetriever> = {
             let mut retriever = Box::new(MockInfoRetriever::new());
             retriever
-                .expect_repository()
+                .expect_info()
                 .with(eq("webpack"))
                 .return_once(|_| {
-                    Ok(Repository::GitHub {
-                        organization: "webpack".into(),
-                        name: "webpack".into(),
+                    Ok(PackageInfo {
+                        latest_version: Some("5.73.1".into()),
+                        repository: Repository::GitHub {
+                            organization: "webpack".into(),
+                            name: "webpack".into(),
+                        },
+                        repository_directory: None,
                     })
                 });
-            retriever
-                .expect_latest_version()
-                .with(eq("webpack"))
-                .return_once(|_| Ok("5.73.1".into()));
-            retriever
-                .expect_repository()
-                .returning(|_| Ok(Repository::Unknown));
-            retriever
-                .expect_latest_version()
-                .returning(|_| Ok("1.0.0".into()));
+            retriever.expect_info().returning(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("1.0.0".into()),
+                    repository: Repository::Unknown,
+                    repository_directory: None,
+                })
+            });
             retriever
         };
 
         let dependency_reader = DependencyReader::new(yarn_lock_file(), retriever);
-        let dependencies = dependency_reader.dependencies().await;
+        let dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await;
 
         let deps = dependencies.unwrap().collect::<Vec<_>>().await;
         let webpack_dependency = deps.iter().find(|dep| dep.name == "webpack").unwrap();
@@ -178,6 +326,12 @@ mod tests {
                     organization: "webpack".to_string(),
                     name: "webpack".to_string(),
                 },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: Some(75),
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
             }
         );
         assert_eq!(
@@ -187,11 +341,120 @@ mod tests {
                 version: "0.3.1".to_string(),
                 latest_version: Some("1.0.0".to_string()),
                 repository: Repository::Unknown,
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: Some(4),
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
             }
         );
     }
 
+    #[tokio::test]
+    async fn it_retrieves_all_the_dependencies_from_a_berry_lock_file() {
+        let retriever: Box<dyn InfoRetriever> = {
+            let mut retriever = Box::new(MockInfoRetriever::new());
+            retriever
+                .expect_info()
+                .with(eq("webpack"))
+                .return_once(|_| {
+                    Ok(PackageInfo {
+                        latest_version: Some("5.73.1".into()),
+                        repository: Repository::GitHub {
+                            organization: "webpack".into(),
+                            name: "webpack".into(),
+                        },
+                        repository_directory: None,
+                    })
+                });
+            retriever.expect_info().returning(|_| {
+                Ok(PackageInfo {
+                    latest_version: Some("1.0.0".into()),
+                    repository: Repository::Unknown,
+                    repository_directory: None,
+                })
+            });
+            retriever
+        };
+
+        let dependency_reader = DependencyReader::new(yarn_berry_lock_file(), retriever);
+        let dependencies = dependency_reader
+            .dependencies(&DependencyFilter::default())
+            .await;
+
+        let deps = dependencies.unwrap().collect::<Vec<_>>().await;
+        let webpack_dependency = deps.iter().find(|dep| dep.name == "webpack").unwrap();
+        let gen_mapping_dependency = deps
+            .iter()
+            .find(|dep| dep.name == "@jridgewell/gen-mapping")
+            .unwrap();
+
+        assert_eq!(deps.len(), 6);
+        assert_eq!(deps.iter().filter(|dep| dep.name == "resolve").count(), 1);
+        assert!(!deps.iter().any(|dep| dep.name == "my-app"));
+        assert_eq!(
+            webpack_dependency,
+            &Dependency {
+                name: "webpack".to_string(),
+                version: "5.73.0".to_string(),
+                latest_version: Some("5.73.1".to_string()),
+                repository: Repository::GitHub {
+                    organization: "webpack".to_string(),
+                    name: "webpack".to_string(),
+                },
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: Some(5),
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+        assert_eq!(
+            gen_mapping_dependency,
+            &Dependency {
+                name: "@jridgewell/gen-mapping".to_string(),
+                version: "0.3.1".to_string(),
+                latest_version: Some("1.0.0".to_string()),
+                repository: Repository::Unknown,
+                retrieval_error: None,
+                ecosystem: None,
+                transitive_dependencies: Some(3),
+                kind: DependencyKind::Prod,
+                repository_directory: None,
+                lock_file: None,
+            }
+        );
+    }
+
+    #[test]
+    fn it_takes_the_package_name_once_from_the_scoped_multi_constraint_headers() {
+        assert_eq!(
+            package_name(r#""@babel/core@^7.0.0, @babel/core@^7.1.0":"#),
+            Some("@babel/core".to_string())
+        );
+    }
+
+    #[test]
+    fn it_takes_the_package_name_once_from_the_unscoped_multi_constraint_headers() {
+        assert_eq!(
+            package_name(r#""lodash@^4.0.0, lodash@~4.17.0":"#),
+            Some("lodash".to_string())
+        );
+        assert_eq!(package_name("lodash@^4.17.21:"), Some("lodash".to_string()));
+    }
+
+    #[test]
+    fn it_does_not_take_a_package_name_from_a_header_without_range() {
+        assert_eq!(package_name("@babel/core:"), None);
+    }
+
     fn yarn_lock_file() -> &'static [u8] {
         include_bytes!("../../../tests/fixtures/yarn.lock")
     }
+
+    fn yarn_berry_lock_file() -> &'static [u8] {
+        include_bytes!("../../../tests/fixtures/yarn-berry.lock")
+    }
 }
@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use log::warn;
+use semver::{Version, VersionReq};
+
+use crate::pkg::policy::Severity;
+use crate::{Dependency, Evaluation, Policy};
+
+/// Dependency that fails the blocklist when its version is in the range.
+pub struct BlockedDependency {
+    pub name: String,
+    pub version_range: VersionReq,
+    pub reason: Option<String>,
+}
+
+impl BlockedDependency {
+    /// A version that is not semver, e.g. the revision of a git dependency, is only blocked by a
+    /// range matching every version.
+    fn blocks(&self, dependency: &Dependency) -> bool {
+        if self.name != dependency.name {
+            return false;
+        }
+
+        let version = &dependency.version;
+        let Ok(semver) = Version::parse(version.strip_prefix('v').unwrap_or(version)) else {
+            warn!(
+                "the version {} of {} is not a semver version, so it is only blocked by the range *",
+                version, dependency.name
+            );
+            return self.version_range == VersionReq::STAR;
+        };
+        self.version_range.matches(&semver)
+    }
+}
+
+/// Fails the dependencies in the list of blocked ones, e.g. versions known to be compromised,
+/// whatever the result of the other policies.
+pub struct Blocklist {
+    blocked: Vec<BlockedDependency>,
+}
+
+#[async_trait]
+impl Policy for Blocklist {
+    fn name(&self) -> &'static str {
+        "blocklist"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let Some(blocked) = self
+            .blocked
+            .iter()
+            .find(|blocked| blocked.blocks(dependency))
+        else {
+            return Ok(Evaluation::Pass {
+                policy_name: "blocklist".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        let reason = blocked.reason.clone().unwrap_or_else(|| {
+            format!(
+                "the version {} is blocked by the range {}",
+                dependency.version, blocked.version_range
+            )
+        });
+        Ok(Evaluation::Fail {
+            policy_name: "blocklist".to_string(),
+            dependency: dependency.clone(),
+            reason,
+            fail_score: 1.0,
+            severity: Severity::Error,
+            measurement: None,
+        })
+    }
+}
+
+impl Blocklist {
+    pub fn new(blocked: Vec<BlockedDependency>) -> Self {
+        Self { blocked }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn when_the_version_is_in_a_blocked_range_it_should_fail_with_its_reason() {
+        let policy = Blocklist::new(vec![
+            blocked("event-stream", "=3.3.6", Some("compromised release")),
+            blocked("colors", ">=1.4.1, <1.5", None),
+        ]);
+
+        assert_eq!(
+            policy
+                .evaluate(&dependency("event-stream", "3.3.6"))
+                .await
+                .unwrap(),
+            Evaluation::Fail {
+                policy_name: "blocklist".to_string(),
+                dependency: dependency("event-stream", "3.3.6"),
+                reason: "compromised release".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            }
+        );
+        let Evaluation::Fail { reason, .. } = policy
+            .evaluate(&dependency("colors", "1.4.44"))
+            .await
+            .unwrap()
+        else {
+            panic!("colors@1.4.44 should be blocked");
+        };
+        assert_eq!(
+            reason,
+            "the version 1.4.44 is blocked by the range >=1.4.1, <1.5"
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_version_is_not_in_a_blocked_range_it_should_pass() {
+        let policy = Blocklist::new(vec![blocked("event-stream", "=3.3.6", None)]);
+
+        for dependency in [
+            dependency("event-stream", "3.3.5"),
+            dependency("event-stream-fork", "3.3.6"),
+        ] {
+            assert!(matches!(
+                policy.evaluate(&dependency).await.unwrap(),
+                Evaluation::Pass { .. }
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn a_version_that_is_not_semver_is_only_blocked_by_any_version() {
+        assert!(matches!(
+            Blocklist::new(vec![blocked("left-pad", "*", None)])
+                .evaluate(&dependency("left-pad", "0a1b2c3"))
+                .await
+                .unwrap(),
+            Evaluation::Fail { .. }
+        ));
+        assert!(matches!(
+            Blocklist::new(vec![blocked("left-pad", "<0.1.0", None)])
+                .evaluate(&dependency("left-pad", "0a1b2c3"))
+                .await
+                .unwrap(),
+            Evaluation::Pass { .. }
+        ));
+    }
+
+    fn blocked(name: &str, version_range: &str, reason: Option<&str>) -> BlockedDependency {
+        BlockedDependency {
+            name: name.to_string(),
+            version_range: VersionReq::parse(version_range).unwrap(),
+            reason: reason.map(ToString::to_string),
+        }
+    }
+
+    fn dependency(name: &str, version: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: version.to_string(),
+            ..Dependency::default()
+        }
+    }
+}
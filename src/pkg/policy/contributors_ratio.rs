@@ -1,69 +1,54 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use anyhow::{anyhow, Context};
+use anyhow::Context;
 use async_trait::async_trait;
-use itertools::Itertools;
 
-use crate::pkg::policy::{CommitRetriever, Evaluation, Policy};
+use crate::pkg::policy::{
+    commits_in_latest_releases, ratio_by_author, CommitRetriever, Evaluation, Measurement, Policy,
+    Severity,
+};
 use crate::Dependency;
 
+const GITHUB_NOREPLY_EMAIL_DOMAIN: &str = "@users.noreply.github.com";
+
 pub struct ContributorsRatio {
     retriever: Arc<dyn CommitRetriever>,
     max_number_of_releases_to_check: usize,
     max_contributor_ratio: f64,
+    normalize_emails: bool,
+    author_by_email: HashMap<String, String>,
 }
 
 #[async_trait]
 impl Policy for ContributorsRatio {
-    #[allow(clippy::cast_precision_loss)]
+    fn name(&self) -> &'static str {
+        "contributors_ratio"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
     async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
         let repo_url = dependency
             .repository
             .url()
             .context("the repository doesn't have a URL")?;
 
-        let all_tags = self
-            .retriever
-            .all_tags(&repo_url)
-            .await
-            .map_err(|e| anyhow!("unable to retrieve all tags for repo {}: {}", &repo_url, e))?
-            .into_iter();
-        let tags_to_check = all_tags.rev().take(self.max_number_of_releases_to_check);
-        let tag_names = tags_to_check.map(|tag| tag.name).collect::<HashSet<_>>();
-
-        let all_commits_for_each_tag = self
-            .retriever
-            .commits_for_each_tag(&repo_url)
-            .await
-            .map_err(|e| {
-                anyhow!(
-                    "unable to retrieve commits for each tag for repo {}: {}",
-                    &repo_url,
-                    e
-                )
-            })?;
-
-        let commits_to_check = all_commits_for_each_tag
-            .into_iter()
-            .filter(|(key, _)| tag_names.contains(key))
-            .flat_map(|(_, value)| value)
-            .unique_by(|commit| commit.id.clone());
-
-        let authors_in_all_releases = commits_to_check.map(|commit| commit.author_email);
-
-        let number_of_different_authors = authors_in_all_releases
-            .dedup_with_count()
-            .collect::<Vec<_>>();
-
-        let all_authors_count: usize = number_of_different_authors
-            .iter()
-            .map(|(count, _)| count)
-            .sum();
+        let commits_to_check = commits_in_latest_releases(
+            self.retriever.as_ref(),
+            &repo_url,
+            dependency.repository_directory.as_deref(),
+            self.max_number_of_releases_to_check,
+        )
+        .await?;
 
-        let authors_with_rate = number_of_different_authors
-            .into_iter()
-            .map(|(count, author)| (count as f64 / all_authors_count as f64, author));
+        let authors_with_rate = ratio_by_author(
+            commits_to_check
+                .into_iter()
+                .map(|commit| self.author_identity(&commit.author_email)),
+        );
 
         for (rate, author) in authors_with_rate {
             if rate > self.max_contributor_ratio && self.max_contributor_ratio > 0.0 {
@@ -76,6 +61,12 @@ impl Policy for ContributorsRatio {
                         rate, self.max_contributor_ratio, author
                     ),
                     fail_score,
+                    severity: Severity::Error,
+                    measurement: Some(
+                        Measurement::new(rate, self.max_contributor_ratio)
+                            .with_detail("author", &author)
+                            .with_detail("releases", &self.max_number_of_releases_to_check),
+                    ),
                 });
             }
         }
@@ -91,11 +82,53 @@ impl ContributorsRatio {
         retriever: R,
         max_number_of_releases_to_check: usize,
         max_contributor_ratio: f64,
+        normalize_emails: bool,
+        author_aliases: &HashMap<String, Vec<String>>,
     ) -> Self {
+        let author_by_email = author_aliases
+            .iter()
+            .flat_map(|(author, emails)| {
+                emails.iter().map(move |email| {
+                    (
+                        Self::normalized_email(email, normalize_emails),
+                        author.clone(),
+                    )
+                })
+            })
+            .collect();
+
         Self {
             retriever: retriever.into(),
             max_number_of_releases_to_check,
             max_contributor_ratio,
+            normalize_emails,
+            author_by_email,
+        }
+    }
+
+    /// Returns the author identity of an email, which is the author it is aliased to, if any, or
+    /// the email itself, normalized when enabled.
+    fn author_identity(&self, author_email: &str) -> String {
+        let email = Self::normalized_email(author_email, self.normalize_emails);
+
+        self.author_by_email.get(&email).cloned().unwrap_or(email)
+    }
+
+    /// Lowercases the email and turns GitHub noreply emails like
+    /// `12345+name@users.noreply.github.com` into the user name.
+    fn normalized_email(email: &str, normalize_emails: bool) -> String {
+        if !normalize_emails {
+            return email.to_string();
+        }
+
+        let email = email.trim().to_lowercase();
+        let Some(user) = email.strip_suffix(GITHUB_NOREPLY_EMAIL_DOMAIN) else {
+            return email;
+        };
+
+        match user.split_once('+') {
+            Some((id, name)) if id.chars().all(|c| c.is_ascii_digit()) => name.to_string(),
+            _ => user.to_string(),
         }
     }
 }
@@ -124,16 +157,19 @@ mod tests {
                             name: "v0.1.2".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_640_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.3".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_641_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.4".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_642_477_360,
+                            is_signed: false,
                         },
                     ])
                 });
@@ -162,7 +198,8 @@ mod tests {
             });
             Box::new(retriever) as Box<dyn CommitRetriever>
         };
-        let contributors_ratio_policy = ContributorsRatio::new(retriever, 1, 0.9);
+        let contributors_ratio_policy =
+            ContributorsRatio::new(retriever, 1, 0.9, false, &HashMap::new());
 
         let dependency = Dependency {
             repository: GitHub {
@@ -195,16 +232,19 @@ mod tests {
                             name: "v0.1.2".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_640_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.3".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_641_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.4".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_642_477_360,
+                            is_signed: false,
                         },
                     ])
                 });
@@ -225,7 +265,8 @@ mod tests {
             });
             Box::new(retriever) as Box<dyn CommitRetriever>
         };
-        let contributors_ratio_policy = ContributorsRatio::new(retriever, 1, 0.9);
+        let contributors_ratio_policy =
+            ContributorsRatio::new(retriever, 1, 0.9, false, &HashMap::new());
 
         let dependency = Dependency {
             repository: GitHub {
@@ -243,6 +284,7 @@ mod tests {
                 dependency: dep,
                 reason,
                 fail_score,
+                ..
             } => {
                 assert_eq!(policy_name, "contributors_ratio");
                 assert_eq!(dep, dependency);
@@ -252,9 +294,126 @@ mod tests {
                 );
                 assert!((fail_score - 1.111_111_111_111_111_2).abs() < f64::EPSILON);
             }
-            Evaluation::Pass { .. } => {
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
+                unreachable!()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn if_the_emails_are_normalized_the_github_noreply_emails_of_an_author_are_the_same_contributor(
+    ) {
+        let retriever = retriever_with_authors(&[
+            "12345+SomeAuthor@users.noreply.github.com",
+            "someauthor@users.noreply.github.com",
+            "SomeOtherAuthor@example.com",
+        ]);
+        let contributors_ratio_policy =
+            ContributorsRatio::new(retriever, 1, 0.6, true, &HashMap::new());
+
+        let result = contributors_ratio_policy.evaluate(&dependency()).await;
+
+        match result.unwrap() {
+            Evaluation::Fail { reason, .. } => {
+                assert_eq!(
+                    reason,
+                    "the rate of contribution is too high (0.6666666666666666 > 0.6) for author someauthor"
+                );
+            }
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
                 unreachable!()
             }
         }
     }
+
+    #[tokio::test]
+    async fn if_the_emails_are_not_normalized_the_github_noreply_emails_are_different_contributors()
+    {
+        let retriever = retriever_with_authors(&[
+            "12345+SomeAuthor@users.noreply.github.com",
+            "someauthor@users.noreply.github.com",
+            "SomeOtherAuthor@example.com",
+        ]);
+        let contributors_ratio_policy =
+            ContributorsRatio::new(retriever, 1, 0.6, false, &HashMap::new());
+
+        let result = contributors_ratio_policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "contributors_ratio".to_string(),
+                dependency: dependency()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn the_aliased_emails_are_the_same_contributor() {
+        let retriever = retriever_with_authors(&[
+            "12345+SomeAuthor@users.noreply.github.com",
+            "Some.Author@Company.com",
+            "SomeOtherAuthor@example.com",
+        ]);
+        let author_aliases = HashMap::from([(
+            "some_author".to_string(),
+            vec![
+                "someauthor".to_string(),
+                "some.author@company.com".to_string(),
+            ],
+        )]);
+        let contributors_ratio_policy =
+            ContributorsRatio::new(retriever, 1, 0.6, true, &author_aliases);
+
+        let result = contributors_ratio_policy.evaluate(&dependency()).await;
+
+        match result.unwrap() {
+            Evaluation::Fail { reason, .. } => {
+                assert_eq!(
+                    reason,
+                    "the rate of contribution is too high (0.6666666666666666 > 0.6) for author some_author"
+                );
+            }
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn retriever_with_authors(author_emails: &[&str]) -> Box<dyn CommitRetriever> {
+        let commits = author_emails
+            .iter()
+            .enumerate()
+            .map(|(id, author_email)| Commit {
+                id: id.to_string(),
+                author_name: "SomeName".to_string(),
+                author_email: (*author_email).to_string(),
+                creation_timestamp: 0,
+            })
+            .collect::<Vec<_>>();
+
+        let mut retriever = MockCommitRetriever::new();
+        retriever.expect_all_tags().returning(|_| {
+            Ok(vec![Tag {
+                name: "v0.1.4".to_string(),
+                commit_id: "234234231".to_string(),
+                commit_timestamp: 1_642_477_360,
+                is_signed: false,
+            }])
+        });
+        retriever
+            .expect_commits_for_each_tag()
+            .return_once(move |_| Ok(HashMap::from([("v0.1.4".to_string(), commits)])));
+        Box::new(retriever)
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
 }
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::pkg::policy::{ContributionDataRetriever, IssueWindow, Measurement, Severity};
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the repository closes too few of the issues opened in the window, e.g. fewer than
+/// half of them, which means its backlog keeps growing.
+pub struct IssueCloseRate {
+    min_close_rate: f64,
+    window: IssueWindow,
+    contribution_data_retriever: Arc<dyn ContributionDataRetriever>,
+}
+
+#[async_trait]
+impl Policy for IssueCloseRate {
+    fn name(&self) -> &'static str {
+        "issue_close_rate"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let close_rate = self
+            .contribution_data_retriever
+            .get_issue_close_rate(&dependency.repository, self.window)
+            .await
+            .map_err(|e| anyhow!("error retrieving issue close rate: {}", e))?;
+
+        if close_rate < self.min_close_rate {
+            Ok(Evaluation::Fail {
+                policy_name: "issue_close_rate".to_string(),
+                dependency: dependency.clone(),
+                reason: format!(
+                    "the ratio of closed to opened issues is {}, which is lower than the minimum allowed ratio of {}",
+                    close_rate, self.min_close_rate
+                ),
+                fail_score: (self.min_close_rate - close_rate) / self.min_close_rate,
+                severity: Severity::Error,
+                measurement: Some(self.window.add_detail_to(
+                    Measurement::new(close_rate, self.min_close_rate),
+                    "last_issues",
+                )),
+            })
+        } else {
+            Ok(Evaluation::Pass {
+                policy_name: "issue_close_rate".to_string(),
+                dependency: dependency.clone(),
+            })
+        }
+    }
+}
+
+impl IssueCloseRate {
+    pub fn new<C: Into<Arc<dyn ContributionDataRetriever>>>(
+        contribution_data_retriever: C,
+        min_close_rate: f64,
+        window: IssueWindow,
+    ) -> Self {
+        Self {
+            contribution_data_retriever: contribution_data_retriever.into(),
+            min_close_rate,
+            window,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::super::{ContributionDataRetriever, MockContributionDataRetriever, Policy};
+    use super::*;
+    use crate::pkg::Repository::GitHub;
+    use crate::{Dependency, Evaluation};
+
+    #[tokio::test]
+    async fn it_passes_if_the_close_rate_is_higher_than_the_minimum_allowed() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_issue_close_rate()
+                .with(eq(dependency().repository), eq(IssueWindow::Days(90)))
+                .return_once(|_, _| Ok(0.8));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let issue_close_rate = IssueCloseRate::new(retriever, 0.5, IssueWindow::Days(90));
+
+        let evaluation = issue_close_rate.evaluate(&dependency()).await;
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Pass {
+                policy_name: "issue_close_rate".to_string(),
+                dependency: dependency()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_fails_if_the_close_rate_is_lower_than_the_minimum_allowed() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_issue_close_rate()
+                .return_once(|_, _| Ok(0.25));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let issue_close_rate = IssueCloseRate::new(retriever, 0.5, IssueWindow::Last(100));
+
+        let evaluation = issue_close_rate.evaluate(&dependency()).await;
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "issue_close_rate".to_string(),
+                dependency: dependency(),
+                reason: "the ratio of closed to opened issues is 0.25, which is lower than the minimum allowed ratio of 0.5".to_string(),
+                fail_score: 0.5,
+                severity: Severity::Error,
+                measurement: Some(
+                    Measurement::new(0.25, 0.5).with_detail("last_issues", &100)
+                ),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_returns_an_error_if_the_close_rate_cannot_be_retrieved() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_issue_close_rate()
+                .return_once(|_, _| Err("unknown repository".into()));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let issue_close_rate = IssueCloseRate::new(retriever, 0.5, IssueWindow::Last(100));
+
+        let evaluation = issue_close_rate.evaluate(&dependency()).await;
+        assert!(evaluation.is_err());
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
@@ -3,21 +3,29 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use async_trait::async_trait;
 
-use crate::pkg::policy::ContributionDataRetriever;
+use crate::pkg::policy::{ContributionDataRetriever, IssueWindow, Measurement, Severity};
 use crate::{Dependency, Evaluation, Policy};
 
 pub struct MaxIssueLifespan {
     max_issue_lifespan: f64,
-    last_issues: usize,
+    window: IssueWindow,
     contribution_data_retriever: Arc<dyn ContributionDataRetriever>,
 }
 
 #[async_trait]
 impl Policy for MaxIssueLifespan {
+    fn name(&self) -> &'static str {
+        "max_issue_lifespan"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
     async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
         let issue_lifespan = self
             .contribution_data_retriever
-            .get_issue_lifespan(&dependency.repository, self.last_issues)
+            .get_issue_lifespan(&dependency.repository, self.window)
             .await
             .map_err(|e| anyhow!("error retrieving issue lifespan: {}", e))?;
 
@@ -27,7 +35,7 @@ impl Policy for MaxIssueLifespan {
             } else {
                 issue_lifespan / self.max_issue_lifespan
             };
-            Ok(Evaluation::Fail{policy_name: "max_issue_lifespan".to_string(), dependency: dependency.clone(), reason: format!("the issue lifespan is {} seconds, which is greater than the maximum allowed lifespan of {} seconds", issue_lifespan, self.max_issue_lifespan), fail_score})
+            Ok(Evaluation::Fail{policy_name: "max_issue_lifespan".to_string(), dependency: dependency.clone(), reason: format!("the issue lifespan is {} seconds, which is greater than the maximum allowed lifespan of {} seconds", issue_lifespan, self.max_issue_lifespan), fail_score, severity: Severity::Error, measurement: Some(self.window.add_detail_to(Measurement::new(issue_lifespan, self.max_issue_lifespan), "last_issues"))})
         } else {
             Ok(Evaluation::Pass {
                 policy_name: "max_issue_lifespan".to_string(),
@@ -41,12 +49,12 @@ impl MaxIssueLifespan {
     pub fn new<C: Into<Arc<dyn ContributionDataRetriever>>>(
         contribution_data_retriever: C,
         max_issue_lifespan: f64,
-        last_issues: usize,
+        window: IssueWindow,
     ) -> Self {
         Self {
             contribution_data_retriever: contribution_data_retriever.into(),
             max_issue_lifespan,
-            last_issues,
+            window,
         }
     }
 }
@@ -55,6 +63,7 @@ impl MaxIssueLifespan {
 mod tests {
     use super::super::{ContributionDataRetriever, MockContributionDataRetriever, Policy};
     use super::*;
+    use crate::pkg::DependencyKind;
     use crate::pkg::Repository::GitHub;
     use crate::{Dependency, Evaluation};
 
@@ -69,7 +78,11 @@ mod tests {
         };
 
         let max_allowed_issue_lifespan = 100_f64;
-        let issue_lifespan = MaxIssueLifespan::new(retriever, max_allowed_issue_lifespan, 100);
+        let issue_lifespan = MaxIssueLifespan::new(
+            retriever,
+            max_allowed_issue_lifespan,
+            IssueWindow::Last(100),
+        );
 
         let evaluation = issue_lifespan.evaluate(&dependency()).await;
         assert_eq!(
@@ -92,7 +105,11 @@ mod tests {
         };
 
         let max_allowed_issue_lifespan = 100_f64;
-        let issue_lifespan = MaxIssueLifespan::new(retriever, max_allowed_issue_lifespan, 100);
+        let issue_lifespan = MaxIssueLifespan::new(
+            retriever,
+            max_allowed_issue_lifespan,
+            IssueWindow::Last(100),
+        );
 
         let evaluation = issue_lifespan.evaluate(&dependency()).await;
         match evaluation.unwrap() {
@@ -101,13 +118,14 @@ mod tests {
                 dependency: dep,
                 reason,
                 fail_score,
+                ..
             } => {
                 assert_eq!(policy_name, "max_issue_lifespan");
                 assert_eq!(dep, dependency());
                 assert_eq!(reason, "the issue lifespan is 102 seconds, which is greater than the maximum allowed lifespan of 100 seconds");
                 assert!((fail_score - 1.02).abs() < f64::EPSILON);
             }
-            Evaluation::Pass { .. } => {
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
                 unreachable!()
             }
         }
@@ -122,6 +140,12 @@ mod tests {
                 organization: "some_org".to_string(),
                 name: "some_name".to_string(),
             },
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
         }
     }
 }
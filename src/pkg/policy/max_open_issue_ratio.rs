@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::pkg::policy::{ContributionDataRetriever, IssueWindow, Measurement, Severity};
+use crate::{Dependency, Evaluation, Policy};
+
+pub struct MaxOpenIssueRatio {
+    max_ratio: f64,
+    window: IssueWindow,
+    contribution_data_retriever: Arc<dyn ContributionDataRetriever>,
+}
+
+#[async_trait]
+impl Policy for MaxOpenIssueRatio {
+    fn name(&self) -> &'static str {
+        "max_open_issue_ratio"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let open_issue_ratio = self
+            .contribution_data_retriever
+            .get_open_issue_ratio(&dependency.repository, self.window)
+            .await
+            .map_err(|e| anyhow!("error retrieving open issue ratio: {}", e))?;
+
+        if open_issue_ratio > self.max_ratio {
+            let fail_score = if self.max_ratio == 0.0 {
+                1.0
+            } else {
+                open_issue_ratio / self.max_ratio
+            };
+            Ok(Evaluation::Fail {
+                policy_name: "max_open_issue_ratio".to_string(),
+                dependency: dependency.clone(),
+                reason: format!(
+                    "the ratio of open issues is {}, which is greater than the maximum allowed ratio of {}",
+                    open_issue_ratio, self.max_ratio
+                ),
+                fail_score,
+                severity: Severity::Error,
+                measurement: Some(self.window.add_detail_to(Measurement::new(open_issue_ratio, self.max_ratio), "last_issues")),
+            })
+        } else {
+            Ok(Evaluation::Pass {
+                policy_name: "max_open_issue_ratio".to_string(),
+                dependency: dependency.clone(),
+            })
+        }
+    }
+}
+
+impl MaxOpenIssueRatio {
+    pub fn new<C: Into<Arc<dyn ContributionDataRetriever>>>(
+        contribution_data_retriever: C,
+        max_ratio: f64,
+        window: IssueWindow,
+    ) -> Self {
+        Self {
+            contribution_data_retriever: contribution_data_retriever.into(),
+            max_ratio,
+            window,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::super::{ContributionDataRetriever, MockContributionDataRetriever, Policy};
+    use super::*;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository::GitHub;
+    use crate::{Dependency, Evaluation};
+
+    #[tokio::test]
+    async fn it_passes_if_the_open_issue_ratio_is_lower_than_the_maximum_allowed() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_open_issue_ratio()
+                .with(eq(dependency().repository), eq(IssueWindow::Last(100)))
+                .return_once(|_, _| Ok(0.2));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let open_issue_ratio = MaxOpenIssueRatio::new(retriever, 0.5, IssueWindow::Last(100));
+
+        let evaluation = open_issue_ratio.evaluate(&dependency()).await;
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Pass {
+                policy_name: "max_open_issue_ratio".to_string(),
+                dependency: dependency()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_fails_if_the_open_issue_ratio_is_higher_than_the_maximum_allowed() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_open_issue_ratio()
+                .return_once(|_, _| Ok(0.75));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let open_issue_ratio = MaxOpenIssueRatio::new(retriever, 0.5, IssueWindow::Last(100));
+
+        let evaluation = open_issue_ratio.evaluate(&dependency()).await;
+        match evaluation.unwrap() {
+            Evaluation::Fail {
+                policy_name,
+                dependency: dep,
+                reason,
+                fail_score,
+                ..
+            } => {
+                assert_eq!(policy_name, "max_open_issue_ratio");
+                assert_eq!(dep, dependency());
+                assert_eq!(
+                    reason,
+                    "the ratio of open issues is 0.75, which is greater than the maximum allowed ratio of 0.5"
+                );
+                assert!((fail_score - 1.5).abs() < f64::EPSILON);
+            }
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
+                unreachable!()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn it_measures_the_open_issue_ratio_of_the_issues_created_in_the_last_days() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_open_issue_ratio()
+                .with(eq(dependency().repository), eq(IssueWindow::Days(30)))
+                .return_once(|_, _| Ok(0.75));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let open_issue_ratio = MaxOpenIssueRatio::new(retriever, 0.5, IssueWindow::Days(30));
+
+        let evaluation = open_issue_ratio.evaluate(&dependency()).await;
+        let Evaluation::Fail { measurement, .. } = evaluation.unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(
+            measurement,
+            Some(Measurement::new(0.75, 0.5).with_detail("since_days", &30))
+        );
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            name: "foo".to_string(),
+            version: "1.2.3".to_string(),
+            latest_version: Some("1.2.4".to_string()),
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_name".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
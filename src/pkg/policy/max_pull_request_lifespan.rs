@@ -3,21 +3,29 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use async_trait::async_trait;
 
-use crate::pkg::policy::ContributionDataRetriever;
+use crate::pkg::policy::{ContributionDataRetriever, IssueWindow, Measurement, Severity};
 use crate::{Dependency, Evaluation, Policy};
 
 pub struct MaxPullRequestLifespan {
     max_issue_lifespan: f64,
-    last_issues: usize,
+    window: IssueWindow,
     contribution_data_retriever: Arc<dyn ContributionDataRetriever>,
 }
 
 #[async_trait]
 impl Policy for MaxPullRequestLifespan {
+    fn name(&self) -> &'static str {
+        "max_pull_request_lifespan"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
     async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
         let issue_lifespan = self
             .contribution_data_retriever
-            .get_pull_request_lifespan(&dependency.repository, self.last_issues)
+            .get_pull_request_lifespan(&dependency.repository, self.window)
             .await
             .map_err(|e| anyhow!("error retrieving pull request lifespan: {}", e))?;
 
@@ -32,6 +40,8 @@ impl Policy for MaxPullRequestLifespan {
                 dependency: dependency.clone(),
                 reason: format!("the pull request lifespan is {} seconds, which is greater than the maximum allowed lifespan of {} seconds", issue_lifespan, self.max_issue_lifespan),  
                 fail_score,
+                severity: Severity::Error,
+                measurement: Some(self.window.add_detail_to(Measurement::new(issue_lifespan, self.max_issue_lifespan), "last_pull_requests")),
             })
         } else {
             Ok(Evaluation::Pass {
@@ -46,12 +56,12 @@ impl MaxPullRequestLifespan {
     pub fn new<C: Into<Arc<dyn ContributionDataRetriever>>>(
         contribution_data_retriever: C,
         max_issue_lifespan: f64,
-        last_issues: usize,
+        window: IssueWindow,
     ) -> Self {
         Self {
             contribution_data_retriever: contribution_data_retriever.into(),
             max_issue_lifespan,
-            last_issues,
+            window,
         }
     }
 }
@@ -61,6 +71,7 @@ mod tests {
 
     use super::super::{ContributionDataRetriever, MockContributionDataRetriever, Policy};
     use super::*;
+    use crate::pkg::DependencyKind;
     use crate::pkg::Repository::GitHub;
     use crate::{Dependency, Evaluation};
 
@@ -75,8 +86,11 @@ mod tests {
         };
 
         let max_allowed_issue_lifespan = 100_f64;
-        let issue_lifespan =
-            MaxPullRequestLifespan::new(retriever, max_allowed_issue_lifespan, 100);
+        let issue_lifespan = MaxPullRequestLifespan::new(
+            retriever,
+            max_allowed_issue_lifespan,
+            IssueWindow::Last(100),
+        );
 
         let evaluation = issue_lifespan.evaluate(&dependency()).await;
         assert_eq!(
@@ -99,8 +113,11 @@ mod tests {
         };
 
         let max_allowed_issue_lifespan = 100_f64;
-        let issue_lifespan =
-            MaxPullRequestLifespan::new(retriever, max_allowed_issue_lifespan, 100);
+        let issue_lifespan = MaxPullRequestLifespan::new(
+            retriever,
+            max_allowed_issue_lifespan,
+            IssueWindow::Last(100),
+        );
 
         let evaluation = issue_lifespan.evaluate(&dependency()).await;
         match evaluation.unwrap() {
@@ -109,6 +126,7 @@ mod tests {
                 dependency: dep,
                 reason,
                 fail_score,
+                ..
             } => {
                 assert_eq!(policy_name, "max_pull_request_lifespan".to_string());
                 assert_eq!(dep, dependency());
@@ -118,7 +136,7 @@ mod tests {
                 );
                 assert!((fail_score - 1.02).abs() < f64::EPSILON);
             }
-            Evaluation::Pass { .. } => {
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
                 unreachable!()
             }
         }
@@ -133,6 +151,12 @@ mod tests {
                 organization: "some_org".to_string(),
                 name: "some_name".to_string(),
             },
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
         }
     }
 }
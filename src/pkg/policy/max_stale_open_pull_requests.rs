@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::pkg::policy::{ContributionDataRetriever, IssueWindow, Measurement, Severity};
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when too many pull requests have been left open for too long, which shows that the
+/// maintainers are not responsive to the contributions.
+pub struct MaxStaleOpenPullRequests {
+    max_pull_requests: usize,
+    stale_after_days: u64,
+    window: IssueWindow,
+    contribution_data_retriever: Arc<dyn ContributionDataRetriever>,
+}
+
+#[async_trait]
+impl Policy for MaxStaleOpenPullRequests {
+    fn name(&self) -> &'static str {
+        "max_stale_open_pull_requests"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let stale_open_pull_requests = self
+            .contribution_data_retriever
+            .get_stale_open_pr_count(&dependency.repository, self.window, self.stale_after_days)
+            .await
+            .map_err(|e| anyhow!("error retrieving stale open pull requests: {}", e))?;
+
+        if stale_open_pull_requests > self.max_pull_requests {
+            let fail_score = if self.max_pull_requests == 0 {
+                1.0
+            } else {
+                stale_open_pull_requests as f64 / self.max_pull_requests as f64
+            };
+            let measurement = Measurement::new(
+                stale_open_pull_requests as f64,
+                self.max_pull_requests as f64,
+            )
+            .with_detail("stale_after_days", &self.stale_after_days);
+            Ok(Evaluation::Fail {
+                policy_name: "max_stale_open_pull_requests".to_string(),
+                dependency: dependency.clone(),
+                reason: format!(
+                    "there are {} pull requests open for more than {} days, which is more than the maximum of {}",
+                    stale_open_pull_requests, self.stale_after_days, self.max_pull_requests
+                ),
+                fail_score,
+                severity: Severity::Error,
+                measurement: Some(
+                    self.window
+                        .add_detail_to(measurement, "last_pull_requests"),
+                ),
+            })
+        } else {
+            Ok(Evaluation::Pass {
+                policy_name: "max_stale_open_pull_requests".to_string(),
+                dependency: dependency.clone(),
+            })
+        }
+    }
+}
+
+impl MaxStaleOpenPullRequests {
+    pub fn new<C: Into<Arc<dyn ContributionDataRetriever>>>(
+        contribution_data_retriever: C,
+        max_pull_requests: usize,
+        stale_after_days: u64,
+        window: IssueWindow,
+    ) -> Self {
+        Self {
+            contribution_data_retriever: contribution_data_retriever.into(),
+            max_pull_requests,
+            stale_after_days,
+            window,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::super::{ContributionDataRetriever, MockContributionDataRetriever, Policy};
+    use super::*;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository::GitHub;
+    use crate::{Dependency, Evaluation};
+
+    #[tokio::test]
+    async fn it_passes_if_the_stale_open_pull_requests_are_not_more_than_the_maximum_allowed() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_stale_open_pr_count()
+                .with(
+                    eq(dependency().repository),
+                    eq(IssueWindow::Last(300)),
+                    eq(90),
+                )
+                .return_once(|_, _, _| Ok(10));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let policy = MaxStaleOpenPullRequests::new(retriever, 10, 90, IssueWindow::Last(300));
+
+        let evaluation = policy.evaluate(&dependency()).await;
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Pass {
+                policy_name: "max_stale_open_pull_requests".to_string(),
+                dependency: dependency()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_fails_if_the_stale_open_pull_requests_are_more_than_the_maximum_allowed() {
+        let retriever = {
+            let mut retriever = MockContributionDataRetriever::new();
+            retriever
+                .expect_get_stale_open_pr_count()
+                .return_once(|_, _, _| Ok(15));
+            Box::new(retriever) as Box<dyn ContributionDataRetriever>
+        };
+
+        let policy = MaxStaleOpenPullRequests::new(retriever, 10, 90, IssueWindow::Last(300));
+
+        let evaluation = policy.evaluate(&dependency()).await;
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "max_stale_open_pull_requests".to_string(),
+                dependency: dependency(),
+                reason: "there are 15 pull requests open for more than 90 days, which is more than the maximum of 10"
+                    .to_string(),
+                fail_score: 1.5,
+                severity: Severity::Error,
+                measurement: Some(
+                    Measurement::new(15.0, 10.0)
+                        .with_detail("stale_after_days", &90)
+                        .with_detail("last_pull_requests", &300)
+                ),
+            }
+        );
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            name: "foo".to_string(),
+            version: "1.2.3".to_string(),
+            latest_version: Some("1.2.4".to_string()),
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_name".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use super::{commit_retrieval_error, Clock, CommitRetriever, Evaluation};
+use crate::pkg::policy::{Measurement, Policy, Severity};
+use crate::Dependency;
+
+const DAYS_TO_SECONDS: u64 = 24 * 60 * 60;
+
+pub struct MaxTimeSinceLastCommit {
+    retriever: Arc<dyn CommitRetriever>,
+    max_days: u64,
+    clock: Box<dyn Clock>,
+}
+
+#[async_trait]
+impl Policy for MaxTimeSinceLastCommit {
+    fn name(&self) -> &'static str {
+        "max_days_since_last_commit"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let repository_url = dependency
+            .repository
+            .url()
+            .context("the repository did not contain a URL")?;
+        let latest_commit_timestamp = self
+            .retriever
+            .latest_commit_timestamp(&repository_url)
+            .await
+            .map_err(|e| {
+                commit_retrieval_error(e.as_ref(), "error retrieving the latest commit".to_string())
+            })?;
+
+        let seconds_since_last_commit = self
+            .clock
+            .now_timestamp()
+            .saturating_sub(latest_commit_timestamp);
+        let max_seconds = self.max_days * DAYS_TO_SECONDS;
+
+        if seconds_since_last_commit <= max_seconds {
+            return Ok(Evaluation::Pass {
+                policy_name: "max_days_since_last_commit".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let fail_score =
+            (seconds_since_last_commit - max_seconds) as f64 / max_seconds.max(1) as f64;
+        #[allow(clippy::cast_precision_loss)]
+        let measurement = Measurement::new(
+            (seconds_since_last_commit / DAYS_TO_SECONDS) as f64,
+            self.max_days as f64,
+        );
+        Ok(Evaluation::Fail {
+            policy_name: "max_days_since_last_commit".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "the last commit was {} days ago, which is more than the maximum of {} days",
+                seconds_since_last_commit / DAYS_TO_SECONDS,
+                self.max_days
+            ),
+            fail_score,
+            severity: Severity::Error,
+            measurement: Some(measurement),
+        })
+    }
+}
+
+impl MaxTimeSinceLastCommit {
+    pub fn new<R>(retriever: R, max_days: u64, clock: Box<dyn Clock>) -> Self
+    where
+        R: Into<Arc<dyn CommitRetriever>>,
+    {
+        Self {
+            retriever: retriever.into(),
+            max_days,
+            clock,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::super::{MockClock, MockCommitRetriever};
+    use super::*;
+    use crate::pkg::Repository::GitHub;
+
+    const NOW: u64 = 1_648_583_009;
+
+    #[tokio::test]
+    async fn when_the_last_commit_is_recent_it_should_pass_the_policy_evaluation() {
+        let policy = MaxTimeSinceLastCommit::new(
+            retriever_with_latest_commit_at(NOW - 10 * DAYS_TO_SECONDS),
+            30,
+            clock(),
+        );
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "max_days_since_last_commit".to_string(),
+                dependency,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_last_commit_is_too_old_it_should_fail_proportionally_to_the_excess() {
+        let policy = MaxTimeSinceLastCommit::new(
+            retriever_with_latest_commit_at(NOW - 45 * DAYS_TO_SECONDS),
+            30,
+            clock(),
+        );
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "max_days_since_last_commit".to_string(),
+                dependency,
+                reason:
+                    "the last commit was 45 days ago, which is more than the maximum of 30 days"
+                        .to_string(),
+                fail_score: 0.5,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(45.0, 30.0)),
+            }
+        );
+    }
+
+    fn retriever_with_latest_commit_at(timestamp: u64) -> Box<dyn CommitRetriever> {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_latest_commit_timestamp()
+            .with(eq("https://github.com/some_org/some_repo"))
+            .returning(move |_| Ok(timestamp));
+        Box::new(retriever)
+    }
+
+    fn clock() -> Box<dyn Clock> {
+        let mut clock = MockClock::new();
+        clock.expect_now_timestamp().return_const(NOW);
+        Box::new(clock)
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
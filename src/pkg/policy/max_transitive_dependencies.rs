@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use log::info;
+
+use crate::pkg::policy::{Measurement, Severity};
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the dependency pulls in more packages than the maximum, directly or transitively, as
+/// each of them widens the attack surface. Only the lock files that record which packages each one
+/// requires, like the npm and yarn ones, tell the number of packages.
+pub struct MaxTransitiveDependencies {
+    max_transitive: usize,
+}
+
+#[async_trait]
+impl Policy for MaxTransitiveDependencies {
+    fn name(&self) -> &'static str {
+        "max_transitive_dependencies"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let Some(transitive_dependencies) = dependency.transitive_dependencies else {
+            info!(
+                "the lock file of {} doesn't record the packages it pulls in",
+                dependency.name
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "max_transitive_dependencies".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        if transitive_dependencies <= self.max_transitive {
+            return Ok(Evaluation::Pass {
+                policy_name: "max_transitive_dependencies".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let fail_score = if self.max_transitive == 0 {
+            1.0
+        } else {
+            transitive_dependencies as f64 / self.max_transitive as f64
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let measurement =
+            Measurement::new(transitive_dependencies as f64, self.max_transitive as f64);
+        Ok(Evaluation::Fail {
+            policy_name: "max_transitive_dependencies".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "the dependency pulls in {} packages, which is more than the maximum of {}",
+                transitive_dependencies, self.max_transitive
+            ),
+            fail_score,
+            severity: Severity::Error,
+            measurement: Some(measurement),
+        })
+    }
+}
+
+impl MaxTransitiveDependencies {
+    pub fn new(max_transitive: usize) -> Self {
+        Self { max_transitive }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn when_the_dependency_pulls_in_too_many_packages_it_should_fail() {
+        let policy = MaxTransitiveDependencies::new(20);
+
+        let evaluation = policy.evaluate(&dependency(Some(30))).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "max_transitive_dependencies".to_string(),
+                dependency: dependency(Some(30)),
+                reason: "the dependency pulls in 30 packages, which is more than the maximum of 20"
+                    .to_string(),
+                fail_score: 1.5,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(30.0, 20.0)),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_dependency_pulls_in_few_packages_it_should_pass() {
+        let policy = MaxTransitiveDependencies::new(20);
+
+        let evaluation = policy.evaluate(&dependency(Some(20))).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn when_the_lock_file_has_no_tree_it_should_pass() {
+        let policy = MaxTransitiveDependencies::new(0);
+
+        let evaluation = policy.evaluate(&dependency(None)).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    fn dependency(transitive_dependencies: Option<usize>) -> Dependency {
+        Dependency {
+            name: "express".to_string(),
+            version: "4.18.2".to_string(),
+            ecosystem: Some("npm"),
+            transitive_dependencies,
+            ..Dependency::default()
+        }
+    }
+}
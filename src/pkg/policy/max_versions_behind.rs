@@ -0,0 +1,200 @@
+use async_trait::async_trait;
+use log::{info, warn};
+use semver::Version;
+
+use crate::pkg::policy::{Measurement, Severity};
+use crate::{Dependency, Evaluation, Policy};
+
+const MINOR_VERSION_FAIL_SCORE: f64 = 0.1;
+
+/// Fails when the dependency is more major versions behind its latest version than allowed, or
+/// when it is in the latest major version but more minor versions behind than allowed. Each
+/// major version over the limit adds 1.0 to the fail score, and each minor version adds 0.1.
+pub struct MaxVersionsBehind {
+    max_major_behind: u64,
+    max_minor_behind: u64,
+}
+
+#[async_trait]
+impl Policy for MaxVersionsBehind {
+    fn name(&self) -> &'static str {
+        "max_versions_behind"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let pass = Evaluation::Pass {
+            policy_name: "max_versions_behind".to_string(),
+            dependency: dependency.clone(),
+        };
+
+        let Some(latest_version) = &dependency.latest_version else {
+            info!(
+                "skipping the versions behind check for {}: the latest version is unknown",
+                dependency.name
+            );
+            return Ok(pass);
+        };
+
+        let (Ok(version), Ok(latest_version)) = (
+            Version::parse(&dependency.version),
+            Version::parse(latest_version),
+        ) else {
+            warn!(
+                "skipping the versions behind check for {}: {} or {} is not a semver version",
+                dependency.name, dependency.version, latest_version
+            );
+            return Ok(pass);
+        };
+
+        let majors_behind = latest_version.major.saturating_sub(version.major);
+        if majors_behind > self.max_major_behind {
+            #[allow(clippy::cast_precision_loss)]
+            let fail_score = (majors_behind - self.max_major_behind) as f64;
+            #[allow(clippy::cast_precision_loss)]
+            let measurement = Measurement::new(majors_behind as f64, self.max_major_behind as f64)
+                .with_detail("versions", "major")
+                .with_detail("latest_version", &latest_version);
+            return Ok(Evaluation::Fail {
+                policy_name: "max_versions_behind".to_string(),
+                dependency: dependency.clone(),
+                reason: format!(
+                    "{majors_behind} major versions behind the latest version {latest_version}, but the maximum allowed is {}",
+                    self.max_major_behind
+                ),
+                fail_score,
+                severity: Severity::Error,
+                measurement: Some(measurement),
+            });
+        }
+
+        let minors_behind = if majors_behind == 0 {
+            latest_version.minor.saturating_sub(version.minor)
+        } else {
+            0
+        };
+        if minors_behind > self.max_minor_behind {
+            #[allow(clippy::cast_precision_loss)]
+            let fail_score =
+                (minors_behind - self.max_minor_behind) as f64 * MINOR_VERSION_FAIL_SCORE;
+            #[allow(clippy::cast_precision_loss)]
+            let measurement = Measurement::new(minors_behind as f64, self.max_minor_behind as f64)
+                .with_detail("versions", "minor")
+                .with_detail("latest_version", &latest_version);
+            return Ok(Evaluation::Fail {
+                policy_name: "max_versions_behind".to_string(),
+                dependency: dependency.clone(),
+                reason: format!(
+                    "{minors_behind} minor versions behind the latest version {latest_version}, but the maximum allowed is {}",
+                    self.max_minor_behind
+                ),
+                fail_score,
+                severity: Severity::Error,
+                measurement: Some(measurement),
+            });
+        }
+
+        Ok(pass)
+    }
+}
+
+impl MaxVersionsBehind {
+    pub fn new(max_major_behind: u64, max_minor_behind: u64) -> Self {
+        Self {
+            max_major_behind,
+            max_minor_behind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn when_the_dependency_is_within_the_limits_it_should_pass_the_policy_evaluation() {
+        let policy = MaxVersionsBehind::new(1, 2);
+        let dependency = dependency("1.2.3", Some("2.0.0"));
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "max_versions_behind".to_string(),
+                dependency,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_there_are_too_many_major_versions_behind_it_should_fail_the_policy_evaluation() {
+        let policy = MaxVersionsBehind::new(1, 2);
+        let dependency = dependency("1.2.3", Some("4.0.0"));
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "max_versions_behind".to_string(),
+                dependency,
+                reason:
+                    "3 major versions behind the latest version 4.0.0, but the maximum allowed is 1"
+                        .to_string(),
+                fail_score: 2.0,
+                severity: Severity::Error,
+                measurement: Some(
+                    Measurement::new(3.0, 1.0)
+                        .with_detail("versions", "major")
+                        .with_detail("latest_version", "4.0.0")
+                ),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_there_are_too_many_minor_versions_behind_it_should_fail_the_policy_evaluation() {
+        let policy = MaxVersionsBehind::new(1, 2);
+        let dependency = dependency("1.2.3", Some("1.7.0"));
+
+        let result = policy.evaluate(&dependency).await;
+
+        let Evaluation::Fail {
+            reason, fail_score, ..
+        } = result.unwrap()
+        else {
+            panic!("expected the policy evaluation to fail");
+        };
+        assert_eq!(
+            reason,
+            "5 minor versions behind the latest version 1.7.0, but the maximum allowed is 2"
+        );
+        assert!((fail_score - 0.3).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn when_the_versions_are_unknown_or_not_semver_it_should_pass_the_policy_evaluation() {
+        let policy = MaxVersionsBehind::new(0, 0);
+
+        for dependency in [dependency("1.2.3", None), dependency("1.2", Some("4.0"))] {
+            let result = policy.evaluate(&dependency).await;
+
+            assert_eq!(
+                result.unwrap(),
+                Evaluation::Pass {
+                    policy_name: "max_versions_behind".to_string(),
+                    dependency,
+                }
+            );
+        }
+    }
+
+    fn dependency(version: &str, latest_version: Option<&str>) -> Dependency {
+        Dependency {
+            name: "some_dep".to_string(),
+            version: version.to_string(),
+            latest_version: latest_version.map(ToString::to_string),
+            ..Dependency::default()
+        }
+    }
+}
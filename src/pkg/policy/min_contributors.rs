@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+
+use crate::pkg::policy::{
+    commits_in_latest_releases, CommitRetriever, Evaluation, Measurement, Policy, Severity,
+};
+use crate::Dependency;
+
+pub struct MinContributors {
+    retriever: Arc<dyn CommitRetriever>,
+    max_number_of_releases_to_check: usize,
+    min_number_of_contributors: usize,
+}
+
+#[async_trait]
+impl Policy for MinContributors {
+    fn name(&self) -> &'static str {
+        "min_contributors"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let repo_url = dependency
+            .repository
+            .url()
+            .context("the repository doesn't have a URL")?;
+
+        let commits_to_check = commits_in_latest_releases(
+            self.retriever.as_ref(),
+            &repo_url,
+            dependency.repository_directory.as_deref(),
+            self.max_number_of_releases_to_check,
+        )
+        .await?;
+
+        let number_of_contributors = commits_to_check
+            .into_iter()
+            .map(|commit| commit.author_email)
+            .unique()
+            .count();
+
+        if number_of_contributors < self.min_number_of_contributors {
+            let fail_score = (self.min_number_of_contributors - number_of_contributors) as f64
+                / self.min_number_of_contributors as f64;
+            return Ok(Evaluation::Fail {
+                policy_name: "min_contributors".to_string(),
+                dependency: dependency.clone(),
+                reason: format!(
+                    "there are not enough contributors in the last {} releases ({} < {})",
+                    self.max_number_of_releases_to_check,
+                    number_of_contributors,
+                    self.min_number_of_contributors
+                ),
+                fail_score,
+                severity: Severity::Error,
+                measurement: Some(
+                    Measurement::new(
+                        number_of_contributors as f64,
+                        self.min_number_of_contributors as f64,
+                    )
+                    .with_detail("releases", &self.max_number_of_releases_to_check),
+                ),
+            });
+        }
+
+        Ok(Evaluation::Pass {
+            policy_name: "min_contributors".to_string(),
+            dependency: dependency.clone(),
+        })
+    }
+}
+
+impl MinContributors {
+    pub fn new<R: Into<Arc<dyn CommitRetriever>>>(
+        retriever: R,
+        max_number_of_releases_to_check: usize,
+        min_contributors: usize,
+    ) -> Self {
+        Self {
+            retriever: retriever.into(),
+            max_number_of_releases_to_check,
+            min_number_of_contributors: min_contributors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use mockall::predicate::eq;
+
+    use super::super::{Commit, MockCommitRetriever, Tag};
+    use super::*;
+    use crate::pkg::Repository::GitHub;
+
+    #[tokio::test]
+    async fn if_there_are_enough_contributors_in_the_latest_releases_it_should_pass() {
+        let policy = MinContributors::new(retriever(), 2, 2);
+
+        let result = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "min_contributors".to_string(),
+                dependency: dependency(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn if_there_are_not_enough_contributors_in_the_latest_releases_it_should_fail() {
+        let policy = MinContributors::new(retriever(), 1, 4);
+
+        let result = policy.evaluate(&dependency()).await;
+
+        match result.unwrap() {
+            Evaluation::Fail {
+                policy_name,
+                dependency: dep,
+                reason,
+                fail_score,
+                ..
+            } => {
+                assert_eq!(policy_name, "min_contributors");
+                assert_eq!(dep, dependency());
+                assert_eq!(
+                    reason,
+                    "there are not enough contributors in the last 1 releases (1 < 4)"
+                );
+                assert!((fail_score - 0.75).abs() < f64::EPSILON);
+            }
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn retriever() -> Box<dyn CommitRetriever> {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_all_tags()
+            .with(eq("https://github.com/some_org/some_repo"))
+            .returning(|_| {
+                Ok(vec![
+                    Tag {
+                        name: "v0.1.3".to_string(),
+                        commit_id: "234234231".to_string(),
+                        commit_timestamp: 1_641_477_360,
+                        is_signed: false,
+                    },
+                    Tag {
+                        name: "v0.1.4".to_string(),
+                        commit_id: "234234232".to_string(),
+                        commit_timestamp: 1_642_477_360,
+                        is_signed: false,
+                    },
+                ])
+            });
+        retriever.expect_commits_for_each_tag().returning(|_| {
+            Ok({
+                let mut map = HashMap::new();
+                map.insert(
+                    "v0.1.3".to_string(),
+                    vec![commit("1111111", "SomeOtherAuthor")],
+                );
+                map.insert(
+                    "v0.1.4".to_string(),
+                    vec![
+                        commit("2134324", "SomeAuthor"),
+                        commit("3242134", "SomeAuthor"),
+                    ],
+                );
+                map
+            })
+        });
+        Box::new(retriever)
+    }
+
+    fn commit(id: &str, author_email: &str) -> Commit {
+        Commit {
+            id: id.to_string(),
+            author_name: "SomeName".to_string(),
+            author_email: author_email.to_string(),
+            creation_timestamp: 0,
+        }
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
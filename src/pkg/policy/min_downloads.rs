@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::info;
+
+use crate::pkg::policy::{Measurement, Severity};
+use crate::pkg::InfoRetriever;
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the dependency has been downloaded fewer times than the minimum from its registry,
+/// as obscure packages are more likely to be typosquats or unmaintained.
+pub struct MinDownloads {
+    info_retrievers: HashMap<&'static str, Arc<dyn InfoRetriever>>,
+    min_downloads: u64,
+}
+
+#[async_trait]
+impl Policy for MinDownloads {
+    fn name(&self) -> &'static str {
+        "min_downloads"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let Some(info_retriever) = dependency
+            .ecosystem
+            .and_then(|ecosystem| self.info_retrievers.get(ecosystem))
+        else {
+            info!(
+                "the downloads check is not supported for the ecosystem of {}",
+                dependency.name
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "min_downloads".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        let downloads = info_retriever
+            .downloads(&dependency.name)
+            .await
+            .map_err(|e| anyhow!("error retrieving the downloads of the dependency: {}", e))?;
+
+        match downloads {
+            Some(downloads) if downloads < self.min_downloads => {
+                #[allow(clippy::cast_precision_loss)]
+                let fail_score =
+                    (self.min_downloads - downloads) as f64 / self.min_downloads as f64;
+                #[allow(clippy::cast_precision_loss)]
+                let measurement = Measurement::new(downloads as f64, self.min_downloads as f64);
+                Ok(Evaluation::Fail {
+                    policy_name: "min_downloads".to_string(),
+                    dependency: dependency.clone(),
+                    reason: format!(
+                        "the dependency has been downloaded {} times, which is less than the minimum of {}",
+                        downloads, self.min_downloads
+                    ),
+                    fail_score,
+                    severity: Severity::Error,
+                    measurement: Some(measurement),
+                })
+            }
+            _ => Ok(Evaluation::Pass {
+                policy_name: "min_downloads".to_string(),
+                dependency: dependency.clone(),
+            }),
+        }
+    }
+}
+
+impl MinDownloads {
+    /// Creates the policy with the info retrievers of the ecosystems whose registries publish the
+    /// downloads, by ecosystem.
+    pub fn new(
+        info_retrievers: HashMap<&'static str, Arc<dyn InfoRetriever>>,
+        min_downloads: u64,
+    ) -> Self {
+        Self {
+            info_retrievers,
+            min_downloads,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, Repository};
+
+    #[tokio::test]
+    async fn when_the_downloads_are_too_few_it_should_fail_proportionally_to_the_missing_ones() {
+        let mut info_retriever = MockInfoRetriever::new();
+        info_retriever
+            .expect_downloads()
+            .with(eq("serde"))
+            .once()
+            .return_once(|_| Ok(Some(250)));
+        let policy = MinDownloads::new(
+            HashMap::from([("cargo", Arc::new(info_retriever) as Arc<dyn InfoRetriever>)]),
+            1000,
+        );
+
+        let result = policy.evaluate(&dependency(Some("cargo"))).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "min_downloads".to_string(),
+                dependency: dependency(Some("cargo")),
+                reason:
+                    "the dependency has been downloaded 250 times, which is less than the minimum of 1000"
+                        .to_string(),
+                fail_score: 0.75,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(250.0, 1000.0)),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_downloads_are_enough_it_should_pass_the_policy_evaluation() {
+        let mut info_retriever = MockInfoRetriever::new();
+        info_retriever
+            .expect_downloads()
+            .once()
+            .return_once(|_| Ok(Some(262_515_913)));
+        let policy = MinDownloads::new(
+            HashMap::from([("cargo", Arc::new(info_retriever) as Arc<dyn InfoRetriever>)]),
+            1000,
+        );
+
+        let result = policy.evaluate(&dependency(Some("cargo"))).await;
+
+        assert!(matches!(result.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn when_the_ecosystem_is_not_supported_it_should_pass_the_policy_evaluation() {
+        let policy = MinDownloads::new(HashMap::new(), 1000);
+
+        let result = policy.evaluate(&dependency(Some("npm"))).await;
+
+        assert!(matches!(result.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    fn dependency(ecosystem: Option<&'static str>) -> Dependency {
+        Dependency {
+            name: "serde".to_string(),
+            version: "1.0.152".to_string(),
+            latest_version: Some("1.0.152".to_string()),
+            repository: Repository::GitHub {
+                organization: "serde-rs".to_string(),
+                name: "serde".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
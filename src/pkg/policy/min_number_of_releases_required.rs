@@ -1,22 +1,32 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{anyhow, Context};
+use anyhow::Context;
 use async_trait::async_trait;
+use regex::Regex;
 
-use super::{Clock, CommitRetriever, Evaluation};
-use crate::pkg::policy::Policy;
+use super::{commit_retrieval_error, Clock, CommitRetriever, Evaluation};
+use crate::pkg::policy::{Measurement, Policy, Severity};
 use crate::Dependency;
 
 pub struct MinNumberOfReleasesRequired {
     retriever: Arc<dyn CommitRetriever>,
     number_of_releases: usize,
     duration: Duration,
+    tag_pattern: Regex,
     clock: Box<dyn Clock>,
 }
 
 #[async_trait]
 impl Policy for MinNumberOfReleasesRequired {
+    fn name(&self) -> &'static str {
+        "min_number_of_releases_required"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
     async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
         let repository_url = dependency
             .repository
@@ -26,11 +36,14 @@ impl Policy for MinNumberOfReleasesRequired {
             .retriever
             .all_tags(&repository_url)
             .await
-            .map_err(|e| anyhow!("error retrieving all tags: {}", e))?;
+            .map_err(|e| {
+                commit_retrieval_error(e.as_ref(), "error retrieving all tags".to_string())
+            })?;
 
         let now = self.clock.now_timestamp();
         let num_tags_in_range = all_tags
             .iter()
+            .filter(|tag| self.tag_pattern.is_match(&tag.name))
             .rev()
             .take(self.number_of_releases)
             .filter(|&tag| tag.commit_timestamp >= now - self.duration.as_secs())
@@ -45,6 +58,10 @@ impl Policy for MinNumberOfReleasesRequired {
             #[allow(clippy::cast_precision_loss)]
             let fail_score = (self.number_of_releases as f64 - num_tags_in_range as f64)
                 / self.number_of_releases as f64;
+            #[allow(clippy::cast_precision_loss)]
+            let measurement =
+                Measurement::new(num_tags_in_range as f64, self.number_of_releases as f64)
+                    .with_detail("days", &(self.duration.as_secs() / (24 * 60 * 60)));
             Ok(Evaluation::Fail {
                 policy_name: "min_number_of_releases_required".to_string(),
                 dependency: dependency.clone(),
@@ -55,16 +72,20 @@ impl Policy for MinNumberOfReleasesRequired {
                     num_tags_in_range
                 ),
                 fail_score,
+                severity: Severity::Error,
+                measurement: Some(measurement),
             })
         }
     }
 }
 
 impl MinNumberOfReleasesRequired {
+    /// Creates the policy counting as releases only the tags whose name matches the pattern.
     pub fn new<R>(
         retriever: R,
         number_of_releases: usize,
         duration: Duration,
+        tag_pattern: Regex,
         clock: Box<dyn Clock>,
     ) -> Self
     where
@@ -74,6 +95,7 @@ impl MinNumberOfReleasesRequired {
             retriever: retriever.into(),
             number_of_releases,
             duration,
+            tag_pattern,
             clock,
         }
     }
@@ -87,6 +109,7 @@ mod tests {
 
     use super::super::{MockClock, MockCommitRetriever, Tag};
     use super::*;
+    use crate::pkg::config::min_number_of_releases_required::DEFAULT_TAG_PATTERN;
     use crate::pkg::policy::Evaluation;
     use crate::pkg::Repository::GitHub;
     use crate::Dependency;
@@ -105,16 +128,19 @@ mod tests {
                             name: "v0.1.2".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_640_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.3".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_641_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.4".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_642_477_360,
+                            is_signed: false,
                         },
                     ])
                 });
@@ -128,6 +154,7 @@ mod tests {
             retriever,
             2,
             Duration::from_secs(6 * months_in_seconds),
+            tag_pattern(),
             clock,
         );
 
@@ -163,6 +190,7 @@ mod tests {
                         name: "v0.1.2".to_string(),
                         commit_id: "234234231".to_string(),
                         commit_timestamp: 1_640_477_360,
+                        is_signed: false,
                     }])
                 });
             Box::new(retriever) as Box<dyn CommitRetriever>
@@ -175,6 +203,7 @@ mod tests {
             retriever,
             2,
             Duration::from_secs(6 * months_in_seconds),
+            tag_pattern(),
             clock,
         );
         let dependency = Dependency {
@@ -194,6 +223,8 @@ mod tests {
                 dependency,
                 reason: "expected 2 releases in the last 1260 days, but found 1".to_string(),
                 fail_score: 0.5,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(1.0, 2.0).with_detail("days", &1260)),
             }
         );
     }
@@ -211,16 +242,19 @@ mod tests {
                             name: "v0.1.2".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_440_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.3".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_441_477_360,
+                            is_signed: false,
                         },
                         Tag {
                             name: "v0.1.4".to_string(),
                             commit_id: "234234231".to_string(),
                             commit_timestamp: 1_442_477_360,
+                            is_signed: false,
                         },
                     ])
                 });
@@ -234,6 +268,7 @@ mod tests {
             retriever,
             2,
             Duration::from_secs(6 * months_in_seconds),
+            tag_pattern(),
             clock,
         );
 
@@ -254,7 +289,74 @@ mod tests {
                 dependency,
                 reason: "expected 2 releases in the last 1260 days, but found 0".to_string(),
                 fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(0.0, 2.0).with_detail("days", &1260)),
             }
         );
     }
+
+    #[tokio::test]
+    async fn when_the_recent_tags_are_not_releases_they_should_not_be_counted() {
+        let retriever = {
+            let mut retriever = MockCommitRetriever::new();
+            retriever
+                .expect_all_tags()
+                .with(eq("https://github.com/some_org/some_repo"))
+                .returning(|_| {
+                    Ok(vec![
+                        tag("v0.1.2", 1_440_477_360),
+                        tag("v0.1.3", 1_642_477_360),
+                        tag("nightly", 1_645_477_360),
+                        tag("ci-1234", 1_646_477_360),
+                        tag("v0.0.0-test", 1_647_477_360),
+                    ])
+                });
+            Box::new(retriever) as Box<dyn CommitRetriever>
+        };
+        let mut clock = Box::new(MockClock::new());
+        clock.expect_now_timestamp().return_const(1_648_583_009_u64);
+
+        let months_in_seconds = 30 * 7 * 24 * 60 * 60;
+        let number_of_releases_policy = MinNumberOfReleasesRequired::new(
+            retriever,
+            2,
+            Duration::from_secs(6 * months_in_seconds),
+            tag_pattern(),
+            clock,
+        );
+        let dependency = Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        };
+        let result: Result<Evaluation, anyhow::Error> =
+            number_of_releases_policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "min_number_of_releases_required".to_string(),
+                dependency,
+                reason: "expected 2 releases in the last 1260 days, but found 1".to_string(),
+                fail_score: 0.5,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(1.0, 2.0).with_detail("days", &1260)),
+            }
+        );
+    }
+
+    fn tag(name: &str, commit_timestamp: u64) -> Tag {
+        Tag {
+            name: name.to_string(),
+            commit_id: "234234231".to_string(),
+            commit_timestamp,
+            is_signed: false,
+        }
+    }
+
+    fn tag_pattern() -> Regex {
+        Regex::new(DEFAULT_TAG_PATTERN).unwrap()
+    }
 }
@@ -0,0 +1,271 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use log::warn;
+
+use super::{commit_retrieval_error, Clock, CommitRetriever, Evaluation};
+use crate::pkg::policy::{Measurement, Policy, Severity};
+use crate::Dependency;
+
+const DAYS_TO_SECONDS: u64 = 24 * 60 * 60;
+
+pub struct MinRepositoryAge {
+    retriever: Arc<dyn CommitRetriever>,
+    min_age_days: u64,
+    clock: Box<dyn Clock>,
+}
+
+#[async_trait]
+impl Policy for MinRepositoryAge {
+    fn name(&self) -> &'static str {
+        "min_repository_age"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let repository_url = dependency
+            .repository
+            .url()
+            .context("the repository did not contain a URL")?;
+        let Some(creation_timestamp) = self.creation_timestamp(&repository_url).await? else {
+            warn!(
+                "unable to find the age of the repository {}, considering it old enough",
+                repository_url
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "min_repository_age".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        let repository_age = self
+            .clock
+            .now_timestamp()
+            .saturating_sub(creation_timestamp);
+        let min_age = self.min_age_days * DAYS_TO_SECONDS;
+
+        if repository_age >= min_age {
+            return Ok(Evaluation::Pass {
+                policy_name: "min_repository_age".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let fail_score = (min_age - repository_age) as f64 / min_age as f64;
+        #[allow(clippy::cast_precision_loss)]
+        let measurement = Measurement::new(
+            (repository_age / DAYS_TO_SECONDS) as f64,
+            self.min_age_days as f64,
+        );
+        Ok(Evaluation::Fail {
+            policy_name: "min_repository_age".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "the repository is {} days old, which is less than the minimum of {} days",
+                repository_age / DAYS_TO_SECONDS,
+                self.min_age_days
+            ),
+            fail_score,
+            severity: Severity::Error,
+            measurement: Some(measurement),
+        })
+    }
+}
+
+impl MinRepositoryAge {
+    pub fn new<R>(retriever: R, min_age_days: u64, clock: Box<dyn Clock>) -> Self
+    where
+        R: Into<Arc<dyn CommitRetriever>>,
+    {
+        Self {
+            retriever: retriever.into(),
+            min_age_days,
+            clock,
+        }
+    }
+
+    /// Retrieves the timestamp of the oldest tag, falling back to the earliest commit for the
+    /// repositories without tags. Returns none if the repository has no commits either.
+    async fn creation_timestamp(&self, repository_url: &str) -> Result<Option<u64>, anyhow::Error> {
+        let all_tags = self.retriever.all_tags(repository_url).await.map_err(|e| {
+            commit_retrieval_error(
+                e.as_ref(),
+                format!("unable to retrieve all tags for repo {repository_url}"),
+            )
+        })?;
+        if let Some(oldest_tag_timestamp) = all_tags.iter().map(|tag| tag.commit_timestamp).min() {
+            return Ok(Some(oldest_tag_timestamp));
+        }
+
+        match self
+            .retriever
+            .earliest_commit_timestamp(repository_url)
+            .await
+        {
+            Ok(timestamp) => Ok(Some(timestamp)),
+            Err(e) => {
+                warn!(
+                    "unable to retrieve the earliest commit of {}: {}",
+                    repository_url, e
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::super::{MockClock, MockCommitRetriever, Tag};
+    use super::*;
+    use crate::pkg::Repository::GitHub;
+
+    const NOW: u64 = 1_648_583_009;
+    const REPOSITORY_URL: &str = "https://github.com/some_org/some_repo";
+
+    #[tokio::test]
+    async fn when_the_oldest_tag_is_old_enough_it_should_pass_the_policy_evaluation() {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_all_tags()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| {
+                Ok(vec![
+                    tag("v0.1.0", NOW - 100 * DAYS_TO_SECONDS),
+                    tag("v0.2.0", NOW - 10 * DAYS_TO_SECONDS),
+                ])
+            });
+        let policy =
+            MinRepositoryAge::new(Box::new(retriever) as Box<dyn CommitRetriever>, 90, clock());
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "min_repository_age".to_string(),
+                dependency,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_oldest_tag_is_too_recent_it_should_fail_proportionally_to_the_missing_age() {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_all_tags()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| Ok(vec![tag("v0.1.0", NOW - 30 * DAYS_TO_SECONDS)]));
+        let policy = MinRepositoryAge::new(
+            Box::new(retriever) as Box<dyn CommitRetriever>,
+            120,
+            clock(),
+        );
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "min_repository_age".to_string(),
+                dependency,
+                reason: "the repository is 30 days old, which is less than the minimum of 120 days"
+                    .to_string(),
+                fail_score: 0.75,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(30.0, 120.0)),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_there_are_no_tags_it_should_use_the_earliest_commit() {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_all_tags()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| Ok(vec![]));
+        retriever
+            .expect_earliest_commit_timestamp()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| Ok(NOW - 45 * DAYS_TO_SECONDS));
+        let policy =
+            MinRepositoryAge::new(Box::new(retriever) as Box<dyn CommitRetriever>, 90, clock());
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "min_repository_age".to_string(),
+                dependency,
+                reason: "the repository is 45 days old, which is less than the minimum of 90 days"
+                    .to_string(),
+                fail_score: 0.5,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(45.0, 90.0)),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_there_are_no_tags_nor_commits_it_should_pass_the_policy_evaluation() {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_all_tags()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| Ok(vec![]));
+        retriever
+            .expect_earliest_commit_timestamp()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| Err("HEAD does not point to a commit".into()));
+        let policy =
+            MinRepositoryAge::new(Box::new(retriever) as Box<dyn CommitRetriever>, 90, clock());
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "min_repository_age".to_string(),
+                dependency,
+            }
+        );
+    }
+
+    fn tag(name: &str, commit_timestamp: u64) -> Tag {
+        Tag {
+            name: name.to_string(),
+            commit_id: format!("{name}_commit"),
+            commit_timestamp,
+            is_signed: false,
+        }
+    }
+
+    fn clock() -> Box<dyn Clock> {
+        let mut clock = MockClock::new();
+        clock.expect_now_timestamp().return_const(NOW);
+        Box::new(clock)
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
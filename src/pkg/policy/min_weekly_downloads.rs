@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::info;
+
+use crate::pkg::policy::{Measurement, Severity};
+use crate::pkg::InfoRetriever;
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the dependency has been downloaded fewer times than the minimum from its registry in
+/// the last week, which unlike the total downloads doesn't favor the packages that used to be
+/// popular.
+pub struct MinWeeklyDownloads {
+    info_retrievers: HashMap<&'static str, Arc<dyn InfoRetriever>>,
+    min_weekly_downloads: u64,
+}
+
+#[async_trait]
+impl Policy for MinWeeklyDownloads {
+    fn name(&self) -> &'static str {
+        "min_weekly_downloads"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let Some(info_retriever) = dependency
+            .ecosystem
+            .and_then(|ecosystem| self.info_retrievers.get(ecosystem))
+        else {
+            info!(
+                "the weekly downloads check is not supported for the ecosystem of {}",
+                dependency.name
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "min_weekly_downloads".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        let weekly_downloads = info_retriever
+            .weekly_downloads(&dependency.name)
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "error retrieving the weekly downloads of the dependency: {}",
+                    e
+                )
+            })?;
+
+        match weekly_downloads {
+            Some(weekly_downloads) if weekly_downloads < self.min_weekly_downloads => {
+                #[allow(clippy::cast_precision_loss)]
+                let fail_score = (self.min_weekly_downloads - weekly_downloads) as f64
+                    / self.min_weekly_downloads as f64;
+                #[allow(clippy::cast_precision_loss)]
+                let measurement =
+                    Measurement::new(weekly_downloads as f64, self.min_weekly_downloads as f64);
+                Ok(Evaluation::Fail {
+                    policy_name: "min_weekly_downloads".to_string(),
+                    dependency: dependency.clone(),
+                    reason: format!(
+                        "the dependency has been downloaded {} times in the last week, which is less than the minimum of {}",
+                        weekly_downloads, self.min_weekly_downloads
+                    ),
+                    fail_score,
+                    severity: Severity::Error,
+                    measurement: Some(measurement),
+                })
+            }
+            _ => Ok(Evaluation::Pass {
+                policy_name: "min_weekly_downloads".to_string(),
+                dependency: dependency.clone(),
+            }),
+        }
+    }
+}
+
+impl MinWeeklyDownloads {
+    /// Creates the policy with the info retrievers of the ecosystems whose registries publish the
+    /// weekly downloads, by ecosystem.
+    pub fn new(
+        info_retrievers: HashMap<&'static str, Arc<dyn InfoRetriever>>,
+        min_weekly_downloads: u64,
+    ) -> Self {
+        Self {
+            info_retrievers,
+            min_weekly_downloads,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, Repository};
+
+    #[tokio::test]
+    async fn when_the_weekly_downloads_are_too_few_it_should_fail_proportionally_to_the_missing_ones(
+    ) {
+        let mut info_retriever = MockInfoRetriever::new();
+        info_retriever
+            .expect_weekly_downloads()
+            .with(eq("colors"))
+            .once()
+            .return_once(|_| Ok(Some(40)));
+        let policy = MinWeeklyDownloads::new(
+            HashMap::from([("npm", Arc::new(info_retriever) as Arc<dyn InfoRetriever>)]),
+            200,
+        );
+
+        let result = policy.evaluate(&dependency(Some("npm"))).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "min_weekly_downloads".to_string(),
+                dependency: dependency(Some("npm")),
+                reason: "the dependency has been downloaded 40 times in the last week, which is less than the minimum of 200".to_string(),
+                fail_score: 0.8,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(40.0, 200.0)),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_weekly_downloads_are_enough_it_should_pass_the_policy_evaluation() {
+        let mut info_retriever = MockInfoRetriever::new();
+        info_retriever
+            .expect_weekly_downloads()
+            .once()
+            .return_once(|_| Ok(Some(23_513_215)));
+        let policy = MinWeeklyDownloads::new(
+            HashMap::from([("npm", Arc::new(info_retriever) as Arc<dyn InfoRetriever>)]),
+            200,
+        );
+
+        let result = policy.evaluate(&dependency(Some("npm"))).await;
+
+        assert!(matches!(result.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn when_the_ecosystem_is_not_supported_it_should_pass_the_policy_evaluation() {
+        let policy = MinWeeklyDownloads::new(HashMap::new(), 200);
+
+        let result = policy.evaluate(&dependency(Some("cargo"))).await;
+
+        assert!(matches!(result.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    fn dependency(ecosystem: Option<&'static str>) -> Dependency {
+        Dependency {
+            name: "colors".to_string(),
+            version: "1.4.0".to_string(),
+            latest_version: Some("1.4.0".to_string()),
+            repository: Repository::GitHub {
+                organization: "Marak".to_string(),
+                name: "colors.js".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
@@ -1,19 +1,68 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
+use std::fmt::{Display, Formatter};
 
+use anyhow::anyhow;
 use async_trait::async_trait;
+use clap::ValueEnum;
+use itertools::Itertools;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
+use crate::pkg::config::risk_score;
 use crate::pkg::Repository;
 
+mod blocklist;
 mod contributors_ratio;
+mod issue_close_rate;
 mod max_issue_lifespan;
+mod max_open_issue_ratio;
 mod max_pull_request_lifespan;
+mod max_stale_open_pull_requests;
+mod max_time_since_last_commit;
+mod max_transitive_dependencies;
+mod max_versions_behind;
+mod min_contributors;
+mod min_downloads;
 mod min_number_of_releases_required;
+mod min_repository_age;
+mod min_weekly_downloads;
+mod no_known_vulnerabilities;
+mod no_prerelease_version;
+mod no_rustsec_advisories;
+mod not_deprecated;
+mod recent_contributor_diversity;
+mod repository_archived;
+mod require_recent_patch_release;
+mod require_security_policy;
+mod signed_releases;
+mod with_options;
 
+pub use blocklist::{BlockedDependency, Blocklist};
 pub use contributors_ratio::ContributorsRatio;
+pub use issue_close_rate::IssueCloseRate;
 pub use max_issue_lifespan::MaxIssueLifespan;
+pub use max_open_issue_ratio::MaxOpenIssueRatio;
 pub use max_pull_request_lifespan::MaxPullRequestLifespan;
+pub use max_stale_open_pull_requests::MaxStaleOpenPullRequests;
+pub use max_time_since_last_commit::MaxTimeSinceLastCommit;
+pub use max_transitive_dependencies::MaxTransitiveDependencies;
+pub use max_versions_behind::MaxVersionsBehind;
+pub use min_contributors::MinContributors;
+pub use min_downloads::MinDownloads;
 pub use min_number_of_releases_required::MinNumberOfReleasesRequired;
+pub use min_repository_age::MinRepositoryAge;
+pub use min_weekly_downloads::MinWeeklyDownloads;
+pub use no_known_vulnerabilities::NoKnownVulnerabilities;
+pub use no_prerelease_version::NoPrereleaseVersion;
+pub use no_rustsec_advisories::NoRustsecAdvisories;
+pub use not_deprecated::NotDeprecated;
+pub use recent_contributor_diversity::RecentContributorDiversity;
+pub use repository_archived::RepositoryArchived;
+pub use require_recent_patch_release::RequireRecentPatchRelease;
+pub use require_security_policy::RequireSecurityPolicy;
+pub use signed_releases::SignedReleases;
+pub use with_options::WithOptions;
 
 use crate::Dependency;
 
@@ -30,6 +79,8 @@ pub struct Tag {
     pub name: String,
     pub commit_id: String,
     pub commit_timestamp: u64,
+    /// Whether the tag is annotated and signed with GPG or SSH.
+    pub is_signed: bool,
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -41,8 +92,154 @@ pub trait CommitRetriever: Sync + Send {
         repository_url: &str,
     ) -> Result<HashMap<String, Vec<Commit>>, Box<dyn Error>>;
 
+    /// Retrieves the commits for each tag that touch the directory of the repository, leaving out
+    /// the tags without any, e.g. the releases of the other packages of a monorepo.
+    async fn commits_for_each_tag_in_directory(
+        &self,
+        repository_url: &str,
+        directory: &str,
+    ) -> Result<HashMap<String, Vec<Commit>>, Box<dyn Error>>;
+
     /// Retrieves all the tags from a repository ordered by time, where the latest one is the most recent.
     async fn all_tags(&self, repository_url: &str) -> Result<Vec<Tag>, Box<dyn Error>>;
+
+    /// Retrieves the timestamp of the latest commit in the default branch of a repository.
+    async fn latest_commit_timestamp(&self, repository_url: &str) -> Result<u64, Box<dyn Error>>;
+
+    /// Retrieves the timestamp of the earliest commit in the default branch of a repository.
+    async fn earliest_commit_timestamp(&self, repository_url: &str) -> Result<u64, Box<dyn Error>>;
+}
+
+/// The error returned by a `CommitRetriever` when the repository could not be cloned, so that the
+/// policies depending on it are skipped instead of failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepositoryUnavailable {
+    pub repository_url: String,
+    pub reason: String,
+}
+
+impl Display for RepositoryUnavailable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "repository {} is unavailable: {}",
+            self.repository_url, self.reason
+        )
+    }
+}
+
+impl Error for RepositoryUnavailable {}
+
+/// Converts an error of a `CommitRetriever` into one with the given context, keeping it
+/// downcastable to `RepositoryUnavailable` when the repository could not be cloned.
+fn commit_retrieval_error(error: &(dyn Error + 'static), context: String) -> anyhow::Error {
+    match error.downcast_ref::<RepositoryUnavailable>() {
+        Some(unavailable) => anyhow::Error::new(unavailable.clone()).context(context),
+        None => anyhow!("{}: {}", context, error),
+    }
+}
+
+/// Retrieves the commits of the latest releases of a repository, without duplicates. With the
+/// directory of the package, only the commits touching it are retrieved, and the releases without
+/// any are not counted, as they're the ones of other packages of the same repository.
+async fn commits_in_latest_releases(
+    retriever: &dyn CommitRetriever,
+    repo_url: &str,
+    directory: Option<&str>,
+    max_number_of_releases_to_check: usize,
+) -> anyhow::Result<Vec<Commit>> {
+    let all_tags = retriever
+        .all_tags(repo_url)
+        .await
+        .map_err(|e| {
+            commit_retrieval_error(
+                e.as_ref(),
+                format!("unable to retrieve all tags for repo {repo_url}"),
+            )
+        })?
+        .into_iter();
+
+    let all_commits_for_each_tag = match directory {
+        Some(directory) => {
+            retriever
+                .commits_for_each_tag_in_directory(repo_url, directory)
+                .await
+        }
+        None => retriever.commits_for_each_tag(repo_url).await,
+    }
+    .map_err(|e| {
+        commit_retrieval_error(
+            e.as_ref(),
+            format!("unable to retrieve commits for each tag for repo {repo_url}"),
+        )
+    })?;
+
+    let tags_to_check = all_tags
+        .rev()
+        .filter(|tag| directory.is_none() || all_commits_for_each_tag.contains_key(&tag.name))
+        .take(max_number_of_releases_to_check);
+    let tag_names = tags_to_check.map(|tag| tag.name).collect::<HashSet<_>>();
+
+    Ok(all_commits_for_each_tag
+        .into_iter()
+        .filter(|(key, _)| tag_names.contains(key))
+        .flat_map(|(_, value)| value)
+        .unique_by(|commit| commit.id.clone())
+        .collect())
+}
+
+/// Computes the ratio of the commits made by each author, sorted by author.
+#[allow(clippy::cast_precision_loss)]
+fn ratio_by_author<I>(authors: I) -> Vec<(f64, String)>
+where
+    I: IntoIterator<Item = String>,
+{
+    let commits_by_author = authors
+        .into_iter()
+        .sorted()
+        .dedup_with_count()
+        .collect::<Vec<_>>();
+
+    let all_commits: usize = commits_by_author.iter().map(|(count, _)| count).sum();
+
+    commits_by_author
+        .into_iter()
+        .map(|(count, author)| (count as f64 / all_commits as f64, author))
+        .collect()
+}
+
+/// The issues or pull requests of a repository that are considered, the most recently created
+/// first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IssueWindow {
+    /// At most the given number of them.
+    Last(usize),
+    /// The ones created in the given number of days.
+    Days(u64),
+}
+
+impl IssueWindow {
+    /// Builds the window from the count of a config, using the days instead when they are set.
+    pub fn new(count: usize, since_days: Option<u64>) -> Self {
+        since_days.map_or(Self::Last(count), Self::Days)
+    }
+
+    /// Returns the size of the pages to request them in, which is not bigger than the number of
+    /// them considered.
+    pub fn page_size(self, max_page_size: usize) -> usize {
+        match self {
+            Self::Last(count) => count.clamp(1, max_page_size),
+            Self::Days(_) => max_page_size,
+        }
+    }
+
+    /// Adds the window to the details of a measurement, naming the count after what is counted.
+    fn add_detail_to(self, measurement: Measurement, count_name: &str) -> Measurement {
+        match self {
+            Self::Last(count) => measurement.with_detail(count_name, &count),
+            Self::Days(days) => measurement.with_detail("since_days", &days),
+        }
+    }
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -51,13 +248,84 @@ pub trait ContributionDataRetriever: Send + Sync {
     async fn get_issue_lifespan(
         &self,
         repository: &Repository,
-        last_issues: usize,
+        window: IssueWindow,
     ) -> Result<f64, Box<dyn Error>>;
     async fn get_pull_request_lifespan(
         &self,
         repository: &Repository,
-        last_pull_requests: usize,
+        window: IssueWindow,
     ) -> Result<f64, Box<dyn Error>>;
+    /// Retrieves the ratio of the issues in the window that are still open.
+    async fn get_open_issue_ratio(
+        &self,
+        repository: &Repository,
+        window: IssueWindow,
+    ) -> Result<f64, Box<dyn Error>>;
+    /// Retrieves the ratio of closed to opened issues in the window, which is below 1 when they
+    /// are opened faster than they are closed.
+    async fn get_issue_close_rate(
+        &self,
+        repository: &Repository,
+        window: IssueWindow,
+    ) -> Result<f64, Box<dyn Error>>;
+    /// Retrieves the number of pull requests in the window that are still open and were created
+    /// more than the given number of days ago.
+    async fn get_stale_open_pr_count(
+        &self,
+        repository: &Repository,
+        window: IssueWindow,
+        stale_after_days: u64,
+    ) -> Result<usize, Box<dyn Error>>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RepositoryStatusRetriever: Send + Sync {
+    /// Retrieves whether the repository has been archived by its owners.
+    async fn is_archived(&self, repository: &Repository) -> Result<bool, Box<dyn Error>>;
+
+    /// Retrieves whether the repository has a security policy explaining how to report
+    /// vulnerabilities, e.g. a `SECURITY.md` file.
+    async fn has_security_policy(&self, repository: &Repository) -> Result<bool, Box<dyn Error>>;
+}
+
+/// Known vulnerability of a version of a package.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Vulnerability {
+    /// Identifier of the advisory, like `GHSA-c2qf-rxjj-qqgw` or `RUSTSEC-2021-0093`.
+    pub id: String,
+    /// Highest CVSS base score of the advisory, if it has any.
+    pub cvss_score: Option<f64>,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait VulnerabilityRetriever: Send + Sync {
+    /// Retrieves the known vulnerabilities of the version of the dependency, or `None` if the
+    /// vulnerability database doesn't cover its ecosystem.
+    async fn vulnerabilities(
+        &self,
+        dependency: &Dependency,
+    ) -> Result<Option<Vec<Vulnerability>>, Box<dyn Error>>;
+}
+
+/// Advisory of the `RustSec` database affecting a version of a crate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Advisory {
+    /// Identifier of the advisory, like `RUSTSEC-2020-0071`.
+    pub id: String,
+    /// CVSS base score of the advisory, if it has any.
+    pub cvss_score: Option<f64>,
+    /// Kind of the informational advisories, like `unmaintained` or `unsound`, which don't report
+    /// a vulnerability.
+    pub informational: Option<String>,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AdvisoryRetriever: Send + Sync {
+    /// Retrieves the advisories affecting the version of the crate.
+    async fn advisories(&self, name: &str, version: &str) -> Result<Vec<Advisory>, Box<dyn Error>>;
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -66,6 +334,82 @@ pub trait Clock: Sync + Send {
     fn now_timestamp(&self) -> u64;
 }
 
+/// How much a failure of a policy matters, so that only the failures of some severity are
+/// considered when checking the fail threshold.
+#[derive(
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    ValueEnum,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warn,
+    #[default]
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// The value a policy observed for a dependency and the limit it was compared with, so that the
+/// failures can be explained beyond their reason. The details hold the context of the
+/// measurement, e.g. the author whose contribution ratio was observed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub observed: f64,
+    pub limit: f64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+}
+
+impl Measurement {
+    pub fn new(observed: f64, limit: f64) -> Self {
+        Self {
+            observed,
+            limit,
+            details: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_detail(mut self, name: &str, value: &(impl ToString + ?Sized)) -> Self {
+        self.details.insert(name.to_string(), value.to_string());
+        self
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "observed {}, limit {}", self.observed, self.limit)?;
+        if !self.details.is_empty() {
+            let details = self
+                .details
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}"))
+                .join(", ");
+            write!(f, " ({details})")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Evaluation {
     Pass {
@@ -77,32 +421,193 @@ pub enum Evaluation {
         dependency: Dependency,
         reason: String,
         fail_score: f64,
+        severity: Severity,
+        measurement: Option<Measurement>,
+    },
+    /// The policy could not be evaluated, e.g. because the repository of the dependency is not
+    /// available, which is reported apart from the failures.
+    Skip {
+        policy_name: String,
+        dependency: Dependency,
+        reason: String,
     },
 }
 
 impl Evaluation {
     pub fn policy(&self) -> &str {
         match self {
-            Evaluation::Fail { policy_name, .. } | Evaluation::Pass { policy_name, .. } => {
-                policy_name
-            }
+            Evaluation::Fail { policy_name, .. }
+            | Evaluation::Pass { policy_name, .. }
+            | Evaluation::Skip { policy_name, .. } => policy_name,
         }
     }
 
     pub fn dependency(&self) -> &Dependency {
         match self {
-            Evaluation::Fail { dependency, .. } | Evaluation::Pass { dependency, .. } => dependency,
+            Evaluation::Fail { dependency, .. }
+            | Evaluation::Pass { dependency, .. }
+            | Evaluation::Skip { dependency, .. } => dependency,
         }
     }
 
     pub fn fail_score(&self) -> f64 {
         match self {
-            Evaluation::Pass { .. } => 0.0,
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => 0.0,
             Evaluation::Fail { fail_score, .. } => *fail_score,
         }
     }
 }
 
+/// Returns whether any dependency has failed a policy and the sum of its fail scores is greater
+/// than the threshold. A threshold of zero or less is exceeded by any failure. Only the failures
+/// with at least the given severity are considered.
+pub fn exceeds_fail_threshold<'a, T>(
+    evaluations: T,
+    fail_threshold: f64,
+    min_severity: Severity,
+) -> bool
+where
+    T: IntoIterator<Item = &'a Evaluation>,
+{
+    failures_per_dependency(evaluations, min_severity)
+        .values()
+        .any(|failures| {
+            let score: f64 = failures.iter().map(|failure| failure.fail_score()).sum();
+            fail_threshold <= 0.0 || score > fail_threshold
+        })
+}
+
+/// Same as [`exceeds_fail_threshold`], comparing the risk score of each dependency with the
+/// threshold instead of the sum of its fail scores.
+pub fn exceeds_risk_threshold<'a, T>(
+    evaluations: T,
+    risk_threshold: f64,
+    min_severity: Severity,
+    normalization: &risk_score::Config,
+) -> bool
+where
+    T: IntoIterator<Item = &'a Evaluation>,
+{
+    failures_per_dependency(evaluations, min_severity)
+        .values()
+        .any(|failures| {
+            risk_threshold <= 0.0
+                || risk_score(failures.iter().copied(), normalization) > risk_threshold
+        })
+}
+
+/// Normalizes the fail scores of the evaluations of a dependency into a risk score between 0 and
+/// 100. Each weighted fail score contributes at most `max_contribution`, and the sum of the
+/// contributions is scaled so that `saturation` is 100, rounded to one decimal.
+pub fn risk_score<'a, T>(evaluations: T, normalization: &risk_score::Config) -> f64
+where
+    T: IntoIterator<Item = &'a Evaluation>,
+{
+    let contributions: f64 = evaluations
+        .into_iter()
+        .map(|evaluation| evaluation.fail_score().min(normalization.max_contribution))
+        .sum();
+    let score = (100.0 * contributions / normalization.saturation).min(100.0);
+    (score * 10.0).round() / 10.0
+}
+
+/// Groups the failures with at least the given severity by dependency.
+fn failures_per_dependency<'a, T>(
+    evaluations: T,
+    min_severity: Severity,
+) -> HashMap<&'a Dependency, Vec<&'a Evaluation>>
+where
+    T: IntoIterator<Item = &'a Evaluation>,
+{
+    let mut failures: HashMap<&Dependency, Vec<&Evaluation>> = HashMap::new();
+    for evaluation in evaluations {
+        let Evaluation::Fail { severity, .. } = evaluation else {
+            continue;
+        };
+        if *severity < min_severity {
+            continue;
+        }
+
+        failures
+            .entry(evaluation.dependency())
+            .or_default()
+            .push(evaluation);
+    }
+    failures
+}
+
+/// Keeps all the evaluations of the dependencies that failed any policy, dropping the ones of the
+/// dependencies that passed every policy.
+pub fn evaluations_of_failing_dependencies(evaluations: &[Evaluation]) -> Vec<Evaluation> {
+    let failing_dependencies = evaluations
+        .iter()
+        .filter(|evaluation| matches!(evaluation, Evaluation::Fail { .. }))
+        .map(Evaluation::dependency)
+        .collect::<HashSet<_>>();
+
+    evaluations
+        .iter()
+        .filter(|evaluation| failing_dependencies.contains(evaluation.dependency()))
+        .cloned()
+        .collect()
+}
+
+/// Counts of the dependencies of a scan, and the sum of the fail scores of all their failures.
+#[derive(Debug, Default, PartialEq)]
+pub struct ScanSummary {
+    pub dependencies: usize,
+    pub passing: usize,
+    pub failing: usize,
+    pub fail_score: f64,
+}
+
+impl ScanSummary {
+    /// Summarizes the evaluations, where a dependency is failing if it failed any policy.
+    pub fn new<'a, T>(evaluations: T) -> Self
+    where
+        T: IntoIterator<Item = &'a Evaluation>,
+    {
+        let mut failed_per_dependency: HashMap<&Dependency, bool> = HashMap::new();
+        let mut fail_score = 0.0;
+        for evaluation in evaluations {
+            let has_failed = failed_per_dependency
+                .entry(evaluation.dependency())
+                .or_insert(false);
+            if let Evaluation::Fail {
+                fail_score: score, ..
+            } = evaluation
+            {
+                *has_failed = true;
+                fail_score += score;
+            }
+        }
+
+        let failing = failed_per_dependency
+            .values()
+            .filter(|&&has_failed| has_failed)
+            .count();
+        Self {
+            dependencies: failed_per_dependency.len(),
+            passing: failed_per_dependency.len() - failing,
+            failing,
+            fail_score,
+        }
+    }
+}
+
+impl Display for ScanSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} dependencies: {} passing, {} failing, total fail score {}",
+            self.dependencies, self.passing, self.failing, self.fail_score
+        )
+    }
+}
+
+/// Evaluations are equal when they are of the same policy and kind. The failures must also have
+/// the same fail score and measurement, as their reasons are only meant to be displayed, and the
+/// skips the same reason.
 impl PartialEq for Evaluation {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -110,14 +615,16 @@ impl PartialEq for Evaluation {
                 Evaluation::Fail {
                     policy_name: name1,
                     fail_score: score1,
+                    measurement: measurement1,
                     ..
                 },
                 Evaluation::Fail {
                     policy_name: name2,
                     fail_score: score2,
+                    measurement: measurement2,
                     ..
                 },
-            ) => name1 == name2 && score1 == score2,
+            ) => name1 == name2 && score1 == score2 && measurement1 == measurement2,
             (
                 Evaluation::Pass {
                     policy_name: name1, ..
@@ -126,6 +633,18 @@ impl PartialEq for Evaluation {
                     policy_name: name2, ..
                 },
             ) => name1 == name2,
+            (
+                Evaluation::Skip {
+                    policy_name: name1,
+                    reason: reason1,
+                    ..
+                },
+                Evaluation::Skip {
+                    policy_name: name2,
+                    reason: reason2,
+                    ..
+                },
+            ) => name1 == name2 && reason1 == reason2,
             _ => false,
         }
     }
@@ -134,6 +653,250 @@ impl PartialEq for Evaluation {
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait Policy: Send + Sync {
+    /// Returns the name the policy reports its evaluations with.
+    fn name(&self) -> &'static str;
+
+    /// Returns whether the evaluation only depends on the repository of the dependency, so it is
+    /// evaluated once for all the dependencies that share the same repository.
+    fn is_repository_level(&self) -> bool {
+        false
+    }
+
     /// Evaluates the policy.
     async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_displays_the_observed_value_and_the_limit_of_a_measurement() {
+        let measurement = Measurement::new(0.82, 0.5);
+
+        assert_eq!(measurement.to_string(), "observed 0.82, limit 0.5");
+    }
+
+    #[test]
+    fn it_displays_the_details_of_a_measurement() {
+        let measurement = Measurement::new(0.82, 0.5)
+            .with_detail("releases", &3)
+            .with_detail("author", "some_author");
+
+        assert_eq!(
+            measurement.to_string(),
+            "observed 0.82, limit 0.5 (author: some_author, releases: 3)"
+        );
+    }
+
+    #[test]
+    fn it_exceeds_a_zero_threshold_with_any_failure() {
+        let evaluations = vec![pass("foo"), fail("bar", 0.1)];
+
+        assert!(exceeds_fail_threshold(&evaluations, 0.0, Severity::Info));
+    }
+
+    #[test]
+    fn it_does_not_exceed_the_threshold_if_everything_passes() {
+        let evaluations = vec![pass("foo"), pass("bar")];
+
+        assert!(!exceeds_fail_threshold(&evaluations, 0.0, Severity::Info));
+    }
+
+    #[test]
+    fn it_sums_the_fail_scores_of_each_dependency_to_compare_them_with_the_threshold() {
+        let evaluations = vec![fail("foo", 1.0), fail("foo", 1.5), fail("bar", 2.0)];
+
+        assert!(exceeds_fail_threshold(&evaluations, 2.0, Severity::Info));
+        assert!(!exceeds_fail_threshold(&evaluations, 2.5, Severity::Info));
+    }
+
+    #[test]
+    fn it_only_considers_the_failures_with_at_least_the_given_severity() {
+        let evaluations = vec![
+            fail("foo", 1.0),
+            fail_with_severity("bar", 3.0, Severity::Warn),
+        ];
+
+        assert!(exceeds_fail_threshold(&evaluations, 2.0, Severity::Warn));
+        assert!(!exceeds_fail_threshold(&evaluations, 2.0, Severity::Error));
+        assert!(exceeds_fail_threshold(&evaluations, 0.0, Severity::Error));
+    }
+
+    #[test]
+    fn it_caps_and_scales_the_fail_scores_into_a_risk_score() {
+        let evaluations = vec![
+            pass("foo"),
+            fail("foo", 1.02),
+            fail("foo", 0.5),
+            fail("foo", 0.25),
+        ];
+
+        assert_eq!(
+            risk_score(&evaluations, &risk_score::Config::default()),
+            58.3
+        );
+        assert_eq!(
+            risk_score(
+                &evaluations,
+                &risk_score::Config {
+                    max_contribution: 2.0,
+                    saturation: 4.0,
+                }
+            ),
+            44.3
+        );
+    }
+
+    #[test]
+    fn it_limits_the_risk_score_to_100() {
+        let evaluations = vec![fail("foo", 1.0), fail("foo", 5.0), fail("foo", 1.0)];
+
+        assert_eq!(
+            risk_score(&evaluations, &risk_score::Config::default()),
+            100.0
+        );
+        assert_eq!(
+            risk_score(&[pass("foo")], &risk_score::Config::default()),
+            0.0
+        );
+    }
+
+    #[test]
+    fn it_compares_the_risk_score_of_each_dependency_with_the_threshold() {
+        let evaluations = vec![fail("foo", 1.0), fail("foo", 4.0), fail("bar", 1.5)];
+        let normalization = risk_score::Config::default();
+
+        assert!(exceeds_risk_threshold(
+            &evaluations,
+            60.0,
+            Severity::Info,
+            &normalization
+        ));
+        assert!(!exceeds_risk_threshold(
+            &evaluations,
+            66.7,
+            Severity::Info,
+            &normalization
+        ));
+    }
+
+    #[test]
+    fn it_keeps_the_evaluations_of_the_failing_dependencies_only() {
+        let evaluations = vec![pass("foo"), pass("bar"), fail("bar", 1.0), pass("baz")];
+
+        assert_eq!(
+            evaluations_of_failing_dependencies(&evaluations),
+            vec![pass("bar"), fail("bar", 1.0)]
+        );
+    }
+
+    #[test]
+    fn it_summarizes_the_dependencies_passing_and_failing() {
+        let evaluations = vec![
+            pass("foo"),
+            pass("bar"),
+            fail("bar", 1.0),
+            fail("bar", 0.5),
+            fail("baz", 0.25),
+        ];
+
+        let summary = ScanSummary::new(&evaluations);
+
+        assert_eq!(
+            summary,
+            ScanSummary {
+                dependencies: 3,
+                passing: 1,
+                failing: 2,
+                fail_score: 1.75,
+            }
+        );
+        assert_eq!(
+            summary.to_string(),
+            "3 dependencies: 1 passing, 2 failing, total fail score 1.75"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_only_counts_the_releases_touching_the_directory_of_the_package() {
+        let mut retriever = MockCommitRetriever::new();
+        retriever.expect_all_tags().returning(|_| {
+            Ok(["v1.0.0", "v1.1.0", "v1.2.0"]
+                .iter()
+                .map(|name| Tag {
+                    name: (*name).to_string(),
+                    commit_id: (*name).to_string(),
+                    commit_timestamp: 0,
+                    is_signed: false,
+                })
+                .collect())
+        });
+        retriever
+            .expect_commits_for_each_tag_in_directory()
+            .withf(|_, directory| directory == "packages/babel-core")
+            .return_once(|_, _| {
+                Ok(HashMap::from([
+                    ("v1.0.0".to_string(), vec![commit("a")]),
+                    ("v1.2.0".to_string(), vec![commit("c")]),
+                ]))
+            });
+
+        let commits = commits_in_latest_releases(
+            &retriever,
+            "https://github.com/babel/babel",
+            Some("packages/babel-core"),
+            2,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            commits
+                .into_iter()
+                .map(|commit| commit.id)
+                .sorted()
+                .collect::<Vec<_>>(),
+            vec!["a", "c"]
+        );
+    }
+
+    fn commit(id: &str) -> Commit {
+        Commit {
+            id: id.to_string(),
+            author_name: "some_author".to_string(),
+            author_email: "some_author@example.com".to_string(),
+            creation_timestamp: 0,
+        }
+    }
+
+    fn pass(name: &str) -> Evaluation {
+        Evaluation::Pass {
+            policy_name: "some_policy".to_string(),
+            dependency: dependency(name),
+        }
+    }
+
+    fn fail(name: &str, fail_score: f64) -> Evaluation {
+        fail_with_severity(name, fail_score, Severity::Error)
+    }
+
+    fn fail_with_severity(name: &str, fail_score: f64, severity: Severity) -> Evaluation {
+        Evaluation::Fail {
+            policy_name: "some_policy".to_string(),
+            dependency: dependency(name),
+            reason: "some_reason".to_string(),
+            fail_score,
+            severity,
+            measurement: None,
+        }
+    }
+
+    fn dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            ..Dependency::default()
+        }
+    }
+}
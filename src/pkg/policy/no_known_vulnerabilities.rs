@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::info;
+
+use crate::pkg::policy::{Measurement, Severity, VulnerabilityRetriever};
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the version of the dependency has known vulnerabilities, listing their advisories.
+/// The fail score is the highest CVSS score among them divided by 10, or 1 when none of them has
+/// a CVSS score.
+pub struct NoKnownVulnerabilities {
+    vulnerability_retriever: Arc<dyn VulnerabilityRetriever>,
+}
+
+#[async_trait]
+impl Policy for NoKnownVulnerabilities {
+    fn name(&self) -> &'static str {
+        "no_known_vulnerabilities"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let vulnerabilities = self
+            .vulnerability_retriever
+            .vulnerabilities(dependency)
+            .await
+            .map_err(|e| anyhow!("error retrieving the vulnerabilities: {}", e))?;
+
+        let Some(vulnerabilities) = vulnerabilities else {
+            info!(
+                "the vulnerabilities check is not supported for the ecosystem of {}",
+                dependency.name
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "no_known_vulnerabilities".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+        if vulnerabilities.is_empty() {
+            return Ok(Evaluation::Pass {
+                policy_name: "no_known_vulnerabilities".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        let highest_cvss_score = vulnerabilities
+            .iter()
+            .filter_map(|vulnerability| vulnerability.cvss_score)
+            .reduce(f64::max);
+        let fail_score = highest_cvss_score.map_or(1.0, |score| score / 10.0);
+        #[allow(clippy::cast_precision_loss)]
+        let mut measurement = Measurement::new(vulnerabilities.len() as f64, 0.0);
+        if let Some(highest_cvss_score) = highest_cvss_score {
+            measurement = measurement.with_detail("highest_cvss_score", &highest_cvss_score);
+        }
+
+        Ok(Evaluation::Fail {
+            policy_name: "no_known_vulnerabilities".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "the version {} has known vulnerabilities: {}",
+                dependency.version,
+                vulnerabilities
+                    .iter()
+                    .map(|vulnerability| &vulnerability.id)
+                    .join(", ")
+            ),
+            fail_score,
+            severity: Severity::Error,
+            measurement: Some(measurement),
+        })
+    }
+}
+
+impl NoKnownVulnerabilities {
+    pub fn new<R: Into<Arc<dyn VulnerabilityRetriever>>>(vulnerability_retriever: R) -> Self {
+        Self {
+            vulnerability_retriever: vulnerability_retriever.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{MockVulnerabilityRetriever, Vulnerability};
+    use super::*;
+
+    #[tokio::test]
+    async fn when_the_version_has_vulnerabilities_it_should_fail_with_the_highest_cvss_score() {
+        let policy = NoKnownVulnerabilities::new(retriever(Some(vec![
+            vulnerability("GHSA-35jh-r3h4-6jhm", Some(7.2)),
+            vulnerability("GHSA-29mw-wpgm-hmr9", None),
+            vulnerability("CVE-2020-28500", Some(5.3)),
+        ])));
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "no_known_vulnerabilities".to_string(),
+                dependency: dependency(),
+                reason: "the version 4.17.20 has known vulnerabilities: GHSA-35jh-r3h4-6jhm, GHSA-29mw-wpgm-hmr9, CVE-2020-28500".to_string(),
+                fail_score: 0.72,
+                severity: Severity::Error,
+                measurement: Some(
+                    Measurement::new(3.0, 0.0).with_detail("highest_cvss_score", &7.2)
+                ),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_no_vulnerability_has_a_cvss_score_it_should_fail_with_a_score_of_one() {
+        let policy = NoKnownVulnerabilities::new(retriever(Some(vec![vulnerability(
+            "GHSA-29mw-wpgm-hmr9",
+            None,
+        )])));
+
+        let Evaluation::Fail { fail_score, .. } = policy.evaluate(&dependency()).await.unwrap()
+        else {
+            panic!("the dependency should fail");
+        };
+
+        assert_eq!(fail_score, 1.0);
+    }
+
+    #[tokio::test]
+    async fn when_the_version_has_no_vulnerabilities_or_its_ecosystem_is_not_covered_it_should_pass(
+    ) {
+        for vulnerabilities in [Some(vec![]), None] {
+            let policy = NoKnownVulnerabilities::new(retriever(vulnerabilities));
+
+            let evaluation = policy.evaluate(&dependency()).await;
+
+            assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+        }
+    }
+
+    fn retriever(vulnerabilities: Option<Vec<Vulnerability>>) -> Box<dyn VulnerabilityRetriever> {
+        let mut retriever = MockVulnerabilityRetriever::new();
+        retriever
+            .expect_vulnerabilities()
+            .return_once(|_| Ok(vulnerabilities));
+        Box::new(retriever)
+    }
+
+    fn vulnerability(id: &str, cvss_score: Option<f64>) -> Vulnerability {
+        Vulnerability {
+            id: id.to_string(),
+            cvss_score,
+        }
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            name: "lodash".to_string(),
+            version: "4.17.20".to_string(),
+            ecosystem: Some("npm"),
+            ..Dependency::default()
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use log::debug;
+use semver::Version;
+
+use crate::pkg::policy::Severity;
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the pinned version of the dependency is a pre-release, like `1.0.0-rc.1`, which may
+/// be intentional but is often left behind after trying a fix. The versions that are not semver,
+/// like the ones of some Python packages, pass.
+pub struct NoPrereleaseVersion;
+
+#[async_trait]
+impl Policy for NoPrereleaseVersion {
+    fn name(&self) -> &'static str {
+        "no_prerelease_version"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let version = dependency
+            .version
+            .strip_prefix('v')
+            .unwrap_or(&dependency.version);
+        let Ok(version) = Version::parse(version) else {
+            debug!(
+                "the version {} of {} is not a semver version, so it's not a pre-release",
+                dependency.version, dependency.name
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "no_prerelease_version".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        if version.pre.is_empty() {
+            return Ok(Evaluation::Pass {
+                policy_name: "no_prerelease_version".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        Ok(Evaluation::Fail {
+            policy_name: "no_prerelease_version".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "the version {} is the pre-release {} of {}.{}.{}",
+                dependency.version, version.pre, version.major, version.minor, version.patch
+            ),
+            fail_score: 1.0,
+            severity: Severity::Error,
+            measurement: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn when_the_version_is_a_prerelease_it_should_fail() {
+        let evaluation = NoPrereleaseVersion
+            .evaluate(&dependency("2.0.0-beta.3"))
+            .await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "no_prerelease_version".to_string(),
+                dependency: dependency("2.0.0-beta.3"),
+                reason: "the version 2.0.0-beta.3 is the pre-release beta.3 of 2.0.0".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_version_is_a_release_it_should_pass() {
+        let evaluation = NoPrereleaseVersion.evaluate(&dependency("2.0.0")).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn when_the_version_is_not_semver_it_should_pass() {
+        let evaluation = NoPrereleaseVersion.evaluate(&dependency("2.0b3")).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    fn dependency(version: &str) -> Dependency {
+        Dependency {
+            name: "some_dependency".to_string(),
+            version: version.to_string(),
+            ..Dependency::default()
+        }
+    }
+}
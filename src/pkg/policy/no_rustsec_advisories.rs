@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::info;
+
+use crate::pkg::policy::{Advisory, AdvisoryRetriever, Severity};
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the version of a cargo dependency is affected by advisories of the `RustSec`
+/// database, listing them with their severity. The informational advisories, like the unmaintained
+/// crates, fail with a warning only. The dependencies of the other ecosystems pass.
+pub struct NoRustsecAdvisories {
+    advisory_retriever: Arc<dyn AdvisoryRetriever>,
+}
+
+#[async_trait]
+impl Policy for NoRustsecAdvisories {
+    fn name(&self) -> &'static str {
+        "no_rustsec_advisories"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        if dependency.ecosystem != Some("cargo") {
+            info!(
+                "the RustSec advisories only cover the cargo dependencies, skipping {}",
+                dependency.name
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "no_rustsec_advisories".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        let advisories = self
+            .advisory_retriever
+            .advisories(&dependency.name, &dependency.version)
+            .await
+            .map_err(|e| anyhow!("error retrieving the RustSec advisories: {}", e))?;
+        if advisories.is_empty() {
+            return Ok(Evaluation::Pass {
+                policy_name: "no_rustsec_advisories".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        let fail_score = advisories
+            .iter()
+            .filter_map(|advisory| advisory.cvss_score)
+            .reduce(f64::max)
+            .map_or(1.0, |score| score / 10.0);
+        let severity = if advisories
+            .iter()
+            .all(|advisory| advisory.informational.is_some())
+        {
+            Severity::Warn
+        } else {
+            Severity::Error
+        };
+
+        Ok(Evaluation::Fail {
+            policy_name: "no_rustsec_advisories".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "the version {} is affected by the RustSec advisories {}",
+                dependency.version,
+                advisories
+                    .iter()
+                    .map(|advisory| format!("{} ({})", advisory.id, advisory_severity(advisory)))
+                    .join(", ")
+            ),
+            fail_score,
+            severity,
+            measurement: None,
+        })
+    }
+}
+
+impl NoRustsecAdvisories {
+    pub fn new<R: Into<Arc<dyn AdvisoryRetriever>>>(advisory_retriever: R) -> Self {
+        Self {
+            advisory_retriever: advisory_retriever.into(),
+        }
+    }
+}
+
+/// Returns the kind of the informational advisories, or the qualitative rating of the CVSS score
+/// of the vulnerabilities.
+fn advisory_severity(advisory: &Advisory) -> &str {
+    if let Some(informational) = &advisory.informational {
+        return informational;
+    }
+
+    match advisory.cvss_score {
+        None => "unknown severity",
+        Some(score) if score >= 9.0 => "critical",
+        Some(score) if score >= 7.0 => "high",
+        Some(score) if score >= 4.0 => "medium",
+        Some(score) if score > 0.0 => "low",
+        Some(_) => "none",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::MockAdvisoryRetriever;
+    use super::*;
+
+    #[tokio::test]
+    async fn when_the_version_is_affected_by_advisories_it_should_fail_with_their_severity() {
+        let policy = NoRustsecAdvisories::new(retriever(vec![
+            advisory("RUSTSEC-2020-0071", Some(6.2), None),
+            advisory("RUSTSEC-2020-0159", Some(7.5), None),
+            advisory("RUSTSEC-2021-0139", None, Some("unmaintained")),
+        ]));
+
+        let evaluation = policy.evaluate(&dependency(Some("cargo"))).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "no_rustsec_advisories".to_string(),
+                dependency: dependency(Some("cargo")),
+                reason: "the version 0.1.19 is affected by the RustSec advisories RUSTSEC-2020-0071 (medium), RUSTSEC-2020-0159 (high), RUSTSEC-2021-0139 (unmaintained)".to_string(),
+                fail_score: 0.75,
+                severity: Severity::Error,
+                measurement: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_all_the_advisories_are_informational_it_should_fail_with_a_warning() {
+        let policy = NoRustsecAdvisories::new(retriever(vec![advisory(
+            "RUSTSEC-2021-0139",
+            None,
+            Some("unmaintained"),
+        )]));
+
+        let Evaluation::Fail {
+            fail_score,
+            severity,
+            ..
+        } = policy.evaluate(&dependency(Some("cargo"))).await.unwrap()
+        else {
+            panic!("the dependency should fail");
+        };
+
+        assert_eq!(fail_score, 1.0);
+        assert_eq!(severity, Severity::Warn);
+    }
+
+    #[tokio::test]
+    async fn when_the_version_is_not_affected_it_should_pass() {
+        let policy = NoRustsecAdvisories::new(retriever(vec![]));
+
+        let evaluation = policy.evaluate(&dependency(Some("cargo"))).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn when_the_dependency_is_not_a_crate_it_should_pass_without_retrieving_the_advisories() {
+        let mut retriever = MockAdvisoryRetriever::new();
+        retriever.expect_advisories().never();
+        let policy = NoRustsecAdvisories::new(Box::new(retriever) as Box<dyn AdvisoryRetriever>);
+
+        let evaluation = policy.evaluate(&dependency(Some("npm"))).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    fn retriever(advisories: Vec<Advisory>) -> Box<dyn AdvisoryRetriever> {
+        let mut retriever = MockAdvisoryRetriever::new();
+        retriever
+            .expect_advisories()
+            .withf(|name, version| name == "chrono" && version == "0.1.19")
+            .return_once(|_, _| Ok(advisories));
+        Box::new(retriever)
+    }
+
+    fn advisory(id: &str, cvss_score: Option<f64>, informational: Option<&str>) -> Advisory {
+        Advisory {
+            id: id.to_string(),
+            cvss_score,
+            informational: informational.map(ToString::to_string),
+        }
+    }
+
+    fn dependency(ecosystem: Option<&'static str>) -> Dependency {
+        Dependency {
+            name: "chrono".to_string(),
+            version: "0.1.19".to_string(),
+            ecosystem,
+            ..Dependency::default()
+        }
+    }
+}
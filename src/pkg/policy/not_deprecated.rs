@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::info;
+
+use crate::pkg::policy::Severity;
+use crate::pkg::InfoRetriever;
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the pinned version of the dependency has been deprecated or yanked in its registry,
+/// using the deprecation message as the reason.
+pub struct NotDeprecated {
+    info_retrievers: HashMap<&'static str, Arc<dyn InfoRetriever>>,
+}
+
+#[async_trait]
+impl Policy for NotDeprecated {
+    fn name(&self) -> &'static str {
+        "not_deprecated"
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let Some(info_retriever) = dependency
+            .ecosystem
+            .and_then(|ecosystem| self.info_retrievers.get(ecosystem))
+        else {
+            info!(
+                "the deprecation check is not supported for the ecosystem of {}",
+                dependency.name
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "not_deprecated".to_string(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        let deprecation = info_retriever
+            .deprecation(&dependency.name, &dependency.version)
+            .await
+            .map_err(|e| anyhow!("error retrieving the deprecation of the version: {}", e))?;
+
+        match deprecation {
+            Some(reason) => Ok(Evaluation::Fail {
+                policy_name: "not_deprecated".to_string(),
+                dependency: dependency.clone(),
+                reason,
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            }),
+            None => Ok(Evaluation::Pass {
+                policy_name: "not_deprecated".to_string(),
+                dependency: dependency.clone(),
+            }),
+        }
+    }
+}
+
+impl NotDeprecated {
+    /// Creates the policy with the info retrievers of the ecosystems whose registries flag the
+    /// deprecated versions, by ecosystem.
+    pub fn new(info_retrievers: HashMap<&'static str, Arc<dyn InfoRetriever>>) -> Self {
+        Self { info_retrievers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::pkg::{DependencyKind, MockInfoRetriever, Repository};
+
+    #[tokio::test]
+    async fn when_the_version_is_deprecated_it_should_fail_the_policy_evaluation() {
+        let mut info_retriever = MockInfoRetriever::new();
+        info_retriever
+            .expect_deprecation()
+            .with(eq("request"), eq("2.88.2"))
+            .once()
+            .return_once(|_, _| Ok(Some("request has been deprecated".to_string())));
+        let policy = NotDeprecated::new(HashMap::from([(
+            "npm",
+            Arc::new(info_retriever) as Arc<dyn InfoRetriever>,
+        )]));
+
+        let result = policy.evaluate(&dependency(Some("npm"))).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "not_deprecated".to_string(),
+                dependency: dependency(Some("npm")),
+                reason: "request has been deprecated".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_version_is_not_deprecated_it_should_pass_the_policy_evaluation() {
+        let mut info_retriever = MockInfoRetriever::new();
+        info_retriever
+            .expect_deprecation()
+            .once()
+            .return_once(|_, _| Ok(None));
+        let policy = NotDeprecated::new(HashMap::from([(
+            "npm",
+            Arc::new(info_retriever) as Arc<dyn InfoRetriever>,
+        )]));
+
+        let result = policy.evaluate(&dependency(Some("npm"))).await;
+
+        assert!(matches!(result.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn when_the_ecosystem_is_not_supported_it_should_pass_the_policy_evaluation() {
+        let policy = NotDeprecated::new(HashMap::new());
+
+        let result = policy.evaluate(&dependency(Some("pypi"))).await;
+
+        assert!(matches!(result.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    fn dependency(ecosystem: Option<&'static str>) -> Dependency {
+        Dependency {
+            name: "request".to_string(),
+            version: "2.88.2".to_string(),
+            latest_version: Some("2.88.2".to_string()),
+            repository: Repository::GitHub {
+                organization: "request".to_string(),
+                name: "request".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
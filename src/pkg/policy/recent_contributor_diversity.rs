@@ -0,0 +1,266 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use crate::pkg::policy::{
+    commits_in_latest_releases, ratio_by_author, Clock, CommitRetriever, Evaluation, Measurement,
+    Policy, Severity,
+};
+use crate::Dependency;
+
+const DAYS_TO_SECONDS: u64 = 24 * 60 * 60;
+
+/// Fails when a single author made too many of the commits of the latest releases, which catches
+/// the projects whose recent activity comes from one person even if their history is diverse.
+pub struct RecentContributorDiversity {
+    retriever: Arc<dyn CommitRetriever>,
+    window_releases: usize,
+    window_days: Option<u64>,
+    max_recent_contributor_ratio: f64,
+    clock: Box<dyn Clock>,
+}
+
+#[async_trait]
+impl Policy for RecentContributorDiversity {
+    fn name(&self) -> &'static str {
+        "recent_contributor_diversity"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let repo_url = dependency
+            .repository
+            .url()
+            .context("the repository doesn't have a URL")?;
+
+        let mut recent_commits = commits_in_latest_releases(
+            self.retriever.as_ref(),
+            &repo_url,
+            dependency.repository_directory.as_deref(),
+            self.window_releases,
+        )
+        .await?;
+        if let Some(window_days) = self.window_days {
+            let oldest_timestamp = self
+                .clock
+                .now_timestamp()
+                .saturating_sub(window_days * DAYS_TO_SECONDS);
+            recent_commits.retain(|commit| {
+                u64::try_from(commit.creation_timestamp)
+                    .is_ok_and(|timestamp| timestamp >= oldest_timestamp)
+            });
+        }
+
+        let dominant_author =
+            ratio_by_author(recent_commits.into_iter().map(|commit| commit.author_email))
+                .into_iter()
+                .max_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        match dominant_author {
+            Some((ratio, author))
+                if ratio > self.max_recent_contributor_ratio
+                    && self.max_recent_contributor_ratio > 0.0 =>
+            {
+                let measurement = Measurement::new(ratio, self.max_recent_contributor_ratio)
+                    .with_detail("author", &author)
+                    .with_detail("releases", &self.window_releases);
+                Ok(Evaluation::Fail {
+                    policy_name: "recent_contributor_diversity".to_string(),
+                    dependency: dependency.clone(),
+                    reason: format!(
+                        "the rate of contribution to the recent commits is too high ({} > {}) for author {}",
+                        ratio, self.max_recent_contributor_ratio, author
+                    ),
+                    fail_score: ratio / self.max_recent_contributor_ratio,
+                    severity: Severity::Error,
+                    measurement: Some(match self.window_days {
+                        Some(window_days) => measurement.with_detail("days", &window_days),
+                        None => measurement,
+                    }),
+                })
+            }
+            _ => Ok(Evaluation::Pass {
+                policy_name: "recent_contributor_diversity".to_string(),
+                dependency: dependency.clone(),
+            }),
+        }
+    }
+}
+
+impl RecentContributorDiversity {
+    /// Creates the policy checking the commits of the given number of latest releases, narrowed to
+    /// the ones made in the given number of days, if any.
+    pub fn new<R: Into<Arc<dyn CommitRetriever>>>(
+        retriever: R,
+        window_releases: usize,
+        window_days: Option<u64>,
+        max_recent_contributor_ratio: f64,
+        clock: Box<dyn Clock>,
+    ) -> Self {
+        Self {
+            retriever: retriever.into(),
+            window_releases,
+            window_days,
+            max_recent_contributor_ratio,
+            clock,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use mockall::predicate::eq;
+
+    use super::super::{Commit, MockClock, MockCommitRetriever, Tag};
+    use super::*;
+    use crate::pkg::Repository::GitHub;
+
+    const NOW: u64 = 1_648_583_009;
+    const REPOSITORY_URL: &str = "https://github.com/some_org/some_repo";
+
+    #[tokio::test]
+    async fn when_a_single_author_made_the_commits_of_the_latest_release_it_should_fail() {
+        let policy = RecentContributorDiversity::new(retriever(), 1, None, 0.5, clock());
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "recent_contributor_diversity".to_string(),
+                dependency,
+                reason: "the rate of contribution to the recent commits is too high (1 > 0.5) for author newcomer@example.com"
+                    .to_string(),
+                fail_score: 2.0,
+                severity: Severity::Error,
+                measurement: Some(
+                    Measurement::new(1.0, 0.5)
+                        .with_detail("author", &"newcomer@example.com")
+                        .with_detail("releases", &1)
+                ),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_the_older_releases_are_diverse_enough_it_should_pass() {
+        let policy = RecentContributorDiversity::new(retriever(), 2, None, 0.5, clock());
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "recent_contributor_diversity".to_string(),
+                dependency,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_only_checks_the_commits_made_in_the_window_of_days() {
+        let policy = RecentContributorDiversity::new(retriever(), 2, Some(10), 0.5, clock());
+        let dependency = dependency();
+
+        let result = policy.evaluate(&dependency).await;
+
+        let Evaluation::Fail {
+            reason,
+            measurement,
+            ..
+        } = result.unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            reason,
+            "the rate of contribution to the recent commits is too high (1 > 0.5) for author newcomer@example.com"
+        );
+        assert_eq!(
+            measurement,
+            Some(
+                Measurement::new(1.0, 0.5)
+                    .with_detail("author", &"newcomer@example.com")
+                    .with_detail("releases", &2)
+                    .with_detail("days", &10)
+            )
+        );
+    }
+
+    /// Two releases: the older one with the commits of three authors made 30 days ago, and the
+    /// latest one with the commits of a single author made 2 days ago.
+    fn retriever() -> Box<dyn CommitRetriever> {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_all_tags()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| Ok(vec![tag("v1.0.0", 30), tag("v1.1.0", 2)]));
+        retriever
+            .expect_commits_for_each_tag()
+            .with(eq(REPOSITORY_URL))
+            .returning(|_| {
+                Ok(HashMap::from([
+                    (
+                        "v1.0.0".to_string(),
+                        vec![
+                            commit("1", "alice@example.com", 30),
+                            commit("2", "bob@example.com", 30),
+                            commit("3", "carol@example.com", 30),
+                            commit("4", "alice@example.com", 30),
+                            commit("5", "bob@example.com", 30),
+                        ],
+                    ),
+                    (
+                        "v1.1.0".to_string(),
+                        vec![
+                            commit("6", "newcomer@example.com", 2),
+                            commit("7", "newcomer@example.com", 2),
+                        ],
+                    ),
+                ]))
+            });
+        Box::new(retriever)
+    }
+
+    fn tag(name: &str, days_ago: u64) -> Tag {
+        Tag {
+            name: name.to_string(),
+            commit_id: format!("{name}_commit"),
+            commit_timestamp: NOW - days_ago * DAYS_TO_SECONDS,
+            is_signed: false,
+        }
+    }
+
+    fn commit(id: &str, author_email: &str, days_ago: u64) -> Commit {
+        Commit {
+            id: id.to_string(),
+            author_name: author_email.to_string(),
+            author_email: author_email.to_string(),
+            creation_timestamp: i64::try_from(NOW - days_ago * DAYS_TO_SECONDS).unwrap(),
+        }
+    }
+
+    fn clock() -> Box<dyn Clock> {
+        let mut clock = MockClock::new();
+        clock.expect_now_timestamp().return_const(NOW);
+        Box::new(clock)
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::info;
+
+use crate::pkg::policy::{RepositoryStatusRetriever, Severity};
+use crate::pkg::Repository;
+use crate::{Dependency, Evaluation, Policy};
+
+pub struct RepositoryArchived {
+    repository_status_retriever: Arc<dyn RepositoryStatusRetriever>,
+}
+
+#[async_trait]
+impl Policy for RepositoryArchived {
+    fn name(&self) -> &'static str {
+        "repository_archived"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        if !matches!(dependency.repository, Repository::GitHub { .. }) {
+            info!(
+                "the repository archived check is not supported for the repository of {}: {}",
+                dependency.name, dependency.repository
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "repository_archived".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        let archived = self
+            .repository_status_retriever
+            .is_archived(&dependency.repository)
+            .await
+            .map_err(|e| anyhow!("error retrieving the archived status: {}", e))?;
+
+        if archived {
+            Ok(Evaluation::Fail {
+                policy_name: "repository_archived".to_string(),
+                dependency: dependency.clone(),
+                reason: "repository is archived".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            })
+        } else {
+            Ok(Evaluation::Pass {
+                policy_name: "repository_archived".to_string(),
+                dependency: dependency.clone(),
+            })
+        }
+    }
+}
+
+impl RepositoryArchived {
+    pub fn new<R: Into<Arc<dyn RepositoryStatusRetriever>>>(
+        repository_status_retriever: R,
+    ) -> Self {
+        Self {
+            repository_status_retriever: repository_status_retriever.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{MockRepositoryStatusRetriever, Policy};
+    use super::*;
+    use crate::pkg::DependencyKind;
+    use crate::pkg::Repository::GitHub;
+    use crate::{Dependency, Evaluation};
+
+    #[tokio::test]
+    async fn it_fails_if_the_repository_is_archived() {
+        let retriever = {
+            let mut retriever = MockRepositoryStatusRetriever::new();
+            retriever.expect_is_archived().return_once(|_| Ok(true));
+            Box::new(retriever) as Box<dyn RepositoryStatusRetriever>
+        };
+        let policy = RepositoryArchived::new(retriever);
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        match evaluation.unwrap() {
+            Evaluation::Fail {
+                policy_name,
+                dependency: dep,
+                reason,
+                fail_score,
+                ..
+            } => {
+                assert_eq!(policy_name, "repository_archived");
+                assert_eq!(dep, dependency());
+                assert_eq!(reason, "repository is archived");
+                assert!((fail_score - 1.0).abs() < f64::EPSILON);
+            }
+            Evaluation::Pass { .. } | Evaluation::Skip { .. } => {
+                unreachable!()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn it_passes_if_the_repository_is_not_archived() {
+        let retriever = {
+            let mut retriever = MockRepositoryStatusRetriever::new();
+            retriever.expect_is_archived().return_once(|_| Ok(false));
+            Box::new(retriever) as Box<dyn RepositoryStatusRetriever>
+        };
+        let policy = RepositoryArchived::new(retriever);
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Pass {
+                policy_name: "repository_archived".to_string(),
+                dependency: dependency()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_passes_without_checking_if_the_repository_is_not_from_github() {
+        let retriever = {
+            let mut retriever = MockRepositoryStatusRetriever::new();
+            retriever.expect_is_archived().never();
+            Box::new(retriever) as Box<dyn RepositoryStatusRetriever>
+        };
+        let policy = RepositoryArchived::new(retriever);
+        let dependency = Dependency {
+            repository: Repository::Raw {
+                address: "https://example.com/repo.git".to_string(),
+            },
+            ..dependency()
+        };
+
+        let evaluation = policy.evaluate(&dependency).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Pass {
+                policy_name: "repository_archived".to_string(),
+                dependency
+            }
+        );
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            name: "foo".to_string(),
+            version: "1.2.3".to_string(),
+            latest_version: Some("1.2.4".to_string()),
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_name".to_string(),
+            },
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use semver::Version;
+
+use super::{commit_retrieval_error, Clock, CommitRetriever, Evaluation, Tag};
+use crate::pkg::policy::{Measurement, Policy, Severity};
+use crate::Dependency;
+
+const DAYS_TO_SECONDS: u64 = 24 * 60 * 60;
+
+/// Fails when the repository hasn't tagged a patch release, like `1.4.2`, in the last days, which
+/// tells whether the project still ships fixes. The pre-releases and the tags that are not semver
+/// versions are not releases, and the major and minor ones, like `1.5.0`, are not patches.
+pub struct RequireRecentPatchRelease {
+    retriever: Arc<dyn CommitRetriever>,
+    max_days: u64,
+    clock: Box<dyn Clock>,
+}
+
+#[async_trait]
+impl Policy for RequireRecentPatchRelease {
+    fn name(&self) -> &'static str {
+        "require_recent_patch_release"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let repository_url = dependency
+            .repository
+            .url()
+            .context("the repository did not contain a URL")?;
+        let all_tags = self
+            .retriever
+            .all_tags(&repository_url)
+            .await
+            .map_err(|e| {
+                commit_retrieval_error(e.as_ref(), "error retrieving all tags".to_string())
+            })?;
+
+        let Some(latest_patch_release) = all_tags
+            .iter()
+            .filter(|tag| is_patch_release(tag))
+            .max_by_key(|tag| tag.commit_timestamp)
+        else {
+            return Ok(Evaluation::Fail {
+                policy_name: "require_recent_patch_release".to_string(),
+                dependency: dependency.clone(),
+                reason: "the repository has no patch release".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            });
+        };
+
+        let seconds_since_patch = self
+            .clock
+            .now_timestamp()
+            .saturating_sub(latest_patch_release.commit_timestamp);
+        let max_seconds = self.max_days * DAYS_TO_SECONDS;
+
+        if seconds_since_patch <= max_seconds {
+            return Ok(Evaluation::Pass {
+                policy_name: "require_recent_patch_release".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let fail_score = (seconds_since_patch - max_seconds) as f64 / max_seconds.max(1) as f64;
+        #[allow(clippy::cast_precision_loss)]
+        let measurement = Measurement::new(
+            (seconds_since_patch / DAYS_TO_SECONDS) as f64,
+            self.max_days as f64,
+        )
+        .with_detail("tag", &latest_patch_release.name);
+        Ok(Evaluation::Fail {
+            policy_name: "require_recent_patch_release".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "the last patch release, {}, was {} days ago, which is more than the maximum of {} days",
+                latest_patch_release.name,
+                seconds_since_patch / DAYS_TO_SECONDS,
+                self.max_days
+            ),
+            fail_score,
+            severity: Severity::Error,
+            measurement: Some(measurement),
+        })
+    }
+}
+
+impl RequireRecentPatchRelease {
+    pub fn new<R>(retriever: R, max_days: u64, clock: Box<dyn Clock>) -> Self
+    where
+        R: Into<Arc<dyn CommitRetriever>>,
+    {
+        Self {
+            retriever: retriever.into(),
+            max_days,
+            clock,
+        }
+    }
+}
+
+/// Whether the tag is a semver version, with or without a leading `v`, bumping the patch number.
+fn is_patch_release(tag: &Tag) -> bool {
+    Version::parse(tag.name.strip_prefix('v').unwrap_or(&tag.name))
+        .is_ok_and(|version| version.pre.is_empty() && version.patch > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{MockClock, MockCommitRetriever};
+    use super::*;
+    use crate::pkg::Repository::GitHub;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[tokio::test]
+    async fn when_there_is_a_patch_release_in_the_last_days_it_should_pass() {
+        let policy = policy(vec![tag("v1.4.2", 100), tag("v1.5.0", 10)]);
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn when_the_last_patch_release_is_too_old_it_should_fail_even_with_newer_minor_or_pre_releases(
+    ) {
+        let policy = policy(vec![
+            tag("v1.4.2", 300),
+            tag("v1.5.0", 10),
+            tag("v1.5.1-rc.1", 5),
+            tag("nightly-1", 1),
+        ]);
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "require_recent_patch_release".to_string(),
+                dependency: dependency(),
+                reason: "the last patch release, v1.4.2, was 300 days ago, which is more than the maximum of 180 days".to_string(),
+                fail_score: 120.0 / 180.0,
+                severity: Severity::Error,
+                measurement: Some(Measurement::new(300.0, 180.0).with_detail("tag", "v1.4.2")),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn when_there_is_no_patch_release_it_should_fail() {
+        let policy = policy(vec![tag("1.0.0", 10), tag("2.0.0-beta.1", 5)]);
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "require_recent_patch_release".to_string(),
+                dependency: dependency(),
+                reason: "the repository has no patch release".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            }
+        );
+    }
+
+    fn policy(tags: Vec<Tag>) -> RequireRecentPatchRelease {
+        let mut retriever = MockCommitRetriever::new();
+        retriever.expect_all_tags().return_once(move |_| Ok(tags));
+        let mut clock = MockClock::new();
+        clock.expect_now_timestamp().return_const(NOW);
+
+        RequireRecentPatchRelease::new(
+            Box::new(retriever) as Box<dyn CommitRetriever>,
+            180,
+            Box::new(clock),
+        )
+    }
+
+    fn tag(name: &str, days_ago: u64) -> Tag {
+        Tag {
+            name: name.to_string(),
+            commit_id: "234234231".to_string(),
+            commit_timestamp: NOW - days_ago * DAYS_TO_SECONDS,
+            is_signed: false,
+        }
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
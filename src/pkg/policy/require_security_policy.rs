@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::info;
+
+use crate::pkg::policy::{RepositoryStatusRetriever, Severity};
+use crate::pkg::Repository;
+use crate::{Dependency, Evaluation, Policy};
+
+/// Fails when the repository has no security policy, as the maintainers haven't said how the
+/// vulnerabilities should be reported to them.
+pub struct RequireSecurityPolicy {
+    repository_status_retriever: Arc<dyn RepositoryStatusRetriever>,
+}
+
+#[async_trait]
+impl Policy for RequireSecurityPolicy {
+    fn name(&self) -> &'static str {
+        "require_security_policy"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        if !matches!(dependency.repository, Repository::GitHub { .. }) {
+            info!(
+                "the security policy check is not supported for the repository of {}: {}",
+                dependency.name, dependency.repository
+            );
+            return Ok(Evaluation::Pass {
+                policy_name: "require_security_policy".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        let has_security_policy = self
+            .repository_status_retriever
+            .has_security_policy(&dependency.repository)
+            .await
+            .map_err(|e| anyhow!("error retrieving the security policy: {}", e))?;
+
+        if has_security_policy {
+            Ok(Evaluation::Pass {
+                policy_name: "require_security_policy".to_string(),
+                dependency: dependency.clone(),
+            })
+        } else {
+            Ok(Evaluation::Fail {
+                policy_name: "require_security_policy".to_string(),
+                dependency: dependency.clone(),
+                reason: "repository has no security policy".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            })
+        }
+    }
+}
+
+impl RequireSecurityPolicy {
+    pub fn new<R: Into<Arc<dyn RepositoryStatusRetriever>>>(
+        repository_status_retriever: R,
+    ) -> Self {
+        Self {
+            repository_status_retriever: repository_status_retriever.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::MockRepositoryStatusRetriever;
+    use super::*;
+
+    #[tokio::test]
+    async fn it_fails_if_the_repository_has_no_security_policy() {
+        let retriever = {
+            let mut retriever = MockRepositoryStatusRetriever::new();
+            retriever
+                .expect_has_security_policy()
+                .return_once(|_| Ok(false));
+            Box::new(retriever) as Box<dyn RepositoryStatusRetriever>
+        };
+        let policy = RequireSecurityPolicy::new(retriever);
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            evaluation.unwrap(),
+            Evaluation::Fail {
+                policy_name: "require_security_policy".to_string(),
+                dependency: dependency(),
+                reason: "repository has no security policy".to_string(),
+                fail_score: 1.0,
+                severity: Severity::Error,
+                measurement: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_passes_if_the_repository_has_a_security_policy() {
+        let retriever = {
+            let mut retriever = MockRepositoryStatusRetriever::new();
+            retriever
+                .expect_has_security_policy()
+                .return_once(|_| Ok(true));
+            Box::new(retriever) as Box<dyn RepositoryStatusRetriever>
+        };
+        let policy = RequireSecurityPolicy::new(retriever);
+
+        let evaluation = policy.evaluate(&dependency()).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn it_passes_without_checking_if_the_repository_is_not_from_github() {
+        let retriever = {
+            let mut retriever = MockRepositoryStatusRetriever::new();
+            retriever.expect_has_security_policy().never();
+            Box::new(retriever) as Box<dyn RepositoryStatusRetriever>
+        };
+        let policy = RequireSecurityPolicy::new(retriever);
+        let dependency = Dependency {
+            repository: Repository::GitLab {
+                organization: "some_org".to_string(),
+                name: "some_name".to_string(),
+            },
+            ..dependency()
+        };
+
+        let evaluation = policy.evaluate(&dependency).await;
+
+        assert!(matches!(evaluation.unwrap(), Evaluation::Pass { .. }));
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            name: "foo".to_string(),
+            version: "1.2.3".to_string(),
+            repository: Repository::GitHub {
+                organization: "some_org".to_string(),
+                name: "some_name".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use super::{commit_retrieval_error, CommitRetriever, Evaluation};
+use crate::pkg::policy::{Measurement, Policy, Severity};
+use crate::Dependency;
+
+pub struct SignedReleases {
+    retriever: Arc<dyn CommitRetriever>,
+    last_tags: usize,
+    min_signed_ratio: f64,
+}
+
+#[async_trait]
+impl Policy for SignedReleases {
+    fn name(&self) -> &'static str {
+        "signed_releases"
+    }
+
+    fn is_repository_level(&self) -> bool {
+        true
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        let repository_url = dependency
+            .repository
+            .url()
+            .context("the repository did not contain a URL")?;
+        let all_tags = self
+            .retriever
+            .all_tags(&repository_url)
+            .await
+            .map_err(|e| {
+                commit_retrieval_error(e.as_ref(), "error retrieving all tags".to_string())
+            })?;
+
+        let tags_to_check = all_tags
+            .iter()
+            .rev()
+            .take(self.last_tags)
+            .collect::<Vec<_>>();
+        let signed_tags = tags_to_check.iter().filter(|tag| tag.is_signed).count();
+        #[allow(clippy::cast_precision_loss)]
+        let signed_ratio = if tags_to_check.is_empty() {
+            0.0
+        } else {
+            signed_tags as f64 / tags_to_check.len() as f64
+        };
+
+        if signed_ratio >= self.min_signed_ratio {
+            return Ok(Evaluation::Pass {
+                policy_name: "signed_releases".to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+
+        Ok(Evaluation::Fail {
+            policy_name: "signed_releases".to_string(),
+            dependency: dependency.clone(),
+            reason: format!(
+                "only {signed_tags} of the last {} tags are signed ({signed_ratio:.2} < {})",
+                tags_to_check.len(),
+                self.min_signed_ratio
+            ),
+            fail_score: (self.min_signed_ratio - signed_ratio) / self.min_signed_ratio,
+            severity: Severity::Error,
+            measurement: Some(
+                Measurement::new(signed_ratio, self.min_signed_ratio)
+                    .with_detail("signed_tags", &signed_tags)
+                    .with_detail("last_tags", &tags_to_check.len()),
+            ),
+        })
+    }
+}
+
+impl SignedReleases {
+    pub fn new<R>(retriever: R, last_tags: usize, min_signed_ratio: f64) -> Self
+    where
+        R: Into<Arc<dyn CommitRetriever>>,
+    {
+        Self {
+            retriever: retriever.into(),
+            last_tags,
+            min_signed_ratio,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use super::super::{MockCommitRetriever, Tag};
+    use super::*;
+    use crate::pkg::Repository::GitHub;
+
+    #[tokio::test]
+    async fn if_enough_of_the_latest_tags_are_signed_it_should_pass() {
+        let policy = SignedReleases::new(retriever(), 2, 1.0);
+
+        let result = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Pass {
+                policy_name: "signed_releases".to_string(),
+                dependency: dependency(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn if_not_enough_of_the_latest_tags_are_signed_it_should_fail() {
+        let policy = SignedReleases::new(retriever(), 4, 1.0);
+
+        let result = policy.evaluate(&dependency()).await;
+
+        assert_eq!(
+            result.unwrap(),
+            Evaluation::Fail {
+                policy_name: "signed_releases".to_string(),
+                dependency: dependency(),
+                reason: "only 2 of the last 4 tags are signed (0.50 < 1)".to_string(),
+                fail_score: 0.5,
+                severity: Severity::Error,
+                measurement: Some(
+                    Measurement::new(0.5, 1.0)
+                        .with_detail("signed_tags", &2)
+                        .with_detail("last_tags", &4)
+                ),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn if_there_are_no_tags_it_should_fail() {
+        let mut retriever = MockCommitRetriever::new();
+        retriever.expect_all_tags().returning(|_| Ok(vec![]));
+        let policy = SignedReleases::new(Box::new(retriever) as Box<dyn CommitRetriever>, 5, 1.0);
+
+        let result = policy.evaluate(&dependency()).await;
+
+        assert!(matches!(result.unwrap(), Evaluation::Fail { .. }));
+    }
+
+    fn retriever() -> Box<dyn CommitRetriever> {
+        let mut retriever = MockCommitRetriever::new();
+        retriever
+            .expect_all_tags()
+            .with(eq("https://github.com/some_org/some_repo"))
+            .returning(|_| {
+                Ok(vec![
+                    tag("v0.1.0", 1_640_000_000, false),
+                    tag("v0.1.1", 1_641_000_000, false),
+                    tag("v0.1.2", 1_642_000_000, true),
+                    tag("v0.1.3", 1_643_000_000, true),
+                ])
+            });
+        Box::new(retriever)
+    }
+
+    fn tag(name: &str, commit_timestamp: u64, is_signed: bool) -> Tag {
+        Tag {
+            name: name.to_string(),
+            commit_id: format!("{name}_commit"),
+            commit_timestamp,
+            is_signed,
+        }
+    }
+
+    fn dependency() -> Dependency {
+        Dependency {
+            repository: GitHub {
+                organization: "some_org".to_string(),
+                name: "some_repo".to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
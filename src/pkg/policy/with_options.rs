@@ -0,0 +1,156 @@
+use async_trait::async_trait;
+use regex::RegexSet;
+
+use crate::pkg::policy::Severity;
+use crate::{Dependency, Evaluation, Policy};
+
+/// Applies the options every policy accepts to the wrapped policy: it is skipped for the
+/// dependencies whose name matches any of the skip regexes, and its failures are weighted and
+/// reported with the configured severity.
+pub struct WithOptions {
+    policy: Box<dyn Policy>,
+    skip: RegexSet,
+    weight: f64,
+    severity: Severity,
+}
+
+#[async_trait]
+impl Policy for WithOptions {
+    fn name(&self) -> &'static str {
+        self.policy.name()
+    }
+
+    // The evaluations of a repository can't be shared when some of the dependencies of the
+    // repository may be skipped and others not.
+    fn is_repository_level(&self) -> bool {
+        self.skip.is_empty() && self.policy.is_repository_level()
+    }
+
+    async fn evaluate(&self, dependency: &Dependency) -> Result<Evaluation, anyhow::Error> {
+        if self.skip.is_match(&dependency.name) {
+            return Ok(Evaluation::Skip {
+                policy_name: self.policy.name().to_string(),
+                dependency: dependency.clone(),
+                reason: "dependency is in the skip list of the policy".to_string(),
+            });
+        }
+
+        match self.policy.evaluate(dependency).await? {
+            Evaluation::Fail {
+                policy_name,
+                dependency,
+                reason,
+                fail_score,
+                measurement,
+                ..
+            } => Ok(Evaluation::Fail {
+                policy_name,
+                dependency,
+                reason,
+                fail_score: fail_score * self.weight,
+                severity: self.severity,
+                measurement,
+            }),
+            evaluation => Ok(evaluation),
+        }
+    }
+}
+
+impl WithOptions {
+    pub fn new(
+        policy: Box<dyn Policy>,
+        skip: &[String],
+        weight: f64,
+        severity: Severity,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            policy,
+            skip: RegexSet::new(skip)?,
+            weight,
+            severity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pkg::policy::MockPolicy;
+    use crate::pkg::{DependencyKind, Repository};
+
+    #[tokio::test]
+    async fn it_skips_the_dependencies_in_the_skip_list_and_evaluates_the_rest() {
+        let mut policy = Box::new(MockPolicy::new());
+        policy.expect_name().return_const("some_policy_name");
+        policy.expect_evaluate().once().return_once(|dep| {
+            Ok(Evaluation::Pass {
+                policy_name: "some_policy_name".to_string(),
+                dependency: dep.clone(),
+            })
+        });
+        let policy =
+            WithOptions::new(policy, &["^internal-".to_string()], 1.0, Severity::Error).unwrap();
+
+        let skipped = policy.evaluate(&dependency("internal-crate")).await;
+        let evaluated = policy.evaluate(&dependency("serde")).await;
+
+        assert_eq!(
+            skipped.unwrap(),
+            Evaluation::Skip {
+                policy_name: "some_policy_name".to_string(),
+                dependency: dependency("internal-crate"),
+                reason: "dependency is in the skip list of the policy".to_string(),
+            }
+        );
+        assert_eq!(
+            evaluated.unwrap(),
+            Evaluation::Pass {
+                policy_name: "some_policy_name".to_string(),
+                dependency: dependency("serde"),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_weights_the_failures_and_reports_them_with_the_severity() {
+        let mut policy = Box::new(MockPolicy::new());
+        policy.expect_evaluate().once().return_once(|dep| {
+            Ok(Evaluation::Fail {
+                policy_name: "some_policy_name".to_string(),
+                dependency: dep.clone(),
+                reason: "some_reason".to_string(),
+                fail_score: 1.5,
+                severity: Severity::Error,
+                measurement: None,
+            })
+        });
+        let policy = WithOptions::new(policy, &[], 0.5, Severity::Info).unwrap();
+
+        let evaluation = policy.evaluate(&dependency("serde")).await.unwrap();
+
+        assert_eq!(evaluation.fail_score(), 0.75);
+        assert!(matches!(
+            evaluation,
+            Evaluation::Fail {
+                severity: Severity::Info,
+                measurement: None,
+                ..
+            }
+        ));
+    }
+
+    fn dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            latest_version: None,
+            repository: Repository::Unknown,
+            retrieval_error: None,
+            ecosystem: None,
+            transitive_dependencies: None,
+            kind: DependencyKind::Prod,
+            repository_directory: None,
+            lock_file: None,
+        }
+    }
+}
@@ -1,9 +1,19 @@
-#[derive(Copy, Clone)]
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+/// The registry ecosystems of the package managers.
+pub const ECOSYSTEMS: [&str; 6] = ["npm", "cargo", "pypi", "go", "rubygems", "packagist"];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum PackageManager {
     Npm,
     Cargo,
+    /// The direct dependencies declared in `Cargo.toml`, with their version requirements.
+    CargoManifest,
     Yarn,
+    Pnpm,
+    Pip,
+    Poetry,
+    Go,
+    Bundler,
+    Composer,
 }
 
 impl PackageManager {
@@ -12,12 +22,46 @@ impl PackageManager {
             Some(Self::Npm)
         } else if package_file.ends_with("Cargo.lock") {
             Some(Self::Cargo)
+        } else if package_file.ends_with("Cargo.toml") {
+            Some(Self::CargoManifest)
         } else if package_file.ends_with("yarn.lock") {
             Some(Self::Yarn)
+        } else if package_file.ends_with("pnpm-lock.yaml") {
+            Some(Self::Pnpm)
+        } else if package_file.ends_with("requirements.txt") {
+            Some(Self::Pip)
+        } else if package_file.ends_with("poetry.lock") {
+            Some(Self::Poetry)
+        } else if package_file.ends_with("go.mod") {
+            Some(Self::Go)
+        } else if package_file.ends_with("Gemfile.lock") {
+            Some(Self::Bundler)
+        } else if package_file.ends_with("composer.lock") {
+            Some(Self::Composer)
         } else {
             None
         }
     }
+
+    /// Returns the registry ecosystem of the package manager, shared by the package managers
+    /// that resolve their dependencies from the same registry.
+    pub fn ecosystem(self) -> &'static str {
+        match self {
+            Self::Npm | Self::Yarn | Self::Pnpm => "npm",
+            Self::Cargo | Self::CargoManifest => "cargo",
+            Self::Pip | Self::Poetry => "pypi",
+            Self::Go => "go",
+            Self::Bundler => "rubygems",
+            Self::Composer => "packagist",
+        }
+    }
+
+    /// Whether the file declares the dependencies with version requirements instead of locking
+    /// them, so that it's only scanned when given explicitly, not when walking a directory
+    /// that likely has its lock file too.
+    pub fn is_manifest(self) -> bool {
+        matches!(self, Self::CargoManifest)
+    }
 }
 
 #[cfg(test)]
@@ -56,6 +100,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_recognizes_the_cargo_manifest_file() {
+        let package_manager = PackageManager::from_filename("/path/to/Cargo.toml").unwrap();
+
+        assert_eq!(package_manager, PackageManager::CargoManifest);
+        assert_eq!(package_manager.ecosystem(), "cargo");
+        assert!(package_manager.is_manifest());
+    }
+
     #[test]
     fn it_recognizes_the_yarn_package_lock_file() {
         assert_eq!(
@@ -72,8 +125,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_recognizes_the_pnpm_package_lock_file() {
+        assert_eq!(
+            PackageManager::from_filename("pnpm-lock.yaml").unwrap(),
+            PackageManager::Pnpm
+        );
+    }
+
+    #[test]
+    fn it_recognizes_the_pnpm_package_lock_file_even_with_full_path() {
+        assert_eq!(
+            PackageManager::from_filename("/path/to/pnpm-lock.yaml").unwrap(),
+            PackageManager::Pnpm
+        );
+    }
+
+    #[test]
+    fn it_recognizes_the_pip_requirements_file() {
+        assert_eq!(
+            PackageManager::from_filename("/path/to/requirements.txt").unwrap(),
+            PackageManager::Pip
+        );
+    }
+
+    #[test]
+    fn it_recognizes_the_poetry_lock_file() {
+        assert_eq!(
+            PackageManager::from_filename("/path/to/poetry.lock").unwrap(),
+            PackageManager::Poetry
+        );
+    }
+
+    #[test]
+    fn it_recognizes_the_go_mod_file() {
+        assert_eq!(
+            PackageManager::from_filename("/path/to/go.mod").unwrap(),
+            PackageManager::Go
+        );
+    }
+
+    #[test]
+    fn it_recognizes_the_bundler_lock_file() {
+        assert_eq!(
+            PackageManager::from_filename("/path/to/Gemfile.lock").unwrap(),
+            PackageManager::Bundler
+        );
+    }
+
+    #[test]
+    fn it_recognizes_the_composer_lock_file() {
+        assert_eq!(
+            PackageManager::from_filename("/path/to/composer.lock").unwrap(),
+            PackageManager::Composer
+        );
+    }
+
     #[test]
     fn if_it_doesnt_recognize_the_package_manager_returns_none() {
         assert!(PackageManager::from_filename("some-file-name").is_none());
     }
+
+    #[test]
+    fn it_shares_the_ecosystem_of_the_package_managers_of_the_same_registry() {
+        assert_eq!(PackageManager::Yarn.ecosystem(), "npm");
+        assert_eq!(PackageManager::Pnpm.ecosystem(), "npm");
+        assert_eq!(PackageManager::Poetry.ecosystem(), "pypi");
+        assert_eq!(PackageManager::Cargo.ecosystem(), "cargo");
+    }
 }
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Write};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use crate::pkg::Repository;
+use crate::Dependency;
+
+/// Phase of the scan whose durations are recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Phase {
+    RepositoryClone,
+    TagRetrieval,
+    /// Retrieval of the issues and pull requests of a repository.
+    IssueFetch,
+    PolicyEvaluation,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Phase::RepositoryClone => "repository clone",
+            Phase::TagRetrieval => "tag retrieval",
+            Phase::IssueFetch => "issue fetch",
+            Phase::PolicyEvaluation => "policy evaluation",
+        };
+        write!(f, "{name}")
+    }
+}
+
+struct Timing {
+    phase: Phase,
+    subject: String,
+    duration: Duration,
+}
+
+/// Durations of the phases of a scan. The clone and the tag retrieval are recorded by the URL of
+/// the repository, the issue fetch by `organization/name`, and the policy evaluation by
+/// dependency, so that the slowest dependencies are reported with the phases of their
+/// repositories.
+#[derive(Default)]
+pub struct Timings {
+    timings: Mutex<Vec<Timing>>,
+    repositories: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl Timings {
+    pub fn record(&self, phase: Phase, subject: &str, duration: Duration) {
+        if let Ok(mut timings) = self.timings.lock() {
+            timings.push(Timing {
+                phase,
+                subject: subject.to_string(),
+                duration,
+            });
+        }
+    }
+
+    /// Awaits the future, recording its duration when the timings are enabled.
+    pub async fn measure<F>(
+        timings: Option<&Self>,
+        phase: Phase,
+        subject: &str,
+        future: F,
+    ) -> F::Output
+    where
+        F: Future,
+    {
+        let Some(timings) = timings else {
+            return future.await;
+        };
+
+        let start = Instant::now();
+        let output = future.await;
+        timings.record(phase, subject, start.elapsed());
+        output
+    }
+
+    /// Awaits the evaluation of the dependency, recording its duration.
+    pub async fn measure_evaluation<F>(
+        timings: Option<&Self>,
+        dependency: &Dependency,
+        future: F,
+    ) -> F::Output
+    where
+        F: Future,
+    {
+        let Some(timings) = timings else {
+            return future.await;
+        };
+
+        let subject = Self::dependency_subject(dependency);
+        if let Ok(mut repositories) = timings.repositories.lock() {
+            repositories.insert(
+                subject.clone(),
+                Self::repository_subjects(&dependency.repository),
+            );
+        }
+        Self::measure(Some(timings), Phase::PolicyEvaluation, &subject, future).await
+    }
+
+    fn dependency_subject(dependency: &Dependency) -> String {
+        format!("{}@{}", dependency.name, dependency.version)
+    }
+
+    fn repository_subjects(repository: &Repository) -> Vec<String> {
+        let mut subjects = repository.url().into_iter().collect_vec();
+        if let Repository::GitHub { organization, name }
+        | Repository::GitLab { organization, name } = repository
+        {
+            subjects.push(format!("{organization}/{name}"));
+        }
+        subjects
+    }
+
+    /// Returns the total duration of each phase, followed by the slowest dependencies to
+    /// evaluate with the durations of the phases of their repositories.
+    pub fn summary(&self, slowest: usize) -> String {
+        let timings = self.timings.lock().map(|timings| {
+            timings
+                .iter()
+                .map(|timing| (timing.phase, timing.subject.clone(), timing.duration))
+                .collect_vec()
+        });
+        let Ok(timings) = timings else {
+            return String::new();
+        };
+        let repositories = self
+            .repositories
+            .lock()
+            .map(|repositories| repositories.clone())
+            .unwrap_or_default();
+
+        let mut summary = String::new();
+        let _ = writeln!(summary, "timings of the scan:");
+        for (phase, durations) in &timings
+            .iter()
+            .sorted_by_key(|(phase, ..)| *phase)
+            .group_by(|(phase, ..)| *phase)
+        {
+            let durations = durations.map(|(.., duration)| *duration).collect_vec();
+            let calls = if durations.len() == 1 {
+                "call"
+            } else {
+                "calls"
+            };
+            let _ = writeln!(
+                summary,
+                "  {phase}: {} in {} {calls}",
+                format_duration(durations.iter().sum()),
+                durations.len()
+            );
+        }
+
+        let evaluations = timings
+            .iter()
+            .filter(|(phase, ..)| *phase == Phase::PolicyEvaluation)
+            .sorted_by(|(.., a), (.., b)| b.cmp(a))
+            .take(slowest)
+            .collect_vec();
+        if evaluations.is_empty() {
+            return summary;
+        }
+
+        let _ = writeln!(summary, "slowest dependencies:");
+        for (_, dependency, duration) in evaluations {
+            let subjects = repositories.get(dependency).cloned().unwrap_or_default();
+            let phases = timings
+                .iter()
+                .filter(|(phase, subject, _)| {
+                    *phase != Phase::PolicyEvaluation && subjects.contains(subject)
+                })
+                .map(|(phase, _, duration)| format!("{phase} {}", format_duration(*duration)))
+                .join(", ");
+            let _ = if phases.is_empty() {
+                writeln!(summary, "  {dependency}: {}", format_duration(*duration))
+            } else {
+                writeln!(
+                    summary,
+                    "  {dependency}: {} ({phases})",
+                    format_duration(*duration)
+                )
+            };
+        }
+
+        summary
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_summarizes_the_phases_and_the_slowest_dependencies() {
+        let timings = Timings::default();
+        let serde = dependency("serde", "serde-rs", "serde");
+        let rand = dependency("rand", "rust-random", "rand");
+        timings
+            .repositories
+            .lock()
+            .unwrap()
+            .extend([&serde, &rand].map(|dependency| {
+                (
+                    Timings::dependency_subject(dependency),
+                    Timings::repository_subjects(&dependency.repository),
+                )
+            }));
+        let url = |dependency: &Dependency| dependency.repository.url().unwrap();
+        timings.record(
+            Phase::RepositoryClone,
+            &url(&serde),
+            Duration::from_millis(3100),
+        );
+        timings.record(
+            Phase::TagRetrieval,
+            &url(&serde),
+            Duration::from_millis(400),
+        );
+        timings.record(
+            Phase::IssueFetch,
+            "serde-rs/serde",
+            Duration::from_millis(1200),
+        );
+        timings.record(
+            Phase::RepositoryClone,
+            &url(&rand),
+            Duration::from_millis(900),
+        );
+        timings.record(
+            Phase::PolicyEvaluation,
+            "serde@1.0.0",
+            Duration::from_millis(5200),
+        );
+        timings.record(
+            Phase::PolicyEvaluation,
+            "rand@1.0.0",
+            Duration::from_millis(1500),
+        );
+
+        assert_eq!(
+            timings.summary(1),
+            "\
+timings of the scan:
+  repository clone: 4.00s in 2 calls
+  tag retrieval: 0.40s in 1 call
+  issue fetch: 1.20s in 1 call
+  policy evaluation: 6.70s in 2 calls
+slowest dependencies:
+  serde@1.0.0: 5.20s (repository clone 3.10s, tag retrieval 0.40s, issue fetch 1.20s)
+"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_only_records_the_durations_when_the_timings_are_enabled() {
+        let timings = Timings::default();
+
+        let output =
+            Timings::measure(Some(&timings), Phase::IssueFetch, "foo/bar", async { 1 }).await;
+        Timings::measure(None, Phase::IssueFetch, "foo/bar", async {}).await;
+
+        assert_eq!(output, 1);
+        assert_eq!(timings.timings.lock().unwrap().len(), 1);
+    }
+
+    fn dependency(name: &str, organization: &str, repository: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            repository: Repository::GitHub {
+                organization: organization.to_string(),
+                name: repository.to_string(),
+            },
+            ..Dependency::default()
+        }
+    }
+}
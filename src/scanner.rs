@@ -0,0 +1,530 @@
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use futures::future::join_all;
+use log::{debug, error, info, log, warn, Level};
+use regex::RegexSet;
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+use crate::cmd::LogFormat;
+use crate::factory::Factory;
+use crate::infra::json_logger::EVALUATION_LOG_TARGET;
+use crate::infra::scan_state::{self, ScanState};
+use crate::pkg::config::Config;
+use crate::pkg::engine::PolicyExecutor;
+use crate::pkg::DependencyFilter;
+use crate::{Dependency, Evaluation, Result};
+
+pub const DEFAULT_DEPENDENCY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Evaluates the dependencies of a lock file with the configured policies, returning the
+/// evaluations instead of reporting them so that the scan can be used as a library.
+#[allow(clippy::struct_excessive_bools)]
+pub struct Scanner {
+    factory: Factory,
+    only: RegexSet,
+    exclude: RegexSet,
+    prod_only: bool,
+    dependency_timeout: Duration,
+    progress: bool,
+    quiet: bool,
+    log_format: LogFormat,
+    resume: bool,
+}
+
+impl Scanner {
+    pub fn new(config: Config) -> Self {
+        Self::from_factory(Factory::new(Rc::new(config)))
+    }
+
+    pub fn from_factory(factory: Factory) -> Self {
+        Self {
+            factory,
+            only: RegexSet::empty(),
+            exclude: RegexSet::empty(),
+            prod_only: false,
+            dependency_timeout: DEFAULT_DEPENDENCY_TIMEOUT,
+            progress: false,
+            quiet: false,
+            log_format: LogFormat::Text,
+            resume: false,
+        }
+    }
+
+    /// Only scans the dependencies whose name matches any of the regexes, or all of them if
+    /// the set is empty.
+    pub fn set_only(&mut self, only: RegexSet) {
+        self.only = only;
+    }
+
+    /// Skips the dependencies whose name matches any of the regexes, even if matched by `only`.
+    pub fn set_exclude(&mut self, exclude: RegexSet) {
+        self.exclude = exclude;
+    }
+
+    /// Skips the dependencies that the lock files flag as dev ones.
+    pub fn set_prod_only(&mut self, prod_only: bool) {
+        self.prod_only = prod_only;
+    }
+
+    pub fn set_dependency_timeout(&mut self, dependency_timeout: Duration) {
+        self.dependency_timeout = dependency_timeout;
+    }
+
+    /// Reports the number of evaluated dependencies to stderr.
+    pub fn set_progress(&mut self, progress: bool) {
+        self.progress = progress;
+    }
+
+    /// Doesn't log the evaluations of the dependencies that pass or are skipped.
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// Logs the evaluations as JSON objects with their fields, for the `JsonLogger`.
+    pub fn set_log_format(&mut self, log_format: LogFormat) {
+        self.log_format = log_format;
+    }
+
+    /// Skips the dependencies already evaluated by the interrupted scan of the same lock files,
+    /// returning their evaluations together with the ones of the rest of the dependencies.
+    pub fn set_resume(&mut self, resume: bool) {
+        self.resume = resume;
+    }
+
+    /// Scans the dependencies of a lock file, or of the lock files found inside a directory.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the lock file can't be read, the policies of the config can't be built or the
+    /// state of an interrupted scan can't be loaded. The dependencies whose evaluation fails are
+    /// logged and left out of the evaluations instead.
+    pub async fn scan(&mut self, lock_file_name: &str) -> Result<Vec<Evaluation>> {
+        self.scan_all(&[lock_file_name.to_string()]).await
+    }
+
+    /// Scans the dependencies of several lock files at once, or of the lock files found inside
+    /// the directories, evaluating only once the dependencies repeated among them.
+    ///
+    /// # Errors
+    ///
+    /// Fails for the same reasons as [`Scanner::scan`], for any of the lock files.
+    pub async fn scan_all(&mut self, lock_file_names: &[String]) -> Result<Vec<Evaluation>> {
+        let filter = DependencyFilter::new(self.only.clone(), self.exclude.clone(), self.prod_only);
+        let mut dependency_reader = self
+            .factory
+            .lock_files_dependency_reader(lock_file_names, &filter)
+            .await?;
+
+        let lock_files_hash = Factory::lock_files_hash(lock_file_names)?;
+        let scan_state = self.factory.scan_state();
+        let previous_state = self.previous_state(&scan_state, &lock_files_hash)?;
+
+        let engine = Arc::new(self.factory.engine()?);
+        let dependency_timeout = self.dependency_timeout;
+        let progress = self.progress;
+        let quiet = self.quiet;
+        let log_format = self.log_format;
+
+        let mut async_results = Vec::new();
+
+        while let Some(dep) = dependency_reader.next().await {
+            if previous_state.evaluated.contains(&(
+                dep.name.clone(),
+                dep.version.clone(),
+                dep.ecosystem.unwrap_or_default().to_string(),
+            )) {
+                debug!("skipping dependency {} already evaluated", dep.name);
+                continue;
+            }
+
+            let engine = engine.clone();
+            let scan_state = scan_state.clone();
+            let lock_files_hash = lock_files_hash.clone();
+            let task = tokio::spawn(async move {
+                let evaluations = tokio::time::timeout(dependency_timeout, engine.evaluate(&dep))
+                    .await
+                    .unwrap_or_else(|_| {
+                        warn!(
+                            "evaluation of dependency {} timed out after {:?}",
+                            dep.name, dependency_timeout
+                        );
+                        Ok(Self::timed_out_evaluations(
+                            &engine,
+                            &dep,
+                            dependency_timeout,
+                        ))
+                    });
+                let evaluations = match evaluations {
+                    Ok(evaluations) => evaluations,
+                    Err(err) => {
+                        error!("error evaluating dependency {}: {}", dep.name, err);
+                        return None;
+                    }
+                };
+
+                for evaluation in &evaluations {
+                    Self::log_evaluation(evaluation, quiet, log_format);
+                }
+                if let Err(err) = scan_state.save_evaluations(&lock_files_hash, &dep, &evaluations)
+                {
+                    warn!("unable to save the evaluations of {}: {}", dep.name, err);
+                }
+
+                Some(evaluations)
+            });
+            async_results.push(task);
+        }
+
+        let total = async_results.len();
+        let evaluated = Arc::new(AtomicUsize::new(0));
+        let async_results = join_all(async_results.into_iter().map(|task| {
+            let evaluated = evaluated.clone();
+            async move {
+                let result = task.await;
+                let evaluated = evaluated.fetch_add(1, Ordering::SeqCst) + 1;
+                if progress {
+                    eprint!("\revaluated {evaluated}/{total} dependencies");
+                }
+                result
+            }
+        }))
+        .await;
+        if progress {
+            eprintln!();
+        }
+        if let Err(err) = scan_state.clear(&lock_files_hash) {
+            warn!("unable to clear the state of the completed scan: {}", err);
+        }
+
+        Ok(previous_state
+            .evaluations
+            .into_iter()
+            .chain(async_results.into_iter().flatten().flatten().flatten())
+            .collect())
+    }
+
+    /// Loads the state of the interrupted scan when resuming, or clears it to start over.
+    fn previous_state(
+        &self,
+        scan_state: &scan_state::Sqlite,
+        lock_files_hash: &str,
+    ) -> Result<ScanState> {
+        if !self.resume {
+            scan_state
+                .clear(lock_files_hash)
+                .map_err(|e| anyhow!("unable to clear the state of the previous scan: {}", e))?;
+            return Ok(ScanState::default());
+        }
+
+        let previous_state = scan_state
+            .load(lock_files_hash)
+            .map_err(|e| anyhow!("unable to load the state of the interrupted scan: {}", e))?;
+        if !previous_state.evaluated.is_empty() {
+            info!(
+                "resuming the scan, {} dependencies were already evaluated",
+                previous_state.evaluated.len()
+            );
+        }
+        Ok(previous_state)
+    }
+
+    /// Logs the passes and skips at info level, unless quiet, and the failures at warn level.
+    fn log_evaluation(evaluation: &Evaluation, quiet: bool, log_format: LogFormat) {
+        let (level, outcome, mut fields) = match evaluation {
+            Evaluation::Pass { .. } => (
+                Level::Info,
+                "is okay".to_string(),
+                json!({"result": "pass"}),
+            ),
+            Evaluation::Fail {
+                reason,
+                fail_score,
+                severity,
+                ..
+            } => (
+                Level::Warn,
+                format!(
+                    "is not okay: {} (score: {}, severity: {})",
+                    reason,
+                    fail_score,
+                    severity.as_str()
+                ),
+                json!({"result": "fail", "reason": reason, "fail_score": fail_score, "severity": severity.as_str()}),
+            ),
+            Evaluation::Skip { reason, .. } => (
+                Level::Info,
+                format!("was skipped: {reason}"),
+                json!({"result": "skip", "reason": reason}),
+            ),
+        };
+        if quiet && level == Level::Info {
+            return;
+        }
+
+        let dependency = evaluation.dependency();
+        let latest_version = dependency.latest_version.as_deref().unwrap_or("unknown");
+        match log_format {
+            LogFormat::Text => log!(
+                level,
+                "dependency [name={}, version={}, latest version={}, repository={}, policy={}] {}",
+                dependency.name,
+                dependency.version,
+                latest_version,
+                dependency.repository,
+                evaluation.policy(),
+                outcome
+            ),
+            LogFormat::Json => {
+                fields["name"] = json!(dependency.name);
+                fields["version"] = json!(dependency.version);
+                fields["latest_version"] = json!(latest_version);
+                fields["repository"] = json!(dependency.repository.to_string());
+                fields["policy"] = json!(evaluation.policy());
+                log!(target: EVALUATION_LOG_TARGET, level, "{fields}");
+            }
+        }
+    }
+
+    /// Marks every policy of the dependency as skipped because its evaluation took too long.
+    fn timed_out_evaluations(
+        engine: &PolicyExecutor,
+        dependency: &Dependency,
+        timeout: Duration,
+    ) -> Vec<Evaluation> {
+        engine
+            .policy_names(dependency)
+            .into_iter()
+            .map(|policy_name| Evaluation::Skip {
+                policy_name: policy_name.to_string(),
+                dependency: dependency.clone(),
+                reason: format!("evaluation timed out after {}s", timeout.as_secs()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pkg::config::{max_versions_behind, Policies};
+
+    #[tokio::test]
+    async fn it_returns_the_evaluations_of_the_dependencies_of_the_lock_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let lock_file = temp_dir.path().join("Cargo.lock");
+        std::fs::write(
+            &lock_file,
+            "\
+version = 3
+
+[[package]]
+name = \"serde\"
+version = \"1.0.152\"
+source = \"registry+https://github.com/rust-lang/crates.io-index\"
+
+[[package]]
+name = \"regex\"
+version = \"1.7.1\"
+source = \"registry+https://github.com/rust-lang/crates.io-index\"
+",
+        )
+        .unwrap();
+        let config = Config {
+            default_policies: Policies {
+                max_versions_behind: Some(max_versions_behind::Config::default()),
+                ..Policies::default()
+            },
+            ..Config::default()
+        };
+        let mut factory = Factory::new(Rc::new(config));
+        factory.set_offline(true);
+        factory.set_no_cache(true);
+        let mut scanner = Scanner::from_factory(factory);
+        scanner.set_only(RegexSet::new(["^serde$"]).unwrap());
+
+        let evaluations = scanner.scan(lock_file.to_str().unwrap()).await.unwrap();
+
+        assert_eq!(evaluations.len(), 1);
+        assert_eq!(evaluations[0].policy(), "max_versions_behind");
+        assert_eq!(evaluations[0].dependency().name, "serde");
+        assert!(matches!(evaluations[0], Evaluation::Pass { .. }));
+    }
+
+    #[tokio::test]
+    async fn it_tells_the_lock_file_each_dependency_was_read_from() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let lock_files = ["backend", "tools"].map(|directory| {
+            std::fs::create_dir(temp_dir.path().join(directory)).unwrap();
+            temp_dir
+                .path()
+                .join(directory)
+                .join("Cargo.lock")
+                .to_str()
+                .unwrap()
+                .to_string()
+        });
+        for (lock_file, name) in lock_files.iter().zip(["serde", "regex"]) {
+            std::fs::write(
+                lock_file,
+                format!(
+                    "\
+version = 3
+
+[[package]]
+name = \"{name}\"
+version = \"1.0.0\"
+source = \"registry+https://github.com/rust-lang/crates.io-index\"
+"
+                ),
+            )
+            .unwrap();
+        }
+        let config = Config {
+            default_policies: Policies {
+                max_versions_behind: Some(max_versions_behind::Config::default()),
+                ..Policies::default()
+            },
+            ..Config::default()
+        };
+        let mut factory = Factory::new(Rc::new(config));
+        factory.set_offline(true);
+        factory.set_no_cache(true);
+        let mut scanner = Scanner::from_factory(factory);
+
+        let evaluations = scanner.scan_all(&lock_files).await.unwrap();
+
+        let mut lock_file_of_each_dependency = evaluations
+            .iter()
+            .map(|evaluation| {
+                (
+                    evaluation.dependency().name.as_str(),
+                    evaluation.dependency().lock_file.clone().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        lock_file_of_each_dependency.sort();
+        assert_eq!(
+            lock_file_of_each_dependency,
+            vec![
+                ("regex", lock_files[1].clone()),
+                ("serde", lock_files[0].clone())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_resumes_the_scan_skipping_the_dependencies_already_evaluated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let lock_file = temp_dir.path().join("Cargo.lock");
+        std::fs::write(
+            &lock_file,
+            "\
+version = 3
+
+[[package]]
+name = \"serde\"
+version = \"1.0.152\"
+source = \"registry+https://github.com/rust-lang/crates.io-index\"
+
+[[package]]
+name = \"regex\"
+version = \"1.7.1\"
+source = \"registry+https://github.com/rust-lang/crates.io-index\"
+",
+        )
+        .unwrap();
+        let lock_file = lock_file.to_str().unwrap().to_string();
+        let config = Config {
+            default_policies: Policies {
+                max_versions_behind: Some(max_versions_behind::Config::default()),
+                ..Policies::default()
+            },
+            ..Config::default()
+        };
+        let mut factory = Factory::new(Rc::new(config));
+        factory.set_offline(true);
+        factory.set_cache_dir(temp_dir.path().join("cache"));
+        let scan_state = factory.scan_state();
+        let lock_files_hash = Factory::lock_files_hash(&[lock_file.clone()]).unwrap();
+        let serde = Dependency {
+            name: "serde".to_string(),
+            version: "1.0.152".to_string(),
+            ecosystem: Some("cargo"),
+            ..Dependency::default()
+        };
+        let previous_evaluation = Evaluation::Skip {
+            policy_name: "max_versions_behind".to_string(),
+            dependency: serde.clone(),
+            reason: "evaluation timed out after 300s".to_string(),
+        };
+        scan_state
+            .save_evaluations(&lock_files_hash, &serde, &[previous_evaluation.clone()])
+            .unwrap();
+        let mut scanner = Scanner::from_factory(factory);
+        scanner.set_resume(true);
+
+        let evaluations = scanner.scan(&lock_file).await.unwrap();
+
+        assert_eq!(evaluations.len(), 2);
+        assert_eq!(evaluations[0], previous_evaluation);
+        assert_eq!(evaluations[1].dependency().name, "regex");
+        assert!(matches!(evaluations[1], Evaluation::Pass { .. }));
+        assert!(scan_state
+            .load(&lock_files_hash)
+            .unwrap()
+            .evaluated
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_scans_once_the_dependencies_repeated_in_the_lock_files_of_a_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let lock_file_contents = "\
+version = 3
+
+[[package]]
+name = \"serde\"
+version = \"1.0.152\"
+source = \"registry+https://github.com/rust-lang/crates.io-index\"
+";
+        for project in ["first", "second", "node_modules"] {
+            std::fs::create_dir(temp_dir.path().join(project)).unwrap();
+            std::fs::write(
+                temp_dir.path().join(project).join("Cargo.lock"),
+                lock_file_contents,
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            temp_dir.path().join("second").join("requirements.txt"),
+            "serde==1.0.152\n",
+        )
+        .unwrap();
+        let config = Config {
+            default_policies: Policies {
+                max_versions_behind: Some(max_versions_behind::Config::default()),
+                ..Policies::default()
+            },
+            ..Config::default()
+        };
+        let mut factory = Factory::new(Rc::new(config));
+        factory.set_offline(true);
+        factory.set_no_cache(true);
+        let mut scanner = Scanner::from_factory(factory);
+
+        let mut evaluations = scanner
+            .scan_all(&[temp_dir.path().to_str().unwrap().to_string()])
+            .await
+            .unwrap();
+        evaluations.sort_by_key(|e| e.dependency().ecosystem);
+
+        assert_eq!(evaluations.len(), 2);
+        assert_eq!(evaluations[0].dependency().ecosystem, Some("cargo"));
+        assert_eq!(evaluations[1].dependency().ecosystem, Some("pypi"));
+    }
+}